@@ -1,84 +1,462 @@
-use anyhow::bail;
+use std::io::IsTerminal;
+use std::path::Path;
 
-use crate::data::Transaction;
+use anyhow::{Context, bail, ensure};
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use ureq::ResponseExt;
+
+use crate::data::index::feed_index;
 use crate::data::schema::FeedSource;
+use crate::data::{BlogData, Transaction, get_config_value, transact};
+use crate::feed::Fetched;
+use crate::feed::blocklist::Blocklist;
+use crate::feed::links::LinkCleaner;
+use crate::feed::pull::apply_fetched;
+use crate::feed::youtube::{self, YoutubeUrl};
+use crate::utils::http::HttpOptions;
 use crate::utils::progress::spinner;
+use crate::utils::prompt::ask;
 
 /// Sanity cap on feed candidates to validate during HTML discovery.
 const MAX_FEED_CANDIDATES: usize = 20;
 
-pub(crate) fn resolve_feed_url(url: &str) -> anyhow::Result<String> {
-    let client = crate::utils::http::http_client();
+/// How many feed candidates are downloaded at once during HTML discovery.
+const MAX_PARALLEL_CHECKS: usize = 8;
 
-    let sp = spinner(&format!("Fetching {url}..."));
-    let bytes = client.get(url).call()?.body_mut().read_to_vec()?;
+/// A feed found by [`resolve_feed_url`], with its contents if they were
+/// downloaded along the way.
+pub(crate) struct ResolvedFeed {
+    pub url: String,
+    /// Where `url` redirected to, if it did
+    pub redirected_to: Option<String>,
+    pub fetched: Option<Fetched>,
+}
 
-    // Try parsing as RSS/Atom — if it works, the URL is already a feed
-    if is_feed_content(&bytes) {
-        sp.finish_and_clear();
-        return Ok(url.to_string());
+impl ResolvedFeed {
+    fn unfetched(url: String) -> Self {
+        Self {
+            url,
+            redirected_to: None,
+            fetched: None,
+        }
     }
 
-    // Not a feed — try HTML feed discovery
-    sp.set_message(format!("Looking for feeds on {url}..."));
-    let html = String::from_utf8_lossy(&bytes);
-    let base_url = url::Url::parse(url)?;
-    let candidates = crate::feed::discover::discover_feed_urls(&html, &base_url);
+    fn title(&self) -> &str {
+        self.fetched.as_ref().map_or("", |f| f.meta.title.as_str())
+    }
+}
 
-    // Validate candidates by fetching and parsing each one
-    let feeds: Vec<_> = candidates
+/// Ask which of `feeds` to subscribe to, reading the answer from stdin.
+pub(crate) fn select_feeds(feeds: Vec<ResolvedFeed>) -> anyhow::Result<Vec<ResolvedFeed>> {
+    let width = feeds.len().to_string().len();
+    for (i, feed) in feeds.iter().enumerate() {
+        let title = match feed.title() {
+            "" => "(untitled)",
+            title => title,
+        };
+        eprintln!("  {:>width$}) {title}  {}", i + 1, feed.url);
+    }
+    let answer = ask("Subscribe to (e.g. 1 3, 2-4, all): ")?;
+    let picked = parse_selection(&answer, feeds.len())?;
+    Ok(feeds
         .into_iter()
-        .take(MAX_FEED_CANDIDATES)
-        .filter(|u| {
-            sp.set_message(format!("Checking {u}..."));
-            is_valid_feed(&client, u)
-        })
-        .collect();
+        .enumerate()
+        .filter(|(i, _)| picked.contains(&(i + 1)))
+        .map(|(_, feed)| feed)
+        .collect())
+}
 
-    sp.finish_and_clear();
+/// Parse a menu answer such as `1 3`, `1,3`, `2-4` or `all` into the 1-based
+/// numbers it picks out of `count` items.
+fn parse_selection(answer: &str, count: usize) -> anyhow::Result<Vec<usize>> {
+    let answer = answer.trim();
+    if answer.eq_ignore_ascii_case("all") {
+        return Ok((1..=count).collect());
+    }
+    let number = |s: &str| -> anyhow::Result<usize> {
+        match s.trim().parse() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n),
+            _ => bail!("invalid choice {s:?}: pick numbers from 1 to {count}"),
+        }
+    };
+    let mut picked = Vec::new();
+    for part in answer.split([' ', ',']).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((from, to)) => picked.extend(number(from)?..=number(to)?),
+            None => picked.push(number(part)?),
+        }
+    }
+    picked.sort_unstable();
+    picked.dedup();
+    ensure!(!picked.is_empty(), "no feed selected");
+    Ok(picked)
+}
 
+/// Turn what the user passed to `blog feed add` into the feeds to subscribe
+/// to. When a page links to several feeds, `select` (or a terminal on stdin)
+/// shows a menu to pick from instead of failing.
+pub(crate) fn resolve_feed_url(
+    url: &str,
+    http: &HttpOptions,
+    links: &LinkCleaner,
+    select: bool,
+) -> anyhow::Result<Vec<ResolvedFeed>> {
+    let feeds = find_feeds(url, http, links)?;
     match feeds.len() {
         0 => bail!("no feeds found at {url}"),
-        1 => Ok(feeds.into_iter().next().unwrap()),
+        1 => Ok(feeds),
         _ => {
             eprintln!("Multiple feeds found at {url}:");
-            for feed_url in &feeds {
-                eprintln!("  {feed_url}");
+            if select || std::io::stdin().is_terminal() {
+                return select_feeds(feeds);
+            }
+            for feed in &feeds {
+                eprintln!("  {}", feed.url);
             }
             bail!(
-                "multiple feeds found; run `blog feed add <feed-url>` with a specific URL from the list above"
+                "multiple feeds found; run `blog feed add --select {url}` to pick from a menu, or `blog feed add <feed-url>` with a specific URL from the list above"
             );
         }
     }
 }
 
-fn is_feed_content(bytes: &[u8]) -> bool {
-    crate::feed::rss::parse(bytes).is_ok() || crate::feed::atom::parse(bytes).is_ok()
+/// Every feed `url` stands for: itself if it is a feed or a shortcut for
+/// one, or else the feeds its page links to or the first one found at the
+/// usual paths of its site. Fails only if `url` can't be downloaded.
+pub(crate) fn find_feeds(
+    url: &str,
+    http: &HttpOptions,
+    links: &LinkCleaner,
+) -> anyhow::Result<Vec<ResolvedFeed>> {
+    if let Some(feed_url) = crate::feed::shortcuts::expand(url)? {
+        return Ok(vec![ResolvedFeed::unfetched(feed_url)]);
+    }
+
+    let client = crate::utils::http::http_client(http);
+
+    let youtube = youtube::classify(url);
+    if let Some(YoutubeUrl::Feed(feed_url)) = youtube {
+        return Ok(vec![ResolvedFeed::unfetched(feed_url)]);
+    }
+
+    let sp = spinner(&format!("Fetching {url}..."));
+    let mut response = client.get(url).call()?;
+    let final_url = response.get_uri().to_string();
+    let bytes = response
+        .body_mut()
+        .with_config()
+        .limit(http.max_body)
+        .read_to_vec()?;
+
+    if youtube == Some(YoutubeUrl::Page) {
+        sp.finish_and_clear();
+        return youtube::channel_id_in_page(&String::from_utf8_lossy(&bytes))
+            .map(|id| vec![ResolvedFeed::unfetched(youtube::channel_feed_url(&id))])
+            .ok_or_else(|| anyhow::anyhow!("could not find the YouTube channel for {url}"));
+    }
+
+    // Try parsing as RSS/Atom/JSON Feed — if it works, the URL is already a feed
+    if let Some(fetched) = parse_feed(&bytes, &links.with_base(url)) {
+        sp.finish_and_clear();
+        return Ok(vec![ResolvedFeed {
+            url: url.to_string(),
+            redirected_to: (final_url != url).then_some(final_url),
+            fetched: Some(fetched),
+        }]);
+    }
+
+    // Not a feed — try HTML feed discovery
+    sp.set_message(format!("Looking for feeds on {url}..."));
+    let html = String::from_utf8_lossy(&bytes);
+    let base_url = url::Url::parse(url)?;
+    let candidates = crate::feed::discover::discover_feed_urls(&html, &base_url);
+
+    // Validate candidates by fetching and parsing them, a few at a time
+    let candidates: Vec<_> = candidates.into_iter().take(MAX_FEED_CANDIDATES).collect();
+    let check = |u: &String| {
+        sp.set_message(format!("Checking {u}..."));
+        fetch_feed(&client, u, links, http.max_body)
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(MAX_PARALLEL_CHECKS)
+        .build()
+        .context("failed to build feed check thread pool")?;
+    let mut feeds: Vec<_> = pool.install(|| candidates.par_iter().filter_map(check).collect());
+
+    // Nothing on the page worked: fall back to the usual paths at the site
+    // root. These are mostly other names for the same feed, so the first
+    // one that works is enough and the rest are left unchecked.
+    if feeds.is_empty() {
+        let root_urls: Vec<_> = crate::feed::discover::root_feed_urls(&base_url)
+            .into_iter()
+            .filter(|u| !candidates.contains(u))
+            .collect();
+        feeds = pool
+            .install(|| root_urls.par_iter().find_map_first(check))
+            .into_iter()
+            .collect();
+    }
+
+    sp.finish_and_clear();
+    Ok(feeds)
+}
+
+/// The feed in `bytes`, or `None` if it isn't RSS, Atom or JSON Feed.
+fn parse_feed(bytes: &[u8], links: &LinkCleaner) -> Option<Fetched> {
+    let (meta, items) = crate::feed::parse(bytes, links).ok()?;
+    Some(Fetched {
+        meta,
+        items,
+        moved_to: None,
+    })
+}
+
+fn fetch_feed(
+    client: &ureq::Agent,
+    url: &str,
+    links: &LinkCleaner,
+    max_body: u64,
+) -> Option<ResolvedFeed> {
+    let mut resp = client.get(url).call().ok()?;
+    let final_url = resp.get_uri().to_string();
+    let bytes = resp
+        .body_mut()
+        .with_config()
+        .limit(max_body)
+        .read_to_vec()
+        .ok()?;
+    Some(ResolvedFeed {
+        url: url.to_string(),
+        redirected_to: (final_url != url).then_some(final_url),
+        fetched: Some(parse_feed(&bytes, &links.with_base(url))?),
+    })
+}
+
+/// A feed URL reduced to what identifies the feed, so that
+/// `http://a.example/feed/` and `https://A.example/feed` are the same
+/// subscription.
+fn subscription_key(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url.trim()) else {
+        return url.trim().trim_end_matches('/').to_string();
+    };
+    parsed.set_fragment(None);
+    let rest = &parsed[url::Position::BeforeHost..];
+    rest.trim_end_matches('/').to_string()
 }
 
-fn is_valid_feed(client: &ureq::Agent, url: &str) -> bool {
-    let Ok(mut resp) = client.get(url).call() else {
-        return false;
+/// Fail if `feed`, or the URL it redirects to, is already subscribed to,
+/// naming the existing subscription's @shorthand.
+pub(crate) fn ensure_not_subscribed(
+    feeds: &synctato::Table<FeedSource>,
+    feed: &ResolvedFeed,
+) -> anyhow::Result<()> {
+    let keys: Vec<String> = std::iter::once(&feed.url)
+        .chain(&feed.redirected_to)
+        .map(|url| subscription_key(url))
+        .collect();
+    let fi = feed_index(feeds);
+    let Some(entry) = fi
+        .entries
+        .iter()
+        .find(|e| keys.contains(&subscription_key(&e.feed.url)))
+    else {
+        return Ok(());
     };
-    let Ok(bytes) = resp.body_mut().read_to_vec() else {
-        return false;
+    if entry.feed.archived {
+        bail!(
+            "already subscribed to {} as @{} (archived); run `blog feed resume @{}` to sync it again",
+            entry.feed.url,
+            entry.shorthand,
+            entry.shorthand
+        );
+    }
+    bail!(
+        "already subscribed to {} as @{}",
+        entry.feed.url,
+        entry.shorthand
+    )
+}
+
+/// Fail if `url` is on the `blocklist` config.
+pub(crate) fn ensure_not_blocked(blocklist: &Blocklist, url: &str) -> anyhow::Result<()> {
+    match blocklist.blocked_by(url) {
+        Some(entry) => bail!("{url} is blocked by blocklist entry {entry:?}"),
+        None => Ok(()),
+    }
+}
+
+/// Read feed URLs for `blog feed add --file`, one per line, from `path` or
+/// from stdin if it is `-`. Blank lines and `#` comments are skipped.
+pub(crate) fn read_url_list(path: &Path) -> anyhow::Result<Vec<String>> {
+    let content = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?
     };
-    is_feed_content(&bytes)
+    Ok(parse_url_list(&content))
+}
+
+fn parse_url_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
 }
 
-fn normalize_feed_url(url: &str) -> String {
+/// Resolve each of `urls` like `blog feed add` does, carrying on past the ones
+/// that fail, add the rest in one commit and report what was and wasn't added.
+pub(crate) fn cmd_add_bulk(
+    store: &mut BlogData,
+    urls: &[String],
+    http: &HttpOptions,
+    select: bool,
+) -> anyhow::Result<()> {
+    ensure!(!urls.is_empty(), "no feed URLs given");
+    let links = LinkCleaner::from_config(store)?;
+    let blocklist = Blocklist::from_config(store)?;
+    let mut added = Vec::new();
+    let mut failed = Vec::new();
+    for url in urls {
+        let resolved = ensure_not_blocked(&blocklist, url)
+            .and_then(|()| resolve_feed_url(url, http, &links, select))
+            .and_then(|resolved| {
+                for feed in &resolved {
+                    ensure_not_blocked(&blocklist, &feed.url)?;
+                    ensure_not_subscribed(store.feeds(), feed)?;
+                    if let Some(earlier) = added.iter().find(|a: &&ResolvedFeed| {
+                        subscription_key(&a.url) == subscription_key(&feed.url)
+                    }) {
+                        bail!("listed twice, also as {}", earlier.url);
+                    }
+                }
+                Ok(resolved)
+            });
+        match resolved {
+            Ok(resolved) => added.extend(resolved),
+            Err(e) => {
+                eprintln!("Failed to add {url}: {e}");
+                failed.push((url, e));
+            }
+        }
+    }
+
+    let count = added.len();
+    let unfetched = added.iter().any(|feed| feed.fetched.is_none());
+    if count > 0 {
+        let ingest_filter = get_config_value(store, "ingest_filter");
+        transact(store, &format!("add {count} feeds"), |tx| {
+            for feed in added {
+                add_resolved(tx, feed, ingest_filter.as_deref(), &blocklist)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    let noun = |n: usize| if n == 1 { "feed" } else { "feeds" };
+    eprintln!("Added {count} {}.", noun(count));
+    if unfetched {
+        eprintln!("Run `blog sync` to fetch posts.");
+    }
+    if !failed.is_empty() {
+        eprintln!("Failed to add {} {}:", failed.len(), noun(failed.len()));
+        for (url, e) in &failed {
+            eprintln!("  {url}: {e}");
+        }
+        bail!("could not add {} of {} feeds", failed.len(), urls.len());
+    }
+    Ok(())
+}
+
+pub(crate) fn normalize_feed_url(url: &str) -> String {
     url_normalize::normalize_url(url, &url_normalize::Options::default())
         .unwrap_or_else(|_| url.to_string())
 }
 
+pub(crate) fn new_source(url: &str) -> FeedSource {
+    FeedSource {
+        url: normalize_feed_url(url),
+        ..Default::default()
+    }
+}
+
 pub(crate) fn cmd_add(tx: &mut Transaction, url: &str) -> anyhow::Result<()> {
-    let url = normalize_feed_url(url);
-    tx.feeds.upsert(FeedSource {
-        url,
-        title: String::new(),
-        site_url: String::new(),
-        description: String::new(),
-        is_fetched: false,
-    });
+    tx.feeds.upsert(new_source(url));
     Ok(())
 }
+
+/// Subscribe to `feed`, storing its title, description and posts right away
+/// if they were downloaded while resolving it.
+pub(crate) fn add_resolved(
+    tx: &mut Transaction,
+    feed: ResolvedFeed,
+    ingest_filter: Option<&str>,
+    blocklist: &Blocklist,
+) -> anyhow::Result<()> {
+    ensure_not_subscribed(tx.feeds, &feed)?;
+    let source = new_source(&feed.url);
+    match feed.fetched {
+        Some(fetched) => {
+            apply_fetched(
+                tx,
+                vec![(source, Ok(fetched))],
+                &ProgressBar::hidden(),
+                ingest_filter,
+                blocklist,
+            )?;
+        }
+        None => tx.feeds.upsert(source),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::single("2", vec![2])]
+    #[case::spaces("3 1", vec![1, 3])]
+    #[case::commas("1,3", vec![1, 3])]
+    #[case::range("2-4", vec![2, 3, 4])]
+    #[case::overlap("1-2 2\n", vec![1, 2])]
+    #[case::all("all\n", vec![1, 2, 3, 4])]
+    fn test_parse_selection(#[case] answer: &str, #[case] expected: Vec<usize>) {
+        assert_eq!(parse_selection(answer, 4).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::scheme("http://a.example/feed", "https://a.example/feed", true)]
+    #[case::trailing_slash("https://a.example/feed/", "https://a.example/feed", true)]
+    #[case::host_case("https://A.Example/feed", "https://a.example/feed", true)]
+    #[case::default_port("https://a.example:443/feed", "https://a.example/feed", true)]
+    #[case::path_case("https://a.example/Feed", "https://a.example/feed", false)]
+    #[case::query("https://a.example/feed?tag=rust", "https://a.example/feed", false)]
+    #[case::subdomain("https://www.a.example/feed", "https://a.example/feed", false)]
+    fn test_subscription_key(#[case] a: &str, #[case] b: &str, #[case] same: bool) {
+        assert_eq!(subscription_key(a) == subscription_key(b), same);
+    }
+
+    #[test]
+    fn test_parse_url_list() {
+        let content = "# my blogroll\nhttps://a.example\n\n  https://b.example  \n";
+        assert_eq!(
+            parse_url_list(content),
+            vec!["https://a.example", "https://b.example"]
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::zero("0")]
+    #[case::too_big("5")]
+    #[case::word("first")]
+    #[case::open_range("2-")]
+    fn test_parse_selection_invalid(#[case] answer: &str) {
+        assert!(parse_selection(answer, 4).is_err());
+    }
+}
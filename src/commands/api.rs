@@ -0,0 +1,264 @@
+//! The JSON API of `blog serve --api ADDR`, for editors, bots and phone
+//! shortcuts to use the store without running `blog`. Every request needs an
+//! `Authorization: Bearer <token>` header with the token from [`TOKEN_ENV`],
+//! which is kept out of the store like other secrets.
+//!
+//! - `GET /feeds`: the subscribed feeds
+//! - `GET /posts?q=QUERY`: the posts matching a query, as `blog show` takes it
+//! - `POST /posts/<shorthand>/read`: mark a post read
+//! - `POST /feeds` with `{"url": "..."}`: subscribe to a feed, or to the one
+//!   a page links to
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, ensure};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::commands::add::{add_resolved, ensure_not_blocked, find_feeds};
+use crate::commands::open::mark_read_batch;
+use crate::commands::serve::query_from_text;
+use crate::commands::show::read_ids;
+use crate::data::index::feed_index;
+use crate::data::schema::FeedSource;
+use crate::data::{BlogData, get_config_value, transact};
+use crate::feed::blocklist::Blocklist;
+use crate::feed::links::LinkCleaner;
+use crate::query::parse_query;
+use crate::query::resolve::resolve_posts;
+use crate::utils::http::HttpOptions;
+use crate::utils::server::{Request, Response};
+
+/// Environment variable holding the token API clients must send.
+pub(crate) const TOKEN_ENV: &str = "BLOG_API_TOKEN";
+
+/// Largest request body accepted; adding a feed takes a few hundred bytes.
+pub(crate) const MAX_BODY: usize = 64 * 1024;
+
+#[derive(Serialize)]
+struct ApiFeed<'a> {
+    shorthand: &'a str,
+    #[serde(flatten)]
+    feed: &'a FeedSource,
+}
+
+#[derive(Serialize)]
+struct ApiPost<'a> {
+    id: &'a str,
+    shorthand: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<&'a DateTime<Utc>>,
+    /// URL of the post's feed
+    feed: &'a str,
+    link: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    author: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    summary: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    content: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    images: &'a [String],
+    read: bool,
+}
+
+#[derive(Deserialize)]
+struct AddFeed {
+    url: String,
+}
+
+/// The API token from [`TOKEN_ENV`].
+pub(crate) fn token() -> anyhow::Result<String> {
+    let token = std::env::var(TOKEN_ENV).unwrap_or_default();
+    ensure!(
+        !token.is_empty(),
+        "Set {TOKEN_ENV} to the token API clients must send"
+    );
+    Ok(token)
+}
+
+/// Whether the `Authorization` header `header` carries `token`. The
+/// comparison takes as long wherever the first wrong byte is.
+fn authorized(header: Option<&str>, token: &str) -> bool {
+    let Some(given) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn json_response(status: u16, value: &impl Serialize) -> Response {
+    Response {
+        status,
+        content_type: "application/json",
+        body: serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+fn error(status: u16, message: &str) -> Response {
+    json_response(status, &json!({ "error": message }))
+}
+
+fn feeds(store: &BlogData) -> Response {
+    let fi = feed_index(store.feeds());
+    let feeds: Vec<ApiFeed> = fi
+        .entries
+        .iter()
+        .map(|e| ApiFeed {
+            shorthand: &e.shorthand,
+            feed: &e.feed,
+        })
+        .collect();
+    json_response(200, &feeds)
+}
+
+fn posts(store: &BlogData, query_text: &str) -> anyhow::Result<Response> {
+    let query = query_from_text(store, query_text)?;
+    let resolved = resolve_posts(store, &query)?;
+    let feed_urls: HashMap<&str, &str> = store
+        .feeds()
+        .iter()
+        .map(|(id, feed)| (id, feed.url.as_str()))
+        .collect();
+    let read_ids = read_ids(store);
+    let posts: Vec<ApiPost> = resolved
+        .items
+        .iter()
+        .map(|(id, item)| ApiPost {
+            id,
+            shorthand: resolved
+                .shorthands
+                .get(&item.raw_id)
+                .map_or("", String::as_str),
+            title: &item.title,
+            date: item.date.as_ref(),
+            feed: feed_urls
+                .get(item.feed.as_str())
+                .copied()
+                .unwrap_or_default(),
+            link: &item.link,
+            author: &item.author,
+            summary: &item.summary,
+            content: &item.content,
+            images: &item.images,
+            read: read_ids.contains(&item.raw_id),
+        })
+        .collect();
+    Ok(json_response(200, &posts))
+}
+
+fn mark_read(store: &mut BlogData, shorthand: &str) -> anyhow::Result<Response> {
+    if shorthand.is_empty() || !shorthand.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Ok(error(404, "Not Found"));
+    }
+    let query = parse_query(&[shorthand.to_string()])?;
+    let resolved = match resolve_posts(store, &query) {
+        Ok(resolved) if resolved.items.len() == 1 => resolved,
+        _ => return Ok(error(404, &format!("No post {shorthand}"))),
+    };
+    mark_read_batch(store, &resolved.items)?;
+    Ok(json_response(
+        200,
+        &json!({ "shorthand": shorthand, "read": true }),
+    ))
+}
+
+/// Subscribe to the feed at the URL in `body`, fetching it right away like
+/// `blog feed add`. A page linking to several feeds is refused with their
+/// URLs, to pick one from.
+fn add_feed(store: &mut BlogData, body: &[u8]) -> anyhow::Result<Response> {
+    let AddFeed { url } =
+        serde_json::from_slice(body).context("Expected a JSON object with a \"url\"")?;
+    let blocklist = Blocklist::from_config(store)?;
+    ensure_not_blocked(&blocklist, &url)?;
+    let http = HttpOptions::from_config(store)?;
+    let links = LinkCleaner::from_config(store)?;
+    let mut found = find_feeds(&url, &http, &links)?;
+    let Some(feed) = found.pop() else {
+        return Ok(error(400, &format!("No feeds found at {url}")));
+    };
+    if !found.is_empty() {
+        let urls: Vec<&str> = found
+            .iter()
+            .chain([&feed])
+            .map(|f| f.url.as_str())
+            .collect();
+        return Ok(json_response(
+            400,
+            &json!({ "error": format!("Multiple feeds found at {url}"), "feeds": urls }),
+        ));
+    }
+    ensure_not_blocked(&blocklist, &feed.url)?;
+    let feed_url = feed.url.clone();
+    let ingest_filter = get_config_value(store, "ingest_filter");
+    transact(store, &format!("add feed: {feed_url}"), |tx| {
+        add_resolved(tx, feed, ingest_filter.as_deref(), &blocklist)
+    })?;
+    Ok(json_response(201, &json!({ "url": feed_url })))
+}
+
+fn handle(store: &mut BlogData, request: &Request) -> anyhow::Result<Response> {
+    let method = request.method.as_str();
+    let path = request.path.trim_end_matches('/');
+    let response = match (method, path) {
+        ("GET", "/feeds") => feeds(store),
+        ("POST", "/feeds") => add_feed(store, &request.body)?,
+        ("GET", "/posts") => posts(store, request.query.get("q").map_or("", String::as_str))?,
+        (_, "/feeds" | "/posts") => error(405, "Method Not Allowed"),
+        _ => match path
+            .strip_prefix("/posts/")
+            .and_then(|rest| rest.strip_suffix("/read"))
+        {
+            Some(shorthand) if method == "POST" => mark_read(store, shorthand)?,
+            Some(_) => error(405, "Method Not Allowed"),
+            None => error(404, "Not Found"),
+        },
+    };
+    Ok(response)
+}
+
+/// Answer `request` if it carries `token`, reading the store at
+/// `store_path` again so that answers follow changes made meanwhile.
+pub(crate) fn respond(store_path: &Path, token: &str, request: &Request) -> Response {
+    let header = request.headers.get("authorization").map(String::as_str);
+    if !authorized(header, token) {
+        return error(401, "Unauthorized");
+    }
+    let mut store = match BlogData::open(store_path) {
+        Ok(store) => store,
+        Err(e) => return error(500, &format!("{e:#}")),
+    };
+    handle(&mut store, request).unwrap_or_else(|e| error(400, &format!("{e:#}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Some("Bearer s3cret"), true)]
+    #[case(Some("Bearer s3cre"), false)]
+    #[case(Some("Bearer s3cret2"), false)]
+    #[case(Some("s3cret"), false)]
+    #[case(Some("Basic s3cret"), false)]
+    #[case(None, false)]
+    fn test_authorized(#[case] header: Option<&str>, #[case] expected: bool) {
+        assert_eq!(authorized(header, "s3cret"), expected);
+    }
+
+    #[test]
+    fn test_error_is_json() {
+        let response = error(404, "No post \"x\"");
+        assert_eq!(response.status, 404);
+        assert_eq!(response.content_type, "application/json");
+        assert_eq!(response.body, r#"{"error":"No post \"x\""}"#);
+    }
+}
@@ -1,11 +1,12 @@
 use std::path::Path;
+use std::process::{Command, Stdio};
 
-use anyhow::Context;
+use anyhow::{Context, bail};
 
-use crate::data::BlogData;
+use crate::data::{BlogData, apply_author};
 use crate::utils::progress::spinner;
 
-fn expand_url(url: &str) -> String {
+pub(crate) fn expand_url(url: &str) -> String {
     let is_full_url = url.contains(':'); // https://, git@host:, file://
     let is_relative_path = url.starts_with('.'); // ./repo, ../dir/repo
 
@@ -30,7 +31,36 @@ fn has_existing_store(store_dir: &Path) -> bool {
         .unwrap_or(false)
 }
 
-pub(crate) fn cmd_clone(store_dir: &Path, url: &str) -> anyhow::Result<()> {
+/// Whether the store was cloned with limited history (`blog clone` without
+/// `--full`).
+pub(crate) fn is_shallow(store_dir: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(store_dir)
+        .args(["rev-parse", "--is-shallow-repository"])
+        .output()
+        .is_ok_and(|o| o.status.success() && o.stdout.starts_with(b"true"))
+}
+
+/// Fetch the full history of a shallow clone from origin.
+pub(crate) fn unshallow(store_dir: &Path) -> anyhow::Result<()> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(store_dir)
+        .args(["fetch", "--unshallow", "origin"])
+        .stdin(Stdio::inherit())
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git fetch --unshallow failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_clone(store_dir: &Path, url: &str, full: bool) -> anyhow::Result<()> {
     let expanded = expand_url(url);
 
     if has_existing_store(store_dir) {
@@ -53,10 +83,13 @@ pub(crate) fn cmd_clone(store_dir: &Path, url: &str) -> anyhow::Result<()> {
         // Fresh clone
         let sp = spinner(&format!("Cloning into {}...", store_dir.display()));
         synctato::clone_store(store_dir, &expanded).context("failed to clone store")?;
+        if full {
+            unshallow(store_dir)?;
+        }
         sp.finish_with_message(format!("Cloned into {}.", store_dir.display()));
     }
 
-    Ok(())
+    apply_author(&BlogData::open(store_dir)?)
 }
 
 #[cfg(test)]
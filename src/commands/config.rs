@@ -1,16 +1,17 @@
-use crate::data::BlogData;
 use crate::data::schema::MetaEntry;
+use crate::data::{BlogData, apply_author, clear_author, transact};
 
 const CONFIG_PREFIX: &str = "config.";
 
 pub(crate) fn cmd_config_set(store: &mut BlogData, key: &str, value: &str) -> anyhow::Result<()> {
-    store.transact(&format!("config set {key}"), |tx| {
+    transact(store, &format!("config set {key}"), |tx| {
         tx.meta.upsert(MetaEntry {
             key: format!("{CONFIG_PREFIX}{key}"),
             value: value.to_string(),
         });
         Ok(())
-    })
+    })?;
+    apply_author(store)
 }
 
 pub(crate) fn cmd_config_get(store: &BlogData, key: &str) -> anyhow::Result<()> {
@@ -25,8 +26,9 @@ pub(crate) fn cmd_config_unset(store: &mut BlogData, key: &str) -> anyhow::Resul
     let full_key = format!("{CONFIG_PREFIX}{key}");
     let exists = store.meta().iter().any(|(_, e)| e.key == full_key);
     anyhow::ensure!(exists, "No value set for '{key}'");
-    store.transact(&format!("config unset {key}"), |tx| {
+    transact(store, &format!("config unset {key}"), |tx| {
         tx.meta.delete(&full_key);
         Ok(())
-    })
+    })?;
+    clear_author(store, key)
 }
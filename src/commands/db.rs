@@ -0,0 +1,175 @@
+//! Whole-store backups: `blog db export` writes every table into one JSON
+//! document and `blog db import` merges such a document back into a store,
+//! e.g. to move between machines without the git remote.
+
+use std::path::Path;
+
+use anyhow::{Context, ensure};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::data::schema::{
+    FeedItem, FeedSource, LaterEntry, MetaEntry, ReadMark, SavedView, SnoozeEntry,
+};
+use crate::data::{BlogData, SCHEMA_VERSION, transact};
+
+use super::export::write_output;
+
+/// Identifies a backup document, so other JSON isn't imported by mistake.
+const BACKUP_FORMAT: &str = "blogtato-backup";
+
+/// Version of the backup envelope itself. Bump it when the layout below
+/// changes in a way older versions can't read.
+const BACKUP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Backup {
+    format: String,
+    version: u32,
+    /// Schema version of the store the rows came from
+    schema_version: u32,
+    exported_at: DateTime<Utc>,
+    #[serde(default)]
+    feeds: Vec<FeedSource>,
+    #[serde(default)]
+    posts: Vec<FeedItem>,
+    #[serde(default)]
+    reads: Vec<ReadMark>,
+    #[serde(default)]
+    later: Vec<LaterEntry>,
+    #[serde(default)]
+    views: Vec<SavedView>,
+    #[serde(default)]
+    snoozes: Vec<SnoozeEntry>,
+    #[serde(default)]
+    meta: Vec<MetaEntry>,
+}
+
+impl Backup {
+    fn rows(&self) -> usize {
+        self.feeds.len()
+            + self.posts.len()
+            + self.reads.len()
+            + self.later.len()
+            + self.views.len()
+            + self.snoozes.len()
+            + self.meta.len()
+    }
+}
+
+pub(crate) fn cmd_db_export(store: &BlogData, output: Option<&Path>) -> anyhow::Result<()> {
+    let backup = Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        schema_version: SCHEMA_VERSION,
+        exported_at: Utc::now(),
+        feeds: store.feeds().iter().map(|(_, r)| r.clone()).collect(),
+        posts: store.posts().iter().map(|(_, r)| r.clone()).collect(),
+        reads: store.reads().iter().map(|(_, r)| r.clone()).collect(),
+        later: store.later().iter().map(|(_, r)| r.clone()).collect(),
+        views: store.views().iter().map(|(_, r)| r.clone()).collect(),
+        snoozes: store.snoozes().iter().map(|(_, r)| r.clone()).collect(),
+        meta: store.meta().iter().map(|(_, r)| r.clone()).collect(),
+    };
+    write_output(output, |out| {
+        serde_json::to_writer_pretty(&mut *out, &backup)?;
+        writeln!(out)?;
+        Ok(())
+    })?;
+    if let Some(path) = output {
+        eprintln!("Exported {} rows to {}", backup.rows(), path.display());
+    }
+    Ok(())
+}
+
+fn parse_backup(content: &str) -> anyhow::Result<Backup> {
+    let backup: Backup = serde_json::from_str(content).context("Not a blogtato backup")?;
+    ensure!(
+        backup.format == BACKUP_FORMAT,
+        "Not a blogtato backup (format {:?})",
+        backup.format
+    );
+    ensure!(
+        backup.version <= BACKUP_VERSION && backup.schema_version <= SCHEMA_VERSION,
+        "This backup was written by a newer version of blogtato (backup v{}, schema v{}). \
+         Please update blogtato.",
+        backup.version,
+        backup.schema_version
+    );
+    Ok(backup)
+}
+
+/// Merge the backup at `path` (`-` for stdin) into the store. Rows with the
+/// same key as existing ones replace them; nothing is deleted.
+pub(crate) fn cmd_db_import(store: &mut BlogData, path: &Path) -> anyhow::Result<()> {
+    let content = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?
+    };
+    let backup = parse_backup(&content)?;
+    let count = backup.rows();
+    transact(store, &format!("import backup: {count} rows"), |tx| {
+        for row in &backup.feeds {
+            tx.feeds.upsert(row.clone());
+        }
+        for row in &backup.posts {
+            tx.posts.upsert(row.clone());
+        }
+        for row in &backup.reads {
+            tx.reads.upsert(row.clone());
+        }
+        for row in &backup.later {
+            tx.later.upsert(row.clone());
+        }
+        for row in &backup.views {
+            tx.views.upsert(row.clone());
+        }
+        for row in &backup.snoozes {
+            tx.snoozes.upsert(row.clone());
+        }
+        // The store keeps its own schema version
+        for row in backup.meta.iter().filter(|m| m.key != "schema_version") {
+            tx.meta.upsert(row.clone());
+        }
+        Ok(())
+    })?;
+    eprintln!(
+        "Imported {} feeds, {} posts and {} other rows.",
+        backup.feeds.len(),
+        backup.posts.len(),
+        count - backup.feeds.len() - backup.posts.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn envelope(format: &str, version: u32, schema_version: u32) -> String {
+        format!(
+            r#"{{"format": "{format}", "version": {version}, "schema_version": {schema_version},
+                "exported_at": "2024-01-01T00:00:00Z", "views": [{{"name": "v", "query": "~x"}}]}}"#
+        )
+    }
+
+    #[test]
+    fn test_parse_backup_missing_tables_are_empty() {
+        let backup = parse_backup(&envelope(BACKUP_FORMAT, 1, 1)).unwrap();
+        assert_eq!(backup.views.len(), 1);
+        assert!(backup.feeds.is_empty());
+        assert_eq!(backup.rows(), 1);
+    }
+
+    #[rstest]
+    #[case::other_format(&envelope("something-else", 1, 1))]
+    #[case::newer_version(&envelope(BACKUP_FORMAT, BACKUP_VERSION + 1, 1))]
+    #[case::newer_schema(&envelope(BACKUP_FORMAT, 1, SCHEMA_VERSION + 1))]
+    #[case::not_an_envelope(r#"[{"url": "https://a.example"}]"#)]
+    fn test_parse_backup_rejects(#[case] content: &str) {
+        assert!(parse_backup(content).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! `blog diff`: the rows that differ between the store and its remote, or
+//! between any two revisions of it, e.g. to check what another machine
+//! pushed before syncing.
+
+use std::path::Path;
+
+use anyhow::{bail, ensure};
+
+use crate::data::changes::{
+    TABLES, TableChanges, format_counts, git_stdout, rev_exists, table_changes,
+};
+
+/// The remote branch the store syncs with.
+const UPSTREAM: &str = "@{upstream}";
+
+/// The value of `field` in a stored row.
+fn row_label(line: &str, field: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|row| row.get(field)?.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// The rows of a table that changed, one per line and marked `+`, `-` or
+/// `~`, sorted by `field`.
+fn format_rows(changes: &TableChanges, field: &str) -> String {
+    let mut rows: Vec<(String, char)> = Vec::new();
+    for (sign, lines) in [
+        ('+', &changes.added),
+        ('-', &changes.removed),
+        ('~', &changes.changed),
+    ] {
+        rows.extend(lines.iter().map(|line| (row_label(line, field), sign)));
+    }
+    rows.sort();
+    rows.iter()
+        .map(|(label, sign)| format!("  {sign} {label}\n"))
+        .collect()
+}
+
+/// Print how the store's tables differ from `from` to `to`. Without
+/// revisions HEAD is compared with the remote branch, after fetching it if
+/// `fetch` is set; with only `from`, it is compared with HEAD.
+pub(crate) fn cmd_diff(
+    store: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+    fetch: bool,
+) -> anyhow::Result<()> {
+    ensure!(
+        store.join(".git").exists(),
+        "The store at {} isn't a git repository",
+        store.display()
+    );
+    if fetch {
+        git_stdout(store, &["fetch", "--quiet"])?;
+    }
+    let (from, to) = match (from, to) {
+        (None, _) => {
+            if !rev_exists(store, UPSTREAM)? {
+                bail!(
+                    "The store has no remote branch to compare with; name two revisions instead, e.g. `blog diff HEAD~1 HEAD`"
+                );
+            }
+            ("HEAD", UPSTREAM)
+        }
+        (Some(from), to) => (from, to.unwrap_or("HEAD")),
+    };
+    for rev in [from, to] {
+        ensure!(rev_exists(store, rev)?, "Unknown revision: {rev}");
+    }
+
+    let changes = table_changes(store, Some(from), to)?;
+    if changes.is_empty() {
+        eprintln!("No differences.");
+        return Ok(());
+    }
+    println!("{}", format_counts(&changes));
+    for (table, _, _, field) in TABLES {
+        if let (Some(c), Some(field)) = (changes.get(*table), field) {
+            print!("\n{table}\n{}", format_rows(c, field));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rows_sorted_by_label() {
+        let changes = TableChanges {
+            added: vec![r#"{"id":"1","url":"https://b.example/feed"}"#.to_string()],
+            removed: vec![r#"{"id":"2","url":"https://c.example/feed"}"#.to_string()],
+            changed: vec![r#"{"id":"3","url":"https://a.example/feed"}"#.to_string()],
+        };
+        assert_eq!(
+            format_rows(&changes, "url"),
+            "  ~ https://a.example/feed\n  + https://b.example/feed\n  - https://c.example/feed\n"
+        );
+    }
+}
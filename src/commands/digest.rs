@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::{Context, ensure};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::data::schema::FeedItem;
+use crate::data::{BlogData, get_config_value};
+use crate::query::resolve::resolve_posts;
+use crate::query::{Query, QueryDate};
+use crate::utils::date::format_day;
+use crate::utils::html::escape;
+
+/// Environment variable holding the SMTP password.
+const SMTP_PASSWORD_ENV: &str = "BLOG_SMTP_PASSWORD";
+
+/// How `blog digest` delivers the digest.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum DigestOutput {
+    /// Plain text on stdout
+    Text,
+    /// A MIME email on stdout, e.g. for `sendmail -t`
+    Email,
+    /// Submitted to the `smtp_url` server
+    Send,
+}
+
+pub(crate) struct DigestOptions {
+    /// Include posts published since this date, unless the query has a range
+    pub since: QueryDate,
+    pub output: DigestOutput,
+    /// Recipient; overrides `digest_to`
+    pub to: Option<String>,
+}
+
+/// Posts grouped by feed title, newest first within each feed.
+type Groups<'a> = BTreeMap<String, Vec<&'a FeedItem>>;
+
+fn render_text(groups: &Groups, heading: &str) -> String {
+    let mut out = format!("{heading}\n");
+    for (feed, posts) in groups {
+        out.push_str(&format!("\n{feed}\n"));
+        for post in posts {
+            out.push_str(&format!("  {}\n", post.title));
+            if !post.link.is_empty() {
+                out.push_str(&format!("  {}\n", post.link));
+            }
+        }
+    }
+    out
+}
+
+fn render_html(groups: &Groups, heading: &str) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n",
+        escape(heading)
+    );
+    for (feed, posts) in groups {
+        out.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape(feed)));
+        for post in posts {
+            if post.link.is_empty() {
+                out.push_str(&format!("<li>{}</li>\n", escape(&post.title)));
+            } else {
+                out.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    escape(&post.link),
+                    escape(&post.title)
+                ));
+            }
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+fn parse_mailbox(value: &str, what: &str) -> anyhow::Result<Mailbox> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid {what} address: {value:?}"))
+}
+
+fn build_email(
+    store: &BlogData,
+    opts: &DigestOptions,
+    subject: &str,
+    text: String,
+    html: String,
+) -> anyhow::Result<Message> {
+    let to = opts
+        .to
+        .clone()
+        .or_else(|| get_config_value(store, "digest_to"))
+        .context("Set a recipient with --to or `blog config set digest_to <address>`")?;
+    let from = get_config_value(store, "digest_from").unwrap_or_else(|| to.clone());
+    Message::builder()
+        .from(parse_mailbox(&from, "digest_from")?)
+        .to(parse_mailbox(&to, "recipient")?)
+        .subject(subject)
+        .multipart(MultiPart::alternative_plain_html(text, html))
+        .context("Failed to build the digest email")
+}
+
+/// Submit `email` to the server in the `smtp_url` config key, e.g.
+/// `smtps://me@mail.example` or `smtp://me@mail.example:587?tls=required`.
+fn send_email(store: &BlogData, email: &Message) -> anyhow::Result<()> {
+    let smtp_url = get_config_value(store, "smtp_url")
+        .context("Set the mail server with `blog config set smtp_url smtps://user@host`")?;
+    let mut url = url::Url::parse(&smtp_url)
+        .map_err(|e| anyhow::anyhow!("Invalid value for config smtp_url: {smtp_url:?} ({e})"))?;
+    if let Ok(password) = std::env::var(SMTP_PASSWORD_ENV) {
+        url.set_password(Some(&password))
+            .map_err(|_| anyhow::anyhow!("smtp_url {smtp_url:?} can't have a password"))?;
+    }
+    let transport = SmtpTransport::from_url(url.as_str())
+        .with_context(|| format!("Invalid value for config smtp_url: {smtp_url:?}"))?
+        .build();
+    transport.send(email).with_context(|| {
+        format!(
+            "Failed to send the digest via {}",
+            url.host_str().unwrap_or("")
+        )
+    })?;
+    Ok(())
+}
+
+pub(crate) fn cmd_digest(
+    store: &BlogData,
+    query: &Query,
+    opts: &DigestOptions,
+) -> anyhow::Result<()> {
+    let mut query = query.clone();
+    if query.date_filter.since.is_none() && query.date_filter.until.is_none() {
+        query.date_filter.since = Some(opts.since.clone());
+    }
+    let resolved = resolve_posts(store, &query)?;
+    let since = query
+        .date_filter
+        .since
+        .as_ref()
+        .map(|d| format_day(d.resolved));
+    ensure!(
+        !resolved.items.is_empty(),
+        "No new posts{}",
+        since
+            .as_deref()
+            .map(|s| format!(" since {s}"))
+            .unwrap_or_default()
+    );
+
+    let mut groups: Groups = BTreeMap::new();
+    for (_, item) in &resolved.items {
+        let feed = resolved
+            .feed_labels
+            .get(&item.feed)
+            .cloned()
+            .unwrap_or_else(|| item.feed.clone());
+        groups.entry(feed).or_default().push(item);
+    }
+    let count = resolved.items.len();
+    let heading = match (count, since) {
+        (1, Some(s)) => format!("1 new post since {s}"),
+        (n, Some(s)) => format!("{n} new posts since {s}"),
+        (1, None) => "1 new post".to_string(),
+        (n, None) => format!("{n} new posts"),
+    };
+
+    let text = render_text(&groups, &heading);
+    if opts.output == DigestOutput::Text {
+        print!("{text}");
+        return Ok(());
+    }
+    let subject = format!("blogtato digest: {heading}");
+    let email = build_email(store, opts, &subject, text, render_html(&groups, &heading))?;
+    match opts.output {
+        DigestOutput::Send => {
+            send_email(store, &email)?;
+            eprintln!("Sent digest of {count} posts.");
+        }
+        _ => std::io::stdout().lock().write_all(&email.formatted())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_text() {
+        let a = FeedItem {
+            title: "Hello".to_string(),
+            link: "https://a.example/hello".to_string(),
+            ..Default::default()
+        };
+        let b = FeedItem {
+            title: "No link".to_string(),
+            ..Default::default()
+        };
+        let groups: Groups = [("Alpha".to_string(), vec![&a, &b])].into_iter().collect();
+
+        assert_eq!(
+            render_text(&groups, "2 new posts"),
+            "2 new posts\n\nAlpha\n  Hello\n  https://a.example/hello\n  No link\n"
+        );
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let a = FeedItem {
+            title: "Q&A <live>".to_string(),
+            link: "https://a.example/?a=1&b=2".to_string(),
+            ..Default::default()
+        };
+        let groups: Groups = [("Tom & Jerry".to_string(), vec![&a])]
+            .into_iter()
+            .collect();
+
+        let html = render_html(&groups, "1 new post");
+
+        assert!(html.contains("<h2>Tom &amp; Jerry</h2>"));
+        assert!(html.contains(
+            "<li><a href=\"https://a.example/?a=1&amp;b=2\">Q&amp;A &lt;live&gt;</a></li>"
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+//! `blog discover`: list the feeds `blog feed add` would find for a URL,
+//! without subscribing to any, to look at a site first.
+
+use anyhow::ensure;
+use unicode_width::UnicodeWidthStr;
+
+use crate::data::BlogData;
+use crate::display::pad_str;
+use crate::feed::links::LinkCleaner;
+use crate::feed::pull::FetchOptions;
+use crate::utils::http::{HttpOptions, http_client};
+
+use super::add::{ResolvedFeed, ensure_not_subscribed, find_feeds};
+
+/// What is known about a candidate feed.
+#[derive(Debug, PartialEq)]
+struct Candidate {
+    url: String,
+    title: String,
+    format: &'static str,
+    posts: Option<usize>,
+    subscribed: bool,
+}
+
+/// One line per candidate: format, post count, URL and title in aligned
+/// columns, noting those already subscribed to.
+fn format_candidates(candidates: &[Candidate]) -> String {
+    let url_cols = candidates.iter().map(|c| c.url.width()).max().unwrap_or(0);
+    let mut out = String::new();
+    for c in candidates {
+        let format = if c.format.is_empty() { "-" } else { c.format };
+        let posts = match c.posts {
+            Some(1) => "1 post".to_string(),
+            Some(n) => format!("{n} posts"),
+            None => "-".to_string(),
+        };
+        let subscribed = if c.subscribed { " [subscribed]" } else { "" };
+        let line = format!(
+            "{:<9}  {posts:>9}  {}  {}{subscribed}",
+            format,
+            pad_str(&c.url, url_cols),
+            c.title
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// List the feeds found at `url`, downloading those only known by their
+/// URL, e.g. from a YouTube channel page, to tell what they are.
+pub(crate) fn cmd_discover(store: &BlogData, url: &str, http: &HttpOptions) -> anyhow::Result<()> {
+    let links = LinkCleaner::from_config(store)?;
+    let feeds = find_feeds(url, http, &links)?;
+    ensure!(!feeds.is_empty(), "No feeds found at {url}");
+
+    let client = http_client(http);
+    let opts = FetchOptions {
+        http: http.clone(),
+        links,
+        ..FetchOptions::default()
+    };
+    let candidates: Vec<Candidate> = feeds
+        .into_iter()
+        .map(|feed| {
+            let subscribed = ensure_not_subscribed(store.feeds(), &feed).is_err();
+            let ResolvedFeed { url, fetched, .. } = feed;
+            let fetched = fetched.or_else(|| crate::feed::fetch(&client, &url, &opts).ok());
+            Candidate {
+                title: fetched
+                    .as_ref()
+                    .map(|f| f.meta.title.clone())
+                    .unwrap_or_default(),
+                format: fetched.as_ref().map_or("", |f| f.meta.format),
+                posts: fetched.as_ref().map(|f| f.items.len()),
+                url,
+                subscribed,
+            }
+        })
+        .collect();
+    print!("{}", format_candidates(&candidates));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(url: &str, format: &'static str, posts: Option<usize>) -> Candidate {
+        Candidate {
+            url: url.to_string(),
+            title: "Blog".to_string(),
+            format,
+            posts,
+            subscribed: false,
+        }
+    }
+
+    #[test]
+    fn test_format_candidates_aligns_columns() {
+        let candidates = vec![
+            candidate("https://a.example/feed.xml", "RSS", Some(12)),
+            Candidate {
+                subscribed: true,
+                ..candidate("https://a.example/atom", "Atom", Some(1))
+            },
+            Candidate {
+                title: String::new(),
+                ..candidate("https://a.example/x", "", None)
+            },
+        ];
+
+        assert_eq!(
+            format_candidates(&candidates),
+            "RSS         12 posts  https://a.example/feed.xml  Blog\n\
+             Atom          1 post  https://a.example/atom      Blog [subscribed]\n\
+             -                  -  https://a.example/x\n"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! `blog discuss`: find where a post was submitted to Hacker News and Lobsters,
+//! to read what people said about it.
+
+use anyhow::{Context, ensure};
+use serde::Deserialize;
+use url::Url;
+
+use crate::data::BlogData;
+use crate::query::Query;
+use crate::query::resolve::resolve_posts;
+
+use super::open::open_url;
+
+const HN_API: &str = "https://hn.algolia.com/api/v1";
+const HN_ITEM_URL: &str = "https://news.ycombinator.com/item?id=";
+const LOBSTERS_URL: &str = "https://lobste.rs";
+
+/// A submission of the post to a link aggregator.
+#[derive(Debug, PartialEq)]
+struct Thread {
+    site: &'static str,
+    title: String,
+    url: String,
+    points: i64,
+    comments: i64,
+}
+
+#[derive(Deserialize)]
+struct HnResults {
+    hits: Vec<HnHit>,
+}
+
+#[derive(Deserialize)]
+struct HnHit {
+    #[serde(rename = "objectID")]
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    points: Option<i64>,
+    #[serde(default)]
+    num_comments: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct LobstersStory {
+    title: String,
+    #[serde(default)]
+    url: String,
+    comments_url: String,
+    score: i64,
+    comment_count: i64,
+}
+
+/// The part of a URL that identifies the page: no scheme, `www.`, fragment
+/// or trailing slash, so `http://www.a.example/x/` and `https://a.example/x`
+/// are the same page.
+fn page_key(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().trim_end_matches('/').to_string();
+    };
+    parsed.set_fragment(None);
+    let host = parsed.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host).to_string();
+    let rest = &parsed[url::Position::BeforePath..];
+    format!("{host}{}", rest.trim_end_matches('/'))
+}
+
+fn hn_threads(agent: &ureq::Agent, api: &str, link: &str) -> anyhow::Result<Vec<Thread>> {
+    let body = agent
+        .get(format!("{api}/search"))
+        .query("query", link)
+        .query("restrictSearchableAttributes", "url")
+        .query("tags", "story")
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    let results: HnResults = serde_json::from_str(&body)?;
+    // The search is by words, so it also finds other pages of the same site
+    let key = page_key(link);
+    Ok(results
+        .hits
+        .into_iter()
+        .filter(|hit| hit.url.as_deref().is_some_and(|url| page_key(url) == key))
+        .map(|hit| Thread {
+            site: "Hacker News",
+            title: hit.title.unwrap_or_default(),
+            url: format!("{HN_ITEM_URL}{}", hit.id),
+            points: hit.points.unwrap_or_default(),
+            comments: hit.num_comments.unwrap_or_default(),
+        })
+        .collect())
+}
+
+fn lobsters_threads(agent: &ureq::Agent, base: &str, link: &str) -> anyhow::Result<Vec<Thread>> {
+    let body = agent
+        .get(format!("{base}/stories/url/all.json"))
+        .query("url", link)
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    let stories: Vec<LobstersStory> = serde_json::from_str(&body)?;
+    let key = page_key(link);
+    Ok(stories
+        .into_iter()
+        .filter(|story| story.url.is_empty() || page_key(&story.url) == key)
+        .map(|story| Thread {
+            site: "Lobsters",
+            title: story.title,
+            url: story.comments_url,
+            points: story.score,
+            comments: story.comment_count,
+        })
+        .collect())
+}
+
+/// Submissions of `link` on every site, most discussed first. A site that
+/// can't be reached is reported and skipped, unless none can be.
+fn find_threads(
+    agent: &ureq::Agent,
+    hn_api: &str,
+    lobsters_url: &str,
+    link: &str,
+) -> anyhow::Result<Vec<Thread>> {
+    let mut threads = Vec::new();
+    let mut failures = Vec::new();
+    for result in [
+        hn_threads(agent, hn_api, link).context("Hacker News"),
+        lobsters_threads(agent, lobsters_url, link).context("Lobsters"),
+    ] {
+        match result {
+            Ok(found) => threads.extend(found),
+            Err(e) => failures.push(e),
+        }
+    }
+    if failures.len() == 2 {
+        return Err(failures.remove(0));
+    }
+    for e in failures {
+        eprintln!("Warning: could not search {e:#}");
+    }
+    threads.sort_by_key(|t| std::cmp::Reverse((t.comments, t.points)));
+    Ok(threads)
+}
+
+fn format_thread(thread: &Thread) -> String {
+    format!(
+        "{:<11}  {:>4} comments  {:>4} points  {}\n{:13}{}",
+        thread.site, thread.comments, thread.points, thread.url, "", thread.title
+    )
+}
+
+/// List the discussions of the single post matching `query`, or open the
+/// most discussed one when `open` is set.
+pub(crate) fn cmd_discuss(store: &BlogData, query: &Query, open: bool) -> anyhow::Result<()> {
+    let resolved = resolve_posts(store, query)?;
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+    ensure!(
+        resolved.items.len() == 1,
+        "Expected exactly 1 post, got {}",
+        resolved.items.len()
+    );
+    let (_, post) = &resolved.items[0];
+    ensure!(!post.link.is_empty(), "Post has no link");
+
+    let http = crate::utils::http::HttpOptions::from_config(store)?;
+    let agent = crate::utils::http::http_client(&http);
+    let threads = find_threads(&agent, HN_API, LOBSTERS_URL, &post.link)?;
+    ensure!(
+        !threads.is_empty(),
+        "No discussions found for {}",
+        post.link
+    );
+
+    if open {
+        return open_url(&threads[0].url);
+    }
+    for thread in &threads {
+        println!("{}", format_thread(thread));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::same("https://a.example/post", "https://a.example/post", true)]
+    #[case::scheme_www_slash("http://www.a.example/post/", "https://a.example/post", true)]
+    #[case::fragment("https://a.example/post#intro", "https://a.example/post", true)]
+    #[case::other_page("https://a.example/other", "https://a.example/post", false)]
+    #[case::query("https://a.example/post?p=2", "https://a.example/post", false)]
+    fn test_page_key(#[case] a: &str, #[case] b: &str, #[case] same: bool) {
+        assert_eq!(page_key(a) == page_key(b), same);
+    }
+
+    #[test]
+    fn test_find_threads_merges_sites_most_discussed_first() {
+        let server = MockServer::start();
+        let link = "https://blog.example/post";
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/hn/search")
+                .query_param("query", link)
+                .query_param("tags", "story");
+            then.status(200).json_body(serde_json::json!({"hits": [
+                {"objectID": "1", "title": "Post", "url": "https://blog.example/post/",
+                 "points": 50, "num_comments": 10},
+                {"objectID": "2", "title": "Other", "url": "https://blog.example/other",
+                 "points": 500, "num_comments": 300}
+            ]}));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/lobsters/stories/url/all.json")
+                .query_param("url", link);
+            then.status(200).json_body(serde_json::json!([
+                {"title": "Post", "url": link, "comments_url": "https://lobste.rs/s/abc/post",
+                 "score": 20, "comment_count": 15}
+            ]));
+        });
+
+        let agent = crate::utils::http::http_client(&Default::default());
+        let threads =
+            find_threads(&agent, &server.url("/hn"), &server.url("/lobsters"), link).unwrap();
+
+        let urls: Vec<&str> = threads.iter().map(|t| t.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://lobste.rs/s/abc/post",
+                "https://news.ycombinator.com/item?id=1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_threads_skips_unreachable_site() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/hn/search");
+            then.status(503);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/lobsters/stories/url/all.json");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+
+        let agent = crate::utils::http::http_client(&Default::default());
+        let threads = find_threads(
+            &agent,
+            &server.url("/hn"),
+            &server.url("/lobsters"),
+            "https://blog.example/post",
+        )
+        .unwrap();
+        assert!(threads.is_empty());
+
+        assert!(
+            find_threads(
+                &agent,
+                &server.url("/hn"),
+                "http://localhost:1",
+                "https://blog.example/post",
+            )
+            .is_err()
+        );
+    }
+}
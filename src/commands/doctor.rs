@@ -0,0 +1,379 @@
+//! `blog db doctor`: check the store's shard files without loading them, so it
+//! still works when a half-resolved git conflict keeps the store from opening.
+//! With `--fix` the readable rows are written back into clean shards. The same
+//! checks guard `blog maintenance` when it drops old tombstones.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, bail, ensure};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use synctato::{Row, TableRow};
+
+use crate::data::schema::{
+    FeedItem, FeedSource, LaterEntry, MetaEntry, ReadMark, SavedView, SnoozeEntry,
+};
+
+const CONFLICT_MARKERS: &[&str] = &["<<<<<<<", "|||||||", "=======", ">>>>>>>"];
+
+/// Length of the hex IDs of a table, as synctato sizes them from the table's
+/// expected capacity.
+fn id_length(expected_capacity: usize) -> usize {
+    if expected_capacity <= 1 {
+        return 4;
+    }
+    let k = expected_capacity as f64;
+    let n = (500.0 * k * k).ln() / 16_f64.ln();
+    (n.ceil() as usize).max(4)
+}
+
+/// The ID synctato gives a row with this key.
+fn expected_id<T: TableRow>(row: &T) -> String {
+    let hash: String = Sha256::digest(row.key().as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    hash[..id_length(T::EXPECTED_CAPACITY)].to_string()
+}
+
+fn shard_file<T: TableRow>(id: &str) -> String {
+    let end = T::SHARD_CHARACTERS.min(id.len());
+    format!("items_{}.jsonl", &id[..end])
+}
+
+/// The rows of one table as they should be stored, and what was wrong with
+/// the files they were read from.
+struct TableCheck<T> {
+    rows: BTreeMap<String, (Row<T>, String)>,
+    problems: Vec<String>,
+    lines: usize,
+}
+
+impl<T: TableRow> TableCheck<T> {
+    fn new() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            problems: Vec::new(),
+            lines: 0,
+        }
+    }
+
+    /// Check the lines of shard `file`, keeping the rows that can be read.
+    fn check_shard(&mut self, file: &str, content: &str) {
+        for (i, line) in content.lines().enumerate() {
+            let location = format!("{}/{file}:{}", T::TABLE_NAME, i + 1);
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.lines += 1;
+            if CONFLICT_MARKERS.iter().any(|m| line.starts_with(m)) {
+                self.problems
+                    .push(format!("{location}: leftover merge conflict marker"));
+                continue;
+            }
+            let row: Row<T> = match serde_json::from_str(line) {
+                Ok(row) => row,
+                Err(e) => {
+                    self.problems
+                        .push(format!("{location}: unreadable row ({e})"));
+                    continue;
+                }
+            };
+            let row = match row {
+                Row::Live {
+                    id,
+                    inner,
+                    updated_at,
+                } => {
+                    let expected = expected_id(&inner);
+                    if id != expected {
+                        self.problems.push(format!(
+                            "{location}: id {id} doesn't match the row's key (should be {expected})"
+                        ));
+                    }
+                    Row::Live {
+                        id: expected,
+                        inner,
+                        updated_at,
+                    }
+                }
+                tombstone => tombstone,
+            };
+            let id = row.id().to_string();
+            let shard = shard_file::<T>(&id);
+            if shard != file {
+                self.problems
+                    .push(format!("{location}: row {id} belongs in {shard}"));
+            }
+            self.insert(id, row, location);
+        }
+    }
+
+    /// Keep the most recently changed of rows sharing an ID, like a merge does.
+    fn insert(&mut self, id: String, row: Row<T>, location: String) {
+        match self.rows.get(&id) {
+            Some((existing, seen_at)) => {
+                self.problems
+                    .push(format!("{location}: duplicate of row {id} at {seen_at}"));
+                if row.last_modified() > existing.last_modified() {
+                    self.rows.insert(id, (row, location));
+                }
+            }
+            None => {
+                self.rows.insert(id, (row, location));
+            }
+        }
+    }
+
+    fn check_dir(dir: &Path) -> anyhow::Result<Self> {
+        let mut check = Self::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(check);
+        };
+        let mut files: Vec<String> = entries
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .filter(|f| f.starts_with("items_") && f.ends_with(".jsonl"))
+            .collect();
+        files.sort();
+        for file in files {
+            let path = dir.join(&file);
+            let bytes =
+                fs::read(&path).with_context(|| format!("Could not read {}", path.display()))?;
+            check.check_shard(&file, &String::from_utf8_lossy(&bytes));
+        }
+        Ok(check)
+    }
+
+    /// Replace the table's shard files with the checked rows.
+    fn write(&self, dir: &Path) -> anyhow::Result<()> {
+        let mut shards: BTreeMap<String, String> = BTreeMap::new();
+        for (id, (row, _)) in &self.rows {
+            let out = shards.entry(shard_file::<T>(id)).or_default();
+            out.push_str(&serde_json::to_string(row)?);
+            out.push('\n');
+        }
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if name.starts_with("items_") && !shards.contains_key(name.as_ref()) {
+                    fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        for (file, content) in shards {
+            fs::write(dir.join(file), content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check one table, printing its problems, and rewrite it if `fix` is set.
+/// Returns the number of problems and of rows.
+fn doctor_table<T: TableRow>(store: &Path, fix: bool) -> anyhow::Result<(usize, usize)> {
+    let dir = store.join(T::TABLE_NAME);
+    let check = TableCheck::<T>::check_dir(&dir)?;
+    for problem in &check.problems {
+        println!("{problem}");
+    }
+    if fix && !check.problems.is_empty() {
+        check.write(&dir)?;
+    }
+    Ok((check.problems.len(), check.lines))
+}
+
+/// Drop the tombstones of rows deleted before `cutoff` from one table.
+/// Returns how many were dropped.
+fn compact_table<T: TableRow>(store: &Path, cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+    let dir = store.join(T::TABLE_NAME);
+    let mut check = TableCheck::<T>::check_dir(&dir)?;
+    ensure!(
+        check.problems.is_empty(),
+        "The {} table has problems; run `blog db doctor` first",
+        T::TABLE_NAME
+    );
+    let before = check.rows.len();
+    check.rows.retain(
+        |_, (row, _)| !matches!(row, Row::Tombstone { deleted_at, .. } if *deleted_at < cutoff),
+    );
+    let dropped = before - check.rows.len();
+    if dropped > 0 {
+        check.write(&dir)?;
+    }
+    Ok(dropped)
+}
+
+/// Drop the tombstones of rows deleted before `cutoff` from every table.
+/// Returns how many were dropped.
+pub(crate) fn compact_store(store: &Path, cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+    Ok(compact_table::<FeedSource>(store, cutoff)?
+        + compact_table::<FeedItem>(store, cutoff)?
+        + compact_table::<ReadMark>(store, cutoff)?
+        + compact_table::<LaterEntry>(store, cutoff)?
+        + compact_table::<SavedView>(store, cutoff)?
+        + compact_table::<SnoozeEntry>(store, cutoff)?
+        + compact_table::<MetaEntry>(store, cutoff)?)
+}
+
+/// Commit the rewritten files, which after a repair also concludes an
+/// interrupted merge.
+pub(crate) fn commit_store(store: &Path, message: &str) -> anyhow::Result<()> {
+    if !store.join(".git").exists() {
+        return Ok(());
+    }
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(store)
+            .args(args)
+            .output()
+            .context("failed to run git")
+    };
+    git(&["add", "-A"])?;
+    let output = git(&["commit", "-m", message])?;
+    if !output.status.success() && !git(&["diff", "--cached", "--quiet"])?.status.success() {
+        bail!(
+            "failed to commit the store: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_doctor(store: &Path, fix: bool) -> anyhow::Result<()> {
+    let mut problems = 0;
+    let mut rows = 0;
+    for (p, r) in [
+        doctor_table::<FeedSource>(store, fix)?,
+        doctor_table::<FeedItem>(store, fix)?,
+        doctor_table::<ReadMark>(store, fix)?,
+        doctor_table::<LaterEntry>(store, fix)?,
+        doctor_table::<SavedView>(store, fix)?,
+        doctor_table::<SnoozeEntry>(store, fix)?,
+        doctor_table::<MetaEntry>(store, fix)?,
+    ] {
+        problems += p;
+        rows += r;
+    }
+
+    if problems == 0 {
+        eprintln!("No problems found in {rows} rows.");
+        return Ok(());
+    }
+    if !fix {
+        bail!("Found {problems} problems; run `blog db doctor --fix` to repair the store");
+    }
+    commit_store(store, "repair store")?;
+    eprintln!("Repaired {problems} problems. Rows that couldn't be read were dropped.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(key: &str) -> MetaEntry {
+        MetaEntry {
+            key: key.to_string(),
+            value: "v".to_string(),
+        }
+    }
+
+    fn line(id: &str, key: &str, updated_at: &str) -> String {
+        format!(r#"{{"id":"{id}","key":"{key}","value":"v","updated_at":"{updated_at}"}}"#)
+    }
+
+    #[test]
+    fn test_expected_id_matches_synctato() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let table = synctato::Table::<FeedItem>::load(dir.path()).unwrap();
+        let post = FeedItem {
+            raw_id: "https://a.example/1".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(expected_id(&post), table.id_of(&post));
+    }
+
+    #[test]
+    fn test_clean_shard_has_no_problems() {
+        let id = expected_id(&meta("a"));
+        let mut check = TableCheck::<MetaEntry>::new();
+        check.check_shard("items_.jsonl", &line(&id, "a", "2024-01-01T00:00:00Z"));
+
+        assert!(check.problems.is_empty(), "{:?}", check.problems);
+        assert_eq!(check.rows.len(), 1);
+    }
+
+    #[test]
+    fn test_half_merged_conflict_keeps_newest_rows() {
+        let id = expected_id(&meta("a"));
+        let content = [
+            "<<<<<<< HEAD".to_string(),
+            line(&id, "a", "2024-01-01T00:00:00Z"),
+            "=======".to_string(),
+            line(&id, "a", "2024-02-01T00:00:00Z"),
+            ">>>>>>> origin/main".to_string(),
+        ]
+        .join("\n");
+        let mut check = TableCheck::<MetaEntry>::new();
+        check.check_shard("items_.jsonl", &content);
+
+        // Three markers and one duplicate
+        assert_eq!(check.problems.len(), 4, "{:?}", check.problems);
+        let (row, _) = &check.rows[&id];
+        assert_eq!(
+            row.last_modified().unwrap().to_rfc3339(),
+            "2024-02-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_wrong_id_and_shard_are_reported_and_fixed() {
+        let mut check = TableCheck::<MetaEntry>::new();
+        check.check_shard("items_x.jsonl", &line("0000", "a", "2024-01-01T00:00:00Z"));
+
+        assert_eq!(check.problems.len(), 2, "{:?}", check.problems);
+        assert!(check.problems[0].contains("doesn't match the row's key"));
+        assert!(check.problems[1].contains("belongs in items_.jsonl"));
+        assert!(check.rows.contains_key(&expected_id(&meta("a"))));
+    }
+
+    #[test]
+    fn test_compact_store_drops_old_tombstones() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let meta_dir = dir.path().join("meta");
+        fs::create_dir(&meta_dir).unwrap();
+        let live = line(&expected_id(&meta("a")), "a", "2024-01-01T00:00:00Z");
+        fs::write(
+            meta_dir.join("items_.jsonl"),
+            [
+                live.as_str(),
+                r#"{"id":"0001","deleted_at":"2024-01-01T00:00:00Z"}"#,
+                r#"{"id":"0002","deleted_at":"2024-06-01T00:00:00Z"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let cutoff = "2024-03-01T00:00:00Z".parse().unwrap();
+        assert_eq!(compact_store(dir.path(), cutoff).unwrap(), 1);
+        let content = fs::read_to_string(meta_dir.join("items_.jsonl")).unwrap();
+        assert!(content.contains(&live), "{content}");
+        assert!(!content.contains("0001"), "{content}");
+        assert!(content.contains("0002"), "{content}");
+    }
+
+    #[test]
+    fn test_unreadable_line_is_reported() {
+        let mut check = TableCheck::<MetaEntry>::new();
+        check.check_shard("items_.jsonl", "{\"id\": \"ab\"");
+
+        assert_eq!(check.problems.len(), 1);
+        assert!(check.problems[0].starts_with("meta/items_.jsonl:1: unreadable row"));
+        assert!(check.rows.is_empty());
+    }
+}
@@ -1,12 +1,39 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
+use anyhow::{Context, ensure};
 use chrono::{DateTime, Utc};
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use serde::Serialize;
 
 use crate::data::BlogData;
-use crate::data::schema::FeedSource;
+use crate::data::schema::{FeedItem, FeedSource};
 use crate::query::Query;
-use crate::query::resolve::resolve_posts;
+use crate::query::resolve::{ResolvedPosts, resolve_posts};
+use crate::utils::date::format_day;
+use crate::utils::epub::{Book, Chapter, write_epub};
+use crate::utils::html::{escape, to_xhtml};
+
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// One JSON object per post
+    #[default]
+    Jsonl,
+    /// An e-book with one chapter per post
+    Epub,
+    /// A single Atom feed, e.g. to republish your reading river
+    #[value(alias = "feed")]
+    Atom,
+    /// A Markdown document with one section per post, e.g. for note vaults
+    #[value(alias = "md")]
+    Markdown,
+}
+
+/// Posts in an Atom export when no `--limit` is given.
+const ATOM_DEFAULT_LIMIT: usize = 50;
 
 #[derive(Serialize)]
 struct ExportItem<'a> {
@@ -20,9 +47,75 @@ struct ExportItem<'a> {
     read_at: Option<&'a DateTime<Utc>>,
 }
 
-pub(crate) fn cmd_export(store: &BlogData, query: &Query) -> anyhow::Result<()> {
-    let resolved = resolve_posts(store, query)?;
+pub(crate) fn cmd_export(
+    store: &BlogData,
+    query: &Query,
+    format: ExportFormat,
+    output: Option<&Path>,
+    limit: Option<usize>,
+) -> anyhow::Result<()> {
+    let limit = match format {
+        ExportFormat::Atom => limit.or(Some(ATOM_DEFAULT_LIMIT)),
+        _ => limit,
+    };
+    let mut resolved = resolve_posts(store, query)?;
+    if let Some(limit) = limit {
+        resolved.items.truncate(limit);
+    }
+    match format {
+        ExportFormat::Jsonl => write_output(output, |out| export_jsonl(store, &resolved, out)),
+        ExportFormat::Epub => {
+            let path = output.context("EPUB export needs an output file (--output FILE)")?;
+            export_epub(store, &resolved, path)
+        }
+        ExportFormat::Atom => {
+            write_output(output, |out| export_atom(store, &resolved, Utc::now(), out))
+        }
+        ExportFormat::Markdown => {
+            ensure!(!resolved.items.is_empty(), "No matching posts");
+            write_output(output, |out| export_markdown(store, &resolved, out))
+        }
+    }
+}
 
+/// Write to `path`, or to stdout without one.
+pub(crate) fn write_output(
+    path: Option<&Path>,
+    write: impl FnOnce(&mut dyn Write) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    match path {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Could not create {}", path.display()))?;
+            let mut out = BufWriter::new(file);
+            write(&mut out)?;
+            out.flush()?;
+            Ok(())
+        }
+        None => write(&mut io::stdout().lock()),
+    }
+}
+
+fn feed_titles(store: &BlogData) -> HashMap<String, String> {
+    store
+        .feeds()
+        .iter()
+        .map(|(id, feed)| {
+            let title = if feed.title.is_empty() {
+                &feed.url
+            } else {
+                &feed.title
+            };
+            (id.to_string(), title.clone())
+        })
+        .collect()
+}
+
+fn export_jsonl(
+    store: &BlogData,
+    resolved: &ResolvedPosts,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
     let feeds_by_id: HashMap<String, &FeedSource> = store
         .feeds()
         .iter()
@@ -45,8 +138,289 @@ pub(crate) fn cmd_export(store: &BlogData, query: &Query) -> anyhow::Result<()>
                 link: &item.link,
                 read_at: reads.get(&item.raw_id),
             };
-            println!("{}", serde_json::to_string(&export)?);
+            writeln!(out, "{}", serde_json::to_string(&export)?)?;
         }
     }
     Ok(())
 }
+
+fn export_epub(store: &BlogData, resolved: &ResolvedPosts, path: &Path) -> anyhow::Result<()> {
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+
+    let feed_titles = feed_titles(store);
+
+    let now = Utc::now();
+    let book = Book {
+        id: format!("urn:blogtato:export:{}", now.timestamp()),
+        title: format!("blogtato {}", format_day(now)),
+        modified: now,
+        chapters: resolved
+            .items
+            .iter()
+            .map(|(_, item)| {
+                let feed = feed_titles.get(&item.feed).map_or("", String::as_str);
+                post_chapter(item, feed)
+            })
+            .collect(),
+    };
+
+    let file =
+        File::create(path).with_context(|| format!("Could not create {}", path.display()))?;
+    write_epub(BufWriter::new(file), &book)?;
+    eprintln!(
+        "Exported {} post(s) to {}",
+        book.chapters.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Write `resolved` as an Atom feed. Each entry carries a `<source>` naming
+/// the feed it came from.
+fn export_atom(
+    store: &BlogData,
+    resolved: &ResolvedPosts,
+    now: DateTime<Utc>,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let feeds: HashMap<String, &FeedSource> = store
+        .feeds()
+        .iter()
+        .map(|(id, feed)| (id.to_string(), feed))
+        .collect();
+    let feed_titles = feed_titles(store);
+    let updated = resolved
+        .items
+        .iter()
+        .filter_map(|(_, item)| item.date)
+        .max()
+        .unwrap_or(now);
+
+    let mut writer = Writer::new_with_indent(out, b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    let mut feed = BytesStart::new("feed");
+    feed.push_attribute(("xmlns", "http://www.w3.org/2005/Atom"));
+    writer.write_event(Event::Start(feed))?;
+    write_text(&mut writer, "id", "urn:blogtato:river")?;
+    write_text(&mut writer, "title", "blogtato river")?;
+    write_text(&mut writer, "updated", &updated.to_rfc3339())?;
+    writer
+        .create_element("author")
+        .write_inner_content(|w| write_text(w, "name", "blogtato"))?;
+    writer
+        .create_element("generator")
+        .with_attribute(("uri", env!("CARGO_PKG_REPOSITORY")))
+        .write_text_content(BytesText::new("blogtato"))?;
+
+    for (id, item) in &resolved.items {
+        writer.write_event(Event::Start(BytesStart::new("entry")))?;
+        write_text(&mut writer, "id", &format!("urn:blogtato:post:{id}"))?;
+        let title = if item.title.is_empty() {
+            &item.link
+        } else {
+            &item.title
+        };
+        write_text(&mut writer, "title", title)?;
+        write_text(
+            &mut writer,
+            "updated",
+            &item.date.unwrap_or(updated).to_rfc3339(),
+        )?;
+        if let Some(date) = item.date {
+            write_text(&mut writer, "published", &date.to_rfc3339())?;
+        }
+        if !item.link.is_empty() {
+            writer
+                .create_element("link")
+                .with_attributes([("rel", "alternate"), ("href", item.link.as_str())])
+                .write_empty()?;
+        }
+        if !item.summary.is_empty() {
+            writer
+                .create_element("summary")
+                .with_attribute(("type", "html"))
+                .write_text_content(BytesText::new(&item.summary))?;
+        }
+        if !item.content.is_empty() {
+            writer
+                .create_element("content")
+                .with_attribute(("type", "html"))
+                .write_text_content(BytesText::new(&item.content))?;
+        }
+        if let Some(source) = feeds.get(&item.feed) {
+            let title = feed_titles.get(&item.feed).map_or("", String::as_str);
+            writer.create_element("source").write_inner_content(|w| {
+                write_text(w, "id", &source.url)?;
+                write_text(w, "title", title)?;
+                if !source.site_url.is_empty() {
+                    w.create_element("link")
+                        .with_attributes([("rel", "alternate"), ("href", source.site_url.as_str())])
+                        .write_empty()?;
+                }
+                w.create_element("link")
+                    .with_attributes([("rel", "self"), ("href", source.url.as_str())])
+                    .write_empty()?;
+                Ok::<_, io::Error>(())
+            })?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("entry")))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new("feed")))?;
+    writeln!(writer.get_mut())?;
+    Ok(())
+}
+
+fn write_text<W: Write>(writer: &mut Writer<W>, name: &str, text: &str) -> io::Result<()> {
+    writer
+        .create_element(name)
+        .write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+fn export_markdown(
+    store: &BlogData,
+    resolved: &ResolvedPosts,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let feed_titles = feed_titles(store);
+    writeln!(out, "# blogtato export")?;
+    for (_, item) in &resolved.items {
+        let feed = feed_titles.get(&item.feed).map_or("", String::as_str);
+        write!(out, "\n{}", markdown_entry(item, feed))?;
+    }
+    Ok(())
+}
+
+/// Escape characters that would make `text` markup in Markdown.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn markdown_entry(item: &FeedItem, feed_title: &str) -> String {
+    let title = escape_markdown(if item.title.is_empty() {
+        &item.link
+    } else {
+        &item.title
+    });
+    let mut entry = if item.link.is_empty() {
+        format!("## {title}\n\n")
+    } else {
+        format!("## [{title}](<{}>)\n\n", item.link)
+    };
+    if !feed_title.is_empty() {
+        entry.push_str(&format!("- Feed: {}\n", escape_markdown(feed_title)));
+    }
+    if let Some(date) = item.date {
+        entry.push_str(&format!("- Date: {}\n", format_day(date)));
+    }
+    if !item.link.is_empty() {
+        entry.push_str(&format!("- Link: <{}>\n", item.link));
+    }
+    entry
+}
+
+fn post_chapter(item: &FeedItem, feed_title: &str) -> Chapter {
+    let title = if item.title.is_empty() {
+        item.link.clone()
+    } else {
+        item.title.clone()
+    };
+    let mut byline: Vec<String> = Vec::new();
+    if !feed_title.is_empty() {
+        byline.push(escape(feed_title));
+    }
+    if let Some(date) = item.date {
+        byline.push(format_day(date));
+    }
+
+    let mut body = format!("<h1>{}</h1>", escape(&title));
+    if !byline.is_empty() {
+        body.push_str(&format!("<p><em>{}</em></p>", byline.join(" \u{b7} ")));
+    }
+    if item.body().is_empty() {
+        body.push_str("<p>This feed did not include the post content.</p>");
+    } else {
+        body.push_str(&to_xhtml(item.body()));
+    }
+    if item.link.starts_with("http://") || item.link.starts_with("https://") {
+        let link = escape(&item.link);
+        body.push_str(&format!("<hr/><p><a href=\"{link}\">{link}</a></p>"));
+    }
+    Chapter { title, body }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_chapter_includes_content_and_link() {
+        let item = FeedItem {
+            title: "Tips & Tricks".to_string(),
+            date: DateTime::from_timestamp(1_704_067_200, 0),
+            link: "https://example.com/post?a=1&b=2".to_string(),
+            summary: "<p onclick=\"x\">Body<br></p>".to_string(),
+            ..Default::default()
+        };
+        let chapter = post_chapter(&item, "Blog");
+        assert_eq!(chapter.title, "Tips & Tricks");
+        assert_eq!(
+            chapter.body,
+            "<h1>Tips &amp; Tricks</h1>\
+             <p><em>Blog \u{b7} 2024-01-01</em></p>\
+             <p>Body<br/></p>\
+             <hr/><p><a href=\"https://example.com/post?a=1&amp;b=2\">https://example.com/post?a=1&amp;b=2</a></p>"
+        );
+    }
+
+    #[test]
+    fn test_markdown_entry() {
+        let item = FeedItem {
+            title: "Tips & Tricks".to_string(),
+            date: DateTime::from_timestamp(1_704_067_200, 0),
+            link: "https://example.com/post?a=1&b=2".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            markdown_entry(&item, "Blog [b]"),
+            "## [Tips & Tricks](<https://example.com/post?a=1&b=2>)\n\n\
+             - Feed: Blog \\[b\\]\n\
+             - Date: 2024-01-01\n\
+             - Link: <https://example.com/post?a=1&b=2>\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_entry_escapes_title() {
+        let item = FeedItem {
+            title: "Use *this* [not] `that`".to_string(),
+            date: DateTime::from_timestamp(1_704_067_200, 0),
+            ..Default::default()
+        };
+        assert_eq!(
+            markdown_entry(&item, ""),
+            "## Use \\*this\\* \\[not\\] \\`that\\`\n\n- Date: 2024-01-01\n"
+        );
+    }
+
+    #[test]
+    fn test_post_chapter_without_content() {
+        let item = FeedItem {
+            title: "Tips & Tricks".to_string(),
+            ..Default::default()
+        };
+        let chapter = post_chapter(&item, "");
+        assert!(
+            chapter
+                .body
+                .contains("<p>This feed did not include the post content.</p>")
+        );
+    }
+}
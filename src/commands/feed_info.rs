@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+
+use crate::data::BlogData;
+use crate::data::index::{FeedEntry, feed_index, resolve_feed_arg};
+use crate::data::schema::FeedItem;
+use crate::utils::date::format_day;
+
+/// Post statistics shown by `blog feed info`.
+struct PostStats<'a> {
+    count: usize,
+    unread: usize,
+    newest: Option<&'a FeedItem>,
+    oldest: Option<&'a FeedItem>,
+}
+
+fn post_stats<'a>(posts: &[&'a FeedItem], read_ids: &HashSet<&str>) -> PostStats<'a> {
+    let dated = posts.iter().filter(|p| p.date.is_some());
+    PostStats {
+        count: posts.len(),
+        unread: posts
+            .iter()
+            .filter(|p| !read_ids.contains(p.raw_id.as_str()))
+            .count(),
+        newest: dated.clone().max_by_key(|p| p.date).copied(),
+        oldest: dated.min_by_key(|p| p.date).copied(),
+    }
+}
+
+fn describe_post(post: Option<&FeedItem>) -> String {
+    match post {
+        Some(p) => format!("{} {}", p.date.map(format_day).unwrap_or_default(), p.title),
+        None => "-".to_string(),
+    }
+}
+
+fn format_feed_info(entry: &FeedEntry, stats: &PostStats) -> String {
+    let feed = &entry.feed;
+    let status = if let Some(since) = feed.failing_since {
+        format!("failing since {}: {}", format_day(since), feed.fetch_error)
+    } else if !feed.fetch_error.is_empty() {
+        format!("failed: {}", feed.fetch_error)
+    } else if !feed.is_fetched {
+        "not fetched yet".to_string()
+    } else {
+        "ok".to_string()
+    };
+    let mut fields = vec![
+        ("shorthand", format!("@{}", entry.shorthand)),
+        ("id", entry.id.clone()),
+        ("url", feed.url.clone()),
+        ("title", feed.title.clone()),
+        ("site_url", feed.site_url.clone()),
+        ("description", feed.description.clone()),
+        ("folder", feed.folder.clone()),
+        ("last fetch", status),
+    ];
+    if feed.archived {
+        fields.push(("archived", "yes".to_string()));
+    } else if feed.paused {
+        let paused = if feed.hidden {
+            "yes (posts hidden)"
+        } else {
+            "yes"
+        };
+        fields.push(("paused", paused.to_string()));
+    } else if let Some(until) = feed.snoozed_until.filter(|_| feed.is_snoozed(Utc::now())) {
+        fields.push(("snoozed", format!("until {}", format_day(until))));
+    }
+    if !feed.retention.is_empty() {
+        fields.push(("retention", feed.retention.clone()));
+    }
+    if let Some(secs) = feed.timeout {
+        fields.push(("timeout", format!("{secs}s")));
+    }
+    if !feed.hub.is_empty() {
+        fields.push(("websub hub", feed.hub.clone()));
+    }
+    fields.push((
+        "posts",
+        format!("{} ({} unread)", stats.count, stats.unread),
+    ));
+    fields.push(("newest post", describe_post(stats.newest)));
+    fields.push(("oldest post", describe_post(stats.oldest)));
+
+    fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| format!("{name:<12} {value}\n"))
+        .collect()
+}
+
+pub(crate) fn cmd_feed_info(store: &BlogData, feed: &str) -> anyhow::Result<()> {
+    let url = resolve_feed_arg(store.feeds(), feed)?;
+    let fi = feed_index(store.feeds());
+    let entry = fi
+        .entries
+        .iter()
+        .find(|e| e.feed.url == url)
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+
+    let posts: Vec<&FeedItem> = store
+        .posts()
+        .iter()
+        .map(|(_, p)| p)
+        .filter(|p| p.feed == entry.id)
+        .collect();
+    let read_ids: HashSet<&str> = store
+        .reads()
+        .iter()
+        .map(|(_, r)| r.post_id.as_str())
+        .collect();
+
+    print!(
+        "{}",
+        format_feed_info(entry, &post_stats(&posts, &read_ids))
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::schema::FeedSource;
+    use chrono::{TimeZone, Utc};
+
+    fn make_entry() -> FeedEntry {
+        FeedEntry {
+            feed: FeedSource {
+                url: "https://blog.example/feed.xml".to_string(),
+                title: "Example".to_string(),
+                site_url: "https://blog.example".to_string(),
+                is_fetched: true,
+                ..Default::default()
+            },
+            id: "abc123".to_string(),
+            shorthand: "as".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_post_stats() {
+        let day = |d| Some(Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap());
+        let posts = [
+            FeedItem {
+                raw_id: "a".to_string(),
+                date: day(3),
+                ..Default::default()
+            },
+            FeedItem {
+                raw_id: "b".to_string(),
+                ..Default::default()
+            },
+            FeedItem {
+                raw_id: "c".to_string(),
+                date: day(1),
+                ..Default::default()
+            },
+        ];
+        let refs: Vec<&FeedItem> = posts.iter().collect();
+        let read: HashSet<&str> = ["c"].into_iter().collect();
+
+        let stats = post_stats(&refs, &read);
+
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.unread, 2);
+        assert_eq!(stats.newest.unwrap().raw_id, "a");
+        assert_eq!(stats.oldest.unwrap().raw_id, "c");
+    }
+
+    #[test]
+    fn test_format_feed_info() {
+        let posts = [FeedItem {
+            title: "Post a".to_string(),
+            date: Some(Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap()),
+            raw_id: "a".to_string(),
+            ..Default::default()
+        }];
+        let refs: Vec<&FeedItem> = posts.iter().collect();
+        let stats = post_stats(&refs, &HashSet::new());
+
+        let out = format_feed_info(&make_entry(), &stats);
+
+        assert_eq!(
+            out,
+            "shorthand    @as\n\
+             id           abc123\n\
+             url          https://blog.example/feed.xml\n\
+             title        Example\n\
+             site_url     https://blog.example\n\
+             last fetch   ok\n\
+             posts        1 (1 unread)\n\
+             newest post  2024-01-03 Post a\n\
+             oldest post  2024-01-03 Post a\n"
+        );
+    }
+
+    #[test]
+    fn test_format_feed_info_shows_failure_and_pause() {
+        let mut entry = make_entry();
+        entry.feed.fetch_error = "http status: 404".to_string();
+        entry.feed.paused = true;
+        let stats = post_stats(&[], &HashSet::new());
+
+        let out = format_feed_info(&entry, &stats);
+
+        assert!(out.contains("last fetch   failed: http status: 404\n"));
+        assert!(out.contains("paused       yes\n"));
+        assert!(out.contains("newest post  -\n"));
+
+        entry.feed.failing_since = Some("2024-01-02T10:00:00Z".parse().unwrap());
+        let out = format_feed_info(&entry, &stats);
+        assert!(out.contains("last fetch   failing since 2024-01-02: http status: 404\n"));
+    }
+
+    #[test]
+    fn test_format_feed_info_shows_snooze_until_it_ends() {
+        let mut entry = make_entry();
+        let stats = post_stats(&[], &HashSet::new());
+
+        entry.feed.snoozed_until = Some("2999-06-01T00:00:00Z".parse().unwrap());
+        assert!(format_feed_info(&entry, &stats).contains("snoozed      until 2999-06-01\n"));
+
+        entry.feed.snoozed_until = Some("2024-06-01T00:00:00Z".parse().unwrap());
+        assert!(!format_feed_info(&entry, &stats).contains("snoozed"));
+    }
+}
@@ -1,16 +1,106 @@
+use std::collections::HashMap;
+
 use anyhow::ensure;
+use chrono::Utc;
+use unicode_width::UnicodeWidthStr;
 
 use crate::data::BlogData;
-use crate::data::index::feed_index;
+use crate::data::index::{FeedEntry, feed_index};
+use crate::data::schema::FeedItem;
+use crate::display::{pad_str, truncate_str};
+use crate::utils::date::format_day;
+
+/// Widest the feed name column of `feed ls --by-recent` gets.
+const MAX_NAME_COLS: usize = 30;
 
-pub(crate) fn cmd_feed_ls(store: &BlogData) -> anyhow::Result<()> {
+fn status(entry: &FeedEntry) -> String {
+    if entry.feed.archived {
+        " [archived]".to_string()
+    } else if entry.feed.paused {
+        " [paused]".to_string()
+    } else if let Some(until) = entry
+        .feed
+        .snoozed_until
+        .filter(|_| entry.feed.is_snoozed(Utc::now()))
+    {
+        format!(" [snoozed until {}]", format_day(until))
+    } else {
+        String::new()
+    }
+}
+
+/// One line per feed with its newest post's date and title in aligned
+/// columns, most recent first. Feeds without dated posts come last.
+fn format_by_recent(entries: &[(&FeedEntry, Option<&FeedItem>)]) -> String {
+    let mut rows: Vec<_> = entries.to_vec();
+    rows.sort_by_key(|(_, newest)| std::cmp::Reverse(newest.and_then(|p| p.date)));
+
+    let name = |entry: &FeedEntry| {
+        let name = if entry.feed.title.is_empty() {
+            &entry.feed.url
+        } else {
+            &entry.feed.title
+        };
+        truncate_str(name, MAX_NAME_COLS)
+    };
+    let shorthand_cols = rows
+        .iter()
+        .map(|(e, _)| e.shorthand.width() + 1)
+        .max()
+        .unwrap_or(0);
+    let name_cols = rows.iter().map(|(e, _)| name(e).width()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (entry, newest) in rows {
+        let (date, title) = match newest {
+            Some(post) => (
+                post.date.map(format_day).unwrap_or_default(),
+                post.title.as_str(),
+            ),
+            None => (String::new(), ""),
+        };
+        let line = format!(
+            "{}  {:<10}  {}  {}{}",
+            pad_str(&format!("@{}", entry.shorthand), shorthand_cols),
+            if date.is_empty() { "-" } else { &date },
+            pad_str(&name(entry), name_cols),
+            title,
+            status(entry)
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+pub(crate) fn cmd_feed_ls(store: &BlogData, by_recent: bool) -> anyhow::Result<()> {
     let fi = feed_index(store.feeds());
     ensure!(!fi.entries.is_empty(), "No feeds found");
+    if by_recent {
+        let mut newest: HashMap<&str, &FeedItem> = HashMap::new();
+        for (_, post) in store.posts().iter() {
+            let current = newest.entry(post.feed.as_str()).or_insert(post);
+            if post.date > current.date {
+                *current = post;
+            }
+        }
+        let entries: Vec<_> = fi
+            .entries
+            .iter()
+            .map(|e| (e, newest.get(e.id.as_str()).copied()))
+            .collect();
+        print!("{}", format_by_recent(&entries));
+        return Ok(());
+    }
     for e in &fi.entries {
+        let paused = status(e);
         if e.feed.title.is_empty() {
-            println!("@{} {}", e.shorthand, e.feed.url);
+            println!("@{} {}{}", e.shorthand, e.feed.url, paused);
         } else {
-            println!("@{} {} ({})", e.shorthand, e.feed.url, e.feed.title);
+            println!(
+                "@{} {} ({}){}",
+                e.shorthand, e.feed.url, e.feed.title, paused
+            );
         }
     }
     Ok(())
@@ -0,0 +1,56 @@
+use anyhow::ensure;
+
+use crate::data::Transaction;
+use crate::data::index::resolve_feed_arg;
+
+/// Folder names as given, with `/` between nested folders and no empty ones.
+fn normalize_folder(folder: &str) -> anyhow::Result<String> {
+    let parts: Vec<&str> = folder.split('/').map(str::trim).collect();
+    ensure!(
+        parts.iter().all(|p| !p.is_empty()),
+        "Invalid folder {folder:?}: use names separated by /, e.g. Tech/Rust"
+    );
+    Ok(parts.join("/"))
+}
+
+/// File the feed named by `feed` (URL or @shorthand) in `folder`, e.g.
+/// `Tech/Rust`, or take it out of its folder when `folder` is `none`.
+/// Returns the feed's URL.
+pub(crate) fn cmd_folder(tx: &mut Transaction, feed: &str, folder: &str) -> anyhow::Result<String> {
+    let url = resolve_feed_arg(tx.feeds, feed)?;
+    let mut source = tx
+        .feeds
+        .get(&url)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+    source.folder = if folder == "none" {
+        String::new()
+    } else {
+        normalize_folder(folder)?
+    };
+    tx.feeds.upsert(source);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("Tech", "Tech")]
+    #[case("Tech/Rust", "Tech/Rust")]
+    #[case(" News / World ", "News/World")]
+    fn test_normalize_folder(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(normalize_folder(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("/Tech")]
+    #[case("Tech//Rust")]
+    #[case("Tech/")]
+    fn test_normalize_folder_rejects_empty_names(#[case] input: &str) {
+        assert!(normalize_folder(input).is_err());
+    }
+}
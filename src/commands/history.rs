@@ -0,0 +1,136 @@
+//! `blog history`: the store's git log told in terms of feeds and posts, with
+//! the rows each commit added, removed or changed.
+
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::{Context, ensure};
+use chrono::DateTime;
+
+use crate::data::changes::{TableChanges, count_rows, format_counts, git_stdout, table_changes};
+use crate::display::Style;
+use crate::utils::date::format_minute;
+use crate::utils::http::host_of;
+
+/// What a commit did, in words, from the action in its message and the rows
+/// it changed. Messages that aren't recognised, e.g. because the
+/// `commit_message` config key changed them, are shown as they are.
+fn describe(subject: &str, changes: &BTreeMap<String, TableChanges>) -> String {
+    let of = |table: &str| changes.get(table).cloned().unwrap_or_default();
+    if subject.starts_with("pull feeds") {
+        return format!(
+            "pulled {}",
+            count_rows(of("posts").added.len(), "post", "posts")
+        );
+    }
+    if let Some(url) = subject.strip_prefix("add feed: ") {
+        return format!("added feed {}", host_of(url));
+    }
+    if let Some(url) = subject.strip_prefix("push: ") {
+        return format!("received posts from {}", host_of(url));
+    }
+    if let Some(target) = subject.strip_prefix("remove ")
+        && url::Url::parse(target).is_ok()
+    {
+        return format!("removed feed {}", host_of(target));
+    }
+    match subject {
+        "init store" => "created the store".to_string(),
+        "sync" => "synced with the remote".to_string(),
+        "mark read" => format!(
+            "marked {} read",
+            count_rows(of("reads").added.len(), "post", "posts")
+        ),
+        "mark unread" => format!(
+            "marked {} unread",
+            count_rows(of("reads").removed.len(), "post", "posts")
+        ),
+        _ => subject.to_string(),
+    }
+}
+
+/// Print the `limit` most recent commits of the store, newest first.
+pub(crate) fn cmd_history(store: &Path, limit: usize) -> anyhow::Result<()> {
+    ensure!(
+        store.join(".git").exists(),
+        "The store at {} has no history; it isn't a git repository",
+        store.display()
+    );
+    let log = git_stdout(
+        store,
+        &[
+            "log",
+            "--first-parent",
+            &format!("--max-count={limit}"),
+            "--format=%H%x00%P%x00%ct%x00%s",
+        ],
+    )?;
+    let s = Style::new(std::io::stdout().is_terminal());
+    for line in log.lines() {
+        let fields: Vec<&str> = line.splitn(4, '\0').collect();
+        let [hash, parents, time, subject] = fields[..] else {
+            continue;
+        };
+        let time = time
+            .parse()
+            .ok()
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+            .context("git printed an invalid commit time")?;
+        // Merges are compared with the branch they were made on
+        let parent = parents.split_whitespace().next();
+        let changes = table_changes(store, parent, hash)?;
+        let mut out = format!("{}  {}", format_minute(time), describe(subject, &changes));
+        if !changes.is_empty() {
+            out.push_str(&format!(
+                "  {}({}){}",
+                s.dim,
+                format_counts(&changes),
+                s.reset
+            ));
+        }
+        println!("{out}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn changes(table: &str, added: usize, removed: usize) -> BTreeMap<String, TableChanges> {
+        BTreeMap::from([(
+            table.to_string(),
+            TableChanges {
+                added: vec![String::new(); added],
+                removed: vec![String::new(); removed],
+                changed: Vec::new(),
+            },
+        )])
+    }
+
+    #[rstest]
+    #[case::pull("pull feeds", changes("posts", 23, 0), "pulled 23 posts")]
+    #[case::pull_one("pull feeds", changes("posts", 1, 0), "pulled 1 post")]
+    #[case::add(
+        "add feed: https://example.com/feed.xml",
+        changes("feeds", 1, 0),
+        "added feed example.com"
+    )]
+    #[case::remove(
+        "remove https://example.com/feed.xml",
+        changes("feeds", 0, 1),
+        "removed feed example.com"
+    )]
+    #[case::remove_view("remove view rust", changes("views", 0, 1), "remove view rust")]
+    #[case::mark_read("mark read", changes("reads", 2, 0), "marked 2 posts read")]
+    #[case::custom("[laptop] pull feeds", BTreeMap::new(), "[laptop] pull feeds")]
+    fn test_describe(
+        #[case] subject: &str,
+        #[case] changes: BTreeMap<String, TableChanges>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(describe(subject, &changes), expected);
+    }
+}
@@ -5,30 +5,153 @@ use quick_xml::Reader;
 use quick_xml::XmlVersion;
 use quick_xml::events::Event;
 
-use crate::data::BlogData;
+use crate::data::{BlogData, get_config_value, transact};
+use crate::feed::blocklist::Blocklist;
+use crate::feed::links::LinkCleaner;
+use crate::utils::http::HttpOptions;
 
-use super::add::cmd_add;
+use super::add::{
+    ResolvedFeed, add_resolved, cmd_add, ensure_not_subscribed, find_feeds, select_feeds,
+};
 
 pub(crate) fn cmd_import(store: &mut BlogData, path: &Path) -> anyhow::Result<()> {
     let content = fs::read_to_string(path)?;
-    let urls = parse_opml_urls(&content);
+    let mut urls = parse_opml_urls(&content);
 
     if urls.is_empty() {
         anyhow::bail!("no feeds found in {}", path.display());
     }
-
-    store.transact(&format!("import {} feeds from OPML", urls.len()), |tx| {
-        for url in &urls {
-            cmd_add(tx, url)?;
+    let blocklist = Blocklist::from_config(store)?;
+    urls.retain(|url| match blocklist.blocked_by(url) {
+        Some(entry) => {
+            eprintln!("Skipped {url}, blocked by blocklist entry {entry:?}");
+            false
         }
-        Ok(())
-    })?;
+        None => true,
+    });
+
+    transact(
+        store,
+        &format!("import {} feeds from OPML", urls.len()),
+        |tx| {
+            for url in &urls {
+                cmd_add(tx, url)?;
+            }
+            Ok(())
+        },
+    )?;
 
     eprintln!("Imported {} feeds.", urls.len());
     eprintln!("Run `blog sync` to fetch posts.");
     Ok(())
 }
 
+/// Look for feeds on every site bookmarked in the browser export at `path`
+/// and subscribe to the ones picked from a menu.
+pub(crate) fn cmd_import_bookmarks(
+    store: &mut BlogData,
+    path: &Path,
+    http: &HttpOptions,
+) -> anyhow::Result<()> {
+    let content = fs::read_to_string(path)?;
+    let sites = bookmarked_sites(&parse_bookmark_urls(&content));
+    if sites.is_empty() {
+        anyhow::bail!("no bookmarks found in {}", path.display());
+    }
+
+    let links = LinkCleaner::from_config(store)?;
+    let blocklist = Blocklist::from_config(store)?;
+    let mut found: Vec<ResolvedFeed> = Vec::new();
+    let mut with_feeds = 0;
+    for site in &sites {
+        if blocklist.blocks(site) {
+            continue;
+        }
+        let feeds = match find_feeds(site, http, &links) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                tracing::info!(site = %site, error = %e, "bookmarked site unavailable");
+                continue;
+            }
+        };
+        let new: Vec<ResolvedFeed> = feeds
+            .into_iter()
+            .filter(|feed| {
+                !blocklist.blocks(&feed.url)
+                    && ensure_not_subscribed(store.feeds(), feed).is_ok()
+                    && !found.iter().any(|f| f.url == feed.url)
+            })
+            .collect();
+        with_feeds += usize::from(!new.is_empty());
+        found.extend(new);
+    }
+    anyhow::ensure!(
+        !found.is_empty(),
+        "No new feeds found on the {} bookmarked sites",
+        sites.len()
+    );
+
+    eprintln!(
+        "Found {} feeds on {with_feeds} of {} bookmarked sites:",
+        found.len(),
+        sites.len()
+    );
+    let picked = select_feeds(found)?;
+    let count = picked.len();
+    let ingest_filter = get_config_value(store, "ingest_filter");
+    transact(
+        store,
+        &format!("import {count} feeds from bookmarks"),
+        |tx| {
+            for feed in picked {
+                add_resolved(tx, feed, ingest_filter.as_deref(), &blocklist)?;
+            }
+            Ok(())
+        },
+    )?;
+    eprintln!("Imported {count} feeds.");
+    Ok(())
+}
+
+/// The links in a Netscape-format bookmarks file, as exported by browsers:
+/// the `HREF` of each `<A>`.
+fn parse_bookmark_urls(html: &str) -> Vec<String> {
+    // Lowercasing ASCII keeps byte offsets, so they index `html` too
+    let lower = html.to_ascii_lowercase();
+    let mut urls = Vec::new();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find("href=\"") {
+        let start = from + i + "href=\"".len();
+        let Some(len) = html[start..].find('"') else {
+            break;
+        };
+        urls.push(html[start..start + len].replace("&amp;", "&"));
+        from = start + len;
+    }
+    urls
+}
+
+/// The home pages of the web sites `urls` are on, each once, in the order
+/// they first appear.
+fn bookmarked_sites(urls: &[String]) -> Vec<String> {
+    let mut sites: Vec<String> = Vec::new();
+    for url in urls {
+        let Ok(url) = url::Url::parse(url.trim()) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        if let Ok(home) = url.join("/") {
+            let home = home.to_string();
+            if !sites.contains(&home) {
+                sites.push(home);
+            }
+        }
+    }
+    sites
+}
+
 fn parse_opml_urls(xml: &str) -> Vec<String> {
     let mut reader = Reader::from_str(xml);
     let mut urls = Vec::new();
@@ -97,6 +220,31 @@ mod tests {
         assert_eq!(urls, vec!["https://example.com/c.xml"]);
     }
 
+    #[test]
+    fn test_bookmarked_sites_from_netscape_export() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000">Blogs</H3>
+    <DL><p>
+        <DT><A HREF="https://blog.example.com/posts/1?a=1&amp;b=2" ADD_DATE="1700000000">Post</A>
+        <DT><A HREF="https://blog.example.com/about">About</A>
+        <DT><A href="http://other.example:8080/x">Other</A>
+    </DL><p>
+    <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+    <DT><A HREF="place:sort=8">Recent</A>
+</DL><p>"#;
+
+        let urls = parse_bookmark_urls(html);
+        assert_eq!(urls[0], "https://blog.example.com/posts/1?a=1&b=2");
+        assert_eq!(
+            bookmarked_sites(&urls),
+            ["https://blog.example.com/", "http://other.example:8080/"]
+        );
+    }
+
     #[test]
     fn test_parse_opml_empty() {
         let opml = r#"<?xml version="1.0"?><opml><body></body></opml>"#;
@@ -0,0 +1,80 @@
+//! `blog init`: turn the store directory into a git repository that can be
+//! synced, in one step.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use anyhow::{Context, bail, ensure};
+
+use super::clone::expand_url;
+
+/// Files synctato writes next to the table files that only matter on this
+/// machine: lock files and shards being written.
+const GITIGNORE: &str = ".lock\n*.tmp\n";
+
+/// Keeps git from converting line endings of the table files, which synctato
+/// always writes with `\n`.
+const GITATTRIBUTES: &str = "*.jsonl -text\n";
+
+fn git(store: &Path, args: &[&str]) -> anyhow::Result<Output> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(store)
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output)
+}
+
+/// Create the store directory with a git repository on `main`, holding the
+/// rows already in the directory, if any, and pointing `origin` at `remote`.
+pub(crate) fn cmd_init(store: &Path, remote: Option<&str>) -> anyhow::Result<()> {
+    ensure!(
+        !store.join(".git").exists(),
+        "{} is already a git repository; use `blog git remote add origin <url>` to add a remote",
+        store.display()
+    );
+    std::fs::create_dir_all(store)
+        .with_context(|| format!("failed to create {}", store.display()))?;
+
+    git(store, &["init", "--quiet"])?;
+    // `git init -b` needs git 2.28
+    git(store, &["symbolic-ref", "HEAD", "refs/heads/main"])?;
+    for (name, content) in [(".gitignore", GITIGNORE), (".gitattributes", GITATTRIBUTES)] {
+        let path = store.join(name);
+        if !path.exists() {
+            std::fs::write(&path, content)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+    }
+    git(store, &["add", "-A"])?;
+    // Like the commits synctato makes, fall back to its own identity when
+    // git doesn't have one
+    let mut commit = vec![];
+    if git(store, &["var", "GIT_COMMITTER_IDENT"]).is_err() {
+        commit.extend([
+            "-c",
+            "user.name=synctato",
+            "-c",
+            "user.email=synctato@localhost",
+        ]);
+    }
+    commit.extend(["commit", "--quiet", "-m", "init store"]);
+    git(store, &commit)?;
+
+    if let Some(remote) = remote {
+        git(store, &["remote", "add", "origin", &expand_url(remote)])?;
+    }
+    eprintln!("Initialized a store in {}.", store.display());
+    if remote.is_some() {
+        eprintln!("Run `blog sync` to push it.");
+    }
+    Ok(())
+}
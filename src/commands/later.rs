@@ -0,0 +1,144 @@
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+
+use anyhow::ensure;
+
+use crate::commands::open::print_posts;
+use crate::commands::snooze::due_ids;
+use crate::data::index::feed_index;
+use crate::data::schema::{FeedItem, LaterEntry, ReadMark};
+use crate::data::{BlogData, transact};
+use crate::display::{RenderCtx, Theme, build_feed_folders, render_grouped};
+use crate::query::resolve::{ResolvedPosts, resolve_posts};
+use crate::query::{Query, parse_query};
+
+/// Queue the posts matching `query` to read later.
+pub(crate) fn cmd_later_add(store: &mut BlogData, query: &Query) -> anyhow::Result<()> {
+    let resolved = resolve_posts(store, query)?;
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+    let now = chrono::Utc::now();
+    let added = transact(store, "read later", |tx| {
+        let mut added = 0;
+        for (_, item) in &resolved.items {
+            if !tx.later.contains_key(&item.raw_id) {
+                tx.later.upsert(LaterEntry {
+                    post_id: item.raw_id.clone(),
+                    added_at: now,
+                });
+                added += 1;
+            }
+        }
+        Ok(added)
+    })?;
+    eprintln!(
+        "Queued {added} post(s) to read later ({} in queue).",
+        store.later().iter().count()
+    );
+    Ok(())
+}
+
+/// Take the posts matching `query` off the queue without reading them.
+pub(crate) fn cmd_later_rm(store: &mut BlogData, query: &Query) -> anyhow::Result<()> {
+    let resolved = resolve_posts(store, query)?;
+    let queued: Vec<&FeedItem> = resolved
+        .items
+        .iter()
+        .map(|(_, item)| item)
+        .filter(|item| store.later().contains_key(&item.raw_id))
+        .collect();
+    ensure!(
+        !queued.is_empty(),
+        "No matching posts in the read-later queue"
+    );
+    transact(store, "remove from read later", |tx| {
+        for item in &queued {
+            tx.later.delete(&item.raw_id);
+        }
+        Ok(())
+    })?;
+    eprintln!(
+        "Removed {} post(s) from the read-later queue.",
+        queued.len()
+    );
+    Ok(())
+}
+
+/// The queued posts, first queued first.
+fn queue(store: &BlogData) -> anyhow::Result<ResolvedPosts> {
+    // Queued posts stay visible even if their feed is hidden or they are read
+    let mut resolved = resolve_posts(store, &parse_query(&[".all".to_string()])?)?;
+    let added: HashMap<&str, _> = store
+        .later()
+        .iter()
+        .map(|(_, e)| (e.post_id.as_str(), e.added_at))
+        .collect();
+    resolved
+        .items
+        .retain(|(_, item)| added.contains_key(item.raw_id.as_str()));
+    resolved
+        .items
+        .sort_by_key(|(_, item)| added[item.raw_id.as_str()]);
+    Ok(resolved)
+}
+
+pub(crate) fn cmd_later_ls(store: &BlogData) -> anyhow::Result<()> {
+    let resolved = queue(store)?;
+    ensure!(
+        !resolved.items.is_empty(),
+        "The read-later queue is empty. Queue posts with `blog <shorthand> later`."
+    );
+
+    let read_ids: HashSet<String> = store
+        .reads()
+        .iter()
+        .map(|(_, r)| r.post_id.clone())
+        .collect();
+    let refs: Vec<&FeedItem> = resolved.items.iter().map(|(_, item)| item).collect();
+    let max_width = terminal_size::terminal_size().map(|(w, _)| w.0 as usize);
+    let ctx = RenderCtx {
+        all_keys: &[],
+        shorthands: &resolved.shorthands,
+        feed_labels: &resolved.feed_labels,
+        feed_folders: build_feed_folders(&feed_index(store.feeds())),
+        read_ids: &read_ids,
+        due_ids: due_ids(store, chrono::Utc::now()),
+        color: std::io::stdout().is_terminal(),
+        theme: Theme::load(store)?,
+        shorthand_width: RenderCtx::shorthand_width_from(&refs, &resolved.shorthands),
+        title_width: RenderCtx::title_width_from(&refs, max_width),
+        max_width,
+        long: false,
+        show_author: false,
+        dim_read: false,
+        highlight: &[],
+        per_group: None,
+    };
+    print!("{}", render_grouped(&refs, &ctx));
+    Ok(())
+}
+
+/// Read the first queued post, marking it read and taking it off the queue.
+pub(crate) fn cmd_later_pop(store: &mut BlogData, plain: bool) -> anyhow::Result<()> {
+    let resolved = queue(store)?;
+    ensure!(!resolved.items.is_empty(), "The read-later queue is empty");
+    let head = &resolved.items[..1];
+    print_posts(head, &resolved.feed_labels, plain)?;
+
+    let post_id = head[0].1.raw_id.clone();
+    let now = chrono::Utc::now();
+    transact(store, "read from later", |tx| {
+        tx.later.delete(&post_id);
+        if !tx.reads.contains_key(&post_id) {
+            tx.reads.upsert(ReadMark {
+                post_id: post_id.clone(),
+                read_at: now,
+            });
+        }
+        Ok(())
+    })?;
+    match resolved.items.len() - 1 {
+        0 => eprintln!("The read-later queue is now empty."),
+        n => eprintln!("{n} post(s) left to read later."),
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Keeping the store small: `blog maintenance` drops rows deleted long ago
+//! from the table files and repacks the git repository, and `blog sync` lets
+//! git repack on its own once enough loose objects pile up.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, bail};
+use chrono::Utc;
+
+use crate::data::{BlogData, get_config_parsed};
+use crate::utils::progress::spinner;
+
+use super::doctor::{commit_store, compact_store};
+
+/// Days a deleted row is kept as a tombstone, so that machines which haven't
+/// synced since still learn it was deleted, unless the `tombstone_days`
+/// config key says otherwise.
+const DEFAULT_TOMBSTONE_DAYS: u32 = 90;
+
+fn git_gc(store: &Path, args: &[&str]) -> anyhow::Result<()> {
+    tracing::info!(?args, "git gc");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(store)
+        .args(["gc", "--quiet"])
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git gc failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Run `git gc --auto` after a sync, unless the `auto_gc` config key is
+/// false. Git only repacks once there are enough loose objects, so this is
+/// cheap most of the time; a failure is reported but doesn't fail the sync.
+pub(crate) fn auto_gc(store: &BlogData) {
+    if !store.path().join(".git").exists() {
+        return;
+    }
+    let result =
+        get_config_parsed(store, "auto_gc").and_then(|enabled: Option<bool>| match enabled {
+            Some(false) => Ok(()),
+            _ => git_gc(store.path(), &["--auto"]),
+        });
+    if let Err(e) = result {
+        eprintln!("warning: {e:#}");
+    }
+}
+
+pub(crate) fn cmd_maintenance(store: &BlogData) -> anyhow::Result<()> {
+    let days = get_config_parsed(store, "tombstone_days")?.unwrap_or(DEFAULT_TOMBSTONE_DAYS);
+    let cutoff = Utc::now()
+        .checked_sub_signed(chrono::Duration::days(days.into()))
+        .with_context(|| {
+            format!("Invalid value for config tombstone_days: {days} is too many days")
+        })?;
+    let dropped = {
+        let _lock = store.lock()?;
+        let dropped = compact_store(store.path(), cutoff)?;
+        if dropped > 0 {
+            commit_store(store.path(), "compact store")?;
+        }
+        dropped
+    };
+    match dropped {
+        0 => eprintln!("No rows deleted more than {days} days ago."),
+        1 => eprintln!("Dropped 1 row deleted more than {days} days ago."),
+        n => eprintln!("Dropped {n} rows deleted more than {days} days ago."),
+    }
+
+    if store.path().join(".git").exists() {
+        let sp = spinner("Packing the git repository...");
+        git_gc(store.path(), &[])?;
+        sp.finish_with_message("Packing the git repository... done.");
+    }
+    Ok(())
+}
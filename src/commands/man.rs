@@ -0,0 +1,294 @@
+//! `blog man`: write man pages for `blog` and each of its commands from the
+//! clap definitions, for distributions to package.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Context;
+use clap::{Arg, Command};
+
+/// Escape `text` for roff: backslashes and dashes, and dots or quotes that
+/// would start a request at the beginning of a line.
+fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{line}")
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn bold(text: &str) -> String {
+    format!("\\fB{}\\fR", escape(text))
+}
+
+fn italic(text: &str) -> String {
+    format!("\\fI{}\\fR", escape(text))
+}
+
+fn value_name(arg: &Arg) -> String {
+    arg.get_value_names()
+        .and_then(|names| names.first())
+        .map_or_else(|| arg.get_id().as_str().to_uppercase(), |n| n.to_string())
+}
+
+/// How `arg` is written on the command line, e.g. `-n, --limit <N>`.
+fn usage(arg: &Arg) -> String {
+    if arg.is_positional() {
+        let name = format!("<{}>", value_name(arg));
+        let repeated = if arg.get_num_args().is_some_and(|n| n.max_values() > 1) {
+            "..."
+        } else {
+            ""
+        };
+        return if arg.is_required_set() {
+            italic(&format!("{name}{repeated}"))
+        } else {
+            italic(&format!("[{name}]{repeated}"))
+        };
+    }
+    let mut names = vec![];
+    if let Some(short) = arg.get_short() {
+        names.push(bold(&format!("-{short}")));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(bold(&format!("--{long}")));
+    }
+    let mut usage = names.join(", ");
+    if arg.get_action().takes_values() {
+        usage.push(' ');
+        usage.push_str(&italic(&format!("<{}>", value_name(arg))));
+    }
+    usage
+}
+
+/// What `arg` does, with its choices and default.
+fn description(arg: &Arg) -> String {
+    let mut text = arg
+        .get_long_help()
+        .or(arg.get_help())
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let possible = arg.get_possible_values();
+    let values: Vec<&str> = possible
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name())
+        .collect();
+    if !values.is_empty() && arg.get_action().takes_values() {
+        text.push_str(&format!(" [possible values: {}]", values.join(", ")));
+    }
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        text.push_str(&format!(" [default: {}]", defaults.join(",")));
+    }
+    escape(text.trim())
+}
+
+fn subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help")
+}
+
+/// The man page of `cmd`, reached from the command line as `path`, e.g.
+/// `["blog", "feed", "add"]`.
+fn render(cmd: &Command, path: &[&str]) -> String {
+    let page = path.join("-");
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        ".TH {} 1 \"\" \"{} {}\"",
+        escape(&page.to_uppercase()),
+        path[0],
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let about = cmd.get_about().map(ToString::to_string).unwrap_or_default();
+    let _ = writeln!(out, ".SH NAME");
+    let _ = writeln!(out, "{} \\- {}", escape(&page), escape(&about));
+
+    let args: Vec<&Arg> = cmd.get_arguments().filter(|a| !a.is_hide_set()).collect();
+    let _ = writeln!(out, ".SH SYNOPSIS");
+    let mut synopsis = vec![bold(&path.join(" "))];
+    if args.iter().any(|a| !a.is_positional()) {
+        synopsis.push(format!("[{}]", italic("OPTIONS")));
+    }
+    synopsis.extend(args.iter().filter(|a| a.is_positional()).map(|a| usage(a)));
+    if subcommands(cmd).next().is_some() {
+        synopsis.push(if cmd.is_subcommand_required_set() {
+            italic("<COMMAND>")
+        } else {
+            italic("[COMMAND]")
+        });
+    }
+    let _ = writeln!(out, "{}", synopsis.join(" "));
+
+    if let Some(long_about) = cmd.get_long_about() {
+        let _ = writeln!(out, ".SH DESCRIPTION");
+        let _ = writeln!(out, "{}", escape(&long_about.to_string()));
+    }
+
+    if !args.is_empty() {
+        let _ = writeln!(out, ".SH OPTIONS");
+        for arg in args {
+            let _ = writeln!(out, ".TP\n{}\n{}", usage(arg), description(arg));
+        }
+    }
+
+    if subcommands(cmd).next().is_some() {
+        let _ = writeln!(out, ".SH COMMANDS");
+        for sub in subcommands(cmd) {
+            let about = sub.get_about().map(ToString::to_string).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                ".TP\n{}(1)\n{}",
+                bold(&format!("{page}-{}", sub.get_name())),
+                escape(&about)
+            );
+        }
+    }
+
+    if let Some(after_help) = cmd.get_after_long_help().or(cmd.get_after_help()) {
+        // Laid out for the terminal already, so kept as it is
+        let _ = writeln!(
+            out,
+            ".SH EXTRA\n.nf\n{}\n.fi",
+            escape(&after_help.to_string())
+        );
+    }
+
+    if let Some((_, parent)) = path.split_last()
+        && !parent.is_empty()
+    {
+        let _ = writeln!(out, ".SH \"SEE ALSO\"\n{}(1)", bold(&parent.join("-")));
+    }
+    out
+}
+
+/// Write the page of `cmd` and of all its subcommands to `dir`, returning
+/// how many were written.
+fn write_pages(cmd: &Command, path: &mut Vec<String>, dir: &Path) -> anyhow::Result<usize> {
+    let names: Vec<&str> = path.iter().map(String::as_str).collect();
+    let file = dir.join(format!("{}.1", names.join("-")));
+    std::fs::write(&file, render(cmd, &names))
+        .with_context(|| format!("failed to write {}", file.display()))?;
+    let mut written = 1;
+    for sub in subcommands(cmd) {
+        path.push(sub.get_name().to_string());
+        written += write_pages(sub, path, dir)?;
+        path.pop();
+    }
+    Ok(written)
+}
+
+/// Write a man page for `cmd`, the whole command line, and one for each of
+/// its subcommands, e.g. `blog-feed-add.1`, into `dir`.
+pub(crate) fn cmd_man(mut cmd: Command, dir: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    // Fills in --help and the global options of subcommands
+    cmd.build();
+    let name = cmd.get_name().to_string();
+    let written = write_pages(&cmd, &mut vec![name], dir)?;
+    eprintln!("Wrote {written} man pages to {}.", dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ArgAction;
+    use rstest::rstest;
+
+    fn command() -> Command {
+        Command::new("blog")
+            .about("A simple RSS/Atom feed reader")
+            .arg(
+                Arg::new("store")
+                    .long("store")
+                    .value_name("PATH")
+                    .global(true)
+                    .help("Use the store at this path"),
+            )
+            .subcommand(
+                Command::new("feed")
+                    .about("Manage feed subscriptions")
+                    .subcommand(
+                        Command::new("add")
+                            .about("Subscribe to a feed")
+                            .arg(Arg::new("url").required(true).help("Feed URL"))
+                            .arg(
+                                Arg::new("yes")
+                                    .short('y')
+                                    .long("yes")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Don't ask"),
+                            ),
+                    ),
+            )
+    }
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case("--no-mark", "\\-\\-no\\-mark")]
+    #[case("C:\\store", "C:\\estore")]
+    #[case(".all shows all", "\\&.all shows all")]
+    fn test_escape(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(escape(input), expected);
+    }
+
+    #[test]
+    fn test_render_lists_options_and_subcommands() {
+        let mut cmd = command();
+        cmd.build();
+
+        let page = render(&cmd, &["blog"]);
+
+        assert!(page.starts_with(".TH BLOG 1"));
+        assert!(page.contains("blog \\- A simple RSS/Atom feed reader"));
+        assert!(page.contains("\\fB\\-\\-store\\fR \\fI<PATH>\\fR\nUse the store at this path"));
+        assert!(page.contains("\\fBblog\\-feed\\fR(1)\nManage feed subscriptions"));
+        assert!(!page.contains("SEE ALSO"));
+    }
+
+    #[test]
+    fn test_render_subcommand_page() {
+        let mut cmd = command();
+        cmd.build();
+        let add = cmd
+            .find_subcommand("feed")
+            .and_then(|feed| feed.find_subcommand("add"))
+            .unwrap();
+
+        let page = render(add, &["blog", "feed", "add"]);
+
+        assert!(page.starts_with(".TH BLOG\\-FEED\\-ADD 1"));
+        assert!(page.contains("\\fBblog feed add\\fR [\\fIOPTIONS\\fR] \\fI<URL>\\fR\n"));
+        assert!(page.contains("\\fB\\-y\\fR, \\fB\\-\\-yes\\fR\nDon't ask"));
+        // Global options are documented on every page
+        assert!(page.contains("\\fB\\-\\-store\\fR"));
+        assert!(page.contains(".SH \"SEE ALSO\"\n\\fBblog\\-feed\\fR(1)"));
+    }
+
+    #[test]
+    fn test_cmd_man_writes_a_page_per_command() {
+        let dir = tempfile::tempdir().unwrap();
+
+        cmd_man(command(), dir.path()).unwrap();
+
+        let mut pages: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        pages.sort();
+        assert_eq!(pages, ["blog-feed-add.1", "blog-feed.1", "blog.1"]);
+    }
+}
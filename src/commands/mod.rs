@@ -1,11 +1,37 @@
 pub mod add;
+pub mod api;
 pub mod clone;
 pub mod config;
+pub mod db;
+pub mod diff;
+pub mod digest;
+pub mod discover;
+pub mod discuss;
+pub mod doctor;
 pub mod export;
 pub mod feed_export;
+pub mod feed_info;
 pub mod feed_ls;
+pub mod folder;
+pub mod history;
 pub mod import;
+pub mod init;
+pub mod later;
+pub mod maintenance;
+pub mod man;
+pub mod new;
 pub mod open;
+pub mod pause;
+pub mod plugin;
 pub mod remove;
+pub mod retain;
+pub mod save;
+pub mod search;
+pub mod serve;
 pub mod show;
+pub mod snooze;
+pub mod suggest;
 pub mod sync;
+pub mod timeout;
+pub mod view;
+pub mod watch;
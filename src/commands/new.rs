@@ -0,0 +1,92 @@
+//! `blog new`: list the posts that arrived since the last `blog new`,
+//! whether read or not. Which posts were already listed is remembered on this
+//! machine only, outside the synced store, so every device has its own mark.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::data::schema::FeedItem;
+use crate::data::{BlogData, store_key};
+use crate::display::render_grouped;
+use crate::query::Query;
+use crate::query::resolve::resolve_posts;
+
+use super::show::{format_summary, listing_ctx, read_ids};
+
+/// File with the IDs of the posts this machine has seen in the store.
+fn seen_file(store: &BlogData) -> anyhow::Result<PathBuf> {
+    let dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .context("could not determine local state directory")?;
+    Ok(dir
+        .join("blogtato")
+        .join("seen")
+        .join(format!("{}.json", store_key(store))))
+}
+
+fn load_seen(path: &Path) -> anyhow::Result<Option<HashSet<String>>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Could not read {}", path.display()))
+            .map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Could not read {}", path.display())),
+    }
+}
+
+fn save_seen(path: &Path, seen: &HashSet<String>) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut ids: Vec<&String> = seen.iter().collect();
+    ids.sort();
+    std::fs::write(path, serde_json::to_string(&ids)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub(crate) fn cmd_new(store: &BlogData, query: &Query) -> anyhow::Result<()> {
+    let path = seen_file(store)?;
+    let current: HashSet<String> = store.posts().iter().map(|(id, _)| id.to_string()).collect();
+    let Some(seen) = load_seen(&path)? else {
+        save_seen(&path, &current)?;
+        eprintln!(
+            "Noted the {} posts in the store; from now on `blog new` lists the posts added after them.",
+            current.len()
+        );
+        return Ok(());
+    };
+
+    let mut resolved = resolve_posts(store, query)?;
+    resolved.items.retain(|(id, _)| !seen.contains(id));
+
+    // Posts that are gone don't need remembering; ones left out by the query
+    // stay new until a `blog new` lists them
+    let mut seen: HashSet<String> = seen.intersection(&current).cloned().collect();
+    seen.extend(resolved.items.iter().map(|(id, _)| id.clone()));
+
+    if resolved.items.is_empty() {
+        save_seen(&path, &seen)?;
+        eprintln!("No new posts since the last `blog new`.");
+        return Ok(());
+    }
+
+    let refs: Vec<&FeedItem> = resolved.items.iter().map(|(_, item)| item).collect();
+    let read_ids = read_ids(store);
+    let ctx = listing_ctx(
+        store,
+        &refs,
+        &query.keys,
+        &resolved.shorthands,
+        &resolved.feed_labels,
+        &read_ids,
+        false,
+    )?;
+    print!("{}", render_grouped(&refs, &ctx));
+    let query_text = format!("{query} new");
+    eprint!("{}", format_summary(&refs, query_text.trim(), ctx.color));
+
+    save_seen(&path, &seen)
+}
@@ -1,11 +1,50 @@
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+
 use anyhow::ensure;
 
-use crate::data::BlogData;
-use crate::data::schema::{FeedItem, ReadMark};
-use crate::query::Query;
+use crate::data::index::feed_index;
+use chrono::{DateTime, Utc};
+
+use crate::data::schema::{FeedItem, MetaEntry, ReadMark};
+use crate::data::{BlogData, Transaction, transact};
+use crate::display::{article_links, format_article};
 use crate::query::resolve::resolve_posts;
+use crate::query::{Query, QueryDate, ReadFilter};
+use crate::utils::date::local_date;
+use crate::utils::prompt::confirm;
+
+/// Open every post matching the query rather than a single one.
+pub(crate) struct OpenGroup {
+    /// Only the posts from this day
+    pub day: Option<QueryDate>,
+    /// Don't ask before opening them
+    pub yes: bool,
+}
+
+/// Open the single post matching `query`, or every one with `group`, and
+/// mark them read unless `mark` is unset.
+pub(crate) fn cmd_open(
+    store: &mut BlogData,
+    query: &Query,
+    group: Option<&OpenGroup>,
+    mark: bool,
+) -> anyhow::Result<()> {
+    if let Some(group) = group {
+        return open_group(store, query, group, mark);
+    }
+    if let Some(shorthand) = feed_only(query) {
+        let fi = feed_index(store.feeds());
+        let feed = fi
+            .feed_for_shorthand(shorthand)
+            .ok_or_else(|| anyhow::anyhow!("Unknown feed shorthand: @{shorthand}"))?;
+        ensure!(
+            !feed.site_url.is_empty(),
+            "Feed @{shorthand} has no website link"
+        );
+        return open_url(&feed.site_url);
+    }
 
-pub(crate) fn cmd_open(store: &mut BlogData, query: &Query) -> anyhow::Result<()> {
     let resolved = resolve_posts(store, query)?;
     ensure!(!resolved.items.is_empty(), "No matching posts");
     ensure!(
@@ -15,11 +54,84 @@ pub(crate) fn cmd_open(store: &mut BlogData, query: &Query) -> anyhow::Result<()
     );
     let (_, item) = &resolved.items[0];
     ensure!(!item.link.is_empty(), "Post has no link");
+    open_url(&item.link)?;
+    if mark {
+        mark_read_batch(store, &resolved.items)?;
+    }
+    Ok(())
+}
+
+/// Open the posts matching `query`, oldest first so that the browser's tabs
+/// are in order, after asking how many unless `group.yes` is set.
+fn open_group(
+    store: &mut BlogData,
+    query: &Query,
+    group: &OpenGroup,
+    mark: bool,
+) -> anyhow::Result<()> {
+    let mut resolved = resolve_posts(store, query)?;
+    if let Some(day) = &group.day {
+        let day = local_date(day.resolved);
+        resolved
+            .items
+            .retain(|(_, item)| item.date.is_some_and(|d| local_date(d) == day));
+    }
+    resolved.items.retain(|(_, item)| !item.link.is_empty());
+    ensure!(!resolved.items.is_empty(), "No matching posts with links");
+    resolved.items.reverse();
+
+    let count = resolved.items.len();
+    let posts = if count == 1 { "post" } else { "posts" };
+    if !group.yes && !confirm(&format!("Open {count} {posts} in the browser?"))? {
+        eprintln!("Nothing opened.");
+        return Ok(());
+    }
+    for (_, item) in &resolved.items {
+        open_url(&item.link)?;
+    }
+    if mark {
+        mark_read_batch(store, &resolved.items)?;
+    }
+    Ok(())
+}
+
+/// Open the comments page of the single post matching `query`, or print its
+/// URL when `plain` is set.
+pub(crate) fn cmd_comments(store: &BlogData, query: &Query, plain: bool) -> anyhow::Result<()> {
+    let resolved = resolve_posts(store, query)?;
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+    ensure!(
+        resolved.items.len() == 1,
+        "Expected exactly 1 post, got {}",
+        resolved.items.len()
+    );
+    let (_, item) = &resolved.items[0];
+    ensure!(!item.comments.is_empty(), "Post has no comments link");
+    if plain {
+        println!("{}", item.comments);
+        return Ok(());
+    }
+    open_url(&item.comments)
+}
+
+/// The feed shorthand when the query selects a feed and nothing else, as in
+/// `blog @hn open`.
+fn feed_only(query: &Query) -> Option<&str> {
+    let only_feed = query.keys.is_empty()
+        && query.shorthands.is_empty()
+        && query.id_filter.is_none()
+        && query.date_filter.since.is_none()
+        && query.date_filter.until.is_none()
+        && query.read_filter == ReadFilter::Any;
+    query.filter.as_deref().filter(|_| only_feed)
+}
+
+pub(crate) fn open_url(url: &str) -> anyhow::Result<()> {
     match std::env::var("BROWSER") {
         Ok(browser) => {
             // Run directly so TUI browsers (w3m, elinks) inherit the terminal
             let status = std::process::Command::new(&browser)
-                .arg(&item.link)
+                .arg(url)
                 .status()
                 .map_err(|e| anyhow::anyhow!("Could not open URL: {}", e))?;
             if !status.success() {
@@ -27,22 +139,182 @@ pub(crate) fn cmd_open(store: &mut BlogData, query: &Query) -> anyhow::Result<()
             }
         }
         Err(_) => {
-            open::that(&item.link).map_err(|e| anyhow::anyhow!("Could not open URL: {}", e))?;
+            open::that(url).map_err(|e| anyhow::anyhow!("Could not open URL: {}", e))?;
         }
     }
-    eprintln!("Opened in browser: {}", item.link);
-    mark_read_batch(store, &resolved.items)?;
+    eprintln!("Opened in browser: {url}");
     Ok(())
 }
 
-pub(crate) fn cmd_read(store: &mut BlogData, query: &Query) -> anyhow::Result<()> {
+/// Meta key of the post `blog read` showed last, which `--next` and `--prev`
+/// move on from. It is synced, so reading can go on from another machine.
+const LAST_READ_KEY: &str = "last_read";
+
+/// Which neighbour of the last read post to read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ReadStep {
+    /// The one listed after it
+    Next,
+    /// The one listed before it
+    Prev,
+}
+
+/// The post listed next to the last read one among `items`, in listing order.
+fn step_from_last_read<'a>(
+    store: &BlogData,
+    items: &'a [(String, FeedItem)],
+    step: ReadStep,
+) -> anyhow::Result<&'a [(String, FeedItem)]> {
+    let last = store
+        .meta()
+        .iter()
+        .find(|(_, e)| e.key == LAST_READ_KEY)
+        .map(|(_, e)| e.value.clone())
+        .ok_or_else(|| anyhow::anyhow!("No post read yet; start with `blog <shorthand> read`"))?;
+    let pos = items
+        .iter()
+        .position(|(_, item)| item.raw_id == last)
+        .ok_or_else(|| anyhow::anyhow!("The last read post isn't among the matching posts"))?;
+    let target = match step {
+        ReadStep::Next => Some(pos + 1).filter(|&i| i < items.len()),
+        ReadStep::Prev => pos.checked_sub(1),
+    };
+    match target {
+        Some(i) => Ok(&items[i..=i]),
+        None if step == ReadStep::Next => anyhow::bail!("The last read post is the last one"),
+        None => anyhow::bail!("The last read post is the first one"),
+    }
+}
+
+/// Print matching posts rendered for the terminal, or just their URLs when
+/// `plain` is set. With `link`, open that numbered link of a single post
+/// instead, or print it when `plain` is set. With `step`, only the post next
+/// to the last read one is read. The posts are marked read unless `mark` is
+/// unset.
+pub(crate) fn cmd_read(
+    store: &mut BlogData,
+    query: &Query,
+    plain: bool,
+    link: Option<usize>,
+    step: Option<ReadStep>,
+    mark: bool,
+) -> anyhow::Result<()> {
     let resolved = resolve_posts(store, query)?;
     ensure!(!resolved.items.is_empty(), "No matching posts");
-    for (_, item) in &resolved.items {
-        ensure!(!item.link.is_empty(), "Post has no link");
-        println!("{}", item.link);
+    let items = match step {
+        Some(step) => step_from_last_read(store, &resolved.items, step)?,
+        None => &resolved.items[..],
+    };
+    match link {
+        Some(n) => {
+            ensure!(
+                items.len() == 1,
+                "--link needs a single post, but {} match",
+                items.len()
+            );
+            let links = article_links(&items[0].1);
+            let url = match n.checked_sub(1).and_then(|i| links.get(i)) {
+                Some(url) => url,
+                None if links.is_empty() => anyhow::bail!("Post has no links"),
+                None => anyhow::bail!("No link [{n}]; the post has {} links", links.len()),
+            };
+            if plain {
+                println!("{url}");
+            } else {
+                open_url(url)?;
+            }
+        }
+        None => print_posts(items, &resolved.feed_labels, plain)?,
+    }
+    let items = items.to_vec();
+    let now = chrono::Utc::now();
+    let action = if mark {
+        "mark read"
+    } else {
+        "remember last read"
+    };
+    transact(store, action, |tx| {
+        if mark {
+            mark_read(tx, &items, now);
+        }
+        if let Some((_, last)) = items.last() {
+            tx.meta.upsert(MetaEntry {
+                key: LAST_READ_KEY.to_string(),
+                value: last.raw_id.clone(),
+            });
+        }
+        Ok(())
+    })
+}
+
+/// Print `items` rendered for the terminal, or just their URLs when `plain`
+/// is set.
+pub(crate) fn print_posts(
+    items: &[(String, FeedItem)],
+    feed_labels: &HashMap<String, String>,
+    plain: bool,
+) -> anyhow::Result<()> {
+    if plain {
+        for (_, item) in items {
+            ensure!(!item.link.is_empty(), "Post has no link");
+            println!("{}", item.link);
+        }
+    } else {
+        let color = std::io::stdout().is_terminal();
+        let max_width = terminal_size::terminal_size().map(|(w, _)| w.0 as usize);
+        let articles: Vec<String> = items
+            .iter()
+            .map(|(_, item)| {
+                let label = feed_labels
+                    .get(&item.feed)
+                    .map_or(item.feed.as_str(), String::as_str);
+                format_article(item, label, max_width, color)
+            })
+            .collect();
+        print!("{}", articles.join("\n"));
+    }
+    Ok(())
+}
+
+/// Position of the next post in `items` (sorted newest first): the oldest
+/// dated post, or the last undated one if none has a date.
+fn queue_head(items: &[(String, FeedItem)]) -> Option<usize> {
+    items
+        .iter()
+        .rposition(|(_, item)| item.date.is_some())
+        .or_else(|| items.len().checked_sub(1))
+}
+
+/// Read, or with `open` open, the oldest unread post matching `query`.
+/// Marking it read moves the queue on, so the position is kept on every
+/// synced machine.
+pub(crate) fn cmd_next(
+    store: &mut BlogData,
+    query: &Query,
+    plain: bool,
+    open: bool,
+) -> anyhow::Result<()> {
+    ensure!(
+        matches!(query.read_filter, ReadFilter::Any | ReadFilter::Unread),
+        "next command only goes through unread posts"
+    );
+    let mut query = query.clone();
+    query.read_filter = ReadFilter::Unread;
+    let resolved = resolve_posts(store, &query)?;
+    let head = queue_head(&resolved.items).ok_or_else(|| anyhow::anyhow!("No unread posts"))?;
+    let next = &resolved.items[head..=head];
+    if open {
+        ensure!(!next[0].1.link.is_empty(), "Post has no link");
+        open_url(&next[0].1.link)?;
+    } else {
+        print_posts(next, &resolved.feed_labels, plain)?;
+    }
+    mark_read_batch(store, next)?;
+    match resolved.items.len() - 1 {
+        0 => eprintln!("That was the last unread post."),
+        1 => eprintln!("1 unread post left."),
+        n => eprintln!("{n} unread posts left."),
     }
-    mark_read_batch(store, &resolved.items)?;
     Ok(())
 }
 
@@ -53,23 +325,34 @@ pub(crate) fn cmd_unread(store: &mut BlogData, query: &Query) -> anyhow::Result<
     Ok(())
 }
 
-fn mark_read_batch(store: &mut BlogData, items: &[(String, FeedItem)]) -> anyhow::Result<()> {
+pub(crate) fn mark_read_batch(
+    store: &mut BlogData,
+    items: &[(String, FeedItem)],
+) -> anyhow::Result<()> {
     let now = chrono::Utc::now();
-    store.transact("mark read", |tx| {
-        for (_, item) in items {
-            if !tx.reads.contains_key(&item.raw_id) {
-                tx.reads.upsert(ReadMark {
-                    post_id: item.raw_id.clone(),
-                    read_at: now,
-                });
-            }
-        }
+    transact(store, "mark read", |tx| {
+        mark_read(tx, items, now);
         Ok(())
     })
 }
 
+/// Mark `items` read, which also ends their snoozes.
+fn mark_read(tx: &mut Transaction<'_>, items: &[(String, FeedItem)], now: DateTime<Utc>) {
+    let post_ids: HashSet<&str> = items.iter().map(|(_, item)| item.raw_id.as_str()).collect();
+    tx.snoozes
+        .delete_where(|e| post_ids.contains(e.post_id.as_str()));
+    for (_, item) in items {
+        if !tx.reads.contains_key(&item.raw_id) {
+            tx.reads.upsert(ReadMark {
+                post_id: item.raw_id.clone(),
+                read_at: now,
+            });
+        }
+    }
+}
+
 fn mark_unread_batch(store: &mut BlogData, items: &[(String, FeedItem)]) -> anyhow::Result<()> {
-    store.transact("mark unread", |tx| {
+    transact(store, "mark unread", |tx| {
         for (_, item) in items {
             tx.reads.delete(&item.raw_id);
         }
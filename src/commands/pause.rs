@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+
+use crate::data::Transaction;
+use crate::data::index::resolve_feed_arg;
+
+/// Set whether the feed named by `feed` (URL or @shorthand) is paused, and
+/// whether its posts are hidden meanwhile. Returns the feed's URL.
+pub(crate) fn cmd_set_paused(
+    tx: &mut Transaction,
+    feed: &str,
+    paused: bool,
+    hidden: bool,
+) -> anyhow::Result<String> {
+    let url = resolve_feed_arg(tx.feeds, feed)?;
+    let mut source = tx
+        .feeds
+        .get(&url)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+    source.paused = paused;
+    source.hidden = paused && hidden;
+    if !paused {
+        source.snoozed_until = None;
+    }
+    // Resuming an archived feed subscribes to it again
+    source.archived &= paused;
+    tx.feeds.upsert(source);
+    Ok(url)
+}
+
+/// Snooze the feed named by `feed` (URL or @shorthand) until `until`: it
+/// isn't fetched and its posts are hidden until then, or until it is
+/// resumed. Returns the feed's URL.
+pub(crate) fn cmd_snooze(
+    tx: &mut Transaction,
+    feed: &str,
+    until: DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let url = resolve_feed_arg(tx.feeds, feed)?;
+    let mut source = tx
+        .feeds
+        .get(&url)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+    source.snoozed_until = Some(until);
+    tx.feeds.upsert(source);
+    Ok(url)
+}
@@ -0,0 +1,92 @@
+//! External subcommands: like git and cargo, `blog podcast ...` runs a
+//! `blog-podcast` executable found on PATH, so extensions don't need to patch
+//! blogtato. The plugin gets the store path in `RSS_STORE`, so it can read the
+//! store directly or run `blog` against the same store.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Context;
+
+/// Prefix of plugin executables.
+const PLUGIN_PREFIX: &str = "blog-";
+
+/// Whether `name` could be a plugin. Queries like `@hn`, `~rust` or `3d..`
+/// never are, so they don't cost a PATH lookup.
+fn is_plugin_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The `blog-<name>` executable in the directories of `path` (a PATH value).
+pub(crate) fn find_plugin(name: &str, path: Option<&OsStr>) -> Option<PathBuf> {
+    if !is_plugin_name(name) {
+        return None;
+    }
+    let file = format!("{PLUGIN_PREFIX}{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path?)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Run `plugin` with `args` on the store at `store`, returning its exit code.
+pub(crate) fn run_plugin(plugin: &Path, store: &Path, args: &[String]) -> anyhow::Result<i32> {
+    let status = Command::new(plugin)
+        .args(args)
+        .env("RSS_STORE", store)
+        .status()
+        .with_context(|| format!("Could not run {}", plugin.display()))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::word("podcast", true)]
+    #[case::dashes("podcast-dl_2", true)]
+    #[case::feed("@hn", false)]
+    #[case::search("~rust", false)]
+    #[case::date_range("3d..", false)]
+    #[case::grouping("/d", false)]
+    #[case::path("../podcast", false)]
+    fn test_is_plugin_name(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(is_plugin_name(name), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_plugin_needs_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::TempDir::new().unwrap();
+        let second = tempfile::TempDir::new().unwrap();
+        let not_executable = first.path().join("blog-podcast");
+        std::fs::write(&not_executable, "").unwrap();
+        let plugin = second.path().join("blog-podcast");
+        std::fs::write(&plugin, "").unwrap();
+        std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+
+        assert_eq!(find_plugin("podcast", Some(&path)), Some(plugin));
+        assert_eq!(find_plugin("missing", Some(&path)), None);
+        assert_eq!(find_plugin("podcast", None), None);
+    }
+}
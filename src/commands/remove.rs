@@ -1,15 +1,13 @@
+use std::collections::HashMap;
+
 use anyhow::bail;
 
-use crate::data::Transaction;
-use crate::data::index::resolve_shorthand;
+use crate::data::index::resolve_feed_arg;
+use crate::data::{BlogData, Transaction, transact};
+use crate::utils::prompt::confirm;
 
-pub(crate) fn cmd_remove(tx: &mut Transaction, url: &str) -> anyhow::Result<()> {
-    let url = if let Some(shorthand) = url.strip_prefix('@') {
-        resolve_shorthand(tx.feeds, shorthand)
-            .ok_or_else(|| anyhow::anyhow!("Unknown feed shorthand: @{}", shorthand))?
-    } else {
-        url.to_string()
-    };
+fn cmd_remove(tx: &mut Transaction, url: &str) -> anyhow::Result<()> {
+    let url = resolve_feed_arg(tx.feeds, url)?;
 
     match tx.feeds.delete(&url) {
         Some(feed_id) => {
@@ -20,3 +18,98 @@ pub(crate) fn cmd_remove(tx: &mut Transaction, url: &str) -> anyhow::Result<()>
 
     Ok(())
 }
+
+/// Unsubscribe from `url` but keep its posts, marking the feed archived. It is
+/// paused so it isn't synced anymore; resuming or re-adding it subscribes again.
+fn archive(tx: &mut Transaction, url: &str) -> anyhow::Result<()> {
+    let Some(mut source) = tx.feeds.get(url).cloned() else {
+        bail!("Feed not found: {}", url);
+    };
+    source.paused = true;
+    source.hidden = false;
+    source.archived = true;
+    tx.feeds.upsert(source);
+    Ok(())
+}
+
+/// Unsubscribe from the feeds given by URL or @shorthand, after listing them
+/// with their post counts and asking for confirmation unless `yes` is set.
+/// With `keep_posts` the feeds are archived instead of deleted.
+pub(crate) fn cmd_remove_feeds(
+    store: &mut BlogData,
+    args: &[String],
+    yes: bool,
+    keep_posts: bool,
+) -> anyhow::Result<()> {
+    let mut urls: Vec<String> = Vec::new();
+    for arg in args.iter().filter(|arg| !arg.is_empty()) {
+        let url = resolve_feed_arg(store.feeds(), arg)?;
+        if store.feeds().get(&url).is_none() {
+            bail!("Feed not found: {}", url);
+        }
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    if urls.is_empty() {
+        return Ok(());
+    }
+
+    if keep_posts {
+        let message = match urls.as_slice() {
+            [url] => format!("archive {url}"),
+            _ => format!("archive {} feeds", urls.len()),
+        };
+        transact(store, &message, |tx| {
+            for url in &urls {
+                archive(tx, url)?;
+            }
+            Ok(())
+        })?;
+        for url in &urls {
+            eprintln!("Archived {url}; its posts are kept");
+        }
+        return Ok(());
+    }
+
+    if !yes {
+        eprintln!("This will remove:");
+        // One pass over the posts, however many feeds are removed
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, post) in store.posts().iter() {
+            *counts.entry(post.feed.as_str()).or_default() += 1;
+        }
+        let mut total = 0;
+        for url in &urls {
+            let feed = store.feeds().get(url).unwrap();
+            let feed_id = store.feeds().id_of(feed);
+            let posts = counts.get(feed_id.as_str()).copied().unwrap_or(0);
+            total += posts;
+            let title = if feed.title.is_empty() {
+                String::new()
+            } else {
+                format!("{} ", feed.title)
+            };
+            eprintln!("  {title}{url} ({posts} posts)");
+        }
+        let feeds = if urls.len() == 1 { "feed" } else { "feeds" };
+        if !confirm(&format!("Remove {} {feeds} and {total} posts?", urls.len()))? {
+            bail!("Aborted; nothing was removed");
+        }
+    }
+
+    let message = match urls.as_slice() {
+        [url] => format!("remove {url}"),
+        _ => format!("remove {} feeds", urls.len()),
+    };
+    transact(store, &message, |tx| {
+        for url in &urls {
+            cmd_remove(tx, url)?;
+        }
+        Ok(())
+    })?;
+    for url in &urls {
+        eprintln!("Removed {url}");
+    }
+    Ok(())
+}
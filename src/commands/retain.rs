@@ -0,0 +1,25 @@
+use crate::data::Transaction;
+use crate::data::index::resolve_feed_arg;
+use crate::feed::retention::Retention;
+
+/// Set the retention policy of the feed named by `feed` (URL or @shorthand),
+/// or go back to the `retention` config key when `policy` is `default`.
+/// Returns the feed's URL.
+pub(crate) fn cmd_retain(tx: &mut Transaction, feed: &str, policy: &str) -> anyhow::Result<String> {
+    let url = resolve_feed_arg(tx.feeds, feed)?;
+    let mut source = tx
+        .feeds
+        .get(&url)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+    source.retention = if policy == "default" {
+        String::new()
+    } else {
+        let retention: Retention = policy
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid retention {policy:?}: {e}"))?;
+        retention.to_string()
+    };
+    tx.feeds.upsert(source);
+    Ok(url)
+}
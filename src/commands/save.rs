@@ -0,0 +1,142 @@
+//! Sending posts to a read-it-later or bookmark service.
+//!
+//! The service is picked with the `save_service` and `save_url` config keys.
+//! Its API token is read from [`TOKEN_ENV`] so it never ends up in the store.
+
+use std::str::FromStr;
+
+use anyhow::{Context, ensure};
+use serde_json::json;
+
+use crate::data::schema::FeedItem;
+use crate::data::{BlogData, get_config_parsed, get_config_value};
+use crate::query::Query;
+use crate::query::resolve::resolve_posts;
+
+const TOKEN_ENV: &str = "BLOG_SAVE_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SaveService {
+    Wallabag,
+    Linkding,
+    Shiori,
+}
+
+impl FromStr for SaveService {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "wallabag" => Ok(Self::Wallabag),
+            "linkding" => Ok(Self::Linkding),
+            "shiori" => Ok(Self::Shiori),
+            _ => anyhow::bail!("expected wallabag, linkding or shiori"),
+        }
+    }
+}
+
+/// An API request that saves one post.
+#[derive(Debug, PartialEq)]
+struct SaveRequest {
+    url: String,
+    auth: (&'static str, String),
+    body: serde_json::Value,
+}
+
+impl SaveService {
+    fn request(self, base_url: &str, token: &str, post: &FeedItem) -> SaveRequest {
+        let base_url = base_url.trim_end_matches('/');
+        match self {
+            Self::Wallabag => SaveRequest {
+                url: format!("{base_url}/api/entries.json"),
+                auth: ("Authorization", format!("Bearer {token}")),
+                body: json!({"url": post.link, "title": post.title}),
+            },
+            Self::Linkding => SaveRequest {
+                url: format!("{base_url}/api/bookmarks/"),
+                auth: ("Authorization", format!("Token {token}")),
+                body: json!({"url": post.link, "title": post.title}),
+            },
+            Self::Shiori => SaveRequest {
+                url: format!("{base_url}/api/bookmarks"),
+                auth: ("X-Session-Id", token.to_string()),
+                body: json!({"url": post.link, "title": post.title, "createArchive": false}),
+            },
+        }
+    }
+}
+
+pub(crate) fn cmd_save(store: &BlogData, query: &Query) -> anyhow::Result<()> {
+    let service: SaveService = get_config_parsed(store, "save_service")?.context(
+        "Set the service with `blog config set save_service <wallabag|linkding|shiori>`",
+    )?;
+    let base_url = get_config_value(store, "save_url")
+        .context("Set the service address with `blog config set save_url <URL>`")?;
+    let token = std::env::var(TOKEN_ENV)
+        .map_err(|_| anyhow::anyhow!("Set {TOKEN_ENV} to your API token for {base_url}"))?;
+
+    let resolved = resolve_posts(store, query)?;
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+    for (_, post) in &resolved.items {
+        ensure!(!post.link.is_empty(), "Post has no link: {}", post.title);
+    }
+
+    let http = crate::utils::http::HttpOptions::from_config(store)?;
+    let agent = crate::utils::http::http_client(&http);
+    for (_, post) in &resolved.items {
+        let request = service.request(&base_url, &token, post);
+        agent
+            .post(&request.url)
+            .header(request.auth.0, &request.auth.1)
+            .header("Content-Type", "application/json")
+            .send(request.body.to_string())
+            .with_context(|| format!("Failed to save {}", post.link))?;
+        eprintln!("Saved {}", post.link);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::wallabag(
+        SaveService::Wallabag,
+        "https://bag.example/api/entries.json",
+        ("Authorization", "Bearer t0k")
+    )]
+    #[case::linkding(
+        SaveService::Linkding,
+        "https://bag.example/api/bookmarks/",
+        ("Authorization", "Token t0k")
+    )]
+    #[case::shiori(
+        SaveService::Shiori,
+        "https://bag.example/api/bookmarks",
+        ("X-Session-Id", "t0k")
+    )]
+    fn test_request(#[case] service: SaveService, #[case] url: &str, #[case] auth: (&str, &str)) {
+        let post = FeedItem {
+            title: "Hello".to_string(),
+            link: "https://blog.example/hello".to_string(),
+            ..Default::default()
+        };
+        let request = service.request("https://bag.example/", "t0k", &post);
+
+        assert_eq!(request.url, url);
+        assert_eq!((request.auth.0, request.auth.1.as_str()), auth);
+        assert_eq!(request.body["url"], "https://blog.example/hello");
+        assert_eq!(request.body["title"], "Hello");
+    }
+
+    #[test]
+    fn test_parse_service() {
+        assert_eq!(
+            "linkding".parse::<SaveService>().unwrap(),
+            SaveService::Linkding
+        );
+        assert!("pocket".parse::<SaveService>().is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! `blog search`: ranked full-text search over post titles and summaries.
+//!
+//! Results are numbered by rank, so `blog search rust --open 1` opens the top
+//! hit without looking up its shorthand.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, ensure};
+use chrono::Duration;
+
+use crate::commands::open::{mark_read_batch, open_url};
+use crate::commands::show::{listing_ctx, read_ids};
+use crate::data::BlogData;
+use crate::data::index::{FeedIndex, feed_index};
+use crate::data::schema::FeedItem;
+use crate::data::search::{SearchIndex, SearchQuery};
+use crate::display::{RenderCtx, build_feed_labels, render_grouped};
+use crate::query::QueryDate;
+use crate::query::resolve::post_index;
+
+pub(crate) const SEARCH_DEFAULT_LIMIT: usize = 20;
+
+/// Split `text` into words, keeping `"quoted phrases"` together.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Narrow `query` by a `date:` condition such as `>2024-01-01`, `<=1w` or
+/// `2024-03-15` (that day only).
+fn apply_date(query: &mut SearchQuery, condition: &str) -> anyhow::Result<()> {
+    let (op, value) = match condition.find(|c: char| !matches!(c, '<' | '>' | '=')) {
+        Some(i) => condition.split_at(i),
+        None => bail!("Expected a date after date:{condition}"),
+    };
+    let day = value.parse::<QueryDate>()?.resolved;
+    let next_day = day + Duration::days(1);
+    match op {
+        ">" => query.since = Some(next_day),
+        ">=" => query.since = Some(day),
+        "<" => query.until = Some(day),
+        "<=" => query.until = Some(next_day),
+        "" | "=" => {
+            query.since = Some(day);
+            query.until = Some(next_day);
+        }
+        _ => bail!("Invalid date condition: date:{condition}"),
+    }
+    Ok(())
+}
+
+/// Read the search terms, taking `feed:@x` and `date:` filters out of the
+/// text that goes to the index.
+fn parse_search(args: &[String], fi: &FeedIndex) -> anyhow::Result<SearchQuery> {
+    let mut query = SearchQuery::default();
+    let mut words = Vec::new();
+    for token in tokenize(&args.join(" ")) {
+        if let Some(feed) = token.strip_prefix("feed:") {
+            let Some(shorthand) = feed.strip_prefix('@') else {
+                bail!("Expected feed:@shorthand, got {token}");
+            };
+            let id = fi
+                .id_for_shorthand(shorthand)
+                .ok_or_else(|| anyhow::anyhow!("Unknown feed shorthand: @{shorthand}"))?;
+            query.feed = Some(id.to_string());
+        } else if let Some(condition) = token.strip_prefix("date:") {
+            apply_date(&mut query, condition)?;
+        } else {
+            words.push(token);
+        }
+    }
+    query.text = words.join(" ");
+    Ok(query)
+}
+
+/// Lowercase words of the search `text` to highlight in results, leaving out
+/// operators, field names and excluded words.
+fn highlight_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for token in tokenize(text) {
+        if token.starts_with('-') || matches!(token.as_str(), "AND" | "OR" | "NOT") {
+            continue;
+        }
+        let token = token.trim_start_matches('+');
+        let value = match token.split_once(':') {
+            Some((field, value)) if field.chars().all(char::is_alphabetic) => value,
+            _ => token,
+        };
+        terms.extend(
+            value
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase),
+        );
+    }
+    terms
+}
+
+/// The listing of `items` with each post numbered by its rank.
+fn format_ranked(items: &[&FeedItem], ctx: &RenderCtx) -> String {
+    let width = items.len().to_string().len();
+    let mut out = String::new();
+    for (rank, item) in items.iter().enumerate() {
+        for (i, line) in render_grouped(&[item], ctx).lines().enumerate() {
+            let number = if i == 0 {
+                (rank + 1).to_string()
+            } else {
+                String::new()
+            };
+            out.push_str(&format!("{number:>width$} {line}\n"));
+        }
+    }
+    out
+}
+
+/// Search the posts of `store`, printing the best `limit` matches or, with
+/// `open`, opening the match with that rank.
+pub(crate) fn cmd_search(
+    store: &mut BlogData,
+    args: &[String],
+    limit: usize,
+    long: bool,
+    open: Option<usize>,
+) -> anyhow::Result<()> {
+    let fi = feed_index(store.feeds());
+    let query = parse_search(args, &fi)?;
+
+    let index = SearchIndex::open(store)?;
+    index.update(store)?;
+    let ids = index.search(&query, limit.max(open.unwrap_or(0)))?;
+
+    let by_id: HashMap<&str, &FeedItem> = store.posts().iter().collect();
+    let results: Vec<(String, FeedItem)> = ids
+        .iter()
+        .filter_map(|id| {
+            by_id
+                .get(id.as_str())
+                .map(|&item| (id.clone(), item.clone()))
+        })
+        .collect();
+    ensure!(!results.is_empty(), "No matching posts");
+
+    if let Some(n) = open {
+        let Some(result) = n.checked_sub(1).and_then(|i| results.get(i)) else {
+            bail!("No result {n}; the search found {}", results.len());
+        };
+        ensure!(!result.1.link.is_empty(), "Post has no link");
+        open_url(&result.1.link)?;
+        return mark_read_batch(store, std::slice::from_ref(result));
+    }
+
+    let items: Vec<&FeedItem> = results.iter().map(|(_, item)| item).collect();
+    let posts = post_index(store);
+    let shorthands: HashMap<String, String> = results
+        .iter()
+        .filter_map(|(_, item)| {
+            let shorthand = posts.shorthand(&item.raw_id)?;
+            Some((item.raw_id.clone(), shorthand.to_string()))
+        })
+        .collect();
+    let read_ids = read_ids(store);
+    let feed_labels = build_feed_labels(&fi);
+    let terms = highlight_terms(&query.text);
+    let mut ctx = listing_ctx(
+        store,
+        &items,
+        &[],
+        &shorthands,
+        &feed_labels,
+        &read_ids,
+        long,
+    )?;
+    // Leave room for the rank column
+    ctx.max_width = ctx
+        .max_width
+        .map(|w| w.saturating_sub(items.len().to_string().len() + 1));
+    ctx.title_width = RenderCtx::title_width_from(&items, ctx.max_width);
+    ctx.highlight = &terms;
+    print!("{}", format_ranked(&items, &ctx));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::words("Rust async", &["rust", "async"])]
+    #[case::phrase("\"borrow checker\"", &["borrow", "checker"])]
+    #[case::operators("rust OR go -java +wasm", &["rust", "go", "wasm"])]
+    #[case::field("title:Rust", &["rust"])]
+    fn test_highlight_terms(#[case] text: &str, #[case] expected: &[&str]) {
+        assert_eq!(highlight_terms(text), expected);
+    }
+
+    #[test]
+    fn test_tokenize_keeps_phrases() {
+        assert_eq!(
+            tokenize(r#"rust "borrow checker" date:>2024-01-01"#),
+            ["rust", "\"borrow checker\"", "date:>2024-01-01"]
+        );
+    }
+
+    fn day(d: u32) -> Option<chrono::DateTime<Utc>> {
+        Some(Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap())
+    }
+
+    #[rstest]
+    #[case::after(">2024-01-10", day(11), None)]
+    #[case::from(">=2024-01-10", day(10), None)]
+    #[case::before("<2024-01-10", None, day(10))]
+    #[case::through("<=2024-01-10", None, day(11))]
+    #[case::on("2024-01-10", day(10), day(11))]
+    fn test_apply_date(
+        #[case] condition: &str,
+        #[case] since: Option<chrono::DateTime<Utc>>,
+        #[case] until: Option<chrono::DateTime<Utc>>,
+    ) {
+        let mut query = SearchQuery::default();
+        apply_date(&mut query, condition).unwrap();
+        assert_eq!((query.since, query.until), (since, until));
+    }
+
+    #[rstest]
+    #[case::no_date(">")]
+    #[case::bad_operator("=>2024-01-01")]
+    #[case::bad_date(">soon")]
+    fn test_apply_date_invalid(#[case] condition: &str) {
+        assert!(apply_date(&mut SearchQuery::default(), condition).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! `blog serve`: the store over HTTP. `--web ADDR` serves a read-only web
+//! interface for skimming posts in a browser on the same machine: `/` lists
+//! the posts of the query in `?q=`, grouped as it says, and
+//! `/post/<shorthand>` shows one post with the content its feed gave.
+//! `--api ADDR` serves the JSON API of [`api`].
+
+use std::fmt::Write as _;
+use std::net::TcpListener;
+use std::path::Path;
+
+use anyhow::{Context, ensure};
+
+use crate::commands::api;
+use crate::commands::show::{listing_ctx, read_ids};
+use crate::commands::view::expand_views;
+use crate::data::schema::FeedItem;
+use crate::data::{BlogData, get_config_value};
+use crate::display::{RenderCtx, group_posts};
+use crate::query::resolve::resolve_posts;
+use crate::query::{DEFAULT_QUERY, GroupKey, Query, parse_query, parse_query_str};
+use crate::utils::date::format_day;
+use crate::utils::html::{escape, to_xhtml};
+use crate::utils::server::{Request, Response, serve};
+
+/// Browsers only send GETs here, so anything bigger isn't from one.
+const MAX_BODY: usize = 64 * 1024;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:50em;margin:auto;padding:1em;line-height:1.4}\
+.meta{color:#777;font-size:90%}li.read a{color:#777}img{max-width:100%}";
+
+/// A page and the status it is served with.
+#[derive(Debug, PartialEq)]
+struct Page {
+    status: u16,
+    body: String,
+}
+
+fn page(status: u16, title: &str, content: &str) -> Page {
+    Page {
+        status,
+        body: format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{STYLE}</style></head>\n<body>\n{content}</body></html>\n",
+            escape(title)
+        ),
+    }
+}
+
+fn error_page(status: u16, message: &str) -> Page {
+    page(
+        status,
+        message,
+        &format!(
+            "<p><a href=\"/\">Posts</a></p>\n<p>{}</p>\n",
+            escape(message)
+        ),
+    )
+}
+
+fn query_form(query_text: &str) -> String {
+    format!(
+        "<form action=\"/\"><input name=\"q\" size=\"40\" value=\"{}\"> <button>Show</button></form>\n",
+        escape(query_text)
+    )
+}
+
+/// A post as a list entry linking to its page, with the feed and date
+/// unless the posts are grouped by them.
+fn render_item(out: &mut String, item: &FeedItem, ctx: &RenderCtx) {
+    let class = if ctx.read_ids.contains(&item.raw_id) {
+        "read"
+    } else {
+        "unread"
+    };
+    let shorthand = ctx.shorthands.get(&item.raw_id).map_or("", String::as_str);
+    let mut meta = vec![];
+    if !ctx.all_keys.contains(&GroupKey::Feed) {
+        let label = ctx
+            .feed_labels
+            .get(&item.feed)
+            .map_or(item.feed.as_str(), String::as_str);
+        meta.push(label.to_string());
+    }
+    if !ctx.all_keys.contains(&GroupKey::Date)
+        && let Some(date) = item.date
+    {
+        meta.push(format_day(date));
+    }
+    let _ = writeln!(
+        out,
+        "<li class=\"{class}\"><a href=\"/post/{shorthand}\">{}</a> <span class=\"meta\">{}</span></li>",
+        escape(&item.title),
+        escape(&meta.join(" \u{b7} "))
+    );
+}
+
+/// `items` as nested sections, one level per group key, like `blog show`.
+fn render_groups(
+    out: &mut String,
+    items: &[&FeedItem],
+    keys: &[GroupKey],
+    depth: usize,
+    ctx: &RenderCtx,
+) {
+    let Some((&key, rest)) = keys.split_first() else {
+        out.push_str("<ul>\n");
+        for item in items {
+            render_item(out, item, ctx);
+        }
+        out.push_str("</ul>\n");
+        return;
+    };
+    let heading = if depth == 0 { "h2" } else { "h3" };
+    for (name, group) in group_posts(key, items, ctx) {
+        let _ = writeln!(
+            out,
+            "<section>\n<{heading}>{} ({})</{heading}>",
+            escape(&name),
+            group.len()
+        );
+        render_groups(out, &group, rest, depth + 1, ctx);
+        out.push_str("</section>\n");
+    }
+}
+
+/// The query written as `query_text`, or the default query when it is empty.
+pub(crate) fn query_from_text(store: &BlogData, query_text: &str) -> anyhow::Result<Query> {
+    if query_text.trim().is_empty() {
+        let default = get_config_value(store, "default_query");
+        parse_query_str(default.as_deref().unwrap_or(DEFAULT_QUERY))
+    } else {
+        let args = query_text.split_whitespace().map(String::from).collect();
+        parse_query(&expand_views(args, store)?)
+    }
+}
+
+/// The listing of the posts matching `query_text`.
+fn listing(store: &BlogData, query_text: &str) -> anyhow::Result<Page> {
+    let query = query_from_text(store, query_text)?;
+    let resolved = resolve_posts(store, &query)?;
+    let items: Vec<&FeedItem> = resolved.items.iter().map(|(_, item)| item).collect();
+    let read_ids = read_ids(store);
+    let mut ctx = listing_ctx(
+        store,
+        &items,
+        &query.keys,
+        &resolved.shorthands,
+        &resolved.feed_labels,
+        &read_ids,
+        false,
+    )?;
+    ctx.color = false;
+
+    let text = query.to_string();
+    let mut content = query_form(&text);
+    if items.is_empty() {
+        content.push_str("<p>No matching posts</p>\n");
+    } else {
+        let keys: Vec<GroupKey> = query
+            .keys
+            .iter()
+            .copied()
+            .filter(|key| *key != GroupKey::None)
+            .collect();
+        let _ = writeln!(content, "<p class=\"meta\">{} post(s)</p>", items.len());
+        render_groups(&mut content, &items, &keys, 0, &ctx);
+    }
+    Ok(page(200, &text, &content))
+}
+
+/// The page of the post named by `shorthand`, with its content cleaned up
+/// to simple formatting.
+fn article(store: &BlogData, shorthand: &str) -> anyhow::Result<Page> {
+    if shorthand.is_empty() || !shorthand.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Ok(error_page(404, "Not Found"));
+    }
+    let query = parse_query(&[shorthand.to_string()])?;
+    let resolved = match resolve_posts(store, &query) {
+        Ok(resolved) if resolved.items.len() == 1 => resolved,
+        _ => return Ok(error_page(404, &format!("No post {shorthand}"))),
+    };
+    let item = &resolved.items[0].1;
+    let mut meta = vec![
+        resolved
+            .feed_labels
+            .get(&item.feed)
+            .cloned()
+            .unwrap_or_else(|| item.feed.clone()),
+    ];
+    if !item.author.is_empty() {
+        meta.push(item.author.clone());
+    }
+    if let Some(date) = item.date {
+        meta.push(format_day(date));
+    }
+    let mut content = format!(
+        "<p><a href=\"/\">Posts</a></p>\n<article>\n<h1>{}</h1>\n<p class=\"meta\">{}</p>\n",
+        escape(&item.title),
+        escape(&meta.join(" \u{b7} "))
+    );
+    // Anything but a web link, e.g. `javascript:`, stays text
+    if item.link.starts_with("https://") || item.link.starts_with("http://") {
+        let _ = writeln!(
+            content,
+            "<p><a href=\"{0}\">{0}</a></p>",
+            escape(&item.link)
+        );
+    }
+    content.push_str(&to_xhtml(item.body()));
+    for image in &item.images {
+        if image.starts_with("https://") || image.starts_with("http://") {
+            let _ = writeln!(content, "<p><img src=\"{}\" alt=\"\"></p>", escape(image));
+        }
+    }
+    content.push_str("\n</article>\n");
+    Ok(page(200, &item.title, &content))
+}
+
+fn handle(store: &BlogData, request: &Request) -> Page {
+    if request.method != "GET" {
+        return error_page(405, "Method Not Allowed");
+    }
+    let result = match request.path.as_str() {
+        "/" => listing(store, request.query.get("q").map_or("", String::as_str)),
+        path => match path.strip_prefix("/post/") {
+            Some(shorthand) => article(store, shorthand),
+            None => Ok(error_page(404, "Not Found")),
+        },
+    };
+    result.unwrap_or_else(|e| error_page(400, &format!("{e:#}")))
+}
+
+/// Answer `request` to the web interface, reading the store at
+/// `store_path` again so that the pages follow syncs made meanwhile.
+fn respond(store_path: &Path, request: &Request) -> Response {
+    let page = match BlogData::open(store_path) {
+        Ok(store) => handle(&store, request),
+        Err(e) => error_page(500, &format!("{e:#}")),
+    };
+    Response {
+        status: page.status,
+        content_type: "text/html; charset=utf-8",
+        body: page.body,
+    }
+}
+
+/// Where `blog serve` listens.
+pub(crate) struct ServeOptions {
+    /// Address of the web interface
+    pub web: Option<String>,
+    /// Address of the JSON API
+    pub api: Option<String>,
+}
+
+fn bind(addr: &str) -> anyhow::Result<TcpListener> {
+    TcpListener::bind(addr).with_context(|| format!("Failed to listen on {addr}"))
+}
+
+/// Serve the store at `store_path` as `opts` says until interrupted.
+pub(crate) fn cmd_serve(store_path: &Path, opts: &ServeOptions) -> anyhow::Result<()> {
+    ensure!(
+        opts.web.is_some() || opts.api.is_some(),
+        "Nothing to serve: pass --web ADDR, --api ADDR or both"
+    );
+    // Checked before listening, so a missing token fails right away
+    let token = match opts.api {
+        Some(_) => Some(api::token()?),
+        None => None,
+    };
+    let web = opts.web.as_deref().map(bind).transpose()?;
+    let api = opts.api.as_deref().map(bind).transpose()?;
+    std::thread::scope(|scope| {
+        if let (Some(addr), Some(listener)) = (&opts.web, web) {
+            eprintln!("Serving the web interface on http://{addr}/");
+            scope.spawn(move || serve(listener, MAX_BODY, |request| respond(store_path, request)));
+        }
+        if let (Some(addr), Some(listener), Some(token)) = (&opts.api, api, token) {
+            eprintln!("Serving the API on http://{addr}/");
+            scope.spawn(move || {
+                serve(listener, api::MAX_BODY, |request| {
+                    api::respond(store_path, &token, request)
+                })
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_escapes_title() {
+        let page = page(200, "<b>Tom & Jerry</b>", "<p>hi</p>\n");
+        assert!(
+            page.body
+                .contains("<title>&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;</title>")
+        );
+        assert!(page.body.contains("<body>\n<p>hi</p>\n</body>"));
+    }
+
+    #[test]
+    fn test_query_form_keeps_query() {
+        assert_eq!(
+            query_form("@blog \"x\""),
+            "<form action=\"/\"><input name=\"q\" size=\"40\" value=\"@blog &quot;x&quot;\"> <button>Show</button></form>\n"
+        );
+    }
+}
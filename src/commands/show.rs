@@ -1,42 +1,244 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::IsTerminal;
 
 use anyhow::ensure;
 
-use crate::data::BlogData;
+use crate::commands::snooze::due_ids;
+use crate::data::index::feed_index;
 use crate::data::schema::FeedItem;
-use crate::display::{RenderCtx, Style, render_grouped};
-use crate::query::Query;
+use crate::data::{BlogData, get_config_parsed};
+use crate::display::{RenderCtx, Style, Theme, build_feed_folders, render_grouped};
+use crate::feed::lang::{normalize, post_lang};
 use crate::query::resolve::resolve_posts;
+use crate::query::{DateFilter, GroupKey, Query, ReadFilter};
+use crate::utils::date::format_day;
 
-pub(crate) fn cmd_show(store: &BlogData, query: &Query, query_text: &str) -> anyhow::Result<()> {
-    let resolved = resolve_posts(store, query)?;
+/// How `blog show` prints posts.
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+pub(crate) enum ShowFormat {
+    /// Decorated listing, grouped as the query says
+    #[default]
+    Text,
+    /// One line per post with tab-separated shorthand, date, feed, title and
+    /// link, for awk, cut or fzf
+    Tsv,
+}
+
+/// Which date `blog show` orders, groups and filters posts by.
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+pub(crate) enum SortKey {
+    /// When the feed says the post was published
+    #[default]
+    Date,
+    /// When the post was first fetched
+    Seen,
+}
+
+/// How listings show posts that were already read, set with the `read_posts`
+/// config key. Queries with `.read` or `.all` list them in any case.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum ReadPosts {
+    /// Like unread ones, only without the `*` marker
+    Show,
+    /// Dimmed, when writing to a terminal
+    #[default]
+    Dim,
+    /// Not at all
+    Hide,
+}
+
+impl std::str::FromStr for ReadPosts {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "show" => Ok(Self::Show),
+            "dim" => Ok(Self::Dim),
+            "hide" => Ok(Self::Hide),
+            _ => anyhow::bail!("expected show, dim or hide"),
+        }
+    }
+}
+
+/// Presentation options for `blog show` that are not part of the query.
+#[derive(Default)]
+pub(crate) struct ShowOptions {
+    /// Print the categories and a short summary excerpt under each post
+    pub long: bool,
+    /// Only show posts in this language (ISO 639-1 or 639-3 code)
+    pub lang: Option<String>,
+    /// Only show posts the feed changed after they were first fetched
+    pub updated: bool,
+    /// Only show posts filed under this category
+    pub category: Option<String>,
+    pub sort: SortKey,
+    /// Posts listed per group before the rest are summed up
+    pub per_group: Option<usize>,
+    pub format: ShowFormat,
+    /// End each post with a NUL byte instead of a newline (implies TSV)
+    pub print0: bool,
+}
+
+pub(crate) fn cmd_show(
+    store: &BlogData,
+    query: &Query,
+    query_text: &str,
+    opts: &ShowOptions,
+) -> anyhow::Result<()> {
+    let mut resolved = match opts.sort {
+        SortKey::Date => resolve_posts(store, query)?,
+        SortKey::Seen => resolve_by_seen(store, query)?,
+    };
+    if let Some(lang) = &opts.lang {
+        let lang = normalize(lang);
+        resolved
+            .items
+            .retain(|(_, post)| post_lang(post).as_deref() == Some(lang.as_str()));
+    }
+    if opts.updated {
+        resolved.items.retain(|(_, post)| post.is_updated());
+    }
+    if let Some(category) = &opts.category {
+        resolved
+            .items
+            .retain(|(_, post)| post.has_category(category));
+    }
+    let read_ids = read_ids(store);
+    if query.read_filter == ReadFilter::Any
+        && get_config_parsed(store, "read_posts")? == Some(ReadPosts::Hide)
+    {
+        resolved
+            .items
+            .retain(|(_, post)| !read_ids.contains(&post.raw_id));
+    }
     ensure!(!resolved.items.is_empty(), "No matching posts");
 
-    let read_ids: HashSet<String> = store
+    let refs: Vec<&FeedItem> = resolved.items.iter().map(|(_, item)| item).collect();
+    if opts.print0 || matches!(opts.format, ShowFormat::Tsv) {
+        let end = if opts.print0 { '\0' } else { '\n' };
+        print!(
+            "{}",
+            format_records(&refs, &resolved.shorthands, &resolved.feed_labels, end)
+        );
+        return Ok(());
+    }
+    let mut ctx = listing_ctx(
+        store,
+        &refs,
+        &query.keys,
+        &resolved.shorthands,
+        &resolved.feed_labels,
+        &read_ids,
+        opts.long,
+    )?;
+    ctx.per_group = opts.per_group;
+    print!("{}", render_grouped(&refs, &ctx));
+
+    // Summary goes to stderr so it doesn't pollute piped/redirected output
+    eprint!("{}", format_summary(&refs, query_text, ctx.color));
+
+    Ok(())
+}
+
+/// The posts matching `query` with the date they were first fetched in place
+/// of their publication date, newest first, so date ranges and `/d` or `/w`
+/// groups follow it. Posts fetched before that was recorded keep their
+/// publication date.
+fn resolve_by_seen(
+    store: &BlogData,
+    query: &Query,
+) -> anyhow::Result<crate::query::resolve::ResolvedPosts> {
+    let undated = Query {
+        date_filter: DateFilter {
+            since: None,
+            until: None,
+        },
+        ..query.clone()
+    };
+    let mut resolved = resolve_posts(store, &undated)?;
+    for (_, post) in &mut resolved.items {
+        post.date = post.first_seen_at.or(post.date);
+    }
+    let DateFilter { since, until } = &query.date_filter;
+    resolved.items.retain(|(_, post)| {
+        since
+            .as_ref()
+            .is_none_or(|s| post.date.is_some_and(|d| d >= s.resolved))
+            && until
+                .as_ref()
+                .is_none_or(|u| post.date.is_some_and(|d| d <= u.resolved))
+    });
+    resolved
+        .items
+        .sort_by_key(|(_, post)| std::cmp::Reverse(post.date));
+    Ok(resolved)
+}
+
+/// Raw IDs of the posts marked read.
+pub(crate) fn read_ids(store: &BlogData) -> HashSet<String> {
+    store
         .reads()
         .iter()
         .map(|(_, r)| r.post_id.clone())
-        .collect();
+        .collect()
+}
 
-    let color = std::io::stdout().is_terminal();
+/// How to render `items` as a post listing on stdout, grouped by `keys`.
+pub(crate) fn listing_ctx<'a>(
+    store: &BlogData,
+    items: &[&FeedItem],
+    keys: &'a [GroupKey],
+    shorthands: &'a HashMap<String, String>,
+    feed_labels: &'a HashMap<String, String>,
+    read_ids: &'a HashSet<String>,
+    long: bool,
+) -> anyhow::Result<RenderCtx<'a>> {
     let max_width = terminal_size::terminal_size().map(|(w, _)| w.0 as usize);
-    let refs: Vec<&FeedItem> = resolved.items.iter().map(|(_, item)| item).collect();
-    let ctx = RenderCtx {
-        all_keys: &query.keys,
-        shorthands: &resolved.shorthands,
-        feed_labels: &resolved.feed_labels,
-        read_ids: &read_ids,
-        color,
-        shorthand_width: RenderCtx::shorthand_width_from(&refs, &resolved.shorthands),
+    Ok(RenderCtx {
+        all_keys: keys,
+        shorthands,
+        feed_labels,
+        feed_folders: build_feed_folders(&feed_index(store.feeds())),
+        read_ids,
+        due_ids: due_ids(store, chrono::Utc::now()),
+        color: std::io::stdout().is_terminal(),
+        theme: Theme::load(store)?,
+        shorthand_width: RenderCtx::shorthand_width_from(items, shorthands),
+        title_width: RenderCtx::title_width_from(items, max_width),
         max_width,
-    };
-    print!("{}", render_grouped(&refs, &ctx));
-
-    // Summary goes to stderr so it doesn't pollute piped/redirected output
-    eprint!("{}", format_summary(&refs, query_text, color));
+        long,
+        show_author: get_config_parsed(store, "show_author")?.unwrap_or(false),
+        dim_read: get_config_parsed::<ReadPosts>(store, "read_posts")?.unwrap_or_default()
+            == ReadPosts::Dim,
+        highlight: &[],
+        per_group: None,
+    })
+}
 
-    Ok(())
+/// `items` as flat records of tab-separated shorthand, date, feed, title and
+/// link, each ended by `end`. Tabs, line breaks and NULs inside fields become
+/// spaces so every record splits the same way.
+fn format_records(
+    items: &[&FeedItem],
+    shorthands: &HashMap<String, String>,
+    feed_labels: &HashMap<String, String>,
+    end: char,
+) -> String {
+    let clean = |field: &str| field.replace(['\t', '\n', '\r', '\0'], " ");
+    let mut out = String::new();
+    for item in items {
+        let fields = [
+            shorthands.get(&item.raw_id).cloned().unwrap_or_default(),
+            item.date.map(format_day).unwrap_or_default(),
+            feed_labels.get(&item.feed).cloned().unwrap_or_default(),
+            item.title.clone(),
+            item.link.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| clean(f)).collect();
+        out.push_str(&fields.join("\t"));
+        out.push(end);
+    }
+    out
 }
 
 pub(crate) fn format_summary(items: &[&FeedItem], query_text: &str, color: bool) -> String {
@@ -62,13 +264,30 @@ mod tests {
     fn make_item(title: &str, feed: &str, raw_id: &str) -> FeedItem {
         FeedItem {
             title: title.to_string(),
-            date: None,
             feed: feed.to_string(),
-            link: String::new(),
             raw_id: raw_id.to_string(),
+            ..Default::default()
         }
     }
 
+    #[test]
+    fn test_format_records() {
+        let mut post = make_item("Tabs\tand\nlines", "f1", "id-a");
+        post.date = Some("2024-01-15T10:00:00Z".parse().unwrap());
+        post.link = "https://a.example/1".to_string();
+        let undated = make_item("Undated", "f2", "id-b");
+        let shorthands = HashMap::from([("id-a".to_string(), "as".to_string())]);
+        let labels = HashMap::from([("f1".to_string(), "@a A Blog".to_string())]);
+
+        let out = format_records(&[&post, &undated], &shorthands, &labels, '\0');
+
+        assert_eq!(
+            out,
+            "as\t2024-01-15\t@a A Blog\tTabs and lines\thttps://a.example/1\0\
+             \t\t\tUndated\t\0"
+        );
+    }
+
     #[test]
     fn test_format_summary_multiple_posts_multiple_feeds() {
         let items = vec![
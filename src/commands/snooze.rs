@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+
+use anyhow::ensure;
+use chrono::{DateTime, Utc};
+
+use crate::data::schema::SnoozeEntry;
+use crate::data::{BlogData, transact};
+use crate::query::Query;
+use crate::query::resolve::resolve_posts;
+use crate::utils::date::format_day;
+
+/// Leave the posts matching `query` out of listings until `until`, after
+/// which they are listed again marked as due. Snoozing a snoozed post
+/// moves its time.
+pub(crate) fn cmd_snooze(
+    store: &mut BlogData,
+    query: &Query,
+    until: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let resolved = resolve_posts(store, query)?;
+    ensure!(!resolved.items.is_empty(), "No matching posts");
+    transact(
+        store,
+        &format!("snooze until {}", format_day(until)),
+        |tx| {
+            for (_, item) in &resolved.items {
+                tx.snoozes.upsert(SnoozeEntry {
+                    post_id: item.raw_id.clone(),
+                    until,
+                });
+            }
+            Ok(())
+        },
+    )?;
+    eprintln!(
+        "Snoozed {} post(s) until {}.",
+        resolved.items.len(),
+        format_day(until)
+    );
+    Ok(())
+}
+
+/// Raw IDs of the posts whose snooze is over at `now`, until they are read.
+pub(crate) fn due_ids(store: &BlogData, now: DateTime<Utc>) -> HashSet<String> {
+    store
+        .snoozes()
+        .iter()
+        .filter(|(_, e)| e.is_due(now))
+        .map(|(_, e)| e.post_id.clone())
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! `blog suggest`: find feeds like the ones subscribed to, on the sites they
+//! link to, and offer them for subscription.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::ensure;
+use url::Url;
+
+use crate::data::index::{FeedEntry, feed_index, resolve_feed_arg};
+use crate::data::{BlogData, get_config_value, transact};
+use crate::feed::blocklist::Blocklist;
+use crate::feed::discover::linked_sites;
+use crate::feed::links::LinkCleaner;
+use crate::utils::http::{HttpOptions, get_bytes, http_client};
+use crate::utils::progress::spinner;
+
+use super::add::{ResolvedFeed, add_resolved, ensure_not_subscribed, find_feeds, select_feeds};
+
+/// Most recent posts of each feed whose links are counted.
+const RECENT_POSTS: usize = 50;
+
+/// Sites looked for feeds on, most often linked first.
+const MAX_SUGGESTED_SITES: usize = 10;
+
+/// Sites linked to all the time that aren't blogs, with their subdomains.
+const IGNORED_SITES: &[&str] = &[
+    "amazon.com",
+    "bsky.app",
+    "facebook.com",
+    "github.com",
+    "google.com",
+    "instagram.com",
+    "linkedin.com",
+    "mastodon.social",
+    "news.ycombinator.com",
+    "reddit.com",
+    "t.co",
+    "twitter.com",
+    "wikipedia.org",
+    "x.com",
+    "youtu.be",
+    "youtube.com",
+];
+
+/// The host `url` is on without `www.`, so both name the same site.
+fn site_host(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+fn is_ignored(host: &str) -> bool {
+    IGNORED_SITES.iter().any(|site| {
+        host == *site
+            || host
+                .strip_suffix(site)
+                .is_some_and(|sub| sub.ends_with('.'))
+    })
+}
+
+/// The sites in `mentions`, lists of home pages each linked from one page or
+/// post, linked from the most of them first, leaving out `known` hosts.
+fn rank_sites(mentions: &[Vec<String>], known: &HashSet<String>) -> Vec<String> {
+    let mut counts: HashMap<String, (usize, &str)> = HashMap::new();
+    for sites in mentions {
+        let hosts: HashSet<(String, &str)> = sites
+            .iter()
+            .filter_map(|site| Some((site_host(site)?, site.as_str())))
+            .collect();
+        for (host, site) in hosts {
+            counts.entry(host).or_insert((0, site)).0 += 1;
+        }
+    }
+    let mut ranked: Vec<(String, usize, &str)> = counts
+        .into_iter()
+        .filter(|(host, _)| !known.contains(host) && !is_ignored(host))
+        .map(|(host, (count, site))| (host, count, site))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTED_SITES)
+        .map(|(_, _, site)| site.to_string())
+        .collect()
+}
+
+/// The sites linked from the home page of `entry`'s site, e.g. its blogroll,
+/// and from each of its recent posts.
+fn linked_from(store: &BlogData, entry: &FeedEntry, http: &HttpOptions) -> Vec<Vec<String>> {
+    let mut mentions = Vec::new();
+    if let Ok(site) = Url::parse(&entry.feed.site_url) {
+        let sp = spinner(&format!("Fetching {site}..."));
+        match get_bytes(&http_client(http), site.as_str(), http.max_body) {
+            Ok(download) => mentions.push(linked_sites(
+                &String::from_utf8_lossy(&download.body),
+                &site,
+            )),
+            Err(e) => tracing::info!(site = %site, error = %e, "site unavailable"),
+        }
+        sp.finish_and_clear();
+    }
+    let mut posts: Vec<_> = store
+        .posts()
+        .iter()
+        .map(|(_, post)| post)
+        .filter(|post| post.feed == entry.id)
+        .collect();
+    posts.sort_by_key(|post| std::cmp::Reverse(post.date));
+    for post in posts.into_iter().take(RECENT_POSTS) {
+        let Ok(base) = Url::parse(&post.link).or_else(|_| Url::parse(&entry.feed.url)) else {
+            continue;
+        };
+        mentions.push(linked_sites(post.body(), &base));
+    }
+    mentions
+}
+
+/// Look for feeds on the sites `feed` (URL or @shorthand), or else every
+/// feed subscribed to, links to most, and subscribe to the ones picked from
+/// a menu.
+pub(crate) fn cmd_suggest(
+    store: &mut BlogData,
+    feed: Option<&str>,
+    http: &HttpOptions,
+) -> anyhow::Result<()> {
+    let fi = feed_index(store.feeds());
+    ensure!(!fi.entries.is_empty(), "No feeds found");
+    let url = feed
+        .map(|f| resolve_feed_arg(store.feeds(), f))
+        .transpose()?;
+    let entries: Vec<&FeedEntry> = fi
+        .entries
+        .iter()
+        .filter(|e| url.as_ref().is_none_or(|url| e.feed.url == *url))
+        .collect();
+
+    let known: HashSet<String> = fi
+        .entries
+        .iter()
+        .flat_map(|e| [site_host(&e.feed.url), site_host(&e.feed.site_url)])
+        .flatten()
+        .collect();
+    let mentions: Vec<Vec<String>> = entries
+        .iter()
+        .flat_map(|entry| linked_from(store, entry, http))
+        .collect();
+    let sites = rank_sites(&mentions, &known);
+    ensure!(!sites.is_empty(), "No linked sites to suggest feeds from");
+
+    let links = LinkCleaner::from_config(store)?;
+    let blocklist = Blocklist::from_config(store)?;
+    let mut found: Vec<ResolvedFeed> = Vec::new();
+    for site in sites.iter().filter(|site| !blocklist.blocks(site)) {
+        let feeds = match find_feeds(site, http, &links) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                tracing::info!(site = %site, error = %e, "linked site unavailable");
+                continue;
+            }
+        };
+        let new: Vec<ResolvedFeed> = feeds
+            .into_iter()
+            .filter(|feed| {
+                !blocklist.blocks(&feed.url)
+                    && ensure_not_subscribed(store.feeds(), feed).is_ok()
+                    && !found.iter().any(|f| f.url == feed.url)
+            })
+            .collect();
+        found.extend(new);
+    }
+    ensure!(
+        !found.is_empty(),
+        "No new feeds found on the {} most linked sites",
+        sites.len()
+    );
+
+    eprintln!(
+        "Found {} feeds on the {} most linked sites:",
+        found.len(),
+        sites.len()
+    );
+    let picked = select_feeds(found)?;
+    let count = picked.len();
+    let ingest_filter = get_config_value(store, "ingest_filter");
+    transact(store, &format!("add {count} suggested feeds"), |tx| {
+        for feed in picked {
+            add_resolved(tx, feed, ingest_filter.as_deref(), &blocklist)?;
+        }
+        Ok(())
+    })?;
+    eprintln!("Subscribed to {count} feeds.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sites(urls: &[&str]) -> Vec<String> {
+        urls.iter().map(|u| u.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rank_sites_counts_each_page_once() {
+        let mentions = vec![
+            sites(&["https://a.example/", "https://b.example/"]),
+            sites(&["https://www.b.example/", "https://github.com/"]),
+            sites(&["https://b.example/", "https://known.example/"]),
+            sites(&["https://c.example/", "https://gist.github.com/"]),
+        ];
+        let known = HashSet::from(["known.example".to_string()]);
+
+        assert_eq!(
+            rank_sites(&mentions, &known),
+            [
+                "https://b.example/",
+                "https://a.example/",
+                "https://c.example/"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rank_sites_keeps_the_most_linked() {
+        let mentions: Vec<Vec<String>> = (0..20)
+            .map(|i| sites(&[&format!("https://s{i:02}.example/")]))
+            .collect();
+
+        let ranked = rank_sites(&mentions, &HashSet::new());
+
+        assert_eq!(ranked.len(), MAX_SUGGESTED_SITES);
+        assert_eq!(ranked[0], "https://s00.example/");
+    }
+}
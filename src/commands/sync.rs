@@ -1,29 +1,95 @@
 use std::collections::HashSet;
-use std::time::Duration;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
-use indicatif::{ProgressBar, ProgressStyle};
+use chrono::Utc;
+use indicatif::ProgressBar;
 use synctato::{SyncEvent, SyncResult};
 
+use crate::commands::add::normalize_feed_url;
+use crate::commands::clone::{is_shallow, unshallow};
+use crate::commands::maintenance::auto_gc;
 use crate::data::BlogData;
 use crate::data::index::{FeedIndex, feed_index};
-use crate::data::schema::FeedSource;
-use crate::utils::progress::spinner;
+use crate::data::schema::{FeedItem, FeedSource};
+use crate::display::build_feed_labels;
+use crate::query::resolve::post_index;
+use crate::utils::date::format_day;
+use crate::utils::progress::{is_quiet, progress_bar, spinner};
 use crate::utils::version_check::check_for_newer_version;
 
-use crate::feed::pull::{apply_fetched, fetch_feeds};
+use crate::data::search::SearchIndex;
+use crate::data::{apply_author, get_config_parsed, get_config_value, transact};
+use crate::feed::blocklist::Blocklist;
+use crate::feed::greader;
+use crate::feed::links::LinkCleaner;
+use crate::feed::notify::notify_new_posts;
+use crate::feed::pull::{FetchFailure, FetchOptions, apply_fetched, apply_greader, fetch_feeds};
+use crate::feed::retention::{Retention, apply_retention, skip_expired};
 
 #[cfg(test)]
 use crate::data::index::FeedEntry;
 
 const CRATES_IO_URL: &str = "https://crates.io/api/v1/crates/blogtato";
 
+/// Options for `blog sync` given on the command line.
+#[derive(Default)]
+pub(crate) struct SyncOptions {
+    /// Maximum number of feeds fetched at once; overrides `fetch_jobs`
+    pub jobs: Option<NonZeroUsize>,
+    /// Sync with the git remote even when only selected feeds are fetched
+    pub git: bool,
+    /// Don't fetch feeds
+    pub no_fetch: bool,
+    /// Don't pull from or push to the git remote
+    pub no_push: bool,
+    /// Only fetch the feeds whose last fetch failed
+    pub retry_failed: bool,
+    /// List the new posts after the summary
+    pub verbose: bool,
+}
+
+/// Returned by `blog sync` when some feeds couldn't be fetched, after the
+/// rest of the sync went through, so that it exits with
+/// [`FeedsFailed::EXIT_CODE`] instead of the usual 1.
+#[derive(Debug)]
+pub(crate) struct FeedsFailed(pub usize);
+
+impl FeedsFailed {
+    pub(crate) const EXIT_CODE: i32 = 2;
+}
+
+impl std::fmt::Display for FeedsFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            1 => write!(f, "Failed to fetch 1 feed"),
+            n => write!(f, "Failed to fetch {n} feeds"),
+        }
+    }
+}
+
+impl std::error::Error for FeedsFailed {}
+
+/// Which network parts of a sync to run.
+pub(crate) struct SyncSteps {
+    /// Pull from and push to the git remote
+    pub remote: bool,
+    /// Fetch feeds, and pull from a Google Reader API server
+    pub feeds: bool,
+}
+
 fn do_sync_remote(store: &mut BlogData) -> anyhow::Result<SyncResult> {
     let mut sp: Option<ProgressBar> = None;
-    store.sync_remote(|event| match event {
+    let mut started = Instant::now();
+    let result = store.sync_remote(|event| match event {
         SyncEvent::Fetching => {
+            started = Instant::now();
             sp = Some(spinner("Fetching..."));
         }
         SyncEvent::FetchDone => {
+            tracing::info!(ms = started.elapsed().as_millis(), "git fetch");
             if let Some(s) = sp.take() {
                 s.finish_with_message("Fetching... done.");
             }
@@ -34,9 +100,11 @@ fn do_sync_remote(store: &mut BlogData) -> anyhow::Result<SyncResult> {
             } else {
                 "Pushing..."
             };
+            started = Instant::now();
             sp = Some(spinner(msg));
         }
         SyncEvent::PushDone { first_push } => {
+            tracing::info!(ms = started.elapsed().as_millis(), first_push, "git push");
             let msg = if first_push {
                 "Pushing to remote (first sync)... done."
             } else {
@@ -47,9 +115,11 @@ fn do_sync_remote(store: &mut BlogData) -> anyhow::Result<SyncResult> {
             }
         }
         SyncEvent::MergingRemote => {
+            started = Instant::now();
             sp = Some(spinner("Merging remote data..."));
         }
         SyncEvent::MergeDone { counts } => {
+            tracing::info!(ms = started.elapsed().as_millis(), ?counts, "git merge");
             if let Some(s) = sp.take() {
                 let detail: Vec<String> = counts
                     .iter()
@@ -61,17 +131,123 @@ fn do_sync_remote(store: &mut BlogData) -> anyhow::Result<SyncResult> {
                 ));
             }
         }
-    })
+    });
+    if let Ok(result) = &result {
+        let outcome = match result {
+            SyncResult::NoGitRepo => "no git repository",
+            SyncResult::NoRemote => "no remote",
+            SyncResult::AlreadyUpToDate => "already up to date",
+            SyncResult::Synced => "synced",
+        };
+        tracing::info!(outcome, "git sync");
+    }
+    result
+}
+
+/// Sync with the remote. Some servers refuse pushes or merges from a shallow
+/// clone, so if that fails on one, fetch the full history and try again.
+fn sync_remote_deepening(store: &mut BlogData) -> anyhow::Result<SyncResult> {
+    let result = match do_sync_remote(store) {
+        Err(e) if is_shallow(store.path()) => {
+            if !is_quiet() {
+                eprintln!("Sync from a shallow clone failed ({e:#}); fetching full history...");
+            }
+            unshallow(store.path())?;
+            do_sync_remote(store)
+        }
+        result => result,
+    }?;
+    // The commit author may have been changed on another machine
+    apply_author(store)?;
+    Ok(result)
+}
+
+/// Git remotes other than `origin`, which sync only pushes to.
+fn mirror_remotes(path: &Path) -> Vec<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("remote")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|r| !r.is_empty() && *r != "origin")
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Push to every mirror remote. Mirrors are backups: a failed push is
+/// reported but doesn't fail the sync.
+fn push_mirrors(path: &Path) {
+    for remote in mirror_remotes(path) {
+        let sp = spinner(&format!("Pushing to {remote}..."));
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["push", &remote, "HEAD"])
+            .stdin(Stdio::inherit())
+            .output();
+        tracing::info!(
+            remote,
+            ok = output.as_ref().is_ok_and(|o| o.status.success()),
+            "git push to mirror"
+        );
+        match output {
+            Ok(o) if o.status.success() => {
+                sp.finish_with_message(format!("Pushing to {remote}... done."));
+            }
+            Ok(o) => {
+                sp.finish_and_clear();
+                eprintln!(
+                    "warning: failed to push to {remote}: {}",
+                    String::from_utf8_lossy(&o.stderr).trim()
+                );
+            }
+            Err(e) => {
+                sp.finish_and_clear();
+                eprintln!("warning: failed to push to {remote}: {e}");
+            }
+        }
+    }
+}
+
+/// Combine command-line options with the `fetch_*` config keys.
+pub(crate) fn fetch_options(store: &BlogData, opts: &SyncOptions) -> anyhow::Result<FetchOptions> {
+    let mut fetch = FetchOptions::default();
+    if let Some(jobs) = opts.jobs.or(get_config_parsed(store, "fetch_jobs")?) {
+        fetch.jobs = NonZeroUsize::get(jobs);
+    }
+    if let Some(jobs) = get_config_parsed::<NonZeroUsize>(store, "fetch_jobs_per_host")? {
+        fetch.jobs_per_host = jobs.get();
+    }
+    if let Some(retries) = get_config_parsed(store, "fetch_retries")? {
+        fetch.retry.retries = retries;
+    }
+    if let Some(ms) = get_config_parsed(store, "fetch_backoff_ms")? {
+        fetch.retry.backoff = Duration::from_millis(ms);
+    }
+    fetch.http = crate::utils::http::HttpOptions::from_config(store)?;
+    fetch.links = LinkCleaner::from_config(store)?;
+    Ok(fetch)
 }
 
 fn resolve_sync_sources(
     feed_index: &FeedIndex,
     selectors: &[String],
 ) -> anyhow::Result<Vec<FeedSource>> {
+    // Paused and snoozed feeds are only fetched when asked for by name
     if selectors.is_empty() {
+        let now = Utc::now();
         return Ok(feed_index
             .entries
             .iter()
+            .filter(|entry| !entry.feed.is_on_hold(now))
             .map(|entry| entry.feed.clone())
             .collect());
     }
@@ -96,65 +272,247 @@ fn resolve_sync_sources(
     Ok(resolved)
 }
 
-pub(crate) fn cmd_sync(store: &mut BlogData, selectors: &[String]) -> anyhow::Result<()> {
-    // Sync with remote first so we discover feeds added on other devices
-    let result = do_sync_remote(store)?;
-
-    let needs_push = match result {
-        SyncResult::NoRemote => {
-            eprintln!(
-                "warning: no remote configured; run `blog git remote add origin <url>` to enable sync"
-            );
-            false
+/// Log in to the account named by the `greader_url` and `greader_user`
+/// config keys, if any.
+fn greader_client(
+    store: &BlogData,
+    fetch_opts: &FetchOptions,
+) -> anyhow::Result<Option<greader::Client>> {
+    let Some(url) = get_config_value(store, "greader_url") else {
+        return Ok(None);
+    };
+    let user = get_config_value(store, "greader_user")
+        .ok_or_else(|| anyhow::anyhow!("greader_url is set but greader_user is not"))?;
+    let password = std::env::var(greader::PASSWORD_ENV).map_err(|_| {
+        anyhow::anyhow!(
+            "Set {} to the API password for {user} at {url}",
+            greader::PASSWORD_ENV
+        )
+    })?;
+    let agent = crate::utils::http::http_client(&fetch_opts.http);
+    greader::Client::login(agent, &url, &user, &password).map(Some)
+}
+
+/// Pull subscriptions, posts and read state from a Google Reader API server
+/// and push back what was read here. Returns the URLs of the feeds it covers
+/// and the new posts.
+fn pull_greader(
+    store: &mut BlogData,
+    client: &greader::Client,
+) -> anyhow::Result<(HashSet<String>, Vec<FeedItem>)> {
+    let sp = spinner("Syncing with Google Reader API...");
+    let subscriptions = client.subscriptions()?;
+    let items = client.items()?;
+    let ingest_filter = get_config_value(store, "ingest_filter");
+    let blocklist = Blocklist::from_config(store)?;
+    let (read_here, new_posts) = transact(store, "pull feeds from Google Reader API", |tx| {
+        apply_greader(
+            tx,
+            &subscriptions,
+            items,
+            &sp,
+            ingest_filter.as_deref(),
+            &blocklist,
+        )
+    })?;
+    client.mark_read(&read_here)?;
+    sp.finish_with_message(format!(
+        "Syncing with Google Reader API... done ({} feeds).",
+        subscriptions.len()
+    ));
+    let urls = subscriptions
+        .iter()
+        .map(|s| normalize_feed_url(&s.url))
+        .collect();
+    Ok((urls, new_posts))
+}
+
+pub(crate) fn cmd_sync(
+    store: &mut BlogData,
+    selectors: &[String],
+    opts: &SyncOptions,
+) -> anyhow::Result<()> {
+    let fetch_opts = fetch_options(store, opts)?;
+    let steps = SyncSteps {
+        // Fetching a few feeds, e.g. one just added, shouldn't wait on the remote
+        remote: !opts.no_push && ((selectors.is_empty() && !opts.retry_failed) || opts.git),
+        feeds: !opts.no_fetch,
+    };
+    let offline = !steps.remote && !steps.feeds;
+    let (new_posts, failures) = sync_feeds(store, &fetch_opts, &steps, |fi| {
+        let mut sources = resolve_sync_sources(fi, selectors)?;
+        if opts.retry_failed {
+            sources.retain(|s| !s.fetch_error.is_empty());
         }
-        SyncResult::NoGitRepo => false,
-        SyncResult::Synced | SyncResult::AlreadyUpToDate => true,
+        Ok(sources)
+    })?;
+    if steps.feeds && !is_quiet() {
+        eprint!("{}", new_posts_summary(store, &new_posts, opts.verbose));
+    }
+    eprint!("{}", failure_report(&failures));
+    auto_gc(store);
+
+    if !offline
+        && !is_quiet()
+        && let Ok(Some(status)) = check_for_newer_version(CRATES_IO_URL, env!("CARGO_PKG_VERSION"))
+    {
+        eprintln!(
+            "Note: blogtato {} is available (you have {}). Run `cargo install blogtato` to update.",
+            status.latest, status.current
+        );
+    }
+
+    if !failures.is_empty() {
+        return Err(FeedsFailed(failures.len()).into());
+    }
+    Ok(())
+}
+
+/// A line for each feed that couldn't be fetched, with the error and, if the
+/// fetch before failed too, since when fetches have been failing.
+pub(crate) fn failure_report(failures: &[FetchFailure]) -> String {
+    let mut failures: Vec<&FetchFailure> = failures.iter().collect();
+    failures.sort_by(|a, b| a.url.cmp(&b.url));
+    failures
+        .iter()
+        .map(|f| {
+            let streak = f
+                .failing_since
+                .map(|since| format!(" (failing since {})", format_day(since)))
+                .unwrap_or_default();
+            format!("Error fetching {}: {}{streak}\n", f.url, f.error)
+        })
+        .collect()
+}
+
+/// "12 new posts from 5 feeds", followed with `verbose` by the shorthand,
+/// title and feed of each new post.
+fn new_posts_summary(store: &BlogData, posts: &[FeedItem], verbose: bool) -> String {
+    if posts.is_empty() {
+        return "No new posts.\n".to_string();
+    }
+    let feeds: HashSet<&str> = posts.iter().map(|p| p.feed.as_str()).collect();
+    let plural = |n: usize, word: &str| {
+        if n == 1 {
+            format!("{n} {word}")
+        } else {
+            format!("{n} {word}s")
+        }
+    };
+    let mut out = format!(
+        "{} from {}.\n",
+        plural(posts.len(), "new post"),
+        plural(feeds.len(), "feed")
+    );
+    if verbose {
+        let index = post_index(store);
+        let labels = build_feed_labels(&feed_index(store.feeds()));
+        let mut posts: Vec<&FeedItem> = posts.iter().collect();
+        posts.sort_by_key(|p| std::cmp::Reverse(p.date));
+        for post in posts {
+            let shorthand = index.shorthand(&post.raw_id).unwrap_or("");
+            let label = labels.get(&post.feed).map_or("", String::as_str);
+            out.push_str(&format!("  {shorthand}  {} ({label})\n", post.title));
+        }
+    }
+    out
+}
+
+/// Bring the search index up to date, if `blog search` has created one.
+/// A stale index only makes searches slower, so failures are just reported.
+fn update_search_index(store: &BlogData) {
+    let result = SearchIndex::open_existing(store)
+        .and_then(|index| index.map_or(Ok(0), |index| index.update(store)));
+    if let Err(e) = result {
+        eprintln!("warning: failed to update the search index: {e}");
+    }
+}
+
+/// Sync with the remote, fetch the feeds picked by `select`, and push the
+/// result back, skipping what `steps` leaves out. Returns the new posts and
+/// the feeds that couldn't be fetched.
+pub(crate) fn sync_feeds(
+    store: &mut BlogData,
+    fetch_opts: &FetchOptions,
+    steps: &SyncSteps,
+    select: impl FnOnce(&FeedIndex) -> anyhow::Result<Vec<FeedSource>>,
+) -> anyhow::Result<(Vec<FeedItem>, Vec<FetchFailure>)> {
+    // Sync with remote first so we discover feeds added on other devices
+    let needs_push = steps.remote
+        && match sync_remote_deepening(store)? {
+            SyncResult::NoRemote => {
+                eprintln!(
+                    "warning: no remote configured; run `blog git remote add origin <url>` to enable sync"
+                );
+                false
+            }
+            SyncResult::NoGitRepo => false,
+            SyncResult::Synced | SyncResult::AlreadyUpToDate => true,
+        };
+
+    // Feeds the Google Reader API server follows come from there instead
+    let greader = if steps.feeds {
+        greader_client(store, fetch_opts)?
+    } else {
+        None
+    };
+    let (from_server, mut new_posts) = match greader {
+        Some(client) => pull_greader(store, &client)?,
+        None => (HashSet::new(), Vec::new()),
     };
 
     let fi = feed_index(store.feeds());
-    let sources = resolve_sync_sources(&fi, selectors)?;
+    let mut sources = select(&fi)?;
+    sources.retain(|s| steps.feeds && !from_server.contains(&s.url));
 
     // Fetch feeds outside the transaction (network I/O, no lock held)
-    let pb = ProgressBar::new(0);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.cyan} Pulling feeds [{bar:20.cyan/dim}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("=> "),
-    );
-    pb.enable_steady_tick(Duration::from_millis(100));
+    let pb = progress_bar("{spinner:.cyan} Pulling feeds [{bar:20.cyan/dim}] {pos}/{len} {msg}");
 
-    let results = fetch_feeds(&sources, &pb);
+    let results = fetch_feeds(&sources, &pb, fetch_opts);
     pb.finish_and_clear();
 
     // Apply results inside a locked transaction
-    let ingest_filter = crate::data::get_config_value(store, "ingest_filter");
-    store.transact("pull feeds", |tx| {
-        apply_fetched(tx, results, &pb, ingest_filter.as_deref())
+    let ingest_filter = get_config_value(store, "ingest_filter");
+    let blocklist = Blocklist::from_config(store)?;
+    let retention = get_config_parsed::<Retention>(store, "retention")?;
+    let (fetched, failures, expired) = transact(store, "pull feeds", |tx| {
+        let now = Utc::now();
+        let mut results = results;
+        skip_expired(tx, &mut results, retention, now)?;
+        let (fetched, failures) =
+            apply_fetched(tx, results, &pb, ingest_filter.as_deref(), &blocklist)?;
+        Ok((fetched, failures, apply_retention(tx, retention, now)?))
     })?;
+    if expired > 0 && !is_quiet() {
+        eprintln!("Removed {expired} posts past their retention.");
+    }
+    new_posts.extend(fetched);
+    // Retention may have dropped some right away
+    new_posts.retain(|p| store.posts().contains_key(&p.raw_id));
+    notify_new_posts(store, &new_posts);
+    update_search_index(store);
+    // Sort the new posts in now, so that in large stores the next command
+    // naming a post finds their shorthands cached
+    post_index(store);
 
     // Sync again to push the freshly fetched feed data back to remote
     if needs_push {
-        let push_result = do_sync_remote(store)?;
+        let push_result = sync_remote_deepening(store)?;
         match push_result {
             SyncResult::Synced => {} // pushed successfully, spinners already shown
             SyncResult::AlreadyUpToDate => {
-                eprintln!("Already up to date.");
+                if !is_quiet() {
+                    eprintln!("Already up to date.");
+                }
             }
             SyncResult::NoRemote | SyncResult::NoGitRepo => {
                 // Shouldn't happen since we already confirmed remote exists
             }
         }
+        push_mirrors(store.path());
     }
 
-    if let Ok(Some(status)) = check_for_newer_version(CRATES_IO_URL, env!("CARGO_PKG_VERSION")) {
-        eprintln!(
-            "Note: blogtato {} is available (you have {}). Run `cargo install blogtato` to update.",
-            status.latest, status.current
-        );
-    }
-
-    Ok(())
+    Ok((new_posts, failures))
 }
 
 #[cfg(test)]
@@ -164,10 +522,7 @@ mod tests {
     fn make_feed(url: &str) -> FeedSource {
         FeedSource {
             url: url.to_string(),
-            title: String::new(),
-            site_url: String::new(),
-            description: String::new(),
-            is_fetched: false,
+            ..Default::default()
         }
     }
 
@@ -198,6 +553,22 @@ mod tests {
         assert_eq!(resolved[1].url, "https://example.com/b.xml");
     }
 
+    #[test]
+    fn test_resolve_sync_sources_skips_paused_feeds_unless_selected() {
+        let mut index = make_index(&[
+            ("https://example.com/a.xml", "df"),
+            ("https://example.com/b.xml", "dg"),
+        ]);
+        index.entries[0].feed.paused = true;
+
+        let resolved = resolve_sync_sources(&index, &[]).unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].url, "https://example.com/b.xml");
+
+        let resolved = resolve_sync_sources(&index, &["@df".to_string()]).unwrap();
+        assert_eq!(resolved[0].url, "https://example.com/a.xml");
+    }
+
     #[test]
     fn test_resolve_sync_sources_supports_multiple_shorthands() {
         let index = make_index(&[
@@ -0,0 +1,31 @@
+use std::num::NonZeroU64;
+
+use crate::data::Transaction;
+use crate::data::index::resolve_feed_arg;
+
+/// Set how many seconds fetching the feed named by `feed` (URL or @shorthand)
+/// may take, or go back to the `timeout` config key when `seconds` is
+/// `default`. Returns the feed's URL.
+pub(crate) fn cmd_timeout(
+    tx: &mut Transaction,
+    feed: &str,
+    seconds: &str,
+) -> anyhow::Result<String> {
+    let url = resolve_feed_arg(tx.feeds, feed)?;
+    let mut source = tx
+        .feeds
+        .get(&url)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Feed not found: {}", url))?;
+    source.timeout = if seconds == "default" {
+        None
+    } else {
+        let secs: NonZeroU64 = seconds
+            .trim_end_matches('s')
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid timeout {seconds:?}: {e}"))?;
+        Some(secs.get())
+    };
+    tx.feeds.upsert(source);
+    Ok(url)
+}
@@ -0,0 +1,121 @@
+//! Saved queries: `blog view add rust-news ~rust .unread` saves a query that
+//! `blog %rust-news` (or `blog show %rust-news`) runs. Views are stored in
+//! their own table, so they are synced like the rest of the store.
+
+use anyhow::{bail, ensure};
+
+use crate::data::schema::SavedView;
+use crate::data::{BlogData, transact};
+use crate::query::parse_query;
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Replace `%name` arguments with the query saved as view `name`.
+pub(crate) fn expand_views(args: Vec<String>, store: &BlogData) -> anyhow::Result<Vec<String>> {
+    let mut expanded = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.strip_prefix('%') {
+            Some(name) => {
+                let view = store
+                    .views()
+                    .iter()
+                    .find(|(_, v)| v.name == name)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown view: %{name}"))?;
+                expanded.extend(view.query.split_whitespace().map(String::from));
+            }
+            None => expanded.push(arg),
+        }
+    }
+    Ok(expanded)
+}
+
+/// Save `args`, plus a `~word` for each word of `matches` and `.unread` if
+/// `unread` is set, as view `name`, replacing any view of that name.
+pub(crate) fn cmd_view_add(
+    store: &mut BlogData,
+    name: &str,
+    args: &[String],
+    matches: &[String],
+    unread: bool,
+) -> anyhow::Result<()> {
+    ensure!(
+        is_valid_name(name),
+        "Invalid view name {name:?}: use letters, digits, - and _"
+    );
+    if let Some(arg) = args.iter().find(|a| a.starts_with('%')) {
+        bail!("Views can't include other views: {arg}");
+    }
+    let mut query_args = args.to_vec();
+    query_args.extend(
+        matches
+            .iter()
+            .flat_map(|m| m.split_whitespace())
+            .map(|word| format!("~{word}")),
+    );
+    if unread {
+        query_args.push(".unread".to_string());
+    }
+    ensure!(!query_args.is_empty(), "A view needs a query");
+    // Check the syntax now rather than when the view is used
+    parse_query(&query_args)?;
+
+    let query = query_args.join(" ");
+    let replaced = store.views().contains_key(name);
+    transact(store, &format!("save view {name}"), |tx| {
+        tx.views.upsert(SavedView {
+            name: name.to_string(),
+            query: query.clone(),
+        });
+        Ok(())
+    })?;
+    let verb = if replaced { "Updated" } else { "Saved" };
+    eprintln!("{verb} view %{name}: {query}");
+    Ok(())
+}
+
+pub(crate) fn cmd_view_ls(store: &BlogData) -> anyhow::Result<()> {
+    let mut views: Vec<&SavedView> = store.views().iter().map(|(_, v)| v).collect();
+    ensure!(
+        !views.is_empty(),
+        "No saved views; add one with `blog view add <name> <query>`"
+    );
+    views.sort_by(|a, b| a.name.cmp(&b.name));
+    let width = views.iter().map(|v| v.name.len()).max().unwrap_or(0) + 1;
+    for view in views {
+        println!("{:width$}  {}", format!("%{}", view.name), view.query);
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_view_rm(store: &mut BlogData, name: &str) -> anyhow::Result<()> {
+    let name = name.strip_prefix('%').unwrap_or(name);
+    ensure!(store.views().contains_key(name), "Unknown view: %{name}");
+    transact(store, &format!("remove view {name}"), |tx| {
+        tx.views.delete(name);
+        Ok(())
+    })?;
+    eprintln!("Removed view %{name}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::simple("rust-news", true)]
+    #[case::underscore("my_view2", true)]
+    #[case::empty("", false)]
+    #[case::space("rust news", false)]
+    #[case::percent("%rust", false)]
+    fn test_is_valid_name(#[case] name: &str, #[case] valid: bool) {
+        assert_eq!(is_valid_name(name), valid);
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use chrono::Utc;
+use indicatif::ProgressBar;
+
+use crate::data::index::FeedIndex;
+use crate::data::schema::FeedSource;
+use crate::data::{BlogData, transact};
+use crate::feed::Fetched;
+use crate::feed::blocklist::Blocklist;
+use crate::feed::links::LinkCleaner;
+use crate::feed::notify::notify_new_posts;
+use crate::feed::pull::{FetchOptions, apply_fetched};
+use crate::feed::websub::{self, Event, Subscription, Subscriptions};
+
+use super::sync::{SyncOptions, SyncSteps, failure_report, fetch_options, sync_feeds};
+
+/// Address the WebSub callback listener binds to unless configured otherwise.
+const DEFAULT_LISTEN: &str = "127.0.0.1:8470";
+
+const INTERVAL_TOO_LONG: &str = "--interval is too long";
+
+/// Options for `blog watch` given on the command line.
+pub(crate) struct WatchOptions {
+    /// Time between polls of feeds that aren't pushed to us
+    pub interval: Duration,
+    /// Public base URL hubs deliver to; overrides `websub_callback`
+    pub callback: Option<String>,
+    /// Local address to listen on; overrides `websub_listen`
+    pub listen: Option<String>,
+    pub jobs: Option<NonZeroUsize>,
+}
+
+/// State of the WebSub side of `blog watch`.
+struct Hubs {
+    callback: String,
+    client: ureq::Agent,
+    subs: Subscriptions,
+    /// When each verified subscription runs out, by feed URL
+    leases: HashMap<String, Instant>,
+}
+
+impl Hubs {
+    /// Whether `feed` is currently delivered by its hub.
+    fn is_pushed(&self, feed: &FeedSource, now: Instant) -> bool {
+        self.leases.get(&feed.url).is_some_and(|until| *until > now)
+    }
+
+    /// (Re)subscribe to every feed with a hub whose lease runs out before
+    /// `renew_before`.
+    fn subscribe_all(&mut self, fi: &FeedIndex, renew_before: Instant) {
+        for entry in fi
+            .entries
+            .iter()
+            .filter(|e| !e.feed.hub.is_empty() && !e.feed.is_on_hold(Utc::now()))
+        {
+            let feed = &entry.feed;
+            if self
+                .leases
+                .get(&feed.url)
+                .is_some_and(|until| *until > renew_before)
+            {
+                continue;
+            }
+            let secret = match websub::random_secret() {
+                Ok(secret) => secret,
+                Err(e) => {
+                    eprintln!("Error subscribing to {} at {}: {}", feed.url, feed.hub, e);
+                    continue;
+                }
+            };
+            let callback = format!("{}/{}", self.callback.trim_end_matches('/'), entry.id);
+            let mut subs = self.subs.lock().unwrap_or_else(|e| e.into_inner());
+            // Until the hub confirms the renewal, it may still sign with the
+            // secret it has
+            let old_secret = subs
+                .get(&entry.id)
+                .map(|old| old.old_secret.clone().unwrap_or_else(|| old.secret.clone()));
+            let sub = Subscription {
+                feed_url: feed.url.clone(),
+                topic: if feed.topic.is_empty() {
+                    feed.url.clone()
+                } else {
+                    feed.topic.clone()
+                },
+                secret,
+                old_secret,
+            };
+            subs.insert(entry.id.clone(), sub.clone());
+            drop(subs);
+            if let Err(e) = websub::subscribe(&self.client, &feed.hub, &callback, &sub) {
+                eprintln!("Error subscribing to {} at {}: {}", feed.url, feed.hub, e);
+            }
+        }
+    }
+}
+
+fn apply_push(store: &mut BlogData, feed_url: &str, body: &[u8]) -> anyhow::Result<()> {
+    let Some(source) = store.feeds().get(feed_url).cloned() else {
+        return Ok(());
+    };
+    let links = LinkCleaner::from_config(store)?.with_base(feed_url);
+    let (meta, items) = match crate::feed::parse(body, &links) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error parsing push for {feed_url}: {e}");
+            return Ok(());
+        }
+    };
+    let fetched = Fetched {
+        meta,
+        items,
+        moved_to: None,
+    };
+    let ingest_filter = crate::data::get_config_value(store, "ingest_filter");
+    let blocklist = Blocklist::from_config(store)?;
+    let (new_posts, _) = transact(store, &format!("push: {feed_url}"), |tx| {
+        apply_fetched(
+            tx,
+            vec![(source, Ok(fetched))],
+            &ProgressBar::hidden(),
+            ingest_filter.as_deref(),
+            &blocklist,
+        )
+    })?;
+    eprintln!("Received update for {feed_url}");
+    notify_new_posts(store, &new_posts);
+    Ok(())
+}
+
+fn start_listener(
+    store: &BlogData,
+    opts: &WatchOptions,
+    fetch_opts: &FetchOptions,
+    events: mpsc::Sender<Event>,
+) -> anyhow::Result<Option<Hubs>> {
+    let Some(callback) = opts
+        .callback
+        .clone()
+        .or_else(|| crate::data::get_config_value(store, "websub_callback"))
+    else {
+        return Ok(None);
+    };
+    let listen = opts
+        .listen
+        .clone()
+        .or_else(|| crate::data::get_config_value(store, "websub_listen"))
+        .unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+    let listener =
+        TcpListener::bind(&listen).with_context(|| format!("Failed to listen on {listen}"))?;
+    eprintln!("Listening for WebSub pushes on {listen} ({callback})");
+
+    let subs = Subscriptions::default();
+    let shared = subs.clone();
+    std::thread::spawn(move || websub::serve(listener, shared, events));
+
+    Ok(Some(Hubs {
+        callback,
+        client: crate::utils::http::http_client(&fetch_opts.http),
+        subs,
+        leases: HashMap::new(),
+    }))
+}
+
+pub(crate) fn cmd_watch(store: &mut BlogData, opts: &WatchOptions) -> anyhow::Result<()> {
+    let fetch_opts = fetch_options(
+        store,
+        &SyncOptions {
+            jobs: opts.jobs,
+            ..SyncOptions::default()
+        },
+    )?;
+    // Keep a sender here so waiting works the same with or without a listener
+    let (events_tx, events) = mpsc::channel();
+    let mut hubs = start_listener(store, opts, &fetch_opts, events_tx.clone())?;
+
+    // Renew a couple of polls early so a slow hub doesn't leave gaps
+    let renew_ahead = opts.interval.checked_mul(2).context(INTERVAL_TOO_LONG)?;
+    let mut next_poll = Instant::now();
+    loop {
+        let now = Instant::now();
+        if now >= next_poll {
+            let steps = SyncSteps {
+                remote: true,
+                feeds: true,
+            };
+            let renew_before = now.checked_add(renew_ahead).context(INTERVAL_TOO_LONG)?;
+            let (_, failures) = sync_feeds(store, &fetch_opts, &steps, |fi| {
+                if let Some(hubs) = hubs.as_mut() {
+                    hubs.subscribe_all(fi, renew_before);
+                }
+                Ok(fi
+                    .entries
+                    .iter()
+                    .filter(|e| !e.feed.is_on_hold(Utc::now()))
+                    .filter(|e| !hubs.as_ref().is_some_and(|h| h.is_pushed(&e.feed, now)))
+                    .map(|e| e.feed.clone())
+                    .collect())
+            })?;
+            eprint!("{}", failure_report(&failures));
+            next_poll = now.checked_add(opts.interval).context(INTERVAL_TOO_LONG)?;
+        }
+
+        match events.recv_timeout(next_poll.saturating_duration_since(Instant::now())) {
+            Ok(Event::Verified { feed_url, lease }) => {
+                eprintln!("Subscribed to {feed_url} via WebSub");
+                if let Some(hubs) = hubs.as_mut() {
+                    hubs.leases.insert(feed_url, Instant::now() + lease);
+                }
+            }
+            Ok(Event::Content { feed_url, body }) => apply_push(store, &feed_url, &body)?,
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {}
+        }
+    }
+}
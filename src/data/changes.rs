@@ -0,0 +1,211 @@
+//! What changed in the store between two git revisions, in rows rather than
+//! lines: the table files of both are read and their rows compared by ID.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, bail};
+
+/// Tables in the order their changes are listed, with the singular and
+/// plural names of their rows and the field that names a row, if one does
+/// in a way that means something to people.
+pub(crate) const TABLES: &[(&str, &str, &str, Option<&str>)] = &[
+    ("feeds", "feed", "feeds", Some("url")),
+    ("posts", "post", "posts", Some("title")),
+    ("reads", "read mark", "read marks", None),
+    ("later", "read-later entry", "read-later entries", None),
+    ("views", "view", "views", Some("name")),
+    ("snoozes", "snooze", "snoozes", None),
+    ("meta", "setting", "settings", Some("key")),
+];
+
+/// The rows of one table that were added, removed or changed, as the JSON
+/// lines they are stored as. Changed rows are listed with their new line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct TableChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl TableChanges {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn extend(&mut self, other: TableChanges) {
+        self.added.extend(other.added);
+        self.removed.extend(other.removed);
+        self.changed.extend(other.changed);
+    }
+}
+
+/// The lines of the live rows in a table file, by row ID. Deleted rows are
+/// kept as tombstones with a `deleted_at`, and count as absent.
+fn live_rows(content: &str) -> HashMap<String, &str> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let row: serde_json::Value = serde_json::from_str(line).ok()?;
+            if row.get("deleted_at").is_some() {
+                return None;
+            }
+            Some((row.get("id")?.as_str()?.to_string(), line))
+        })
+        .collect()
+}
+
+fn diff_rows(old: &str, new: &str) -> TableChanges {
+    let old = live_rows(old);
+    let new = live_rows(new);
+    let mut changes = TableChanges::default();
+    for (id, line) in &new {
+        match old.get(id) {
+            None => changes.added.push(line.to_string()),
+            Some(before) if before != line => changes.changed.push(line.to_string()),
+            Some(_) => {}
+        }
+    }
+    for (id, line) in &old {
+        if !new.contains_key(id) {
+            changes.removed.push(line.to_string());
+        }
+    }
+    changes
+}
+
+fn git(store: &Path, args: &[&str]) -> anyhow::Result<std::process::Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(store)
+        .args(args)
+        .output()
+        .context("failed to run git")
+}
+
+/// The output of a git command that must succeed.
+pub(crate) fn git_stdout(store: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = git(store, args)?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether `rev` names a commit of the store's repository.
+pub(crate) fn rev_exists(store: &Path, rev: &str) -> anyhow::Result<bool> {
+    let rev = format!("{rev}^{{commit}}");
+    Ok(git(store, &["rev-parse", "--verify", "--quiet", &rev])?
+        .status
+        .success())
+}
+
+/// A file as of `rev`, or nothing if it didn't exist then.
+fn file_at(store: &Path, rev: &str, path: &str) -> anyhow::Result<String> {
+    let output = git(store, &["show", &format!("{rev}:{path}")])?;
+    Ok(if output.status.success() {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    } else {
+        String::new()
+    })
+}
+
+/// How each table changed from `from` to `to`, or in all of `to` when there
+/// is no `from`, e.g. for the first commit. Tables that didn't change are
+/// left out.
+pub(crate) fn table_changes(
+    store: &Path,
+    from: Option<&str>,
+    to: &str,
+) -> anyhow::Result<BTreeMap<String, TableChanges>> {
+    let files = match from {
+        Some(from) => git_stdout(store, &["diff", "--name-only", from, to])?,
+        None => git_stdout(store, &["ls-tree", "-r", "--name-only", to])?,
+    };
+    let mut changes: BTreeMap<String, TableChanges> = BTreeMap::new();
+    for file in files.lines().filter(|f| f.ends_with(".jsonl")) {
+        let Some((table, _)) = file.split_once('/') else {
+            continue;
+        };
+        let old = match from {
+            Some(from) => file_at(store, from, file)?,
+            None => String::new(),
+        };
+        let new = file_at(store, to, file)?;
+        changes
+            .entry(table.to_string())
+            .or_default()
+            .extend(diff_rows(&old, &new));
+    }
+    changes.retain(|_, c| !c.is_empty());
+    Ok(changes)
+}
+
+/// `n` rows, in singular or plural.
+pub(crate) fn count_rows(n: usize, one: &str, many: &str) -> String {
+    format!("{n} {}", if n == 1 { one } else { many })
+}
+
+/// The row counts of the changes, like `+23 posts, -1 feed, ~2 settings`.
+pub(crate) fn format_counts(changes: &BTreeMap<String, TableChanges>) -> String {
+    let mut parts = Vec::new();
+    for (table, one, many, _) in TABLES {
+        let Some(c) = changes.get(*table) else {
+            continue;
+        };
+        for (sign, rows) in [('+', &c.added), ('-', &c.removed), ('~', &c.changed)] {
+            if !rows.is_empty() {
+                parts.push(format!("{sign}{}", count_rows(rows.len(), one, many)));
+            }
+        }
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_rows_counts_tombstones_as_removed() {
+        let old = r#"{"id":"a","key":"x","value":"1"}
+{"id":"b","key":"y","value":"1"}
+{"id":"c","key":"z","value":"1"}"#;
+        let new = r#"{"id":"a","key":"x","value":"1"}
+{"id":"b","key":"y","value":"2"}
+{"id":"c","deleted_at":"2024-01-01T00:00:00Z"}
+{"id":"d","key":"w","value":"1"}"#;
+        let changes = diff_rows(old, new);
+        assert_eq!(changes.added, [r#"{"id":"d","key":"w","value":"1"}"#]);
+        assert_eq!(changes.removed, [r#"{"id":"c","key":"z","value":"1"}"#]);
+        assert_eq!(changes.changed, [r#"{"id":"b","key":"y","value":"2"}"#]);
+    }
+
+    #[test]
+    fn test_format_counts_in_table_order() {
+        let rows = |n: usize| vec![String::new(); n];
+        let changes = BTreeMap::from([
+            (
+                "posts".to_string(),
+                TableChanges {
+                    added: rows(23),
+                    ..Default::default()
+                },
+            ),
+            (
+                "feeds".to_string(),
+                TableChanges {
+                    added: Vec::new(),
+                    removed: rows(1),
+                    changed: rows(2),
+                },
+            ),
+        ]);
+        assert_eq!(format_counts(&changes), "-1 feed, ~2 feeds, +23 posts");
+    }
+}
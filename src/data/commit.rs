@@ -0,0 +1,130 @@
+//! Git commits made for changes to the store.
+//!
+//! The message of each commit can be customised with the `commit_message`
+//! config key, and its author with `commit_author_name` and
+//! `commit_author_email`, which are written to the store repository's git
+//! config. Commits made by synctato itself while syncing ("sync" and merges)
+//! use the same author but their own messages.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, bail};
+
+use super::{BlogData, Transaction, get_config_value};
+
+const DEFAULT_TEMPLATE: &str = "{action}";
+
+/// Fill in `{action}` and `{host}` in a commit message template.
+fn render_message(template: &str, action: &str, host: &str) -> String {
+    template.replace("{action}", action).replace("{host}", host)
+}
+
+/// Name of this machine, for `{host}`.
+fn host_name() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| {
+            Command::new("hostname")
+                .output()
+                .ok()
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        })
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn git_config(dir: &Path, args: &[&str]) -> anyhow::Result<std::process::Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["config", "--local"])
+        .args(args)
+        .output()
+        .context("failed to run git")
+}
+
+/// Config keys naming the commit author, with the `user.<field>` of git's
+/// config they stand for.
+const AUTHOR_KEYS: [(&str, &str); 2] = [
+    ("commit_author_name", "name"),
+    ("commit_author_email", "email"),
+];
+
+/// Point the store repository's `user.<field>` at `value`.
+fn set_identity(dir: &Path, field: &str, value: &str) -> anyhow::Result<()> {
+    let key = format!("user.{field}");
+    let current = git_config(dir, &["--get", &key])?;
+    if String::from_utf8_lossy(&current.stdout).trim() == value {
+        return Ok(());
+    }
+    let output = git_config(dir, &[&key, value])?;
+    if !output.status.success() {
+        bail!(
+            "failed to set {key}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Make the store repository's git identity follow the `commit_author_*`
+/// config keys. synctato signs commits with the repository's identity, so
+/// this is done when the keys are set here or arrive with a sync, rather
+/// than before every commit.
+pub(crate) fn apply_author(store: &BlogData) -> anyhow::Result<()> {
+    if !store.path().join(".git").exists() {
+        return Ok(());
+    }
+    for (key, field) in AUTHOR_KEYS {
+        if let Some(value) = get_config_value(store, key) {
+            set_identity(store.path(), field, &value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Take the identity set for config `key` back out of the store
+/// repository's git config, if `key` is one of the `commit_author_*` keys.
+pub(crate) fn clear_author(store: &BlogData, key: &str) -> anyhow::Result<()> {
+    let Some((_, field)) = AUTHOR_KEYS.iter().find(|(k, _)| *k == key) else {
+        return Ok(());
+    };
+    if store.path().join(".git").exists() {
+        // Fails only if it wasn't set, which is just as good
+        git_config(store.path(), &["--unset", &format!("user.{field}")])?;
+    }
+    Ok(())
+}
+
+/// Run `f` in a store transaction, committing the result with a message
+/// describing `action`.
+pub(crate) fn transact<T>(
+    store: &mut BlogData,
+    action: &str,
+    f: impl FnOnce(&mut Transaction<'_>) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let message = match get_config_value(store, "commit_message") {
+        Some(template) => render_message(&template, action, &host_name()),
+        None => render_message(DEFAULT_TEMPLATE, action, ""),
+    };
+    tracing::debug!(commit = %message, "store transaction");
+    store.transact(&message, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::default(DEFAULT_TEMPLATE, "mark read")]
+    #[case::with_host("{action} ({host})", "mark read (laptop)")]
+    #[case::repeated("[{host}] {action}: {action}", "[laptop] mark read: mark read")]
+    #[case::no_placeholders("blogtato update", "blogtato update")]
+    fn test_render_message(#[case] template: &str, #[case] expected: &str) {
+        assert_eq!(render_message(template, "mark read", "laptop"), expected);
+    }
+}
@@ -31,6 +31,10 @@ impl FeedIndex {
         self.find_by_shorthand(shorthand).map(|e| e.id.as_str())
     }
 
+    pub(crate) fn feed_for_shorthand(&self, shorthand: &str) -> Option<&FeedSource> {
+        self.find_by_shorthand(shorthand).map(|e| &e.feed)
+    }
+
     pub(crate) fn url_for_shorthand(&self, shorthand: &str) -> Option<&str> {
         self.find_by_shorthand(shorthand)
             .map(|e| e.feed.url.as_str())
@@ -66,6 +70,18 @@ pub(crate) fn resolve_shorthand(
         .map(|s| s.to_string())
 }
 
+/// Turn a feed argument, either a URL or `@shorthand`, into the feed's URL.
+pub(crate) fn resolve_feed_arg(
+    feeds_table: &synctato::Table<FeedSource>,
+    arg: &str,
+) -> anyhow::Result<String> {
+    match arg.strip_prefix('@') {
+        Some(shorthand) => resolve_shorthand(feeds_table, shorthand)
+            .ok_or_else(|| anyhow::anyhow!("Unknown feed shorthand: @{}", shorthand)),
+        None => Ok(arg.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,10 +90,7 @@ mod tests {
     fn make_feed(url: &str) -> FeedSource {
         FeedSource {
             url: url.to_string(),
-            title: String::new(),
-            site_url: String::new(),
-            description: String::new(),
-            is_fetched: false,
+            ..Default::default()
         }
     }
 
@@ -1,16 +1,26 @@
+pub(crate) mod changes;
+mod commit;
 pub mod index;
+pub(crate) mod post_cache;
 pub mod schema;
+pub(crate) mod search;
+
+pub(crate) use commit::{apply_author, clear_author, transact};
+
+use std::collections::HashSet;
 
 use schema::{BlogDataSchema, MetaEntry};
+use sha2::{Digest, Sha256};
 use synctato::Store;
 
 pub(crate) type BlogData = Store<BlogDataSchema>;
 pub(crate) type Transaction<'a> = schema::BlogDataSchemaTransaction<'a>;
 
 impl Transaction<'_> {
-    /// Delete posts matching `pred` and cascade-delete their ReadMarks.
+    /// Delete posts matching `pred` and cascade-delete their ReadMarks,
+    /// read-later entries and snoozes.
     pub(crate) fn delete_posts_where(&mut self, pred: impl Fn(&schema::FeedItem) -> bool) {
-        let post_ids: Vec<String> = self
+        let post_ids: HashSet<String> = self
             .posts
             .iter()
             .filter(|(_, p)| pred(p))
@@ -18,6 +28,8 @@ impl Transaction<'_> {
             .collect();
         self.posts.delete_where(pred);
         self.reads.delete_where(|r| post_ids.contains(&r.post_id));
+        self.later.delete_where(|e| post_ids.contains(&e.post_id));
+        self.snoozes.delete_where(|e| post_ids.contains(&e.post_id));
     }
 }
 
@@ -49,7 +61,7 @@ pub(crate) fn check_schema_version(store: &mut BlogData) -> anyhow::Result<()> {
             }
         }
         None => {
-            store.transact("set schema version", |tx| {
+            transact(store, "set schema version", |tx| {
                 tx.meta.upsert(MetaEntry {
                     key: "schema_version".to_string(),
                     value: SCHEMA_VERSION.to_string(),
@@ -62,6 +74,25 @@ pub(crate) fn check_schema_version(store: &mut BlogData) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Read a config value and parse it, naming the key if the stored value is
+/// invalid.
+pub(crate) fn get_config_parsed<T>(store: &BlogData, key: &str) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    get_config_value(store, key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid value for config {key}: {value:?} ({e})"))
+        })
+        .transpose()
+}
+
+/// The value of config `key`. Config lives in the store, so it is committed
+/// to git and synced to every machine; passwords and tokens are read from
+/// environment variables instead.
 pub(crate) fn get_config_value(store: &BlogData, key: &str) -> Option<String> {
     let full_key = format!("config.{key}");
     store
@@ -70,3 +101,14 @@ pub(crate) fn get_config_value(store: &BlogData, key: &str) -> Option<String> {
         .find(|(_, e)| e.key == full_key)
         .map(|(_, e)| e.value.clone())
 }
+
+/// A short key for the store's location, to keep files about this store that
+/// don't belong in it, like the search index, apart from other stores'.
+pub(crate) fn store_key(store: &BlogData) -> String {
+    let path = store
+        .path()
+        .canonicalize()
+        .unwrap_or_else(|_| store.path().to_path_buf());
+    let hash = Sha256::digest(path.to_string_lossy().as_bytes());
+    hash[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
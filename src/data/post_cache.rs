@@ -0,0 +1,164 @@
+//! Listing order, codes and shorthands of the posts of large stores.
+//!
+//! Naming a post by its shorthand means sorting every post and working out
+//! the shortest unique prefix of each code, which is most of what `blog open`
+//! or `blog read` spends with hundreds of thousands of posts. Like the search
+//! index, the result is derived from the store, so it is kept in the user's
+//! cache directory. Sync refreshes it, and any command that finds it out of
+//! date with the posts works it out again.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+use crate::data::{BlogData, store_key};
+
+/// Smaller stores work their shorthands out about as fast as they read them.
+const MIN_CACHED_POSTS: usize = 5_000;
+
+/// First word of the cache file; a different one means another layout.
+const FORMAT: &str = "posts-1";
+
+/// A post's place in the listing, newest first.
+#[derive(Debug, PartialEq)]
+pub(crate) struct PostName {
+    /// ID of the post's row in the posts table
+    pub id: String,
+    /// Full post code; shorthands are prefixes of it
+    pub code: String,
+    pub shorthand: String,
+}
+
+/// Where the cache of `store` is kept, separate for every store.
+fn cache_file(store: &BlogData) -> anyhow::Result<PathBuf> {
+    let cache = dirs::cache_dir().context("could not determine cache directory")?;
+    Ok(cache.join("blogtato").join("posts").join(store_key(store)))
+}
+
+/// Sums up the IDs and dates of `posts`, all that their order and
+/// shorthands depend on, in any order.
+fn fingerprint<'a>(posts: impl Iterator<Item = (&'a str, Option<DateTime<Utc>>)>) -> String {
+    let (count, sum) = posts.fold((0usize, 0u64), |(count, sum), (id, date)| {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        date.map(|d| d.timestamp_micros()).hash(&mut hasher);
+        (count + 1, sum.wrapping_add(hasher.finish()))
+    });
+    format!("{count}-{sum:016x}")
+}
+
+fn read(path: &Path, fingerprint: &str) -> Option<Vec<PostName>> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let mut lines = text.lines();
+    if lines.next()? != format!("{FORMAT} {fingerprint}") {
+        return None;
+    }
+    lines
+        .map(|line| {
+            let mut fields = line.split(' ');
+            let name = PostName {
+                id: fields.next()?.to_string(),
+                code: fields.next()?.to_string(),
+                shorthand: fields.next()?.to_string(),
+            };
+            Some(name)
+        })
+        .collect()
+}
+
+fn write(path: &Path, fingerprint: &str, names: &[PostName]) -> anyhow::Result<()> {
+    let mut text = format!("{FORMAT} {fingerprint}\n");
+    for name in names {
+        text.push_str(&format!("{} {} {}\n", name.id, name.code, name.shorthand));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// The posts of `store` in listing order with their codes and shorthands,
+/// from the cache if it matches the posts, or else from `compute`, which is
+/// then cached for large stores. A cache that can't be written only makes
+/// the next command slower, so that is just logged.
+pub(crate) fn post_names(
+    store: &BlogData,
+    compute: impl FnOnce() -> Vec<PostName>,
+) -> Vec<PostName> {
+    let path = match cache_file(store) {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::debug!(error = %e, "post cache unavailable");
+            return compute();
+        }
+    };
+    let fingerprint = fingerprint(store.posts().iter().map(|(id, post)| (id, post.date)));
+    if let Some(names) = read(&path, &fingerprint) {
+        return names;
+    }
+    let names = compute();
+    if names.len() >= MIN_CACHED_POSTS {
+        match write(&path, &fingerprint, &names) {
+            Ok(()) => {
+                tracing::debug!(path = %path.display(), posts = names.len(), "cached post names")
+            }
+            Err(e) => tracing::debug!(error = %e, "failed to cache post names"),
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn post(id: &'static str, day: u32) -> (&'static str, Option<DateTime<Utc>>) {
+        (
+            id,
+            Some(Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()),
+        )
+    }
+
+    fn fingerprint_of(posts: &[(&str, Option<DateTime<Utc>>)]) -> String {
+        fingerprint(posts.iter().copied())
+    }
+
+    fn name(id: &str, code: &str, shorthand: &str) -> PostName {
+        PostName {
+            id: id.to_string(),
+            code: code.to_string(),
+            shorthand: shorthand.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order_but_not_dates() {
+        let posts = vec![post("aa", 1), post("bb", 2)];
+        let reversed: Vec<_> = posts.iter().rev().copied().collect();
+        assert_eq!(fingerprint_of(&posts), fingerprint_of(&reversed));
+
+        let redated = vec![post("aa", 1), post("bb", 3)];
+        assert_ne!(fingerprint_of(&posts), fingerprint_of(&redated));
+        assert_ne!(fingerprint_of(&posts), fingerprint_of(&posts[..1]));
+    }
+
+    #[test]
+    fn test_read_returns_what_was_written_for_the_same_posts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("posts").join("store");
+        let names = vec![name("bb", "kdfj", "k"), name("aa", "abcd", "a")];
+
+        write(&path, "2-0123", &names).unwrap();
+
+        assert_eq!(read(&path, "2-0123"), Some(names));
+        assert_eq!(read(&path, "2-4567"), None);
+        assert_eq!(read(&dir.path().join("missing"), "2-0123"), None);
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FeedItem {
     pub title: String,
     pub date: Option<DateTime<Utc>>,
@@ -10,6 +10,66 @@ pub struct FeedItem {
     pub link: String,
     #[serde(default)]
     pub raw_id: String,
+    /// Summary or description as published by the feed (may contain HTML).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+    /// Full text of the post when the feed gives it apart from the summary,
+    /// as RSS feeds do in `content:encoded` (may contain HTML).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub content: String,
+    /// Images the feed attaches to the post with Media RSS, such as its
+    /// thumbnail.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    /// Name of the post's author, if the feed gives one.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// Categories or tags the feed files the post under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Link to the post's discussion page, if the feed gives one.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub comments: String,
+    /// When the post was first fetched, which for feeds that backfill old
+    /// posts can be long after it was published. Unknown for posts fetched
+    /// before this was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_at: Option<DateTime<Utc>>,
+    /// ISO 639-1 code of the language the post is written in, if detected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// How often the feed changed the post's title or text after it was first
+    /// fetched.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub edits: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl FeedItem {
+    /// Whether the feed changed the post since it was first fetched.
+    pub fn is_updated(&self) -> bool {
+        self.edits > 0
+    }
+
+    /// The post's text: its full content if the feed gives it, or else the
+    /// summary.
+    pub fn body(&self) -> &str {
+        if self.content.is_empty() {
+            &self.summary
+        } else {
+            &self.content
+        }
+    }
+
+    /// Whether the post is filed under `category`, ignoring case.
+    pub fn has_category(&self, category: &str) -> bool {
+        self.categories
+            .iter()
+            .any(|c| c.to_lowercase() == category.to_lowercase())
+    }
 }
 
 impl synctato::TableRow for FeedItem {
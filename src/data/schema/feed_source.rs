@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use synctato::TableRow;
@@ -6,7 +7,7 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FeedSource {
     pub url: String,
     #[serde(default)]
@@ -17,6 +18,62 @@ pub struct FeedSource {
     pub description: String,
     #[serde(default = "default_true")]
     pub is_fetched: bool,
+    /// WebSub hub advertised by the feed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hub: String,
+    /// The feed's own (`rel="self"`) URL, used as the WebSub topic
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub topic: String,
+    /// Skipped by sync until resumed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub paused: bool,
+    /// Posts are left out of listings while the feed is paused
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// Unsubscribed with `feed rm --keep-posts`: paused for good, but its
+    /// posts stay in the store
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Why the last fetch failed; empty if it succeeded
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub fetch_error: String,
+    /// When fetches of the feed started failing; none once one succeeds.
+    /// Unlike a count of failures, it doesn't change with every sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failing_since: Option<DateTime<Utc>>,
+    /// Overrides the `retention` config key, e.g. `90d` or `200`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub retention: String,
+    /// Overrides the `timeout` config key, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Until when the feed is snoozed: not fetched and its posts hidden, as
+    /// if paused with `--hide`, after which it resumes by itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Folder the feed is filed in, with `/` between nested folders, e.g.
+    /// `Tech/Rust`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub folder: String,
+}
+
+impl FeedSource {
+    /// Whether the feed is snoozed at `now`.
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now)
+    }
+
+    /// Whether sync leaves the feed out unless it is named: while it is
+    /// paused or snoozed.
+    pub fn is_on_hold(&self, now: DateTime<Utc>) -> bool {
+        self.paused || self.is_snoozed(now)
+    }
+
+    /// Whether the feed's posts are left out of listings: while it is paused
+    /// with `--hide` or snoozed.
+    pub fn hides_posts(&self, now: DateTime<Utc>) -> bool {
+        self.hidden || self.is_snoozed(now)
+    }
 }
 
 impl TableRow for FeedSource {
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use synctato::TableRow;
+
+/// A post queued with `blog later`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaterEntry {
+    pub post_id: String,
+    pub added_at: DateTime<Utc>,
+}
+
+impl TableRow for LaterEntry {
+    fn key(&self) -> String {
+        self.post_id.clone()
+    }
+
+    const TABLE_NAME: &'static str = "later";
+    const SHARD_CHARACTERS: usize = 0;
+    const EXPECTED_CAPACITY: usize = 100;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synctato::Table;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let mut table = Table::<LaterEntry>::load(dir.path()).unwrap();
+
+        let entry = LaterEntry {
+            post_id: "some-post-id".to_string(),
+            added_at: Utc::now(),
+        };
+        table.upsert(entry.clone());
+        table.save().unwrap();
+
+        let loaded = Table::<LaterEntry>::load(dir.path()).unwrap();
+        let items: Vec<_> = loaded.iter().map(|(_, e)| e).collect();
+        assert_eq!(items, vec![&entry]);
+    }
+}
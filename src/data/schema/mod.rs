@@ -1,17 +1,26 @@
 mod feed_item;
 mod feed_source;
+mod later_entry;
 mod meta;
 mod read_mark;
+mod saved_view;
+mod snooze_entry;
 
 pub use feed_item::FeedItem;
 pub use feed_source::FeedSource;
+pub use later_entry::LaterEntry;
 pub use meta::MetaEntry;
 pub use read_mark::ReadMark;
+pub use saved_view::SavedView;
+pub use snooze_entry::SnoozeEntry;
 
 synctato::schema!(pub(crate) BlogDataSchema {
     feeds: FeedSource,
     posts: FeedItem,
     reads: ReadMark,
+    later: LaterEntry,
+    views: SavedView,
+    snoozes: SnoozeEntry,
     meta: MetaEntry,
 });
 
@@ -19,5 +28,8 @@ synctato::store!(BlogDataSchema {
     feeds: FeedSource,
     posts: FeedItem,
     reads: ReadMark,
+    later: LaterEntry,
+    views: SavedView,
+    snoozes: SnoozeEntry,
     meta: MetaEntry,
 });
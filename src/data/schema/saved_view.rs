@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use synctato::TableRow;
+
+/// A named query saved with `blog view add`, used as `%name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    /// Query arguments separated by spaces, e.g. `.unread ~rust /d`
+    pub query: String,
+}
+
+impl TableRow for SavedView {
+    fn key(&self) -> String {
+        self.name.clone()
+    }
+
+    const TABLE_NAME: &'static str = "views";
+    const SHARD_CHARACTERS: usize = 0;
+    const EXPECTED_CAPACITY: usize = 1000;
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use synctato::TableRow;
+
+/// A post snoozed with `blog snooze`: left out of listings until `until`,
+/// then listed as due.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnoozeEntry {
+    pub post_id: String,
+    pub until: DateTime<Utc>,
+}
+
+impl SnoozeEntry {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.until <= now
+    }
+}
+
+impl TableRow for SnoozeEntry {
+    fn key(&self) -> String {
+        self.post_id.clone()
+    }
+
+    const TABLE_NAME: &'static str = "snoozes";
+    const SHARD_CHARACTERS: usize = 0;
+    const EXPECTED_CAPACITY: usize = 100;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synctato::Table;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let mut table = Table::<SnoozeEntry>::load(dir.path()).unwrap();
+
+        let entry = SnoozeEntry {
+            post_id: "some-post-id".to_string(),
+            until: Utc::now(),
+        };
+        table.upsert(entry.clone());
+        table.save().unwrap();
+
+        let loaded = Table::<SnoozeEntry>::load(dir.path()).unwrap();
+        let items: Vec<_> = loaded.iter().map(|(_, e)| e).collect();
+        assert_eq!(items, vec![&entry]);
+    }
+}
@@ -0,0 +1,372 @@
+//! Full-text index of post titles and summaries for `blog search`.
+//!
+//! The index is derived from the store, so it lives in the user's cache
+//! directory instead of the synced store. The first search builds it; later
+//! searches and syncs bring it up to date with the posts in the store.
+
+use std::collections::HashSet;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use tantivy::collector::TopDocs;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{FAST, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, Value};
+use tantivy::{Index, IndexWriter, Order, ReloadPolicy, TantivyDocument, Term, doc};
+
+use crate::data::schema::FeedItem;
+use crate::data::{BlogData, store_key};
+use crate::utils::html::to_text;
+
+/// Memory the index writer may use before flushing to disk.
+const WRITER_MEMORY: usize = 50_000_000;
+
+/// File next to the index listing the post IDs it holds, one per line.
+const IDS_FILE: &str = "indexed_ids";
+
+#[derive(Clone, Copy)]
+struct Fields {
+    id: tantivy::schema::Field,
+    title: tantivy::schema::Field,
+    summary: tantivy::schema::Field,
+    feed: tantivy::schema::Field,
+    date: tantivy::schema::Field,
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_text_field("id", STRING | STORED),
+        title: builder.add_text_field("title", TEXT),
+        summary: builder.add_text_field("summary", TEXT),
+        feed: builder.add_text_field("feed", STRING),
+        date: builder.add_i64_field("date", INDEXED | FAST),
+    };
+    (builder.build(), fields)
+}
+
+/// What to search for: words and `"phrases"` in tantivy's query syntax, and
+/// restrictions on the posts that can match.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct SearchQuery {
+    pub text: String,
+    /// Only posts of the feed with this ID
+    pub feed: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    /// Only posts published before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+pub(crate) struct SearchIndex {
+    index: Index,
+    fields: Fields,
+    dir: PathBuf,
+}
+
+/// Where the index of `store` is kept, separate for every store.
+fn index_dir(store: &BlogData) -> anyhow::Result<PathBuf> {
+    let cache = dirs::cache_dir().context("could not determine cache directory")?;
+    Ok(cache.join("blogtato").join("search").join(store_key(store)))
+}
+
+impl SearchIndex {
+    /// Open the index of `store`, creating it if needed.
+    pub(crate) fn open(store: &BlogData) -> anyhow::Result<Self> {
+        Self::open_in(&index_dir(store)?)
+    }
+
+    /// Open the index of `store` if a search has created it.
+    pub(crate) fn open_existing(store: &BlogData) -> anyhow::Result<Option<Self>> {
+        let dir = index_dir(store)?;
+        if !dir.join("meta.json").exists() {
+            return Ok(None);
+        }
+        Self::open_in(&dir).map(Some)
+    }
+
+    fn open_in(dir: &Path) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let (schema, fields) = schema();
+        let directory = tantivy::directory::MmapDirectory::open(dir)?;
+        let index = Index::open_or_create(directory, schema)
+            .with_context(|| format!("Failed to open search index in {}", dir.display()))?;
+        Ok(Self {
+            index,
+            fields,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// IDs of the posts in the index, or `None` if unknown, e.g. after an
+    /// interrupted update.
+    fn indexed_ids(&self) -> Option<HashSet<String>> {
+        let text = std::fs::read_to_string(self.dir.join(IDS_FILE)).ok()?;
+        Some(text.lines().map(str::to_string).collect())
+    }
+
+    fn write_indexed_ids(&self, ids: &HashSet<&str>) -> anyhow::Result<()> {
+        let mut sorted: Vec<&str> = ids.iter().copied().collect();
+        sorted.sort_unstable();
+        let tmp = self.dir.join(format!("{IDS_FILE}.tmp"));
+        std::fs::write(&tmp, sorted.join("\n"))?;
+        std::fs::rename(&tmp, self.dir.join(IDS_FILE))?;
+        Ok(())
+    }
+
+    /// Add the posts of `store` that aren't indexed yet and drop the ones
+    /// that were removed. Returns how many posts were added.
+    pub(crate) fn update(&self, store: &BlogData) -> anyhow::Result<usize> {
+        self.update_from(store.posts().iter())
+    }
+
+    fn update_from<'a>(
+        &self,
+        posts: impl Iterator<Item = (&'a str, &'a FeedItem)>,
+    ) -> anyhow::Result<usize> {
+        let posts: Vec<(&str, &FeedItem)> = posts.collect();
+        let current: HashSet<&str> = posts.iter().map(|(id, _)| *id).collect();
+        let indexed = self.indexed_ids();
+
+        let added: Vec<&(&str, &FeedItem)> = posts
+            .iter()
+            .filter(|(id, _)| indexed.as_ref().is_none_or(|ids| !ids.contains(*id)))
+            .collect();
+        let removed: Vec<&String> = indexed
+            .as_ref()
+            .map(|ids| {
+                ids.iter()
+                    .filter(|id| !current.contains(String::as_str(id)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if added.is_empty() && removed.is_empty() {
+            return Ok(0);
+        }
+
+        let mut writer: IndexWriter = self
+            .index
+            .writer_with_num_threads(1, WRITER_MEMORY)
+            .context("Failed to lock the search index")?;
+        if indexed.is_none() {
+            writer.delete_all_documents()?;
+        }
+        for id in removed {
+            writer.delete_term(Term::from_field_text(self.fields.id, id));
+        }
+        for (id, post) in &added {
+            // Replace any copy left by an update that didn't record its IDs
+            writer.delete_term(Term::from_field_text(self.fields.id, id));
+            let mut doc = doc!(
+                self.fields.id => *id,
+                self.fields.title => post.title.as_str(),
+                self.fields.summary => to_text(&post.summary),
+                self.fields.feed => post.feed.as_str(),
+            );
+            if let Some(date) = post.date {
+                doc.add_i64(self.fields.date, date.timestamp());
+            }
+            writer.add_document(doc)?;
+        }
+        writer.commit()?;
+        self.write_indexed_ids(&current)?;
+        Ok(added.len())
+    }
+
+    /// IDs of up to `limit` posts matching `query`, best match first, or
+    /// newest first when the query has no text.
+    pub(crate) fn search(&self, query: &SearchQuery, limit: usize) -> anyhow::Result<Vec<String>> {
+        let reader = self
+            .index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let searcher = reader.searcher();
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        if !query.text.trim().is_empty() {
+            let mut parser =
+                QueryParser::for_index(&self.index, vec![self.fields.title, self.fields.summary]);
+            parser.set_conjunction_by_default();
+            parser.set_field_boost(self.fields.title, 2.0);
+            let text = parser
+                .parse_query(&query.text)
+                .map_err(|e| anyhow::anyhow!("Invalid search {:?}: {e}", query.text))?;
+            clauses.push((Occur::Must, text));
+        }
+        if let Some(feed) = &query.feed {
+            let term = Term::from_field_text(self.fields.feed, feed);
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+        if query.since.is_some() || query.until.is_some() {
+            let bound = |date: Option<DateTime<Utc>>, make: fn(Term) -> Bound<Term>| {
+                date.map_or(Bound::Unbounded, |d| {
+                    make(Term::from_field_i64(self.fields.date, d.timestamp()))
+                })
+            };
+            let range = RangeQuery::new(
+                bound(query.since, Bound::Included),
+                bound(query.until, Bound::Excluded),
+            );
+            clauses.push((Occur::Must, Box::new(range)));
+        }
+        let has_text = !query.text.trim().is_empty();
+        if clauses.is_empty() {
+            clauses.push((Occur::Must, Box::new(AllQuery)));
+        }
+        let combined = BooleanQuery::new(clauses);
+
+        let addresses: Vec<_> = if has_text {
+            searcher
+                .search(&combined, &TopDocs::with_limit(limit))?
+                .into_iter()
+                .map(|(_, address)| address)
+                .collect()
+        } else {
+            let newest = TopDocs::with_limit(limit).order_by_fast_field::<i64>("date", Order::Desc);
+            searcher
+                .search(&combined, &newest)?
+                .into_iter()
+                .map(|(_, address)| address)
+                .collect()
+        };
+        addresses
+            .into_iter()
+            .map(|address| {
+                let doc: TantivyDocument = searcher.doc(address)?;
+                Ok(doc
+                    .get_first(self.fields.id)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    fn day(day: u32) -> Option<DateTime<Utc>> {
+        Some(Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap())
+    }
+
+    fn posts() -> Vec<(String, FeedItem)> {
+        vec![
+            (
+                "a".to_string(),
+                FeedItem {
+                    title: "Rust borrow checker".to_string(),
+                    date: day(1),
+                    feed: "f1".to_string(),
+                    summary: "<p>Lifetimes explained</p>".to_string(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "b".to_string(),
+                FeedItem {
+                    title: "Weekly notes".to_string(),
+                    date: day(2),
+                    feed: "f2".to_string(),
+                    summary: "<p>Some thoughts on the rust borrow checker</p>".to_string(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "c".to_string(),
+                FeedItem {
+                    title: "Gardening".to_string(),
+                    date: day(3),
+                    feed: "f1".to_string(),
+                    summary: "<p>The checker board of tomatoes</p>".to_string(),
+                    ..Default::default()
+                },
+            ),
+        ]
+    }
+
+    fn indexed(posts: &[(String, FeedItem)]) -> (tempfile::TempDir, SearchIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        let index = SearchIndex::open_in(dir.path()).unwrap();
+        index
+            .update_from(posts.iter().map(|(id, p)| (id.as_str(), p)))
+            .unwrap();
+        (dir, index)
+    }
+
+    fn search(index: &SearchIndex, query: SearchQuery) -> Vec<String> {
+        index.search(&query, 10).unwrap()
+    }
+
+    fn text(text: &str) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::title_ranks_first("borrow checker", &["a", "b"])]
+    #[case::phrase("\"checker board\"", &["c"])]
+    #[case::all_words_required("rust tomatoes", &[])]
+    #[case::no_text("", &["c", "b", "a"])]
+    fn test_search(#[case] query: &str, #[case] expected: &[&str]) {
+        let (_dir, index) = indexed(&posts());
+        assert_eq!(search(&index, text(query)), expected);
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let (_dir, index) = indexed(&posts());
+
+        let by_feed = SearchQuery {
+            feed: Some("f1".to_string()),
+            ..text("checker")
+        };
+        assert_eq!(search(&index, by_feed), ["a", "c"]);
+
+        let by_date = SearchQuery {
+            since: Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()),
+            until: Some(Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap()),
+            ..text("checker")
+        };
+        assert_eq!(search(&index, by_date), ["b"]);
+    }
+
+    #[test]
+    fn test_update_adds_and_removes_posts() {
+        let mut posts = posts();
+        let (_dir, index) = indexed(&posts);
+
+        posts.remove(0);
+        posts.push((
+            "d".to_string(),
+            FeedItem {
+                title: "Borrow checker, again".to_string(),
+                date: day(4),
+                feed: "f2".to_string(),
+                ..Default::default()
+            },
+        ));
+        let added = index
+            .update_from(posts.iter().map(|(id, p)| (id.as_str(), p)))
+            .unwrap();
+
+        assert_eq!(added, 1);
+        assert_eq!(search(&index, text("borrow")), ["d", "b"]);
+    }
+
+    #[test]
+    fn test_invalid_query_is_an_error() {
+        let (_dir, index) = indexed(&posts());
+        assert!(index.search(&text("title:"), 10).is_err());
+    }
+}
@@ -0,0 +1,720 @@
+//! Render post HTML as readable terminal text.
+//!
+//! With color, emphasis uses ANSI styles. Without color (e.g. piped output)
+//! the same structure is expressed as Markdown, so the result can be saved or
+//! fed to other tools. Links are numbered in the text and listed after it.
+
+use unicode_width::UnicodeWidthStr;
+
+use super::Style;
+use crate::data::schema::FeedItem;
+use crate::utils::html::{attr, decode_entities, tag_name};
+
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "head", "noscript"];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "figure",
+    "figcaption",
+    "table",
+    "dl",
+];
+const CODE_BLOCK_INDENT: &str = "    ";
+/// Long lines are hard to read, so wide terminals still wrap at this column.
+const MAX_TEXT_WIDTH: usize = 100;
+const FALLBACK_WIDTH: usize = 80;
+
+/// Continuation prefix of a blockquote, list item or code block.
+struct Prefix {
+    /// Element that pushed it
+    owner: &'static str,
+    text: String,
+}
+
+/// An open `ul` or `ol`.
+struct List {
+    /// `None` for bullets, `Some(n)` for numbering
+    number: Option<usize>,
+    /// Number of prefixes open outside the list
+    depth: usize,
+}
+
+/// Inline emphasis markers: `(open, close)` pairs.
+struct Markers {
+    bold: (&'static str, &'static str),
+    italic: (&'static str, &'static str),
+    code: (&'static str, &'static str),
+    dim: (&'static str, &'static str),
+}
+
+impl Markers {
+    fn new(color: bool) -> Self {
+        if color {
+            Self {
+                bold: ("\x1b[1m", "\x1b[22m"),
+                italic: ("\x1b[3m", "\x1b[23m"),
+                code: ("\x1b[36m", "\x1b[39m"),
+                dim: ("\x1b[2m", "\x1b[22m"),
+            }
+        } else {
+            Self {
+                bold: ("**", "**"),
+                italic: ("*", "*"),
+                code: ("`", "`"),
+                dim: ("", ""),
+            }
+        }
+    }
+}
+
+struct Renderer {
+    width: usize,
+    color: bool,
+    markers: Markers,
+    out: String,
+    line: String,
+    line_width: usize,
+    /// Continuation prefixes for nested blockquotes and list items
+    prefixes: Vec<Prefix>,
+    /// Marker to print instead of the innermost prefix on the next line
+    marker: Option<String>,
+    lists: Vec<List>,
+    pre: usize,
+    pending_space: bool,
+    /// A blank line separates the previous block from the next line written
+    pending_blank: bool,
+    /// Post URL that relative links are resolved against
+    base: Option<url::Url>,
+    /// Link targets in order of first appearance
+    links: Vec<String>,
+    /// Number of the link being rendered, shown after its text
+    open_link: Option<usize>,
+}
+
+impl Renderer {
+    fn new(width: usize, color: bool, base: &str) -> Self {
+        Self {
+            width,
+            color,
+            markers: Markers::new(color),
+            out: String::new(),
+            line: String::new(),
+            line_width: 0,
+            prefixes: Vec::new(),
+            marker: None,
+            lists: Vec::new(),
+            pre: 0,
+            pending_space: false,
+            pending_blank: false,
+            base: url::Url::parse(base).ok(),
+            links: Vec::new(),
+            open_link: None,
+        }
+    }
+
+    /// Start a link to `href`, numbering it unless it points within the page
+    /// or can't be opened.
+    fn open_link(&mut self, href: Option<String>) {
+        let Some(href) = href.map(|h| h.trim().to_string()) else {
+            return;
+        };
+        if href.is_empty() || href.starts_with('#') {
+            return;
+        }
+        let target = match &self.base {
+            Some(base) => base.join(&href),
+            None => url::Url::parse(&href),
+        };
+        let Some(target) = target.ok().filter(is_openable) else {
+            return;
+        };
+        let target = target.to_string();
+        let index = match self.links.iter().position(|l| *l == target) {
+            Some(i) => i,
+            None => {
+                self.links.push(target);
+                self.links.len() - 1
+            }
+        };
+        self.open_link = Some(index + 1);
+    }
+
+    /// End the current link with its number, e.g. `text[3]`.
+    fn close_link(&mut self) {
+        let Some(n) = self.open_link.take() else {
+            return;
+        };
+        let reference = format!("[{n}]");
+        self.start_line();
+        if self.line_width + reference.width() > self.width && self.line_width > self.prefix_width()
+        {
+            self.flush_line();
+            self.start_line();
+        }
+        self.pending_space = false;
+        let (open, close) = self.markers.dim;
+        self.line.push_str(&format!("{open}{reference}{close}"));
+        self.line_width += reference.width();
+    }
+
+    fn start_line(&mut self) {
+        if !self.line.is_empty() {
+            return;
+        }
+        if std::mem::take(&mut self.pending_blank) && !self.out.is_empty() {
+            let blank: String = self.prefixes.iter().map(|p| p.text.as_str()).collect();
+            self.out.push_str(blank.trim_end());
+            self.out.push('\n');
+        }
+        let last = self.prefixes.len().saturating_sub(1);
+        for (i, prefix) in self.prefixes.iter().enumerate() {
+            match self.marker.take() {
+                Some(marker) if i == last => self.line.push_str(&marker),
+                marker => {
+                    self.marker = marker;
+                    self.line.push_str(&prefix.text);
+                }
+            }
+        }
+        self.line_width = self.line.width();
+    }
+
+    fn flush_line(&mut self) {
+        if !self.line.is_empty() {
+            self.out.push_str(self.line.trim_end());
+            self.out.push('\n');
+        }
+        self.line.clear();
+        self.line_width = 0;
+        self.pending_space = false;
+    }
+
+    /// End the current block so the next one starts after a blank line.
+    fn block_break(&mut self) {
+        self.flush_line();
+        self.pending_blank = true;
+    }
+
+    fn push_marker(&mut self, marker: &str) {
+        if marker.is_empty() {
+            return;
+        }
+        self.start_line();
+        if self.pending_space && self.line_width > self.prefix_width() {
+            self.line.push(' ');
+            self.line_width += 1;
+        }
+        self.pending_space = false;
+        self.line.push_str(marker);
+        if !self.color {
+            self.line_width += marker.width();
+        }
+    }
+
+    fn prefix_width(&self) -> usize {
+        self.prefixes.iter().map(|p| p.text.width()).sum()
+    }
+
+    fn push_word(&mut self, word: &str) {
+        self.start_line();
+        let word_width = word.width();
+        let at_line_start = self.line_width <= self.prefix_width();
+        let space = usize::from(self.pending_space && !at_line_start);
+        if !at_line_start && self.line_width + space + word_width > self.width {
+            self.flush_line();
+            self.start_line();
+        } else if space == 1 {
+            self.line.push(' ');
+            self.line_width += 1;
+        }
+        self.pending_space = false;
+        self.line.push_str(word);
+        self.line_width += word_width;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if self.pre > 0 {
+            let mut lines = text.split('\n').peekable();
+            while let Some(part) = lines.next() {
+                self.start_line();
+                self.line.push_str(part);
+                if lines.peek().is_some() {
+                    self.flush_line();
+                }
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        let mut words = text.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            self.push_word(word);
+            if words.peek().is_some() {
+                self.pending_space = true;
+            }
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+    }
+
+    fn push_prefix(&mut self, owner: &'static str, text: &str) {
+        self.prefixes.push(Prefix {
+            owner,
+            text: text.to_string(),
+        });
+    }
+
+    /// Close the innermost `owner` open in the current list, along with
+    /// anything left open inside it. Returns whether there was one.
+    fn pop_prefix(&mut self, owner: &str) -> bool {
+        let floor = self.lists.last().map_or(0, |l| l.depth);
+        match self.prefixes[floor..]
+            .iter()
+            .rposition(|p| p.owner == owner)
+        {
+            Some(i) => {
+                self.prefixes.truncate(floor + i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn open_list_item(&mut self) {
+        self.flush_line();
+        // `</li>` is optional, so a new item also ends the previous one
+        self.pop_prefix("li");
+        let marker = match self.lists.last_mut().and_then(|l| l.number.as_mut()) {
+            Some(n) => {
+                *n += 1;
+                format!("{n}. ")
+            }
+            None => "- ".to_string(),
+        };
+        self.push_prefix("li", &" ".repeat(marker.width()));
+        self.marker = Some(marker);
+    }
+
+    fn open_list(&mut self, number: Option<usize>) {
+        self.flush_line();
+        self.lists.push(List {
+            number,
+            depth: self.prefixes.len(),
+        });
+    }
+
+    fn open(&mut self, name: &str) {
+        match name {
+            "br" => self.flush_line(),
+            "hr" => {
+                self.block_break();
+                self.start_line();
+                self.line.push_str("---");
+                self.block_break();
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block_break();
+                let level = name[1..].parse().unwrap_or(1);
+                self.push_word(&"#".repeat(level));
+                self.pending_space = true;
+                self.push_marker(self.markers.bold.0);
+            }
+            "b" | "strong" => self.push_marker(self.markers.bold.0),
+            "i" | "em" => self.push_marker(self.markers.italic.0),
+            "code" if self.pre == 0 => self.push_marker(self.markers.code.0),
+            "pre" => {
+                self.block_break();
+                if !self.color {
+                    self.start_line();
+                    self.line.push_str("```");
+                    self.flush_line();
+                }
+                // Without color the fence marks the block, so no indent is needed
+                let indent = if self.color { CODE_BLOCK_INDENT } else { "" };
+                self.push_prefix("pre", indent);
+                self.pre += 1;
+                let dim = self.markers.dim.0;
+                self.start_line();
+                self.line.push_str(dim);
+            }
+            "blockquote" => {
+                self.block_break();
+                self.push_prefix("blockquote", "> ");
+            }
+            "ul" => self.open_list(None),
+            "ol" => self.open_list(Some(0)),
+            "li" => self.open_list_item(),
+            "tr" | "dt" | "dd" => self.flush_line(),
+            "td" | "th" => self.pending_space = true,
+            _ if BLOCK_ELEMENTS.contains(&name) => self.block_break(),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.push_marker(self.markers.bold.1);
+                self.block_break();
+            }
+            "b" | "strong" => self.push_marker(self.markers.bold.1),
+            "i" | "em" => self.push_marker(self.markers.italic.1),
+            "code" if self.pre == 0 => self.push_marker(self.markers.code.1),
+            "pre" if self.pre > 0 => {
+                let dim = self.markers.dim.1;
+                self.line.push_str(dim);
+                self.flush_line();
+                self.pop_prefix("pre");
+                self.pre -= 1;
+                if !self.color {
+                    self.start_line();
+                    self.line.push_str("```");
+                }
+                self.block_break();
+            }
+            "blockquote" if self.pop_prefix("blockquote") => self.block_break(),
+            "ul" | "ol" => {
+                self.flush_line();
+                let Some(list) = self.lists.pop() else {
+                    return;
+                };
+                // Including the last item, if its `</li>` was left out
+                self.prefixes.truncate(list.depth);
+                self.marker = None;
+                if self.lists.is_empty() {
+                    self.block_break();
+                }
+            }
+            "li" if self.pop_prefix("li") => {
+                self.flush_line();
+                self.marker = None;
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => self.block_break(),
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> (String, Vec<String>) {
+        self.flush_line();
+        (self.out, self.links)
+    }
+}
+
+/// Render an HTML fragment for the terminal, wrapping text at `width` columns.
+/// Returns the text and the targets of its numbered links, resolved against
+/// `base`.
+fn render_html(html: &str, width: usize, color: bool, base: &str) -> (String, Vec<String>) {
+    let mut r = Renderer::new(width.max(20), color, base);
+    let mut skipping: Option<String> = None;
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        if skipping.is_none() {
+            r.push_text(&rest[..start]);
+        }
+        let Some(end) = rest[start..].find('>').map(|i| i + start) else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        let name = tag_name(tag);
+        if let Some(skipped) = &skipping {
+            if tag.starts_with('/') && *skipped == name {
+                skipping = None;
+            }
+            continue;
+        }
+        if name == "a" {
+            if tag.starts_with('/') {
+                r.close_link();
+            } else {
+                r.open_link(attr(tag, "href"));
+            }
+        } else if tag.starts_with('/') {
+            r.close(&name);
+        } else if SKIPPED_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            skipping = Some(name);
+        } else {
+            r.open(&name);
+        }
+    }
+    if skipping.is_none() {
+        r.push_text(rest);
+    }
+    r.finish()
+}
+
+/// Render a post as a header (title, feed, date and link) followed by its
+/// content.
+pub(crate) fn format_article(
+    item: &FeedItem,
+    feed_label: &str,
+    max_width: Option<usize>,
+    color: bool,
+) -> String {
+    let s = Style::new(color);
+    let width = max_width.unwrap_or(FALLBACK_WIDTH).min(MAX_TEXT_WIDTH);
+    let mut out = String::new();
+    if color {
+        out.push_str(&format!("{}{}{}\n", s.bold, item.title, s.reset));
+    } else {
+        out.push_str(&format!("# {}\n", item.title));
+    }
+    let mut meta = vec![feed_label.to_string()];
+    if !item.author.is_empty() {
+        meta.push(item.author.clone());
+    }
+    if let Some(date) = item.date {
+        meta.push(crate::utils::date::format_day(date));
+    }
+    match item.edits {
+        0 => {}
+        1 => meta.push("updated once".to_string()),
+        n => meta.push(format!("updated {n} times")),
+    }
+    out.push_str(&format!("{}{}{}\n", s.dim, meta.join(" \u{b7} "), s.reset));
+    if !item.link.is_empty() {
+        out.push_str(&format!("{}{}{}\n", s.dim, item.link, s.reset));
+    }
+    let (body, links) = render_body(item, width, color);
+    if !body.is_empty() {
+        out.push('\n');
+        out.push_str(&body);
+    }
+    if !links.is_empty() {
+        out.push('\n');
+        for (i, link) in links.iter().enumerate() {
+            out.push_str(&format!("{}[{}]: {link}{}\n", s.dim, i + 1, s.reset));
+        }
+    }
+    out
+}
+
+/// Whether `--link` may hand `url` to the system opener. Only web and mail
+/// links are allowed, since the opener would run anything else (`file:`,
+/// custom URL handlers) just the same.
+fn is_openable(url: &url::Url) -> bool {
+    matches!(url.scheme(), "http" | "https" | "mailto")
+}
+
+/// A post's content rendered to `width`, with its links followed by the
+/// images the feed attaches to it that the content doesn't link.
+fn render_body(item: &FeedItem, width: usize, color: bool) -> (String, Vec<String>) {
+    let (body, mut links) = render_html(item.body(), width, color, &item.link);
+    for image in &item.images {
+        let openable = url::Url::parse(image).is_ok_and(|u| is_openable(&u));
+        if openable && !links.contains(image) {
+            links.push(image.clone());
+        }
+    }
+    (body, links)
+}
+
+/// The links in a post's content, in the order `format_article` numbers them.
+pub(crate) fn article_links(item: &FeedItem) -> Vec<String> {
+    render_body(item, FALLBACK_WIDTH, false).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn render_text(html: &str, width: usize, color: bool) -> String {
+        render_html(html, width, color, "").0
+    }
+
+    #[rstest]
+    #[case::plain_text("Just text", "Just text\n")]
+    #[case::paragraphs("<p>One</p><p>Two</p>", "One\n\nTwo\n")]
+    #[case::heading("<h2>Title</h2><p>Body</p>", "## **Title**\n\nBody\n")]
+    #[case::emphasis(
+        "<p>Some <strong>bold</strong> and <em>italic</em> text</p>",
+        "Some **bold** and *italic* text\n"
+    )]
+    #[case::inline_code("<p>Run <code>cargo test</code> now</p>", "Run `cargo test` now\n")]
+    #[case::bullets("<ul><li>One</li><li>Two</li></ul>", "- One\n- Two\n")]
+    #[case::numbered("<ol><li>One</li><li>Two</li></ol>", "1. One\n2. Two\n")]
+    #[case::nested_list(
+        "<ul><li>Outer<ul><li>Inner</li></ul></li></ul>",
+        "- Outer\n  - Inner\n"
+    )]
+    #[case::unclosed_items("<ul><li>a<li>b<li>c</ul><p>After</p>", "- a\n- b\n- c\n\nAfter\n")]
+    #[case::unclosed_numbered_items("<ol><li>a<li>b</ol>", "1. a\n2. b\n")]
+    #[case::unclosed_nested_items(
+        "<ul><li>Outer<ul><li>x<li>y</ul><li>Next</ul>",
+        "- Outer\n  - x\n  - y\n- Next\n"
+    )]
+    #[case::stray_item_close(
+        "<blockquote><p>Quoted</p></li><p>Still</p></blockquote>",
+        "> Quoted\n>\n> Still\n"
+    )]
+    #[case::stray_blockquote_close(
+        "<ul><li>a</blockquote> still a</li><li>b</li></ul>",
+        "- a still a\n- b\n"
+    )]
+    #[case::blockquote("<blockquote><p>Quoted</p></blockquote>", "> Quoted\n")]
+    #[case::blockquote_paragraphs(
+        "<blockquote><p>One</p><p>Two</p></blockquote><p>After</p>",
+        "> One\n>\n> Two\n\nAfter\n"
+    )]
+    #[case::code_block(
+        "<pre><code>fn main() {\n    println!();\n}</code></pre>",
+        "```\nfn main() {\n    println!();\n}\n```\n"
+    )]
+    #[case::line_break("one<br>two", "one\ntwo\n")]
+    #[case::entities("<p>Fish &amp; chips</p>", "Fish & chips\n")]
+    #[case::script("<script>var x;</script><p>Visible</p>", "Visible\n")]
+    #[case::comment("<!-- hidden --><p>Shown</p>", "Shown\n")]
+    fn test_render_html_plain(#[case] html: &str, #[case] expected: &str) {
+        assert_eq!(render_text(html, 80, false), expected);
+    }
+
+    #[test]
+    fn test_render_html_wraps_to_width() {
+        let html = "<p>alpha beta gamma delta epsilon zeta eta theta iota kappa</p>";
+        let out = render_text(html, 20, false);
+        assert_eq!(
+            out,
+            "alpha beta gamma\ndelta epsilon zeta\neta theta iota kappa\n"
+        );
+        assert!(out.lines().all(|l| l.width() <= 20));
+    }
+
+    #[test]
+    fn test_render_html_wraps_list_items_with_hanging_indent() {
+        let html = "<ul><li>alpha beta gamma delta epsilon</li></ul>";
+        let out = render_text(html, 20, false);
+        assert_eq!(out, "- alpha beta gamma\n  delta epsilon\n");
+    }
+
+    #[test]
+    fn test_format_article_header_and_body() {
+        let item = FeedItem {
+            title: "Hello".to_string(),
+            date: Some(
+                chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .and_utc(),
+            ),
+            feed: "f".to_string(),
+            link: "https://example.com/hello".to_string(),
+            raw_id: "id".to_string(),
+            summary: "<p>Body</p>".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_article(&item, "@a Blog", None, false),
+            "# Hello\n@a Blog \u{b7} 2024-03-01\nhttps://example.com/hello\n\nBody\n"
+        );
+
+        let edited = FeedItem { edits: 2, ..item };
+        assert!(
+            format_article(&edited, "@a Blog", None, false)
+                .contains("@a Blog \u{b7} 2024-03-01 \u{b7} updated 2 times\n")
+        );
+    }
+
+    #[test]
+    fn test_render_html_color_uses_ansi_instead_of_markdown() {
+        let out = render_text("<p><b>bold</b></p>", 80, true);
+        assert_eq!(out, "\x1b[1mbold\x1b[22m\n");
+    }
+
+    #[test]
+    fn test_render_html_numbers_links() {
+        let html = concat!(
+            "<p>See <a href=\"/docs\">the docs</a> and ",
+            "<a href=\"https://other.example/\">another</a> or ",
+            "<a href=\"/docs\">the docs again</a>.</p>",
+            "<p><a href=\"#top\">Back to top</a></p>",
+        );
+        let (out, links) = render_html(html, 80, false, "https://blog.example/posts/1");
+        assert_eq!(
+            out,
+            "See the docs[1] and another[2] or the docs again[1].\n\nBack to top\n"
+        );
+        assert_eq!(
+            links,
+            ["https://blog.example/docs", "https://other.example/"]
+        );
+    }
+
+    #[rstest]
+    #[case::javascript("javascript:alert(1)")]
+    #[case::file("file:///etc/passwd")]
+    #[case::custom_handler("steam://run/1")]
+    #[case::data("data:text/html,hi")]
+    #[case::relative_without_base("/docs")]
+    fn test_render_html_skips_links_that_are_not_web_or_mail(#[case] href: &str) {
+        let html = format!("<p><a href=\"{href}\">text</a></p>");
+        let (out, links) = render_html(&html, 80, false, "");
+        assert_eq!(out, "text\n");
+        assert!(links.is_empty());
+    }
+
+    #[rstest]
+    #[case::http("http://example.com/")]
+    #[case::https("https://example.com/")]
+    #[case::mailto("mailto:ada@example.com")]
+    fn test_render_html_numbers_web_and_mail_links(#[case] href: &str) {
+        let html = format!("<p><a href=\"{href}\">text</a></p>");
+        let (out, links) = render_html(&html, 80, false, "");
+        assert_eq!(out, "text[1]\n");
+        assert_eq!(links, [href]);
+    }
+
+    #[test]
+    fn test_format_article_lists_links() {
+        let item = FeedItem {
+            title: "Hello".to_string(),
+            feed: "f".to_string(),
+            link: "https://example.com/hello".to_string(),
+            raw_id: "id".to_string(),
+            summary: "<p>A <a href=\"https://rust-lang.org\">link</a></p>".to_string(),
+            ..Default::default()
+        };
+        assert!(
+            format_article(&item, "@a Blog", None, false)
+                .ends_with("A link[1]\n\n[1]: https://rust-lang.org/\n")
+        );
+        assert_eq!(article_links(&item), ["https://rust-lang.org/"]);
+    }
+
+    #[test]
+    fn test_format_article_prefers_content_and_lists_images() {
+        let item = FeedItem {
+            title: "Hello".to_string(),
+            feed: "f".to_string(),
+            raw_id: "id".to_string(),
+            summary: "<p>Excerpt</p>".to_string(),
+            content: "<p>Full <a href=\"https://img.example/a.jpg\">text</a></p>".to_string(),
+            images: vec![
+                "https://img.example/a.jpg".to_string(),
+                "https://img.example/b.jpg".to_string(),
+                "file:///tmp/c.jpg".to_string(),
+            ],
+            ..Default::default()
+        };
+        let article = format_article(&item, "@a Blog", None, false);
+        assert!(article.contains("Full text[1]\n"), "got {article}");
+        assert!(!article.contains("Excerpt"));
+        assert_eq!(
+            article_links(&item),
+            ["https://img.example/a.jpg", "https://img.example/b.jpg"]
+        );
+    }
+}
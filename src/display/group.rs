@@ -1,23 +1,98 @@
+use std::collections::BTreeMap;
 use std::fmt::Write;
 
 use itertools::Itertools;
 
 use crate::data::schema::FeedItem;
-use crate::query::GroupKey;
+use crate::query::{GroupKey, UNTAGGED};
 
-use super::item::format_item;
+use super::item::{format_categories, format_excerpt, format_item};
 use super::{RenderCtx, Style};
 
+/// Posts grouped by tag, one group per category in alphabetical order and
+/// the posts without one last. A post is in the group of each of its
+/// categories.
+fn tag_groups<'a>(items: &[&'a FeedItem]) -> Vec<(String, Vec<&'a FeedItem>)> {
+    let mut tags: BTreeMap<String, (String, Vec<&FeedItem>)> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for item in items {
+        if item.categories.is_empty() {
+            untagged.push(*item);
+        }
+        for category in item.categories.iter().unique_by(|c| c.to_lowercase()) {
+            tags.entry(category.to_lowercase())
+                .or_insert_with(|| (category.clone(), Vec::new()))
+                .1
+                .push(*item);
+        }
+    }
+    let mut groups: Vec<_> = tags.into_values().collect();
+    if !untagged.is_empty() {
+        groups.push((UNTAGGED.to_string(), untagged));
+    }
+    groups
+}
+
+/// `items` split by `key` into groups with their headers, in display order.
+pub(crate) fn group_posts<'a>(
+    key: GroupKey,
+    items: &[&'a FeedItem],
+    ctx: &RenderCtx,
+) -> Vec<(String, Vec<&'a FeedItem>)> {
+    if key == GroupKey::Tag {
+        return tag_groups(items);
+    }
+    let mut sorted = items.to_vec();
+    sorted.sort_by(|a, b| key.compare(a, b, ctx));
+    sorted
+        .iter()
+        .chunk_by(|item| key.identity(item, ctx))
+        .into_iter()
+        .map(|(_, group)| {
+            let group: Vec<&FeedItem> = group.copied().collect();
+            (key.extract(group[0], ctx), group)
+        })
+        .collect()
+}
+
 pub(crate) fn render_grouped(items: &[&FeedItem], ctx: &RenderCtx) -> String {
-    fn recurse(out: &mut String, items: &[&FeedItem], remaining: &[GroupKey], ctx: &RenderCtx) {
-        let depth = ctx.all_keys.len() - remaining.len();
+    fn recurse(
+        out: &mut String,
+        items: &[&FeedItem],
+        remaining: &[GroupKey],
+        depth: usize,
+        ctx: &RenderCtx,
+    ) {
         let indent = "  ".repeat(depth);
+        let s = Style::new(ctx.color);
 
         if remaining.is_empty() {
             let indent_width = depth * 2;
             let content_width = ctx.max_width.map(|w| w.saturating_sub(indent_width));
-            for item in items {
+            // Only groups are capped, not a flat listing
+            let shown = match ctx.per_group {
+                Some(cap) if depth > 0 => cap.min(items.len()),
+                _ => items.len(),
+            };
+            for item in &items[..shown] {
                 writeln!(out, "{indent}{}", format_item(item, content_width, ctx)).unwrap();
+                if ctx.long {
+                    if let Some(line) = format_categories(item, content_width, ctx) {
+                        writeln!(out, "{indent}{line}").unwrap();
+                    }
+                    for line in format_excerpt(item, content_width, ctx) {
+                        writeln!(out, "{indent}{line}").unwrap();
+                    }
+                }
+            }
+            let hidden = items.len() - shown;
+            if hidden > 0 {
+                writeln!(
+                    out,
+                    "{indent}{}\u{2026} and {hidden} more{}",
+                    s.dim, s.reset
+                )
+                .unwrap();
             }
             return;
         }
@@ -25,32 +100,25 @@ pub(crate) fn render_grouped(items: &[&FeedItem], ctx: &RenderCtx) -> String {
         let key = remaining[0];
         let rest = &remaining[1..];
 
-        let mut sorted = items.to_vec();
-        sorted.sort_by(|a, b| key.compare(a, b, ctx.feed_labels));
-
-        let s = Style::new(ctx.color);
-
         let (prefix, suffix) = if depth == 0 {
             ("=== ", " ===")
         } else {
             ("--- ", " ---")
         };
 
-        for (group_val, group) in &sorted
-            .iter()
-            .chunk_by(|item| key.extract(item, ctx.feed_labels))
-        {
-            let group_items: Vec<&FeedItem> = group.copied().collect();
+        for (group_val, group_items) in group_posts(key, items, ctx) {
             writeln!(
                 out,
-                "{indent}{}{prefix}{group_val}{suffix}{}",
-                s.bold, s.reset
+                "{indent}{}{prefix}{group_val} ({}){suffix}{}",
+                ctx.theme.when(ctx.color).header,
+                group_items.len(),
+                s.reset
             )
             .unwrap();
             if depth == 0 {
                 writeln!(out).unwrap();
             }
-            recurse(out, &group_items, rest, ctx);
+            recurse(out, &group_items, rest, depth + 1, ctx);
             if depth == 0 {
                 writeln!(out).unwrap();
                 writeln!(out).unwrap();
@@ -60,7 +128,14 @@ pub(crate) fn render_grouped(items: &[&FeedItem], ctx: &RenderCtx) -> String {
         }
     }
 
+    // `/n` only stands in for "no grouping"
+    let keys: Vec<GroupKey> = ctx
+        .all_keys
+        .iter()
+        .copied()
+        .filter(|key| *key != GroupKey::None)
+        .collect();
     let mut out = String::new();
-    recurse(&mut out, items, ctx.all_keys, ctx);
+    recurse(&mut out, items, &keys, 0, ctx);
     out
 }
@@ -5,20 +5,25 @@ use crate::query::GroupKey;
 
 use super::{RenderCtx, Style};
 
-const READ_MARKER_WIDTH: usize = 2; // "* " or "  "
+const READ_MARKER_WIDTH: usize = 2; // "* " unread or "  " read, with "~" for updated
+const DATE_WIDTH: usize = 10; // "2024-01-15"; "unknown" is padded to match
 const META_PAREN_WIDTH: usize = 3; // " (" + ")"
 const META_TAG_SPACE: usize = 1; // space between tag and blog name
 /// Max share of remaining width allocated to the blog name when truncating.
 /// Keeps titles prominent while still showing enough of the blog name to identify it.
 const BLOG_NAME_BUDGET_PERCENT: usize = 35;
+/// Max number of summary lines shown under each post in long listings.
+const EXCERPT_MAX_LINES: usize = 3;
+/// Wrap width for summaries when the terminal width is unknown (e.g. piped output).
+const EXCERPT_FALLBACK_WIDTH: usize = 80;
 
 pub(crate) fn format_date(item: &FeedItem) -> String {
     item.date
-        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map(crate::utils::date::format_day)
         .unwrap_or_else(|| "unknown".to_string())
 }
 
-fn truncate_str(s: &str, max_cols: usize) -> String {
+pub(crate) fn truncate_str(s: &str, max_cols: usize) -> String {
     if s.width() <= max_cols {
         return s.to_string();
     }
@@ -39,6 +44,137 @@ fn truncate_str(s: &str, max_cols: usize) -> String {
     format!("{}\u{2026}", &s[..end])
 }
 
+/// Pad `s` with spaces to `cols` display columns.
+pub(crate) fn pad_str(s: &str, cols: usize) -> String {
+    format!("{s}{}", " ".repeat(cols.saturating_sub(s.width())))
+}
+
+/// Wrap the words of `text` found in `terms` (lowercase) in `on` and `off`.
+fn highlight(text: &str, terms: &[String], on: &str, off: &str) -> String {
+    if terms.is_empty() || on.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |out: &mut String, word: &mut String| {
+        if terms.contains(&word.to_lowercase()) {
+            out.push_str(&format!("{on}{word}{off}"));
+        } else {
+            out.push_str(word);
+        }
+        word.clear();
+    };
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut out, &mut word);
+            out.push(c);
+        }
+    }
+    flush(&mut out, &mut word);
+    out
+}
+
+/// Greedily wrap `text` into at most `max_lines` lines of `width` columns.
+/// If the text doesn't fit, the last line ends with '…'.
+fn wrap_text(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut words = text.split_whitespace().peekable();
+
+    while let Some(word) = words.peek() {
+        let needed = if current.is_empty() {
+            word.width()
+        } else {
+            current.width() + 1 + word.width()
+        };
+        if needed <= width {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+            words.next();
+        } else if current.is_empty() {
+            // A single word wider than the line: cut it
+            current = truncate_str(word, width);
+            words.next();
+        } else {
+            lines.push(std::mem::take(&mut current));
+            if lines.len() == max_lines {
+                break;
+            }
+        }
+    }
+    if !current.is_empty() && lines.len() < max_lines {
+        lines.push(current);
+    }
+
+    if words.peek().is_some()
+        && let Some(last) = lines.last_mut()
+    {
+        *last = truncate_str(&format!("{last}\u{2026}"), width);
+    }
+    lines
+}
+
+/// Column at which the title starts in a line produced by [`format_item`].
+fn title_column(item: &FeedItem, ctx: &RenderCtx) -> usize {
+    let date_width = if ctx.all_keys.contains(&GroupKey::Date) {
+        0
+    } else {
+        format_date(item).width().max(DATE_WIDTH) + 2
+    };
+    READ_MARKER_WIDTH + date_width + ctx.shorthand_width + 1
+}
+
+/// Summary excerpt lines for long listings, indented to line up with the title.
+pub(super) fn format_excerpt(
+    item: &FeedItem,
+    content_width: Option<usize>,
+    ctx: &RenderCtx,
+) -> Vec<String> {
+    let text = crate::utils::html::to_text(&item.summary);
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let indent = title_column(item, ctx);
+    let width = content_width
+        .unwrap_or(EXCERPT_FALLBACK_WIDTH)
+        .saturating_sub(indent)
+        .max(1);
+    let s = Style::new(ctx.color);
+    let on = &ctx.theme.when(ctx.color).highlight;
+    let off = format!("{}{}", s.reset, s.dim);
+    wrap_text(&text, width, EXCERPT_MAX_LINES)
+        .into_iter()
+        .map(|line| {
+            let line = highlight(&line, ctx.highlight, on, &off);
+            format!("{:indent$}{}{line}{}", "", s.dim, s.reset)
+        })
+        .collect()
+}
+
+/// The post's categories for long listings, on one line lined up with the
+/// title.
+pub(super) fn format_categories(
+    item: &FeedItem,
+    content_width: Option<usize>,
+    ctx: &RenderCtx,
+) -> Option<String> {
+    if item.categories.is_empty() {
+        return None;
+    }
+    let indent = title_column(item, ctx);
+    let text = format!("Categories: {}", item.categories.join(", "));
+    let text = match content_width {
+        Some(width) => truncate_str(&text, width.saturating_sub(indent).max(1)),
+        None => text,
+    };
+    let s = Style::new(ctx.color);
+    Some(format!("{:indent$}{}{text}{}", "", s.dim, s.reset))
+}
+
 /// Split a feed label like "@tag Blog Name" into (Some("@tag"), "Blog Name").
 /// Labels without an @-tag return (None, full_label).
 fn resolve_feed_label(feed_label: &str) -> (Option<&str>, &str) {
@@ -57,6 +193,11 @@ fn meta_fixed_width(tag: Option<&str>) -> usize {
 }
 
 /// Decide how much space title and blog name each get, truncating if needed.
+///
+/// With a known width and a `title_col` (the widest title in the listing), the
+/// title is padded so that blog names line up in a column. The column shrinks
+/// to leave the blog name its share of a narrow terminal; rows with the same
+/// tag width then still line up.
 fn budget_title_and_blog(
     title: &str,
     blog_name: &str,
@@ -64,6 +205,7 @@ fn budget_title_and_blog(
     content_width: Option<usize>,
     fixed_width: usize,
     meta_width: usize,
+    title_col: usize,
 ) -> (String, String) {
     match content_width {
         Some(w) if fixed_width + meta_width < w => {
@@ -71,10 +213,21 @@ fn budget_title_and_blog(
             let title_len = title.width();
             let blog_len = blog_name.width();
 
-            if title_len + blog_len <= remaining {
-                (title.to_string(), blog_name.to_string())
-            } else if !show_feed {
+            if !show_feed {
                 (truncate_str(title, remaining), String::new())
+            } else if title_col > 0 {
+                let blog_budget = (remaining * BLOG_NAME_BUDGET_PERCENT / 100).max(3);
+                let col = if title_col + blog_len <= remaining {
+                    title_col
+                } else {
+                    title_col.min(remaining.saturating_sub(blog_budget))
+                };
+                (
+                    pad_str(&truncate_str(title, col), col),
+                    truncate_str(blog_name, remaining - col),
+                )
+            } else if title_len + blog_len <= remaining {
+                (title.to_string(), blog_name.to_string())
             } else {
                 let blog_budget = (remaining * BLOG_NAME_BUDGET_PERCENT / 100)
                     .max(3)
@@ -109,53 +262,82 @@ pub(super) fn format_item(
         .map(|s| s.as_str())
         .unwrap_or(&item.feed);
 
-    let date_width = if show_date {
-        format_date(item).width() + 2
-    } else {
-        0
-    };
-    let fixed_width = READ_MARKER_WIDTH + date_width + ctx.shorthand_width + 1;
+    let fixed_width = title_column(item, ctx);
 
     let (tag, blog_name) = if show_feed {
         resolve_feed_label(feed_label)
     } else {
         (None, "")
     };
+    let author = if ctx.show_author && !ctx.all_keys.contains(&GroupKey::Author) {
+        item.author.as_str()
+    } else {
+        ""
+    };
+    let blog_name = match (blog_name, author) {
+        (name, "") => name.to_string(),
+        ("", author) => author.to_string(),
+        (name, author) => format!("{name}, {author}"),
+    };
+    let show_meta = show_feed || !author.is_empty();
 
-    let meta_width = if show_feed { meta_fixed_width(tag) } else { 0 };
+    let meta_width = if show_meta { meta_fixed_width(tag) } else { 0 };
 
     let (title, blog) = budget_title_and_blog(
         &item.title,
-        blog_name,
-        show_feed,
+        &blog_name,
+        show_meta,
         content_width,
         fixed_width,
         meta_width,
+        ctx.title_width,
     );
 
     let s = Style::new(ctx.color);
+    let theme = ctx.theme.when(ctx.color);
+    let title = highlight(&title, ctx.highlight, &theme.highlight, s.reset);
 
-    let styled_meta = if show_feed {
+    let styled_meta = if show_meta {
         match tag {
-            Some(t) => format!("{}{} ({t} {blog}){}", s.dim, s.italic, s.reset),
-            None => format!("{}{} ({blog}){}", s.dim, s.italic, s.reset),
+            Some(t) => format!("{} ({t} {blog}){}", theme.feed, s.reset),
+            None => format!("{} ({blog}){}", theme.feed, s.reset),
         }
     } else {
         String::new()
     };
 
     let date_part = if show_date {
-        format!("{}{}{}  ", s.date_color, format_date(item), s.reset)
+        format!(
+            "{}{}{}  ",
+            theme.date,
+            pad_str(&format_date(item), DATE_WIDTH),
+            s.reset
+        )
     } else {
         String::new()
     };
 
-    let read_marker = if is_read { "  " } else { "* " };
+    // Posts back from a snooze stand out whether read or not
+    let is_due = ctx.due_ids.contains(&item.raw_id);
+    let read_marker = match (is_due, is_read, item.is_updated()) {
+        (true, _, false) => "! ",
+        (true, _, true) => "!~",
+        (false, false, false) => "* ",
+        (false, false, true) => "*~",
+        (false, true, false) => "  ",
+        (false, true, true) => " ~",
+    };
 
-    format!(
+    let line = format!(
         "{read_marker}{date_part}{}{shorthand:<sw$}{} {title}{styled_meta}",
-        s.bold,
+        theme.shorthand,
         s.reset,
         sw = ctx.shorthand_width
-    )
+    );
+    if is_read && ctx.dim_read && ctx.color {
+        // Styles end with a reset, after which the line must stay dim
+        let line = line.replace(s.reset, &format!("{}{}", s.reset, s.dim));
+        return format!("{}{line}{}", s.dim, s.reset);
+    }
+    line
 }
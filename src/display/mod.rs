@@ -1,32 +1,76 @@
+mod article;
 mod group;
 mod item;
+mod theme;
 
 use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use itertools::Itertools;
 
 use crate::data::index::FeedIndex;
+use crate::data::schema::FeedSource;
 use crate::query::GroupKey;
 
-pub(crate) use group::render_grouped;
+pub(crate) use article::{article_links, format_article};
+pub(crate) use group::{group_posts, render_grouped};
+pub(crate) use item::{pad_str, truncate_str};
+pub(crate) use theme::Theme;
+
+/// The domain a feed belongs to, from its site URL or else its own URL.
+fn feed_domain(feed: &FeedSource) -> String {
+    let url = if feed.site_url.is_empty() {
+        &feed.url
+    } else {
+        &feed.site_url
+    };
+    let host = crate::utils::http::host_of(url);
+    host.strip_prefix("www.").unwrap_or(&host).to_string()
+}
 
+/// How feeds are named in listings: `@shorthand Title`, with the domain added
+/// when several feeds share a title, so that they can be told apart.
 pub(crate) fn build_feed_labels(fi: &FeedIndex) -> HashMap<String, String> {
+    let title_counts = fi
+        .entries
+        .iter()
+        .filter(|e| !e.feed.title.is_empty())
+        .map(|e| e.feed.title.trim().to_lowercase())
+        .counts();
     fi.entries
         .iter()
         .map(|e| {
-            let label = if e.feed.title.is_empty() {
+            let mut label = if e.feed.title.is_empty() {
                 format!("@{} {}", e.shorthand, e.feed.url)
             } else {
                 format!("@{} {}", e.shorthand, e.feed.title)
             };
+            if title_counts
+                .get(&e.feed.title.trim().to_lowercase())
+                .is_some_and(|&n| n > 1)
+            {
+                write!(label, " ({})", feed_domain(&e.feed)).unwrap();
+            }
+            if e.feed.archived {
+                label.push_str(" [archived]");
+            }
             (e.id.clone(), label)
         })
         .collect()
 }
 
+/// The folders feeds are filed in, by feed ID, for grouping by folder.
+pub(crate) fn build_feed_folders(fi: &FeedIndex) -> HashMap<String, String> {
+    fi.entries
+        .iter()
+        .filter(|e| !e.feed.folder.is_empty())
+        .map(|e| (e.id.clone(), e.feed.folder.clone()))
+        .collect()
+}
+
 pub(crate) struct Style {
     pub bold: &'static str,
     pub dim: &'static str,
-    pub italic: &'static str,
-    pub date_color: &'static str,
     pub reset: &'static str,
 }
 
@@ -36,16 +80,12 @@ impl Style {
             Self {
                 bold: "\x1b[1m",
                 dim: "\x1b[2m",
-                italic: "\x1b[3m",
-                date_color: "\x1b[36m",
                 reset: "\x1b[0m",
             }
         } else {
             Self {
                 bold: "",
                 dim: "",
-                italic: "",
-                date_color: "",
                 reset: "",
             }
         }
@@ -56,10 +96,30 @@ pub(crate) struct RenderCtx<'a> {
     pub all_keys: &'a [GroupKey],
     pub shorthands: &'a HashMap<String, String>,
     pub feed_labels: &'a HashMap<String, String>,
+    /// Folders of the feeds filed in one, by feed ID
+    pub feed_folders: HashMap<String, String>,
     pub read_ids: &'a HashSet<String>,
+    /// Posts whose snooze is over, marked with `!`
+    pub due_ids: HashSet<String>,
     pub color: bool,
+    /// Styles used when `color` is set
+    pub theme: Theme,
     pub shorthand_width: usize,
+    /// Width of the title column that feed names are aligned after, or 0 to
+    /// not align them
+    pub title_width: usize,
     pub max_width: Option<usize>,
+    /// Print a short summary excerpt under each post
+    pub long: bool,
+    /// Show each post's author after the feed name
+    pub show_author: bool,
+    /// Dim the lines of read posts
+    pub dim_read: bool,
+    /// Lowercase words to highlight in titles and excerpts, e.g. search terms
+    pub highlight: &'a [String],
+    /// List at most this many posts per group, followed by how many more
+    /// there are
+    pub per_group: Option<usize>,
 }
 
 impl<'a> RenderCtx<'a> {
@@ -74,6 +134,23 @@ impl<'a> RenderCtx<'a> {
             .max()
             .unwrap_or(0)
     }
+
+    /// The title column for `items`, aligned only on a terminal of known
+    /// `max_width` so that piped output has no padding.
+    pub fn title_width_from(
+        items: &[&crate::data::schema::FeedItem],
+        max_width: Option<usize>,
+    ) -> usize {
+        use unicode_width::UnicodeWidthStr;
+        if max_width.is_none() {
+            return 0;
+        }
+        items
+            .iter()
+            .map(|item| item.title.width())
+            .max()
+            .unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -100,8 +177,7 @@ mod tests {
                 NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
             )),
             feed: feed.to_string(),
-            link: String::new(),
-            raw_id: String::new(),
+            ..Default::default()
         }
     }
 
@@ -135,11 +211,20 @@ mod tests {
         RenderCtx {
             all_keys: keys,
             shorthand_width: RenderCtx::shorthand_width_from(items, shorthands),
+            title_width: RenderCtx::title_width_from(items, max_width),
             shorthands,
             feed_labels,
+            feed_folders: HashMap::new(),
             read_ids,
+            due_ids: HashSet::new(),
             color: false,
+            theme: Theme::default(),
             max_width,
+            long: false,
+            show_author: false,
+            dim_read: false,
+            highlight: &[],
+            per_group: None,
         }
     }
 
@@ -168,14 +253,92 @@ mod tests {
             all_keys: keys,
             shorthands: &shorthands,
             feed_labels: no_labels(),
+            feed_folders: HashMap::new(),
             read_ids: &read_ids,
+            due_ids: HashSet::new(),
             color: false,
+            theme: Theme::default(),
             shorthand_width: 3,
+            title_width: 0,
             max_width: None,
+            long: false,
+            show_author: false,
+            dim_read: false,
+            highlight: &[],
+            per_group: None,
         };
         assert_eq!(item::format_item(&i, None, &ctx), expected);
     }
 
+    #[rstest]
+    #[case::unread(false, "! 2024-01-15  abc Post (Alice)")]
+    #[case::read(true, "! 2024-01-15  abc Post (Alice)")]
+    fn test_format_item_marks_due_posts(#[case] is_read: bool, #[case] expected: &str) {
+        let i = feed_item("Post", "2024-01-15", "Alice");
+        let shorthands = HashMap::from([(i.raw_id.clone(), "abc".to_string())]);
+        let read_ids = if is_read {
+            HashSet::from([i.raw_id.clone()])
+        } else {
+            HashSet::new()
+        };
+        let ctx = RenderCtx {
+            due_ids: HashSet::from([i.raw_id.clone()]),
+            shorthand_width: 3,
+            ..default_ctx(&[], &shorthands, no_labels(), &read_ids, None, &[])
+        };
+        assert_eq!(item::format_item(&i, None, &ctx), expected);
+    }
+
+    #[rstest]
+    #[case::with_feed(&[], true, "  2024-01-15  abc Post (Alice, Jane)")]
+    #[case::grouped_by_feed(&[GroupKey::Feed], true, "  2024-01-15  abc Post (Jane)")]
+    #[case::grouped_by_author(&[GroupKey::Author], true, "  2024-01-15  abc Post (Alice)")]
+    #[case::not_shown(&[], false, "  2024-01-15  abc Post (Alice)")]
+    fn test_format_item_author(
+        #[case] keys: &[GroupKey],
+        #[case] show_author: bool,
+        #[case] expected: &str,
+    ) {
+        let i = FeedItem {
+            author: "Jane".to_string(),
+            ..feed_item("Post", "2024-01-15", "Alice")
+        };
+        let shorthands = HashMap::from([(i.raw_id.clone(), "abc".to_string())]);
+        let read_ids = HashSet::from([i.raw_id.clone()]);
+        let ctx = RenderCtx {
+            show_author,
+            shorthand_width: 3,
+            ..default_ctx(keys, &shorthands, no_labels(), &read_ids, None, &[])
+        };
+        assert_eq!(item::format_item(&i, None, &ctx), expected);
+    }
+
+    #[rstest]
+    #[case::read(true)]
+    #[case::unread(false)]
+    fn test_format_item_dims_read_posts(#[case] is_read: bool) {
+        let i = feed_item_with_raw_id("Post", "2024-01-15", "Alice", "id-a");
+        let read_ids = if is_read {
+            HashSet::from([i.raw_id.clone()])
+        } else {
+            HashSet::new()
+        };
+        let ctx = RenderCtx {
+            color: true,
+            dim_read: true,
+            ..default_ctx(&[], no_labels(), no_labels(), &read_ids, None, &[])
+        };
+        let line = item::format_item(&i, None, &ctx);
+        assert_eq!(line.starts_with("\x1b[2m"), is_read, "{line:?}");
+        if is_read {
+            // Every reset but the last is followed by dim again
+            assert_eq!(
+                line.matches("\x1b[0m").count(),
+                line.matches("\x1b[0m\x1b[2m").count() + 1
+            );
+        }
+    }
+
     #[test]
     fn test_format_date_with_date() {
         let i = feed_item("Post", "2024-01-15", "Alice");
@@ -240,17 +403,49 @@ mod tests {
         assert_eq!(
             output,
             "\
-=== 2024-01-02 ===
+=== 2024-01-02 (2) ===
 
   *  Post A (Alice)
      Post B (Bob)
 
 
-=== 2024-01-01 ===
+=== 2024-01-01 (1) ===
 
   *  Post C (Alice)
 
 
+"
+        );
+    }
+
+    #[test]
+    fn test_render_grouped_caps_items_per_group() {
+        let items = [
+            feed_item("Post A", "2024-01-02", "Alice"),
+            feed_item("Post B", "2024-01-02", "Bob"),
+            feed_item("Post C", "2024-01-02", "Carol"),
+            feed_item("Post D", "2024-01-01", "Alice"),
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let keys = [GroupKey::Date];
+        let mut ctx = default_ctx(&keys, no_labels(), no_labels(), no_reads(), None, &refs);
+        ctx.per_group = Some(1);
+        let output = render_grouped(&refs, &ctx);
+        assert_eq!(
+            output,
+            "\
+=== 2024-01-02 (3) ===
+
+  *  Post A (Alice)
+  … and 2 more
+
+
+=== 2024-01-01 (1) ===
+
+  *  Post D (Alice)
+
+
 "
         );
     }
@@ -270,13 +465,13 @@ mod tests {
         assert_eq!(
             output,
             "\
-=== 2024-01-02 ===
+=== 2024-01-02 (2) ===
 
   *  Post A (Alice)
   *  Post B (Bob)
 
 
-=== 2024-01-01 ===
+=== 2024-01-01 (1) ===
 
   *  Post C (Alice)
 
@@ -299,12 +494,12 @@ mod tests {
         assert_eq!(
             output,
             "\
-=== Alice ===
+=== Alice (1) ===
 
   * 2024-01-01   Post B
 
 
-=== Bob ===
+=== Bob (1) ===
 
   * 2024-01-02   Post A
 
@@ -313,6 +508,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_grouped_by_feed_keeps_feeds_with_same_label_apart() {
+        let items = [
+            feed_item("Post A", "2024-01-02", "f1"),
+            feed_item("Post B", "2024-01-01", "f2"),
+            feed_item("Post C", "2024-01-03", "f1"),
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+        let labels: HashMap<String, String> = [("f1", "Blog"), ("f2", "Blog")]
+            .into_iter()
+            .map(|(id, label)| (id.to_string(), label.to_string()))
+            .collect();
+
+        let keys = [GroupKey::Feed];
+        let ctx = default_ctx(&keys, no_labels(), &labels, no_reads(), None, &refs);
+        let output = render_grouped(&refs, &ctx);
+        assert_eq!(output.matches("=== Blog (").count(), 2, "{output}");
+        let a = output.find("Post A").unwrap();
+        let b = output.find("Post B").unwrap();
+        let c = output.find("Post C").unwrap();
+        assert!(a < b && c < b, "{output}");
+    }
+
+    #[test]
+    fn test_feed_labels_tell_feeds_with_same_title_apart() {
+        let entry = |id: &str, shorthand: &str, title: &str, url: &str, site_url: &str| {
+            crate::data::index::FeedEntry {
+                feed: FeedSource {
+                    url: url.to_string(),
+                    title: title.to_string(),
+                    site_url: site_url.to_string(),
+                    is_fetched: true,
+                    ..Default::default()
+                },
+                id: id.to_string(),
+                shorthand: shorthand.to_string(),
+            }
+        };
+        let fi = FeedIndex {
+            entries: vec![
+                entry(
+                    "1",
+                    "a",
+                    "Blog",
+                    "https://alice.example/feed",
+                    "https://www.alice.example/",
+                ),
+                entry("2", "b", "blog", "https://bob.example/rss", ""),
+                entry("3", "c", "News", "https://news.example/rss", ""),
+            ],
+        };
+        let labels = build_feed_labels(&fi);
+        assert_eq!(labels["1"], "@a Blog (alice.example)");
+        assert_eq!(labels["2"], "@b blog (bob.example)");
+        assert_eq!(labels["3"], "@c News");
+    }
+
     #[test]
     fn test_render_grouped_by_date_then_feed() {
         let items = [
@@ -328,19 +580,19 @@ mod tests {
         assert_eq!(
             output,
             "\
-=== 2024-01-02 ===
+=== 2024-01-02 (2) ===
 
-  --- Alice ---
+  --- Alice (1) ---
     *  Post B
 
-  --- Bob ---
+  --- Bob (1) ---
     *  Post A
 
 
 
-=== 2024-01-01 ===
+=== 2024-01-01 (1) ===
 
-  --- Alice ---
+  --- Alice (1) ---
     *  Post C
 
 
@@ -364,19 +616,19 @@ mod tests {
         assert_eq!(
             output,
             "\
-=== Alice ===
+=== Alice (2) ===
 
-  --- 2024-01-02 ---
+  --- 2024-01-02 (1) ---
     *  Post B
 
-  --- 2024-01-01 ---
+  --- 2024-01-01 (1) ---
     *  Post C
 
 
 
-=== Bob ===
+=== Bob (1) ===
 
-  --- 2024-01-02 ---
+  --- 2024-01-02 (1) ---
     *  Post A
 
 
@@ -410,9 +662,9 @@ mod tests {
         assert_eq!(
             headers,
             vec![
-                "=== 2024-01-03 ===",
-                "=== 2024-01-02 ===",
-                "=== 2024-01-01 ==="
+                "=== 2024-01-03 (1) ===",
+                "=== 2024-01-02 (1) ===",
+                "=== 2024-01-01 (1) ==="
             ]
         );
     }
@@ -432,10 +684,61 @@ mod tests {
         let headers: Vec<&str> = output.lines().filter(|l| l.starts_with("===")).collect();
         assert_eq!(
             headers,
-            vec!["=== Alice ===", "=== Bob ===", "=== Charlie ==="]
+            vec![
+                "=== Alice (1) ===",
+                "=== Bob (1) ===",
+                "=== Charlie (1) ==="
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_grouped_by_tag_lists_posts_under_each_tag() {
+        let tagged = |title: &str, date: &str, categories: &[&str]| FeedItem {
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            ..feed_item(title, date, "Alice")
+        };
+        let items = [
+            tagged("Both", "2024-01-03", &["Rust", "Security"]),
+            tagged("None", "2024-01-02", &[]),
+            tagged("Lower", "2024-01-01", &["rust"]),
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let keys = [GroupKey::Tag];
+        let ctx = default_ctx(&keys, no_labels(), no_labels(), no_reads(), None, &refs);
+        let output = render_grouped(&refs, &ctx);
+        let headers: Vec<&str> = output.lines().filter(|l| l.starts_with("===")).collect();
+        assert_eq!(
+            headers,
+            vec![
+                "=== Rust (2) ===",
+                "=== Security (1) ===",
+                "=== untagged (1) ==="
+            ]
+        );
+        assert_eq!(output.matches("Both").count(), 2, "{output}");
+        let rust = &output[..output.find("=== Security").unwrap()];
+        assert!(
+            rust.find("Both").unwrap() < rust.find("Lower").unwrap(),
+            "{output}"
         );
     }
 
+    #[test]
+    fn test_render_grouped_by_none_is_flat() {
+        let items = [
+            feed_item("Post A", "2024-01-02", "Bob"),
+            feed_item("Post B", "2024-01-01", "Alice"),
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let flat = default_ctx(&[], no_labels(), no_labels(), no_reads(), None, &refs);
+        let keys = [GroupKey::None];
+        let none = default_ctx(&keys, no_labels(), no_labels(), no_reads(), None, &refs);
+        assert_eq!(render_grouped(&refs, &none), render_grouped(&refs, &flat));
+    }
+
     #[test]
     fn test_render_grouped_with_shorthands() {
         let items = [feed_item_with_raw_id(
@@ -452,6 +755,194 @@ mod tests {
         assert_eq!(output, "* 2024-01-02  sDf Post A (Alice)\n");
     }
 
+    #[test]
+    fn test_render_long_shows_summary_excerpt() {
+        let items = [FeedItem {
+            summary: "<p>Hello <b>world</b></p>".to_string(),
+            ..feed_item("Post A", "2024-01-02", "Alice")
+        }];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let ctx = RenderCtx {
+            long: true,
+            ..default_ctx(&[], no_labels(), no_labels(), no_reads(), None, &refs)
+        };
+        let output = render_grouped(&refs, &ctx);
+        assert_eq!(
+            output,
+            "* 2024-01-02   Post A (Alice)\n               Hello world\n"
+        );
+    }
+
+    #[test]
+    fn test_render_long_shows_categories() {
+        let items = [FeedItem {
+            summary: "Hello".to_string(),
+            categories: vec!["security".to_string(), "rust".to_string()],
+            ..feed_item("Post A", "2024-01-02", "Alice")
+        }];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let ctx = RenderCtx {
+            long: true,
+            ..default_ctx(&[], no_labels(), no_labels(), no_reads(), None, &refs)
+        };
+        assert_eq!(
+            render_grouped(&refs, &ctx),
+            "* 2024-01-02   Post A (Alice)\n               Categories: security, rust\n               Hello\n"
+        );
+    }
+
+    #[test]
+    fn test_render_highlights_matching_words() {
+        let items = [FeedItem {
+            summary: "<p>Why rust?</p>".to_string(),
+            ..feed_item("Rust and trust", "2024-01-02", "Alice")
+        }];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+        let terms = ["rust".to_string()];
+        // Only the highlight is styled, to keep the expected output readable
+        let theme = Theme {
+            date: String::new(),
+            shorthand: String::new(),
+            feed: String::new(),
+            header: String::new(),
+            highlight: "<h>".to_string(),
+        };
+
+        let ctx = RenderCtx {
+            long: true,
+            color: true,
+            theme,
+            highlight: &terms,
+            per_group: None,
+            ..default_ctx(&[], no_labels(), no_labels(), no_reads(), None, &refs)
+        };
+        let output = render_grouped(&refs, &ctx);
+        assert_eq!(
+            output,
+            "* 2024-01-02\x1b[0m  \x1b[0m <h>Rust\x1b[0m and trust (Alice)\x1b[0m\n               \x1b[2mWhy <h>rust\x1b[0m\x1b[2m?\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_render_long_without_summary_prints_no_excerpt() {
+        let items = [feed_item("Post A", "2024-01-02", "Alice")];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+
+        let ctx = RenderCtx {
+            long: true,
+            ..default_ctx(&[], no_labels(), no_labels(), no_reads(), None, &refs)
+        };
+        assert_eq!(
+            render_grouped(&refs, &ctx),
+            "* 2024-01-02   Post A (Alice)\n"
+        );
+    }
+
+    #[rstest]
+    #[case::fits("one two three", 20, &["one two three"])]
+    #[case::wraps("one two three", 8, &["one two", "three"])]
+    #[case::capped("aa bb cc dd ee ff gg hh", 5, &["aa bb", "cc dd", "ee f\u{2026}"])]
+    #[case::long_word("abcdefghij", 5, &["abcd\u{2026}"])]
+    fn test_excerpt_wrapping(
+        #[case] summary: &str,
+        #[case] width: usize,
+        #[case] expected: &[&str],
+    ) {
+        let i = FeedItem {
+            summary: summary.to_string(),
+            ..feed_item("Post", "2024-01-15", "Alice")
+        };
+        let keys = [GroupKey::Date];
+        let ctx = RenderCtx {
+            all_keys: &keys,
+            shorthands: no_labels(),
+            feed_labels: no_labels(),
+            feed_folders: HashMap::new(),
+            read_ids: no_reads(),
+            due_ids: HashSet::new(),
+            color: false,
+            theme: Theme::default(),
+            shorthand_width: 0,
+            title_width: 0,
+            max_width: None,
+            long: true,
+            show_author: false,
+            dim_read: false,
+            highlight: &[],
+            per_group: None,
+        };
+        // Title column is 3 wide: read marker plus the space after the shorthand
+        let lines = item::format_excerpt(&i, Some(width + 3), &ctx);
+        let trimmed: Vec<&str> = lines.iter().map(|l| l.trim_start()).collect();
+        assert_eq!(trimmed, expected);
+    }
+
+    #[rstest]
+    #[case::wide_terminal(80)]
+    #[case::narrow_terminal(40)]
+    fn test_feed_names_line_up_in_a_column(#[case] max_width: usize) {
+        use unicode_width::UnicodeWidthStr;
+
+        let items = [
+            feed_item_with_raw_id("Short", "2024-01-15", "feed1", "id1"),
+            feed_item_with_raw_id("你好世界", "2024-01-14", "feed1", "id2"),
+            feed_item_with_raw_id("A somewhat longer post title", "2024-01-13", "feed1", "id3"),
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+        let shorthands: HashMap<String, String> = [("id1", "a"), ("id2", "b"), ("id3", "c")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut labels = HashMap::new();
+        labels.insert("feed1".to_string(), "@x Blog".to_string());
+
+        let ctx = default_ctx(
+            &[],
+            &shorthands,
+            &labels,
+            no_reads(),
+            Some(max_width),
+            &refs,
+        );
+        let output = render_grouped(&refs, &ctx);
+
+        let columns: Vec<usize> = output
+            .lines()
+            .map(|line| line[..line.find(" (@x").unwrap()].width())
+            .collect();
+        assert_eq!(columns.len(), 3);
+        assert!(
+            columns.iter().all(|c| *c == columns[0]),
+            "feed names should line up:\n{output}"
+        );
+        assert!(output.lines().all(|l| l.width() <= max_width), "{output}");
+    }
+
+    #[test]
+    fn test_unknown_date_is_padded_to_date_width() {
+        let mut undated = feed_item_with_raw_id("Undated", "2024-01-15", "feed1", "id2");
+        undated.date = None;
+        let items = [
+            feed_item_with_raw_id("Dated", "2024-01-15", "feed1", "id1"),
+            undated,
+        ];
+        let refs: Vec<&FeedItem> = items.iter().collect();
+        let ctx = default_ctx(
+            &[GroupKey::Feed],
+            no_labels(),
+            no_labels(),
+            no_reads(),
+            None,
+            &refs,
+        );
+
+        let output = render_grouped(&refs, &ctx);
+
+        assert!(output.contains("* 2024-01-15   Dated\n"), "{output}");
+        assert!(output.contains("* unknown      Undated\n"), "{output}");
+    }
     #[test]
     fn test_cjk_characters_respect_display_width() {
         use unicode_width::UnicodeWidthStr;
@@ -0,0 +1,170 @@
+//! Colors of the post listing, configurable with the `colors.date`,
+//! `colors.shorthand`, `colors.feed`, `colors.header` and `colors.highlight`
+//! config keys.
+//!
+//! A value is a space-separated list of attributes (`bold`, `dim`, `italic`,
+//! `underline`), colors (`cyan`, `bright-blue`, a 256-color number or
+//! `#rrggbb`), background colors (`on-<color>`), or `none`.
+
+use std::str::FromStr;
+
+use anyhow::{Context, bail};
+
+use crate::data::{BlogData, get_config_parsed};
+
+/// An ANSI escape sequence setting a text style.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Sgr(pub String);
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// SGR parameters for a color, with `base` 30 for text and 40 for background.
+fn color_params(spec: &str, base: u8) -> anyhow::Result<String> {
+    let extended = base + 8;
+    if let Some(hex) = spec.strip_prefix('#') {
+        anyhow::ensure!(
+            hex.len() == 6 && hex.is_ascii(),
+            "expected #rrggbb, got {spec}"
+        );
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16).with_context(|| format!("invalid color {spec}"))
+        };
+        return Ok(format!(
+            "{extended};2;{};{};{}",
+            channel(0)?,
+            channel(2)?,
+            channel(4)?
+        ));
+    }
+    if let Ok(n) = spec.parse::<u8>() {
+        return Ok(format!("{extended};5;{n}"));
+    }
+    let (name, offset) = match spec.strip_prefix("bright-") {
+        Some(name) => (name, 60),
+        None => (spec, 0),
+    };
+    match COLORS.iter().position(|c| *c == name) {
+        Some(i) => Ok((base + offset + i as u8).to_string()),
+        None => bail!("unknown color {spec}"),
+    }
+}
+
+impl FromStr for Sgr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut params = Vec::new();
+        for word in s.split_whitespace() {
+            params.push(match word {
+                "none" | "default" => continue,
+                "bold" => "1".to_string(),
+                "dim" => "2".to_string(),
+                "italic" => "3".to_string(),
+                "underline" => "4".to_string(),
+                _ => match word.strip_prefix("on-") {
+                    Some(color) => color_params(color, 40)?,
+                    None => color_params(word, 30)?,
+                },
+            });
+        }
+        if params.is_empty() {
+            return Ok(Sgr(String::new()));
+        }
+        Ok(Sgr(format!("\x1b[{}m", params.join(";"))))
+    }
+}
+
+/// Styles of the parts of a post listing.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Theme {
+    pub date: String,
+    pub shorthand: String,
+    /// The feed name after each title
+    pub feed: String,
+    /// Group headers such as `=== 2024-01-15 ===`
+    pub header: String,
+    /// Words matching a search
+    pub highlight: String,
+}
+
+/// The theme used without color, e.g. when piping.
+static PLAIN: Theme = Theme {
+    date: String::new(),
+    shorthand: String::new(),
+    feed: String::new(),
+    header: String::new(),
+    highlight: String::new(),
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            date: "\x1b[36m".to_string(),
+            shorthand: "\x1b[1m".to_string(),
+            feed: "\x1b[2m\x1b[3m".to_string(),
+            header: "\x1b[1m".to_string(),
+            highlight: "\x1b[1;33m".to_string(),
+        }
+    }
+}
+
+impl Theme {
+    /// The default theme with any `colors.*` config keys applied.
+    pub(crate) fn load(store: &BlogData) -> anyhow::Result<Self> {
+        let mut theme = Theme::default();
+        for (key, field) in [
+            ("colors.date", &mut theme.date),
+            ("colors.shorthand", &mut theme.shorthand),
+            ("colors.feed", &mut theme.feed),
+            ("colors.header", &mut theme.header),
+            ("colors.highlight", &mut theme.highlight),
+        ] {
+            if let Some(Sgr(sgr)) = get_config_parsed(store, key)? {
+                *field = sgr;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// This theme, or one without any styling unless `color` is set.
+    pub(crate) fn when(&self, color: bool) -> &Theme {
+        if color { self } else { &PLAIN }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::named("blue", "\x1b[34m")]
+    #[case::bright("bright-blue", "\x1b[94m")]
+    #[case::combined("bold underline red", "\x1b[1;4;31m")]
+    #[case::background("black on-yellow", "\x1b[30;43m")]
+    #[case::palette("208", "\x1b[38;5;208m")]
+    #[case::truecolor("#1a2B3c", "\x1b[38;2;26;43;60m")]
+    #[case::truecolor_background("on-#000000", "\x1b[48;2;0;0;0m")]
+    #[case::none("none", "")]
+    fn test_parse_sgr(#[case] spec: &str, #[case] expected: &str) {
+        assert_eq!(spec.parse::<Sgr>().unwrap().0, expected);
+    }
+
+    #[rstest]
+    #[case::unknown_name("teal")]
+    #[case::short_hex("#fff")]
+    #[case::bad_hex("#gg0000")]
+    #[case::out_of_range("256")]
+    fn test_parse_sgr_invalid(#[case] spec: &str) {
+        assert!(spec.parse::<Sgr>().is_err());
+    }
+
+    #[test]
+    fn test_when_without_color_is_plain() {
+        let theme = Theme::default();
+        assert_eq!(theme.when(true), &theme);
+        assert_eq!(theme.when(false).date, "");
+    }
+}
@@ -4,32 +4,46 @@ use anyhow::Result;
 use atom_syndication::Feed;
 
 use super::FeedMeta;
+use super::links::LinkCleaner;
 use crate::data::schema::FeedItem;
+use crate::utils::html::title_text;
 
-pub fn parse<R: Read>(reader: R) -> Result<(FeedMeta, Vec<FeedItem>)> {
+fn link_with_rel(feed: &Feed, rel: &str) -> String {
+    feed.links()
+        .iter()
+        .find(|l| l.rel() == rel)
+        .map(|l| l.href().to_string())
+        .unwrap_or_default()
+}
+
+pub fn parse<R: Read>(reader: R, links: &LinkCleaner) -> Result<(FeedMeta, Vec<FeedItem>)> {
     let feed = Feed::read_from(BufReader::new(reader))?;
 
     let meta = FeedMeta {
+        format: "Atom",
         title: feed.title().as_str().to_string(),
         site_url: feed
             .links()
             .iter()
             .find(|l| l.rel() == "alternate")
             .or_else(|| feed.links().first())
-            .map(|l| l.href().to_string())
+            .map(|l| links.clean(l.href()))
             .unwrap_or_default(),
         description: feed
             .subtitle()
             .map(|s| s.as_str().to_string())
             .unwrap_or_default(),
+        hub: link_with_rel(&feed, "hub"),
+        topic: link_with_rel(&feed, "self"),
     };
+    let links = links.with_base(&meta.site_url);
 
     let items = feed
         .entries()
         .iter()
         .map(|entry| FeedItem {
             raw_id: entry.id().to_string(),
-            title: entry.title().as_str().to_string(),
+            title: title_text(entry.title().as_str()),
             date: entry
                 .published()
                 .or(Some(entry.updated()))
@@ -40,8 +54,35 @@ pub fn parse<R: Read>(reader: R) -> Result<(FeedMeta, Vec<FeedItem>)> {
                 .iter()
                 .find(|l| l.rel() == "alternate")
                 .or_else(|| entry.links().first())
-                .map(|l| l.href().to_string())
+                .map(|l| links.clean(l.href()))
+                .unwrap_or_default(),
+            summary: entry
+                .summary()
+                .map(|s| s.as_str().to_string())
+                .or_else(|| entry.content().and_then(|c| c.value()).map(String::from))
+                .unwrap_or_default(),
+            content: String::new(),
+            images: Vec::new(),
+            author: entry
+                .authors()
+                .first()
+                .map(|a| a.name().to_string())
+                .unwrap_or_default(),
+            categories: entry
+                .categories()
+                .iter()
+                .map(|c| c.label().unwrap_or(c.term()).to_string())
+                .collect(),
+            comments: entry
+                .links()
+                .iter()
+                .filter(|l| l.rel() == "replies")
+                .min_by_key(|l| l.mime_type() != Some("text/html"))
+                .map(|l| links.clean(l.href()))
                 .unwrap_or_default(),
+            first_seen_at: None,
+            lang: None,
+            edits: 0,
         })
         .collect();
 
@@ -73,7 +114,7 @@ mod tests {
           </entry>
         </feed>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items.len(), 2);
         assert_eq!(items[0].title, "First Post");
@@ -90,6 +131,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_websub_links() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <title>Test</title>
+          <id>urn:test</id>
+          <updated>2024-01-01T00:00:00Z</updated>
+          <link rel="alternate" href="https://blog.example/"/>
+          <link rel="hub" href="https://hub.example/"/>
+          <link rel="self" href="https://blog.example/atom.xml"/>
+        </feed>"#;
+
+        let (meta, _) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(meta.site_url, "https://blog.example/");
+        assert_eq!(meta.hub, "https://hub.example/");
+        assert_eq!(meta.topic, "https://blog.example/atom.xml");
+    }
+
     #[test]
     fn test_timezone_is_normalized_to_utc() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -105,7 +165,7 @@ mod tests {
           </entry>
         </feed>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
         let date = items[0].date.unwrap();
 
         assert_eq!(date.format("%Y-%m-%d").to_string(), "2024-01-02");
@@ -126,7 +186,7 @@ mod tests {
           </entry>
         </feed>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(
             items[0].date.unwrap().format("%Y-%m-%d").to_string(),
@@ -134,6 +194,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summary_falls_back_to_content() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <title>Test</title>
+          <id>urn:test</id>
+          <updated>2024-06-15T00:00:00Z</updated>
+          <entry>
+            <title>With Summary</title>
+            <id>urn:post:1</id>
+            <updated>2024-06-15T00:00:00Z</updated>
+            <summary>The summary</summary>
+            <content type="html">The content</content>
+          </entry>
+          <entry>
+            <title>Content Only</title>
+            <id>urn:post:2</id>
+            <updated>2024-06-15T00:00:00Z</updated>
+            <content type="html">&lt;p&gt;The content&lt;/p&gt;</content>
+          </entry>
+        </feed>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].summary, "The summary");
+        assert_eq!(items[1].summary, "<p>The content</p>");
+    }
+
     #[test]
     fn test_unparseable_date_returns_error() {
         // Atom requires <updated> with a valid datetime; an invalid one
@@ -151,7 +239,7 @@ mod tests {
         </feed>"#;
 
         // atom_syndication requires valid dates, so this should error
-        let result = parse(xml.as_bytes());
+        let result = parse(xml.as_bytes(), &LinkCleaner::default());
         assert!(result.is_err());
     }
 
@@ -164,8 +252,53 @@ mod tests {
           <updated>2024-01-01T00:00:00Z</updated>
         </feed>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert!(items.is_empty());
     }
+
+    #[test]
+    fn test_author_categories_and_comments() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <title>Test Blog</title>
+          <id>urn:test</id>
+          <updated>2024-01-02T00:00:00Z</updated>
+          <entry>
+            <title>Post</title>
+            <id>urn:test:1</id>
+            <updated>2024-01-02T00:00:00Z</updated>
+            <author><name>Jane Doe</name></author>
+            <category term="sec" label="Security"/>
+            <category term="rust"/>
+            <link rel="replies" type="application/atom+xml" href="https://example.com/1/comments.xml"/>
+            <link rel="replies" type="text/html" href="https://example.com/1#comments"/>
+          </entry>
+        </feed>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].author, "Jane Doe");
+        assert_eq!(items[0].categories, ["Security", "rust"]);
+        assert_eq!(items[0].comments, "https://example.com/1#comments");
+    }
+
+    #[test]
+    fn test_html_title() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <title>Test Blog</title>
+          <id>urn:test</id>
+          <updated>2024-01-02T00:00:00Z</updated>
+          <entry>
+            <title type="html">Using &lt;code&gt;Option&amp;lt;T&amp;gt;&lt;/code&gt; &amp;amp; more</title>
+            <id>urn:test:1</id>
+            <updated>2024-01-02T00:00:00Z</updated>
+          </entry>
+        </feed>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].title, "Using Option<T> & more");
+    }
 }
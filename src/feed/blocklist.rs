@@ -0,0 +1,127 @@
+//! The `blocklist` config key: domains and URL prefixes whose posts are
+//! dropped when feeds are fetched and whose feeds can't be subscribed to,
+//! e.g. to keep syndicated spam out of aggregate feeds.
+
+use std::convert::Infallible;
+use std::str::FromStr;
+
+use url::Url;
+
+/// A blocked domain, which also blocks its subdomains, and the path URLs on
+/// it must start with to be blocked, empty for all of them.
+#[derive(Clone, Debug, PartialEq)]
+struct Pattern {
+    text: String,
+    domain: String,
+    path: String,
+}
+
+impl Pattern {
+    fn parse(text: &str) -> Self {
+        let rest = text
+            .strip_prefix("https://")
+            .or_else(|| text.strip_prefix("http://"))
+            .unwrap_or(text);
+        let rest = rest.strip_prefix("*.").unwrap_or(rest);
+        let (domain, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        Self {
+            text: text.to_string(),
+            domain: domain.to_lowercase(),
+            path: path.trim_end_matches('*').to_string(),
+        }
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let on_domain = host == self.domain
+            || host
+                .strip_suffix(&self.domain)
+                .is_some_and(|sub| sub.ends_with('.'));
+        on_domain && url.path().starts_with(&self.path)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Blocklist {
+    patterns: Vec<Pattern>,
+}
+
+/// A comma-separated list of domains, e.g. `spam.example`, or URL prefixes,
+/// e.g. `example.com/sponsored`.
+impl FromStr for Blocklist {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        Ok(Self {
+            patterns: s
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(Pattern::parse)
+                .collect(),
+        })
+    }
+}
+
+impl Blocklist {
+    /// Read the `blocklist` config key.
+    pub(crate) fn from_config(store: &crate::data::BlogData) -> anyhow::Result<Self> {
+        Ok(crate::data::get_config_parsed(store, "blocklist")?.unwrap_or_default())
+    }
+
+    /// The entry blocking `url`, if any. Links that aren't absolute URLs
+    /// are never blocked.
+    pub(crate) fn blocked_by(&self, url: &str) -> Option<&str> {
+        if self.patterns.is_empty() {
+            return None;
+        }
+        let url = Url::parse(url.trim()).ok()?;
+        self.patterns
+            .iter()
+            .find(|p| p.matches(&url))
+            .map(|p| p.text.as_str())
+    }
+
+    pub(crate) fn blocks(&self, url: &str) -> bool {
+        self.blocked_by(url).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::domain("spam.example", "https://spam.example/post/1", true)]
+    #[case::subdomain("spam.example", "https://www.spam.example/post/1", true)]
+    #[case::other_domain("spam.example", "https://notspam.example/post/1", false)]
+    #[case::case_insensitive("Spam.Example", "https://SPAM.example/x", true)]
+    #[case::wildcard("*.spam.example", "https://a.spam.example/x", true)]
+    #[case::with_scheme("https://spam.example", "http://spam.example/x", true)]
+    #[case::path_prefix("news.example/sponsored", "https://news.example/sponsored/x", true)]
+    #[case::trailing_star("news.example/sponsored*", "https://news.example/sponsored-x", true)]
+    #[case::other_path("news.example/sponsored", "https://news.example/world/x", false)]
+    #[case::relative("spam.example", "/post/1", false)]
+    fn test_blocks(#[case] list: &str, #[case] url: &str, #[case] expected: bool) {
+        let blocklist: Blocklist = list.parse().unwrap();
+        assert_eq!(blocklist.blocks(url), expected);
+    }
+
+    #[test]
+    fn test_blocked_by_names_the_entry() {
+        let blocklist: Blocklist = " a.example , b.example/ads ,".parse().unwrap();
+
+        assert_eq!(
+            blocklist.blocked_by("https://b.example/ads/1"),
+            Some("b.example/ads")
+        );
+        assert_eq!(blocklist.blocked_by("https://c.example/"), None);
+        assert!(!Blocklist::default().blocks("https://a.example/"));
+    }
+}
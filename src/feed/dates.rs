@@ -0,0 +1,160 @@
+//! Parsing the publication dates of posts. Feeds are meant to use RFC 2822
+//! (RSS) or RFC 3339 (Atom, JSON Feed), but many write something close to
+//! it, so known variants are repaired before giving up on a date.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+
+const DAY_NAMES: &[&str] = &["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Time zone abbreviations that chrono's RFC 2822 parser doesn't know, with
+/// their offsets.
+const ZONE_OFFSETS: &[(&str, &str)] = &[
+    ("UTC", "+0000"),
+    ("Z", "+0000"),
+    ("BST", "+0100"),
+    ("CET", "+0100"),
+    ("CEST", "+0200"),
+    ("EET", "+0200"),
+    ("EEST", "+0300"),
+    ("IST", "+0530"),
+    ("JST", "+0900"),
+    ("AEST", "+1000"),
+    ("AEDT", "+1100"),
+];
+
+/// Formats without a time zone, read as UTC.
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Parse a post's date, trying RFC 2822, then RFC 2822 with common mistakes
+/// repaired, then RFC 3339 and other ISO 8601 forms.
+pub(crate) fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    DateTime::parse_from_rfc2822(s)
+        .ok()
+        .or_else(|| DateTime::parse_from_rfc2822(&repair_rfc2822(s)).ok())
+        .or_else(|| parse_iso(s))
+        .map(|d| d.to_utc())
+}
+
+/// `s` with the mistakes feeds make in RFC 2822 dates fixed: full day and
+/// month names, a missing comma or day name, `+HH:MM` offsets and zone
+/// abbreviations chrono doesn't know.
+fn repair_rfc2822(s: &str) -> String {
+    let mut words: Vec<String> = s
+        .split_whitespace()
+        .map(|w| w.trim_end_matches(',').to_string())
+        .collect();
+    // The day name is optional and only gets in the way
+    if words.first().is_some_and(|w| {
+        DAY_NAMES
+            .iter()
+            .any(|day| w.to_ascii_lowercase().starts_with(day))
+    }) {
+        words.remove(0);
+    }
+    // "January 5 2024" → "5 January 2024"
+    if words.len() >= 2
+        && words[0].chars().all(|c| c.is_ascii_alphabetic())
+        && words[1].chars().all(|c| c.is_ascii_digit())
+    {
+        words.swap(0, 1);
+    }
+    // "January" or "Sept" → "Jan" or "Sep"
+    if let Some(month) = words.get_mut(1)
+        && month.len() > 3
+        && month.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        month.truncate(3);
+    }
+    if let Some(zone) = words.last_mut() {
+        if let Some(&(_, offset)) = ZONE_OFFSETS
+            .iter()
+            .find(|(name, _)| zone.eq_ignore_ascii_case(name))
+        {
+            *zone = offset.to_string();
+        } else if zone.len() == 6 && zone.starts_with(['+', '-']) && zone.as_bytes()[3] == b':' {
+            zone.remove(3);
+        }
+    }
+    // A time without a zone is taken as UTC
+    if words.last().is_some_and(|w| w.contains(':')) {
+        words.push("+0000".to_string());
+    }
+    words.join(" ")
+}
+
+fn parse_iso(s: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Some(d);
+    }
+    // RFC 3339 with a space instead of `T`, or an offset without a colon
+    let with_t = s.replacen(' ', "T", 1);
+    if let Ok(d) = DateTime::parse_from_rfc3339(&with_t) {
+        return Some(d);
+    }
+    if let Ok(d) = DateTime::parse_from_str(&with_t, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(d);
+    }
+    let utc = FixedOffset::east_opt(0)?;
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .map(|d| d.and_utc().with_timezone(&utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::rfc2822("Mon, 01 Jan 2024 10:00:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::offset("Mon, 01 Jan 2024 23:00:00 -0500", "2024-01-02T04:00:00Z")]
+    #[case::colon_offset("Sun, 18 May 2025 00:00:00 -07:00", "2025-05-18T07:00:00Z")]
+    #[case::no_day_name("01 Jan 2024 10:00:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::wrong_day_name("Fri, 01 Jan 2024 10:00:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::full_names("Monday, 01 January 2024 10:00:00 GMT", "2024-01-01T10:00:00Z")]
+    #[case::september("Tue, 10 Sept 2024 10:00:00 GMT", "2024-09-10T10:00:00Z")]
+    #[case::single_digit_day("Mon, 1 Jan 2024 10:00:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::two_digit_year("Mon, 01 Jan 24 10:00:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::no_seconds("Mon, 01 Jan 2024 10:00 +0000", "2024-01-01T10:00:00Z")]
+    #[case::no_zone("Mon, 01 Jan 2024 10:00:00", "2024-01-01T10:00:00Z")]
+    #[case::utc_name("Mon, 01 Jan 2024 10:00:00 UTC", "2024-01-01T10:00:00Z")]
+    #[case::cest("Mon, 01 Jul 2024 10:00:00 CEST", "2024-07-01T08:00:00Z")]
+    #[case::us_zone("Mon, 01 Jan 2024 10:00:00 EST", "2024-01-01T15:00:00Z")]
+    #[case::month_first("January 5, 2024 10:00:00 +0000", "2024-01-05T10:00:00Z")]
+    #[case::rfc3339("2024-01-01T10:00:00+02:00", "2024-01-01T08:00:00Z")]
+    #[case::rfc3339_fraction("2024-01-01T10:00:00.123Z", "2024-01-01T10:00:00.123Z")]
+    #[case::iso_space("2024-01-01 10:00:00+00:00", "2024-01-01T10:00:00Z")]
+    #[case::iso_no_colon("2024-01-01T10:00:00+0200", "2024-01-01T08:00:00Z")]
+    #[case::iso_naive("2024-01-01T10:00:00", "2024-01-01T10:00:00Z")]
+    #[case::iso_naive_space("2024-01-01 10:00", "2024-01-01T10:00:00Z")]
+    #[case::date_only("2024-01-01", "2024-01-01T00:00:00Z")]
+    #[case::padded("  2024-01-01  ", "2024-01-01T00:00:00Z")]
+    fn test_parse_date(#[case] input: &str, #[case] expected: &str) {
+        let expected = DateTime::parse_from_rfc3339(expected).unwrap().to_utc();
+        assert_eq!(parse_date(input), Some(expected));
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::garbage("not-a-date")]
+    #[case::words("sometime last week")]
+    #[case::bad_day("Mon, 32 Jan 2024 10:00:00 +0000")]
+    fn test_parse_date_rejects(#[case] input: &str) {
+        assert_eq!(parse_date(input), None);
+    }
+}
@@ -16,6 +16,7 @@ const COMMON_FEED_FILENAMES: &[&str] = &[
     "rss",
     "feed.rss",
     "feed.atom",
+    "feed.json",
 ];
 
 /// Feed-like path segments used to identify feed URLs in `<a>` tags.
@@ -157,6 +158,29 @@ fn find_anchor_feed_links(html: &str, page_url: &url::Url) -> Vec<String> {
     dedup.into_urls()
 }
 
+/// The home pages of the other web sites `<a>` tags in `html` link to, once
+/// per site, in the order they first appear. Links are lowercased along the
+/// way, so only their sites are kept.
+pub fn linked_sites(html: &str, page_url: &url::Url) -> Vec<String> {
+    let mut sites: Vec<String> = Vec::new();
+    for_each_tag(html, "a", |tag| {
+        let Some(link) = extract_attr(tag, "href").and_then(|h| page_url.join(h.trim()).ok())
+        else {
+            return;
+        };
+        if !matches!(link.scheme(), "http" | "https") || link.host_str() == page_url.host_str() {
+            return;
+        }
+        if let Ok(home) = link.join("/") {
+            let home = home.to_string();
+            if !sites.contains(&home) {
+                sites.push(home);
+            }
+        }
+    });
+    sites
+}
+
 /// Extract an attribute value from a lowercased HTML tag.
 fn extract_attr(tag_lower: &str, attr_name: &str) -> Option<String> {
     // Find attr_name= preceded by whitespace to avoid matching data-type= when looking for type=
@@ -184,6 +208,16 @@ fn extract_attr(tag_lower: &str, attr_name: &str) -> Option<String> {
     Some(rest[value_start..value_end].to_string())
 }
 
+/// Common feed paths relative to the site root, tried by `blog feed add` when
+/// none of the candidates found on the page turn out to be feeds.
+pub fn root_feed_urls(page_url: &url::Url) -> Vec<String> {
+    COMMON_FEED_FILENAMES
+        .iter()
+        .filter_map(|filename| page_url.join(&format!("/{filename}")).ok())
+        .map(|u| u.to_string())
+        .collect()
+}
+
 fn guess_common_paths(page_url: &url::Url) -> Vec<String> {
     let path = page_url.path();
     let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
@@ -319,6 +353,23 @@ mod tests {
 
     // === Common path fallback ===
 
+    #[test]
+    fn test_linked_sites_lists_other_sites_once() {
+        let html = r#"<ul class="blogroll">
+            <li><a href="https://Friend.example/posts/1">Friend</a></li>
+            <li><a href="https://friend.example/about">About friend</a></li>
+            <li><a href="/archive">Archive</a></li>
+            <li><a href="https://example.com/self">Self</a></li>
+            <li><a href="mailto:me@example.com">Mail</a></li>
+            <li><a href="http://other.example:8080/x">Other</a></li>
+        </ul>"#;
+        let url = parse_url("https://example.com/");
+        assert_eq!(
+            linked_sites(html, &url),
+            ["https://friend.example/", "http://other.example:8080/"]
+        );
+    }
+
     #[test]
     fn test_fallback_includes_parent_paths() {
         let html = "<html></html>";
@@ -375,6 +426,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_root_feed_urls() {
+        let urls = root_feed_urls(&parse_url("https://example.com/blog/post?x=1"));
+        for path in ["/feed", "/rss", "/atom.xml", "/index.xml", "/feed.json"] {
+            assert!(
+                urls.contains(&format!("https://example.com{path}")),
+                "missing {path}"
+            );
+        }
+        assert!(urls.iter().all(|u| !u.contains("/blog/")));
+    }
+
     // === <a> tag feed link discovery ===
 
     #[rstest]
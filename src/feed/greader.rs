@@ -0,0 +1,405 @@
+//! Client for the Google Reader API as implemented by FreshRSS, The Old
+//! Reader, Miniflux and others.
+//!
+//! When an account is configured, `blog sync` takes subscriptions and posts
+//! from the server instead of fetching those feeds itself, and keeps read
+//! state in step in both directions.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use chrono::DateTime;
+use serde::Deserialize;
+
+use crate::data::schema::FeedItem;
+use crate::feed::FeedMeta;
+use crate::utils::html::title_text;
+
+/// Environment variable holding the API password.
+pub(crate) const PASSWORD_ENV: &str = "BLOG_GREADER_PASSWORD";
+
+const READ_TAG: &str = "user/-/state/com.google/read";
+
+/// Items requested per page of the reading list.
+const PAGE_SIZE: usize = 500;
+
+/// Pages of the reading list fetched per sync, newest first.
+const MAX_PAGES: usize = 10;
+
+/// A feed the account is subscribed to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Subscription {
+    /// Server-side stream ID, e.g. `feed/12`
+    pub id: String,
+    pub url: String,
+    pub meta: FeedMeta,
+}
+
+/// A post from the reading list, with the stream it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Item {
+    pub stream_id: String,
+    pub post: FeedItem,
+    pub read: bool,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionList {
+    subscriptions: Vec<RawSubscription>,
+}
+
+#[derive(Deserialize)]
+struct RawSubscription {
+    id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default, rename = "htmlUrl")]
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct Stream {
+    #[serde(default)]
+    items: Vec<RawItem>,
+    continuation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct Origin {
+    #[serde(rename = "streamId")]
+    stream_id: String,
+}
+
+#[derive(Deserialize)]
+struct RawItem {
+    id: String,
+    #[serde(default)]
+    title: String,
+    published: Option<i64>,
+    #[serde(default)]
+    canonical: Vec<Link>,
+    #[serde(default)]
+    alternate: Vec<Link>,
+    summary: Option<Content>,
+    content: Option<Content>,
+    origin: Origin,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+impl RawItem {
+    fn into_item(self) -> Item {
+        let read = self.categories.iter().any(|c| is_read_tag(c));
+        // Plain categories come from the feed; `user/...` ones are the
+        // server's states and labels
+        let categories = self
+            .categories
+            .into_iter()
+            .filter(|c| !c.starts_with("user/"))
+            .collect();
+        let link = self
+            .canonical
+            .into_iter()
+            .chain(self.alternate)
+            .next()
+            .map(|l| l.href)
+            .unwrap_or_default();
+        let summary = self.summary.map(|c| c.content).unwrap_or_default();
+        let content = self.content.map(|c| c.content).unwrap_or_default();
+        // Split like RSS's description and `content:encoded`
+        let (summary, content) = match (summary.is_empty(), content == summary) {
+            (true, _) => (content, String::new()),
+            (false, true) => (summary, String::new()),
+            (false, false) => (summary, content),
+        };
+        let post = crate::feed::sanitize_item(FeedItem {
+            title: title_text(&self.title),
+            date: self.published.and_then(|s| DateTime::from_timestamp(s, 0)),
+            feed: String::new(),
+            link,
+            raw_id: self.id,
+            summary,
+            content,
+            images: Vec::new(),
+            author: self.author,
+            categories,
+            comments: String::new(),
+            first_seen_at: None,
+            lang: None,
+            edits: 0,
+        });
+        Item {
+            stream_id: self.origin.stream_id,
+            post: FeedItem {
+                lang: crate::feed::lang::detect(&post.title, &post.summary),
+                ..post
+            },
+            read,
+        }
+    }
+}
+
+/// Servers spell the read state with the user's numeric ID or with `-`.
+fn is_read_tag(category: &str) -> bool {
+    category
+        .strip_prefix("user/")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(_, state)| state == "state/com.google/read")
+}
+
+/// A logged-in session.
+pub(crate) struct Client {
+    agent: ureq::Agent,
+    /// API root, e.g. `https://rss.example/api/greader.php`
+    base: String,
+    auth: String,
+}
+
+impl Client {
+    pub(crate) fn login(
+        agent: ureq::Agent,
+        base: &str,
+        user: &str,
+        password: &str,
+    ) -> anyhow::Result<Self> {
+        let base = base.trim_end_matches('/').to_string();
+        let body = agent
+            .post(format!("{base}/accounts/ClientLogin"))
+            .send_form([("Email", user), ("Passwd", password)])
+            .with_context(|| format!("Failed to log in to {base} as {user}"))?
+            .body_mut()
+            .read_to_string()?;
+        let Some(auth) = body.lines().find_map(|l| l.strip_prefix("Auth=")) else {
+            bail!("Login to {base} did not return an auth token");
+        };
+        Ok(Self {
+            agent,
+            auth: auth.trim().to_string(),
+            base,
+        })
+    }
+
+    fn authorization(&self) -> String {
+        format!("GoogleLogin auth={}", self.auth)
+    }
+
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<String> {
+        let mut request = self
+            .agent
+            .get(format!("{}/reader/api/0/{path}", self.base))
+            .header("Authorization", self.authorization());
+        for (name, value) in query {
+            request = request.query(*name, *value);
+        }
+        Ok(request.call()?.body_mut().read_to_string()?)
+    }
+
+    pub(crate) fn subscriptions(&self) -> anyhow::Result<Vec<Subscription>> {
+        let body = self.get("subscription/list", &[("output", "json")])?;
+        let list: SubscriptionList = serde_json::from_str(&body)?;
+        Ok(list
+            .subscriptions
+            .into_iter()
+            .map(|s| Subscription {
+                url: if s.url.is_empty() {
+                    s.id.strip_prefix("feed/").unwrap_or(&s.id).to_string()
+                } else {
+                    s.url
+                },
+                id: s.id,
+                meta: FeedMeta {
+                    format: "",
+                    title: s.title,
+                    site_url: s.html_url,
+                    description: String::new(),
+                    hub: String::new(),
+                    topic: String::new(),
+                },
+            })
+            .collect())
+    }
+
+    /// The most recent items across all subscriptions.
+    pub(crate) fn items(&self) -> anyhow::Result<Vec<Item>> {
+        let page_size = PAGE_SIZE.to_string();
+        let mut items = Vec::new();
+        let mut continuation: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let mut query = vec![("output", "json"), ("n", page_size.as_str())];
+            if let Some(c) = &continuation {
+                query.push(("c", c));
+            }
+            let body = self.get(
+                "stream/contents/user/-/state/com.google/reading-list",
+                &query,
+            )?;
+            let stream: Stream = serde_json::from_str(&body)?;
+            items.extend(stream.items.into_iter().map(RawItem::into_item));
+            match stream.continuation {
+                Some(c) if !c.is_empty() => continuation = Some(c),
+                _ => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Mark the given item IDs as read on the server.
+    pub(crate) fn mark_read(&self, ids: &[String]) -> anyhow::Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let token = self.get("token", &[])?;
+        let mut form: Vec<(&str, &str)> = vec![("a", READ_TAG), ("T", token.trim())];
+        form.extend(ids.iter().map(|id| ("i", id.as_str())));
+        self.agent
+            .post(format!("{}/reader/api/0/edit-tag", self.base))
+            .header("Authorization", self.authorization())
+            .send_form(form)?;
+        Ok(())
+    }
+}
+
+/// Group items by the URL of the subscription they belong to, dropping items
+/// from streams that aren't in `subscriptions`.
+pub(crate) fn items_by_feed(
+    subscriptions: &[Subscription],
+    items: Vec<Item>,
+) -> HashMap<String, Vec<Item>> {
+    let urls: HashMap<&str, &str> = subscriptions
+        .iter()
+        .map(|s| (s.id.as_str(), s.url.as_str()))
+        .collect();
+    let mut grouped: HashMap<String, Vec<Item>> = HashMap::new();
+    for item in items {
+        if let Some(url) = urls.get(item.stream_id.as_str()) {
+            grouped.entry(url.to_string()).or_default().push(item);
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::dash("user/-/state/com.google/read", true)]
+    #[case::numeric("user/1005/state/com.google/read", true)]
+    #[case::starred("user/-/state/com.google/starred", false)]
+    #[case::label("user/-/label/Tech", false)]
+    fn test_is_read_tag(#[case] category: &str, #[case] expected: bool) {
+        assert_eq!(is_read_tag(category), expected);
+    }
+
+    #[test]
+    fn test_parse_stream_item() {
+        let json = r#"{
+            "items": [{
+                "id": "tag:google.com,2005:reader/item/0000000000000001",
+                "title": "Hello",
+                "published": 1704067200,
+                "canonical": [{"href": "https://blog.example/hello"}],
+                "summary": {"content": "<p>Short</p>"},
+                "content": {"content": "<p>Long</p>"},
+                "origin": {"streamId": "feed/3", "title": "Blog"},
+                "categories": ["user/-/state/com.google/reading-list", "user/-/state/com.google/read"]
+            }]
+        }"#;
+        let stream: Stream = serde_json::from_str(json).unwrap();
+        let item = stream.items.into_iter().next().unwrap().into_item();
+
+        assert_eq!(item.stream_id, "feed/3");
+        assert!(item.read);
+        assert_eq!(item.post.title, "Hello");
+        assert_eq!(item.post.link, "https://blog.example/hello");
+        assert_eq!(item.post.summary, "<p>Short</p>");
+        assert_eq!(item.post.content, "<p>Long</p>");
+        assert_eq!(
+            item.post.raw_id,
+            "tag:google.com,2005:reader/item/0000000000000001"
+        );
+        assert_eq!(
+            item.post.date.unwrap().format("%Y-%m-%d").to_string(),
+            "2024-01-01"
+        );
+    }
+
+    #[test]
+    fn test_stream_item_is_sanitized() {
+        let json = r#"{
+            "items": [{
+                "id": "1",
+                "title": "<b>Tom &amp; Jerry</b>\u001b[2J",
+                "alternate": [{"href": "https://blog.example/1\u0007"}],
+                "content": {"content": "<p>Only content</p>"},
+                "origin": {"streamId": "feed/3"},
+                "author": "Eve\u001b]0;pwned\u0007"
+            }]
+        }"#;
+        let stream: Stream = serde_json::from_str(json).unwrap();
+        let item = stream.items.into_iter().next().unwrap().into_item();
+
+        assert_eq!(item.post.title, "Tom & Jerry[2J");
+        assert_eq!(item.post.link, "https://blog.example/1");
+        assert_eq!(item.post.author, "Eve]0;pwned");
+        assert_eq!(item.post.summary, "<p>Only content</p>");
+        assert_eq!(item.post.content, "");
+    }
+
+    #[test]
+    fn test_items_by_feed() {
+        let sub = |id: &str, url: &str| Subscription {
+            id: id.to_string(),
+            url: url.to_string(),
+            meta: FeedMeta {
+                format: "",
+                title: String::new(),
+                site_url: String::new(),
+                description: String::new(),
+                hub: String::new(),
+                topic: String::new(),
+            },
+        };
+        let item = |stream: &str, id: &str| Item {
+            stream_id: stream.to_string(),
+            post: FeedItem {
+                title: id.to_string(),
+                raw_id: id.to_string(),
+                ..Default::default()
+            },
+            read: false,
+        };
+        let grouped = items_by_feed(
+            &[
+                sub("feed/1", "https://a.example/rss"),
+                sub("feed/2", "https://b.example/rss"),
+            ],
+            vec![
+                item("feed/1", "x"),
+                item("feed/2", "y"),
+                item("feed/1", "z"),
+                item("feed/9", "w"),
+            ],
+        );
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped["https://a.example/rss"].len(), 2);
+        assert_eq!(grouped["https://b.example/rss"][0].post.raw_id, "y");
+    }
+}
@@ -0,0 +1,207 @@
+//! [JSON Feed](https://www.jsonfeed.org/version/1.1/) parsing.
+
+use std::io::Read;
+
+use anyhow::{Result, ensure};
+use serde::Deserialize;
+
+use super::FeedMeta;
+use super::dates::parse_date;
+use super::links::LinkCleaner;
+use crate::data::schema::FeedItem;
+
+#[derive(Deserialize)]
+struct Feed {
+    version: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    home_page_url: String,
+    #[serde(default)]
+    feed_url: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    hubs: Vec<Hub>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Hub {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(default)]
+    id: serde_json::Value,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    content_html: String,
+    #[serde(default)]
+    content_text: String,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    /// JSON Feed 1.0's single author
+    author: Option<Author>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    #[serde(default)]
+    name: String,
+}
+
+impl Item {
+    /// The item's `id`, which JSON Feed 1.0 allowed to be a number.
+    fn raw_id(&self) -> String {
+        match &self.id {
+            serde_json::Value::String(s) if !s.is_empty() => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ if !self.url.is_empty() => self.url.clone(),
+            _ => self.title.clone(),
+        }
+    }
+}
+
+pub fn parse<R: Read>(reader: R, links: &LinkCleaner) -> Result<(FeedMeta, Vec<FeedItem>)> {
+    let feed: Feed = serde_json::from_reader(reader)?;
+    ensure!(
+        feed.version.starts_with("https://jsonfeed.org/version/"),
+        "not a JSON Feed: {}",
+        feed.version
+    );
+
+    let meta = FeedMeta {
+        format: "JSON Feed",
+        title: feed.title,
+        site_url: links.clean(&feed.home_page_url),
+        description: feed.description,
+        hub: feed
+            .hubs
+            .iter()
+            .find(|h| h.kind.eq_ignore_ascii_case("websub"))
+            .map(|h| h.url.clone())
+            .unwrap_or_default(),
+        topic: feed.feed_url,
+    };
+    let links = links.with_base(&meta.site_url);
+
+    let items = feed
+        .items
+        .into_iter()
+        .map(|item| FeedItem {
+            raw_id: item.raw_id(),
+            title: if item.title.is_empty() {
+                "untitled".to_string()
+            } else {
+                item.title.clone()
+            },
+            date: item
+                .date_published
+                .as_deref()
+                .or(item.date_modified.as_deref())
+                .and_then(parse_date),
+            feed: String::new(),
+            link: links.clean(&item.url),
+            summary: [item.summary, item.content_html, item.content_text]
+                .into_iter()
+                .find(|s| !s.is_empty())
+                .unwrap_or_default(),
+            content: String::new(),
+            images: Vec::new(),
+            author: item
+                .authors
+                .first()
+                .or(item.author.as_ref())
+                .map(|a| a.name.clone())
+                .unwrap_or_default(),
+            categories: item.tags,
+            comments: String::new(),
+            first_seen_at: None,
+            lang: None,
+            edits: 0,
+        })
+        .collect();
+
+    Ok((meta, items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_feed() {
+        let json = r#"{
+          "version": "https://jsonfeed.org/version/1.1",
+          "title": "Test Blog",
+          "home_page_url": "https://blog.example/",
+          "feed_url": "https://blog.example/feed.json",
+          "hubs": [{"type": "WebSub", "url": "https://hub.example/"}],
+          "items": [
+            {
+              "id": "urn:post:1",
+              "url": "https://blog.example/1",
+              "title": "First Post",
+              "content_html": "<p>Hello</p>",
+              "date_published": "2024-01-01T23:00:00-05:00",
+              "authors": [{"name": "Jane Doe"}],
+              "tags": ["rust", "async"]
+            },
+            {"id": 2, "content_text": "No title", "date_modified": "2024-01-03T00:00:00Z",
+             "author": {"name": "Bob"}}
+          ]
+        }"#;
+
+        let (meta, items) = parse(json.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(meta.title, "Test Blog");
+        assert_eq!(meta.site_url, "https://blog.example/");
+        assert_eq!(meta.hub, "https://hub.example/");
+        assert_eq!(meta.topic, "https://blog.example/feed.json");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].raw_id, "urn:post:1");
+        assert_eq!(items[0].link, "https://blog.example/1");
+        assert_eq!(items[0].summary, "<p>Hello</p>");
+        assert_eq!(items[0].author, "Jane Doe");
+        assert_eq!(items[1].author, "Bob");
+        assert_eq!(items[0].categories, ["rust", "async"]);
+        assert_eq!(
+            items[0].date.unwrap().format("%Y-%m-%d %H:%M").to_string(),
+            "2024-01-02 04:00"
+        );
+        assert_eq!(items[1].raw_id, "2");
+        assert_eq!(items[1].title, "untitled");
+        assert_eq!(items[1].summary, "No title");
+        assert_eq!(
+            items[1].date.unwrap().format("%Y-%m-%d").to_string(),
+            "2024-01-03"
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_json() {
+        assert!(
+            parse(
+                r#"{"version": "1", "items": []}"#.as_bytes(),
+                &LinkCleaner::default()
+            )
+            .is_err()
+        );
+        assert!(parse(r#"{"title": "x"}"#.as_bytes(), &LinkCleaner::default()).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! Detecting the language posts are written in, so listings can be narrowed
+//! to one language with `--lang`.
+
+use isolang::Language;
+
+use crate::data::schema::FeedItem;
+use crate::utils::html::to_text;
+
+/// Characters of the summary used for detection; the start of a post is
+/// enough, and keeps long articles from slowing down syncs.
+const SAMPLE_CHARS: usize = 1000;
+
+/// The language of a post with `title` and HTML `summary`, as an ISO 639-1
+/// code such as `en`, or `None` when the text is too short to tell.
+pub(crate) fn detect(title: &str, summary: &str) -> Option<String> {
+    let mut text = title.to_string();
+    text.push_str(". ");
+    text.extend(to_text(summary).chars().take(SAMPLE_CHARS));
+    let info = whatlang::detect(&text).filter(|info| info.is_reliable())?;
+    Some(normalize(info.lang().code()))
+}
+
+/// `code` as an ISO 639-1 code when it has one, so `en` and `eng` match.
+pub(crate) fn normalize(code: &str) -> String {
+    let code = code.trim().to_ascii_lowercase();
+    Language::from_639_3(&code)
+        .and_then(|lang| lang.to_639_1())
+        .map_or(code, str::to_string)
+}
+
+/// The language of `post`, detecting it for posts stored before languages
+/// were.
+pub(crate) fn post_lang(post: &FeedItem) -> Option<String> {
+    post.lang
+        .clone()
+        .or_else(|| detect(&post.title, &post.summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::english(
+        "Why we rewrote our build system",
+        "<p>Last year our builds took almost an hour, and nobody on the team was happy about it.</p>",
+        Some("en")
+    )]
+    #[case::german(
+        "Warum wir unser Build-System neu geschrieben haben",
+        "<p>Letztes Jahr dauerten unsere Builds fast eine Stunde, und niemand im Team war damit zufrieden.</p>",
+        Some("de")
+    )]
+    #[case::too_short("Ok", "", None)]
+    fn test_detect(#[case] title: &str, #[case] summary: &str, #[case] expected: Option<&str>) {
+        assert_eq!(detect(title, summary).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case::two_letter("en", "en")]
+    #[case::three_letter("eng", "en")]
+    #[case::uppercase("DE", "de")]
+    #[case::without_two_letter_code("haw", "haw")]
+    fn test_normalize(#[case] code: &str, #[case] expected: &str) {
+        assert_eq!(normalize(code), expected);
+    }
+}
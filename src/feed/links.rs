@@ -0,0 +1,200 @@
+//! Cleaning up the post links feeds publish before they are stored.
+
+use std::convert::Infallible;
+use std::str::FromStr;
+
+use url::Url;
+
+/// Query parameters dropped from post links unless the `tracking_params`
+/// config key says otherwise. A trailing `*` matches any parameter starting
+/// with the rest.
+const DEFAULT_TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+    "mkt_tok", "igshid", "yclid",
+];
+
+/// Normalizes the links of parsed posts: relative links are made absolute, so
+/// they can be opened, and tracking parameters removed, so the same post is
+/// always stored under the same link and trackers aren't passed on when
+/// sharing it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LinkCleaner {
+    tracking_params: Vec<String>,
+    /// What relative links are relative to
+    base: Option<Url>,
+}
+
+impl Default for LinkCleaner {
+    fn default() -> Self {
+        Self {
+            tracking_params: DEFAULT_TRACKING_PARAMS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            base: None,
+        }
+    }
+}
+
+/// A comma-separated list of parameters replacing the default ones; an empty
+/// list keeps links as they are.
+impl FromStr for LinkCleaner {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        Ok(Self {
+            tracking_params: s
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect(),
+            base: None,
+        })
+    }
+}
+
+impl LinkCleaner {
+    /// Read the `tracking_params` config key.
+    pub(crate) fn from_config(store: &crate::data::BlogData) -> anyhow::Result<Self> {
+        Ok(crate::data::get_config_parsed(store, "tracking_params")?.unwrap_or_default())
+    }
+
+    /// The same cleaner resolving relative links against `base`. Feeds are
+    /// parsed with their own URL as the base, and their posts with the
+    /// website's on top of it, since relative post links are relative to the
+    /// website rather than the feed. A relative `base` is itself resolved
+    /// against the current one, and one that can't be resolved is ignored.
+    pub(crate) fn with_base(&self, base: &str) -> Self {
+        let base = match &self.base {
+            Some(current) => current.join(base.trim()).ok(),
+            None => Url::parse(base.trim()).ok(),
+        };
+        Self {
+            tracking_params: self.tracking_params.clone(),
+            base: base.or_else(|| self.base.clone()),
+        }
+    }
+
+    /// `link` made absolute, if it is relative and there is a base.
+    fn resolve(&self, link: &str) -> String {
+        let link = link.trim();
+        if link.is_empty() || Url::parse(link).is_ok() {
+            return link.to_string();
+        }
+        match self.base.as_ref().and_then(|base| base.join(link).ok()) {
+            Some(url) => url.to_string(),
+            None => link.to_string(),
+        }
+    }
+
+    fn is_tracking(&self, name: &str) -> bool {
+        self.tracking_params
+            .iter()
+            .any(|p| match p.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == p,
+            })
+    }
+
+    /// `link` made absolute and without tracking parameters. Absolute links
+    /// that have none are returned unchanged, byte for byte.
+    pub(crate) fn clean(&self, link: &str) -> String {
+        let link = self.resolve(link);
+        let link = link.as_str();
+        let (rest, fragment) = match link.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (link, None),
+        };
+        let Some((base, query)) = rest.split_once('?') else {
+            return link.to_string();
+        };
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+                !self.is_tracking(name)
+            })
+            .collect();
+        if kept.len() == query.split('&').count() {
+            return link.to_string();
+        }
+        let mut out = base.to_string();
+        if !kept.is_empty() {
+            out.push('?');
+            out.push_str(&kept.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            out.push('#');
+            out.push_str(fragment);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_query("https://a.example/post", "https://a.example/post")]
+    #[case::only_tracking(
+        "https://a.example/post?utm_source=rss&utm_medium=feed",
+        "https://a.example/post"
+    )]
+    #[case::mixed(
+        "https://a.example/post?id=3&fbclid=x&page=2",
+        "https://a.example/post?id=3&page=2"
+    )]
+    #[case::fragment(
+        "https://a.example/post?utm_source=rss#intro",
+        "https://a.example/post#intro"
+    )]
+    #[case::untouched(
+        "https://a.example/post?q=a+b&x=%20",
+        "https://a.example/post?q=a+b&x=%20"
+    )]
+    #[case::similar_name("https://a.example/?utmost=1", "https://a.example/?utmost=1")]
+    #[case::relative("/post?gclid=1", "/post")]
+    fn test_clean_default(#[case] link: &str, #[case] expected: &str) {
+        assert_eq!(LinkCleaner::default().clean(link), expected);
+    }
+
+    #[rstest]
+    #[case::absolute("https://b.example/post", "https://b.example/post")]
+    #[case::root_relative("/post?utm_source=x", "https://a.example/post")]
+    #[case::path_relative("post.html", "https://a.example/blog/post.html")]
+    #[case::protocol_relative("//cdn.example/post", "https://cdn.example/post")]
+    #[case::empty("", "")]
+    fn test_clean_resolves_relative_links(#[case] link: &str, #[case] expected: &str) {
+        let cleaner = LinkCleaner::default().with_base("https://a.example/blog/feed.xml");
+        assert_eq!(cleaner.clean(link), expected);
+    }
+
+    #[test]
+    fn test_with_base_resolves_relative_base() {
+        let cleaner = LinkCleaner::default()
+            .with_base("https://a.example/feeds/all.xml")
+            .with_base("/blog/");
+        assert_eq!(cleaner.clean("post"), "https://a.example/blog/post");
+
+        let no_base = LinkCleaner::default().with_base("/blog/");
+        assert_eq!(no_base.clean("post"), "post");
+    }
+
+    #[test]
+    fn test_configured_params_replace_defaults() {
+        let cleaner: LinkCleaner = "ref, src_*".parse().unwrap();
+        assert_eq!(
+            cleaner.clean("https://a.example/?ref=hn&src_a=1&utm_source=x"),
+            "https://a.example/?utm_source=x"
+        );
+
+        let keep_all: LinkCleaner = "".parse().unwrap();
+        assert_eq!(
+            keep_all.clean("https://a.example/?utm_source=x"),
+            "https://a.example/?utm_source=x"
+        );
+    }
+}
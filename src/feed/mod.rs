@@ -1,47 +1,138 @@
 pub mod atom;
+pub(crate) mod blocklist;
+pub(crate) mod dates;
 pub(crate) mod discover;
+pub(crate) mod greader;
+pub mod json_feed;
+pub(crate) mod lang;
+pub(crate) mod links;
+pub(crate) mod notify;
 pub(crate) mod pull;
+pub(crate) mod retention;
 pub mod rss;
+pub(crate) mod shortcuts;
+pub(crate) mod websub;
+pub(crate) mod youtube;
+
+use itertools::Itertools;
 
 use crate::data::schema::FeedItem;
+use crate::feed::links::LinkCleaner;
+use crate::feed::pull::FetchOptions;
+use crate::utils::http::get_bytes;
+
+/// A successfully downloaded and parsed feed.
+#[derive(Debug)]
+pub struct Fetched {
+    pub meta: FeedMeta,
+    pub items: Vec<FeedItem>,
+    /// New canonical URL if the feed answered with a permanent redirect
+    pub moved_to: Option<String>,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FeedMeta {
+    /// RSS, Atom or JSON Feed, or empty if the posts didn't come from a feed
+    pub format: &'static str,
     pub title: String,
     pub site_url: String,
     pub description: String,
+    /// WebSub hub (`rel="hub"` link), if the feed advertises one
+    pub hub: String,
+    /// The feed's canonical URL (`rel="self"` link)
+    pub topic: String,
 }
 
 fn sanitize(s: &str) -> String {
     s.chars().filter(|c| !c.is_control()).collect()
 }
 
+/// Like [`sanitize`], but keeps line breaks and tabs, which are meaningful in
+/// longer text such as summaries.
+fn sanitize_multiline(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect()
+}
+
+/// `item` without control characters, e.g. terminal escape sequences, and
+/// without blank or repeated categories.
+pub(crate) fn sanitize_item(item: FeedItem) -> FeedItem {
+    FeedItem {
+        title: sanitize(&item.title),
+        link: sanitize(&item.link),
+        raw_id: sanitize(&item.raw_id),
+        summary: sanitize_multiline(&item.summary),
+        content: sanitize_multiline(&item.content),
+        images: item
+            .images
+            .iter()
+            .map(|url| sanitize(url))
+            .filter(|url| !url.is_empty())
+            .collect(),
+        author: sanitize(&item.author),
+        categories: item
+            .categories
+            .iter()
+            .map(|c| sanitize(c).trim().to_string())
+            .filter(|c| !c.is_empty())
+            .unique()
+            .collect(),
+        comments: sanitize(&item.comments),
+        ..item
+    }
+}
+
 fn sanitize_parsed(meta: FeedMeta, items: Vec<FeedItem>) -> (FeedMeta, Vec<FeedItem>) {
     let meta = FeedMeta {
+        format: meta.format,
         title: sanitize(&meta.title),
         site_url: sanitize(&meta.site_url),
         description: sanitize(&meta.description),
+        hub: sanitize(&meta.hub),
+        topic: sanitize(&meta.topic),
     };
-    let items = items
-        .into_iter()
-        .map(|item| FeedItem {
-            title: sanitize(&item.title),
-            link: sanitize(&item.link),
-            raw_id: sanitize(&item.raw_id),
-            ..item
-        })
-        .collect();
-    (meta, items)
+    (meta, items.into_iter().map(sanitize_item).collect())
 }
 
-pub(crate) fn parse(bytes: &[u8]) -> anyhow::Result<(FeedMeta, Vec<FeedItem>)> {
-    let (meta, items) = rss::parse(bytes).or_else(|_| atom::parse(bytes))?;
-    Ok(sanitize_parsed(meta, items))
+pub(crate) fn parse(
+    bytes: &[u8],
+    links: &LinkCleaner,
+) -> anyhow::Result<(FeedMeta, Vec<FeedItem>)> {
+    let parsed = match rss::parse(bytes, links) {
+        Ok(parsed) => parsed,
+        Err(rss) => match atom::parse(bytes, links) {
+            Ok(parsed) => parsed,
+            Err(atom) => {
+                tracing::debug!(rss = %rss, atom = %atom, "not an RSS or Atom feed");
+                json_feed::parse(bytes, links)?
+            }
+        },
+    };
+    let (meta, mut items) = sanitize_parsed(parsed.0, parsed.1);
+    tracing::debug!(
+        format = meta.format,
+        bytes = bytes.len(),
+        posts = items.len(),
+        "parsed feed"
+    );
+    for item in &mut items {
+        item.lang = lang::detect(&item.title, &item.summary);
+    }
+    Ok((meta, items))
 }
 
-pub fn fetch(client: &ureq::Agent, url: &str) -> anyhow::Result<(FeedMeta, Vec<FeedItem>)> {
-    let bytes = client.get(url).call()?.body_mut().read_to_vec()?;
-    parse(&bytes[..])
+pub fn fetch(client: &ureq::Agent, url: &str, opts: &FetchOptions) -> anyhow::Result<Fetched> {
+    let download = opts
+        .retry
+        .run(|| get_bytes(client, url, opts.http.max_body))?;
+    let base = download.moved_to.as_deref().unwrap_or(url);
+    let (meta, items) = parse(&download.body, &opts.links.with_base(base))?;
+    Ok(Fetched {
+        meta,
+        items,
+        moved_to: download.moved_to,
+    })
 }
 
 #[cfg(test)]
@@ -64,6 +155,7 @@ mod tests {
                   <title>{item_title}</title>
                   <guid>{item_raw_id}</guid>
                   <link>{item_link}</link>
+                  <description>{item_summary}</description>
                 </item>
               </channel>
             </rss>"#,
@@ -97,6 +189,11 @@ mod tests {
             } else {
                 "https://example.com/post"
             },
+            item_summary = if field == "item_summary" {
+                MALICIOUS
+            } else {
+                "Clean"
+            },
         )
     }
 
@@ -107,9 +204,10 @@ mod tests {
     #[case::item_title("item_title")]
     #[case::item_link("item_link")]
     #[case::item_raw_id("item_raw_id")]
+    #[case::item_summary("item_summary")]
     fn test_control_characters_are_stripped(#[case] field: &str) {
         let xml = rss_xml(field);
-        let (meta, items) = parse(xml.as_bytes()).unwrap();
+        let (meta, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
         let actual = match field {
             "feed_title" => &meta.title,
             "feed_description" => &meta.description,
@@ -117,6 +215,7 @@ mod tests {
             "item_title" => &items[0].title,
             "item_link" => &items[0].link,
             "item_raw_id" => &items[0].raw_id,
+            "item_summary" => &items[0].summary,
             _ => unreachable!(),
         };
         assert!(
@@ -137,8 +236,7 @@ mod tests {
                     .and_utc(),
             ),
             feed: "abc123".to_string(),
-            link: String::new(),
-            raw_id: String::new(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -150,10 +248,8 @@ mod tests {
     fn test_serde_roundtrip_without_date() {
         let item = FeedItem {
             title: "No Date Post".to_string(),
-            date: None,
             feed: "def456".to_string(),
-            link: String::new(),
-            raw_id: String::new(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -0,0 +1,244 @@
+//! Telling the user's own tools about new posts after they are stored.
+//!
+//! With [`HOOK_ENV`] set, its shell command runs once per new post, with the
+//! post as JSON on stdin and its main fields in `BLOG_*` environment
+//! variables. With `webhook_url` set, new posts are POSTed there in batches.
+//! Failures are reported but never fail the sync.
+
+use std::io::Write;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::data::BlogData;
+use crate::data::schema::FeedItem;
+
+/// Environment variable holding the new post hook. Commands are never read
+/// from the store: anyone able to push to its remote could make every device
+/// that syncs run them.
+pub(crate) const HOOK_ENV: &str = "BLOG_ON_NEW_POST";
+
+/// How long the hook may run for one post before it is killed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A new post with the feed it belongs to, as handed to hooks.
+pub(crate) fn post_json(store: &BlogData, post: &FeedItem) -> serde_json::Value {
+    let feed = store.feeds().iter().find(|(id, _)| *id == post.feed);
+    json!({
+        "title": post.title,
+        "date": post.date,
+        "link": post.link,
+        "raw_id": post.raw_id,
+        "summary": post.summary,
+        "feed": post.feed,
+        "feed_url": feed.map(|(_, f)| f.url.as_str()).unwrap_or_default(),
+        "feed_title": feed.map(|(_, f)| f.title.as_str()).unwrap_or_default(),
+    })
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Wait for `child` to exit, killing it if it runs longer than `timeout`.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> anyhow::Result<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("timed out after {}s", timeout.as_secs_f32());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Run `command` for one post.
+fn run_hook(command: &str, post: &serde_json::Value) -> anyhow::Result<()> {
+    let field = |name: &str| post[name].as_str().unwrap_or_default().to_string();
+    let mut child = shell(command)
+        .env("BLOG_TITLE", field("title"))
+        .env("BLOG_LINK", field("link"))
+        .env("BLOG_DATE", field("date"))
+        .env("BLOG_FEED_URL", field("feed_url"))
+        .env("BLOG_FEED_TITLE", field("feed_title"))
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Written aside, so a hook that never reads a large post can't block
+        // us past the timeout; a broken pipe is not an error either
+        let input = post.to_string();
+        std::thread::spawn(move || {
+            let _ = writeln!(stdin, "{input}");
+        });
+    }
+    let status = wait_timeout(&mut child, HOOK_TIMEOUT)?;
+    anyhow::ensure!(status.success(), "exited with {status}");
+    Ok(())
+}
+
+/// Posts per webhook request, keeping messages within chat services' limits.
+const WEBHOOK_BATCH: usize = 10;
+
+/// How a batch of posts is sent to the webhook.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WebhookFormat {
+    /// `{"text", "content", "posts"}`, understood by Slack and Discord
+    Json,
+    /// The message as plain text, e.g. for ntfy
+    Text,
+}
+
+impl std::str::FromStr for WebhookFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "text" => Ok(Self::Text),
+            _ => anyhow::bail!("expected json or text"),
+        }
+    }
+}
+
+/// A readable message listing `posts`.
+fn format_message(posts: &[serde_json::Value]) -> String {
+    let mut message = match posts.len() {
+        1 => "1 new post".to_string(),
+        n => format!("{n} new posts"),
+    };
+    for post in posts {
+        let text = |name: &str| post[name].as_str().unwrap_or_default();
+        message.push_str(&format!("\n• {}", text("title")));
+        if !text("feed_title").is_empty() {
+            message.push_str(&format!(" ({})", text("feed_title")));
+        }
+        if !text("link").is_empty() {
+            message.push_str(&format!(" {}", text("link")));
+        }
+    }
+    message
+}
+
+fn send_webhook(
+    agent: &ureq::Agent,
+    url: &str,
+    format: WebhookFormat,
+    posts: &[serde_json::Value],
+) -> anyhow::Result<()> {
+    let message = format_message(posts);
+    match format {
+        WebhookFormat::Json => {
+            let body = json!({
+                "text": message,
+                "content": message,
+                "posts": posts,
+            });
+            agent
+                .post(url)
+                .header("Content-Type", "application/json")
+                .send(body.to_string())?;
+        }
+        WebhookFormat::Text => {
+            agent
+                .post(url)
+                .header("Content-Type", "text/plain; charset=utf-8")
+                .send(message)?;
+        }
+    }
+    Ok(())
+}
+
+/// POST `posts` to the `webhook_url`, if configured.
+fn notify_webhook(store: &BlogData, posts: &[serde_json::Value]) {
+    let Some(url) = crate::data::get_config_value(store, "webhook_url") else {
+        return;
+    };
+    let format = match crate::data::get_config_parsed(store, "webhook_format") {
+        Ok(format) => format.unwrap_or(WebhookFormat::Json),
+        Err(e) => {
+            eprintln!("warning: {e}");
+            return;
+        }
+    };
+    let http = crate::utils::http::HttpOptions::from_config(store).unwrap_or_default();
+    let agent = crate::utils::http::http_client(&http);
+    for batch in posts.chunks(WEBHOOK_BATCH) {
+        if let Err(e) = send_webhook(&agent, &url, format, batch) {
+            eprintln!("warning: failed to send new posts to webhook: {e}");
+            return;
+        }
+    }
+}
+
+/// Run the new post hook and send the webhook, if configured, for `posts`.
+pub(crate) fn notify_new_posts(store: &BlogData, posts: &[FeedItem]) {
+    if posts.is_empty() {
+        return;
+    }
+    let json: Vec<serde_json::Value> = posts.iter().map(|p| post_json(store, p)).collect();
+    if let Ok(command) = std::env::var(HOOK_ENV)
+        && !command.is_empty()
+    {
+        for post in &json {
+            if let Err(e) = run_hook(&command, post) {
+                let link = post["link"].as_str().unwrap_or_default();
+                eprintln!("warning: {HOOK_ENV} hook failed for {link}: {e}");
+            }
+        }
+    }
+    notify_webhook(store, &json);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_message() {
+        let posts = [
+            json!({"title": "Hello", "feed_title": "Blog", "link": "https://blog.example/hello"}),
+            json!({"title": "Untitled feed", "feed_title": "", "link": ""}),
+        ];
+
+        assert_eq!(
+            format_message(&posts),
+            "2 new posts\n• Hello (Blog) https://blog.example/hello\n• Untitled feed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_timeout_kills_slow_hook() {
+        let mut child = shell("sleep 5").spawn().unwrap();
+        let started = Instant::now();
+
+        let err = wait_timeout(&mut child, Duration::from_millis(100)).unwrap_err();
+
+        assert!(err.to_string().starts_with("timed out"), "got: {err}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_format_message_single_post() {
+        let posts = [json!({"title": "Hello", "link": "https://blog.example/hello"})];
+
+        assert_eq!(
+            format_message(&posts),
+            "1 new post\n• Hello https://blog.example/hello"
+        );
+    }
+}
@@ -1,34 +1,131 @@
+use std::time::{Duration, Instant};
+
 use chrono::{DateTime, Utc};
 use indicatif::ProgressBar;
 use rayon::prelude::*;
 
+use crate::commands::add::new_source;
 use crate::data::Transaction;
 use crate::data::schema::{FeedItem, FeedSource, ReadMark};
-use crate::feed::FeedMeta;
+use crate::feed::blocklist::Blocklist;
+use crate::feed::links::LinkCleaner;
+use crate::feed::{FeedMeta, Fetched, greader};
+use crate::utils::html::{title_text, to_text};
+use crate::utils::http::{HostLimiter, HttpOptions, RetryPolicy, host_of};
+
+pub(crate) type FetchResult = (FeedSource, Result<Fetched, String>);
 
-pub(crate) type FetchResult = (FeedSource, Result<(FeedMeta, Vec<FeedItem>), String>);
+/// A feed that couldn't be fetched.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FetchFailure {
+    pub url: String,
+    pub error: String,
+    /// When fetches of the feed started failing, if this isn't the first
+    pub failing_since: Option<DateTime<Utc>>,
+}
 
 const INITIAL_RECENT_DAYS: i64 = 60;
 const INITIAL_UNREAD_CAP: usize = 5;
 
-const FETCH_THREADS: usize = 48;
+/// Concurrent requests when neither `--jobs` nor the `fetch_jobs` config key
+/// is set. High enough to keep syncs quick, low enough not to flood slow links.
+const DEFAULT_FETCH_JOBS: usize = 16;
+
+/// Concurrent requests to a single host, so that many subscriptions on one
+/// platform (e.g. substack.com) don't get us rate limited.
+const DEFAULT_JOBS_PER_HOST: usize = 4;
+
+/// Tuning for how feeds are downloaded during a sync.
+#[derive(Clone, Debug)]
+pub(crate) struct FetchOptions {
+    /// Maximum number of requests in flight
+    pub jobs: usize,
+    /// Maximum number of requests in flight to the same host
+    pub jobs_per_host: usize,
+    pub retry: RetryPolicy,
+    pub http: HttpOptions,
+    pub links: LinkCleaner,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            jobs: DEFAULT_FETCH_JOBS,
+            jobs_per_host: DEFAULT_JOBS_PER_HOST,
+            retry: RetryPolicy::default(),
+            http: HttpOptions::default(),
+            links: LinkCleaner::default(),
+        }
+    }
+}
+
+/// Order sources round-robin by host, so feeds on a busy host are spread out
+/// instead of all queueing for the same per-host slots at once.
+fn interleave_by_host(sources: &[FeedSource]) -> Vec<&FeedSource> {
+    let mut hosts: Vec<(String, Vec<&FeedSource>)> = Vec::new();
+    for source in sources {
+        let host = host_of(&source.url);
+        match hosts.iter_mut().find(|(h, _)| *h == host) {
+            Some((_, group)) => group.push(source),
+            None => hosts.push((host, vec![source])),
+        }
+    }
+    let longest = hosts.iter().map(|(_, g)| g.len()).max().unwrap_or(0);
+    (0..longest)
+        .flat_map(|i| hosts.iter().filter_map(move |(_, g)| g.get(i).copied()))
+        .collect()
+}
+
+/// The client to fetch `source` with: `shared`, unless the feed has a timeout
+/// of its own.
+fn feed_client(shared: &ureq::Agent, source: &FeedSource, http: &HttpOptions) -> ureq::Agent {
+    match source.timeout {
+        Some(secs) => {
+            let mut http = http.clone();
+            http.timeouts.total = Duration::from_secs(secs);
+            crate::utils::http::http_client(&http)
+        }
+        None => shared.clone(),
+    }
+}
 
 /// Fetch all feeds in parallel.
-pub(crate) fn fetch_feeds(sources: &[FeedSource], pb: &ProgressBar) -> Vec<FetchResult> {
-    let client = crate::utils::http::http_client();
+pub(crate) fn fetch_feeds(
+    sources: &[FeedSource],
+    pb: &ProgressBar,
+    opts: &FetchOptions,
+) -> Vec<FetchResult> {
+    let client = crate::utils::http::http_client(&opts.http);
+    let limiter = HostLimiter::new(opts.jobs_per_host);
     pb.set_length(sources.len() as u64);
 
     let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(FETCH_THREADS)
+        .num_threads(opts.jobs)
         .build()
         .expect("failed to build fetch thread pool");
 
     pool.install(|| {
-        sources
-            .par_iter()
+        interleave_by_host(sources)
+            .into_par_iter()
             .map(|source| {
+                let _permit = limiter.acquire(&source.url);
                 pb.set_message(source.url.clone());
-                let result = crate::feed::fetch(&client, &source.url).map_err(|e| e.to_string());
+                let client = feed_client(&client, source, &opts.http);
+                let started = Instant::now();
+                let result =
+                    crate::feed::fetch(&client, &source.url, opts).map_err(|e| e.to_string());
+                let ms = started.elapsed().as_millis();
+                match &result {
+                    Ok(fetched) => tracing::info!(
+                        url = %source.url,
+                        ms,
+                        posts = fetched.items.len(),
+                        "fetched feed"
+                    ),
+                    Err(e) => {
+                        tracing::info!(url = %source.url, ms, error = %e, "failed to fetch feed")
+                    }
+                }
                 pb.inc(1);
                 (source.clone(), result)
             })
@@ -87,8 +184,8 @@ mod tests {
             title: id.to_string(),
             date: age_days.map(|d| now - Duration::days(d)),
             feed: "test-feed".to_string(),
-            link: String::new(),
             raw_id: id.to_string(),
+            ..Default::default()
         }
     }
 
@@ -130,9 +227,85 @@ mod tests {
             "ages={ages:?}: expected {expected_unread} unread, got {unread_count} (read_ids={read_ids:?})"
         );
     }
+
+    #[rstest]
+    #[case::same("Title", "<p>Text</p>", "<p>Body</p>", false)]
+    #[case::markup_only("Title", "<div>Text</div>", "<p>Body</p>", false)]
+    #[case::title_markup("<em>Title</em>", "<p>Text</p>", "<p>Body</p>", false)]
+    #[case::title("Title (corrected)", "<p>Text</p>", "<p>Body</p>", true)]
+    #[case::text("Title", "<p>Text, corrected</p>", "<p>Body</p>", true)]
+    #[case::content("Title", "<p>Text</p>", "<p>Body, corrected</p>", true)]
+    #[case::content_markup_only("Title", "<p>Text</p>", "<div>Body</div>", false)]
+    fn test_is_edited(
+        #[case] title: &str,
+        #[case] summary: &str,
+        #[case] content: &str,
+        #[case] expected: bool,
+    ) {
+        let now = Utc::now();
+        let mut old = make_item("a", None, now);
+        old.title = "Title".to_string();
+        old.summary = "<p>Text</p>".to_string();
+        old.content = "<p>Body</p>".to_string();
+        let mut new = old.clone();
+        new.title = title.to_string();
+        new.summary = summary.to_string();
+        new.content = content.to_string();
+        assert_eq!(is_edited(&old, &new), expected);
+    }
+
+    #[test]
+    fn test_interleave_by_host() {
+        let sources: Vec<FeedSource> = [
+            "https://a.substack.com/feed",
+            "https://a.substack.com/feed2",
+            "https://a.substack.com/feed3",
+            "https://blog.example/rss",
+            "https://other.example/atom.xml",
+        ]
+        .iter()
+        .map(|url| FeedSource {
+            url: url.to_string(),
+            is_fetched: true,
+            ..Default::default()
+        })
+        .collect();
+
+        let urls: Vec<&str> = interleave_by_host(&sources)
+            .iter()
+            .map(|s| s.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://a.substack.com/feed",
+                "https://blog.example/rss",
+                "https://other.example/atom.xml",
+                "https://a.substack.com/feed2",
+                "https://a.substack.com/feed3",
+            ]
+        );
+    }
+}
+
+/// Whether `new` changes the title or text of `old`. Markup is ignored, so a
+/// feed reformatting its HTML doesn't count as an edit, and neither does a
+/// title stored before markup was stripped from titles.
+fn is_edited(old: &FeedItem, new: &FeedItem) -> bool {
+    (old.title != new.title && title_text(&old.title) != title_text(&new.title))
+        || (old.summary != new.summary && to_text(&old.summary) != to_text(&new.summary))
+        || (old.content != new.content && to_text(&old.content) != to_text(&new.content))
 }
 
-fn apply_feed(tx: &mut Transaction, mut source: FeedSource, meta: FeedMeta, items: Vec<FeedItem>) {
+/// Store a fetched feed and its posts. Returns the posts that weren't stored
+/// before and aren't read, e.g. because they were marked read on a feed's
+/// first fetch.
+fn apply_feed(
+    tx: &mut Transaction,
+    mut source: FeedSource,
+    meta: FeedMeta,
+    items: Vec<FeedItem>,
+) -> Vec<FeedItem> {
     let feed_id = tx.feeds.id_of(&source);
     let now = Utc::now();
 
@@ -145,19 +318,65 @@ fn apply_feed(tx: &mut Transaction, mut source: FeedSource, meta: FeedMeta, item
         }
     }
 
+    let mut new_posts = Vec::new();
     for mut item in items {
         item.feed = feed_id.clone();
+        match tx.posts.get(&item.raw_id) {
+            Some(old) => {
+                item.edits = old.edits + u32::from(is_edited(old, &item));
+                item.first_seen_at = old.first_seen_at;
+            }
+            None => {
+                item.first_seen_at = Some(now);
+                if !tx.reads.contains_key(&item.raw_id) {
+                    new_posts.push(item.clone());
+                }
+            }
+        }
         tx.posts.upsert(item);
     }
 
     source.is_fetched = true;
+    source.fetch_error.clear();
+    source.failing_since = None;
     source.title = meta.title;
     source.site_url = meta.site_url;
     source.description = meta.description;
+    source.hub = meta.hub;
+    source.topic = meta.topic;
     tx.feeds.upsert(source);
+    new_posts
+}
+
+/// Re-key `source` under `new_url`, keeping its posts attached. If `new_url`
+/// is already subscribed, that subscription and its settings are kept and
+/// `source` becomes it, taking only the posts over.
+fn move_feed(tx: &mut Transaction, source: &mut FeedSource, new_url: &str) {
+    let Some(old_id) = tx.feeds.delete(&source.url) else {
+        return;
+    };
+    match tx.feeds.get(new_url) {
+        Some(existing) => *source = existing.clone(),
+        None => source.url = new_url.to_string(),
+    }
+    let new_id = tx.feeds.id_of(source);
+    let moved: Vec<FeedItem> = tx
+        .posts
+        .iter()
+        .filter(|(_, p)| p.feed == old_id)
+        .map(|(_, p)| FeedItem {
+            feed: new_id.clone(),
+            ..p.clone()
+        })
+        .collect();
+    for item in moved {
+        tx.posts.upsert(item);
+    }
 }
 
-/// Apply fetched feed results to the store.
+/// Apply fetched feed results to the store. Returns the new posts (see
+/// `apply_feed`) and the feeds that couldn't be fetched, whose error is
+/// stored with them. Posts whose links are on `blocklist` are dropped.
 ///
 /// If `ingest_filter` is set and jq fails, the entire sync is aborted rather
 /// than skipping the feed. This is intentional: a broken filter would silently
@@ -167,15 +386,99 @@ pub(crate) fn apply_fetched(
     results: Vec<FetchResult>,
     pb: &ProgressBar,
     ingest_filter: Option<&str>,
-) -> anyhow::Result<()> {
-    for (source, result) in results {
+    blocklist: &Blocklist,
+) -> anyhow::Result<(Vec<FeedItem>, Vec<FetchFailure>)> {
+    let mut new_posts = Vec::new();
+    let mut failures = Vec::new();
+    let now = Utc::now();
+    for (mut source, result) in results {
         match result {
-            Ok((meta, items)) => {
+            Ok(fetched) => {
+                if let Some(new_url) = fetched.moved_to {
+                    pb.suspend(|| eprintln!("Feed moved: {} -> {}", source.url, new_url));
+                    move_feed(tx, &mut source, &new_url);
+                }
+                let mut items = fetched.items;
+                let parsed = items.len();
+                items.retain(|item| !blocklist.blocks(&item.link));
+                if items.len() < parsed {
+                    tracing::debug!(url = %source.url, blocked = parsed - items.len(), "dropped blocked posts");
+                }
                 let items = crate::utils::jq::map_through_jq(items, ingest_filter)?;
-                apply_feed(tx, source, meta, items);
+                let url = source.url.clone();
+                let posts = items.len();
+                let new = apply_feed(tx, source, fetched.meta, items);
+                tracing::debug!(url = %url, posts, new = new.len(), "stored posts");
+                new_posts.extend(new);
+            }
+            Err(e) => {
+                let mut failing_since = None;
+                if let Some(mut stored) = tx.feeds.get(&source.url).cloned() {
+                    failing_since = stored.failing_since;
+                    stored.failing_since.get_or_insert(now);
+                    stored.fetch_error = e.clone();
+                    tx.feeds.upsert(stored);
+                }
+                failures.push(FetchFailure {
+                    url: source.url,
+                    error: e,
+                    failing_since,
+                });
             }
-            Err(e) => pb.suspend(|| eprintln!("Error fetching {}: {}", source.url, e)),
         }
     }
-    Ok(())
+    Ok((new_posts, failures))
+}
+
+/// Apply subscriptions and items from a Google Reader API server.
+///
+/// Subscriptions missing locally are added. Posts read on the server are
+/// marked read here. Returns the IDs of posts read here but not on the
+/// server, so the caller can push them, and the new posts.
+pub(crate) fn apply_greader(
+    tx: &mut Transaction,
+    subscriptions: &[greader::Subscription],
+    items: Vec<greader::Item>,
+    pb: &ProgressBar,
+    ingest_filter: Option<&str>,
+    blocklist: &Blocklist,
+) -> anyhow::Result<(Vec<String>, Vec<FeedItem>)> {
+    let mut grouped = greader::items_by_feed(subscriptions, items);
+    let now = Utc::now();
+    let mut read_here = Vec::new();
+    let mut results = Vec::new();
+
+    for sub in subscriptions {
+        let items = grouped.remove(&sub.url).unwrap_or_default();
+        for item in &items {
+            let locally_read = tx.reads.contains_key(&item.post.raw_id);
+            if item.read && !locally_read {
+                tx.reads.upsert(ReadMark {
+                    post_id: item.post.raw_id.clone(),
+                    read_at: now,
+                });
+            } else if !item.read && locally_read {
+                read_here.push(item.post.raw_id.clone());
+            }
+        }
+        let new = FeedSource {
+            // The server's read state replaces the first-fetch heuristics
+            is_fetched: true,
+            ..new_source(&sub.url)
+        };
+        let source = tx.feeds.get(&new.url).cloned().unwrap_or(new);
+        if source.is_on_hold(now) {
+            continue;
+        }
+        let fetched = Fetched {
+            meta: sub.meta.clone(),
+            items: items.into_iter().map(|i| i.post).collect(),
+            moved_to: None,
+        };
+        results.push((source, Ok(fetched)));
+    }
+
+    // Posts come from the server, so there's nothing to fail here
+    let (new_posts, _) = apply_fetched(tx, results, pb, ingest_filter, blocklist)?;
+    Ok((read_here, new_posts))
 }
@@ -0,0 +1,254 @@
+//! Limits on how many posts are kept, so the store doesn't grow without bound.
+//!
+//! A policy is set globally with the `retention` config key and can be
+//! overridden per feed with `blog feed retain`. It is enforced in the same
+//! transaction that stores fetched posts, so posts that are still in a feed
+//! but outside the policy never reach the store.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::data::Transaction;
+use crate::data::schema::FeedItem;
+use crate::feed::pull::FetchResult;
+
+/// Which posts of a feed to keep.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Retention {
+    /// Drop posts older than this many days
+    pub max_age_days: Option<u32>,
+    /// Drop all but this many newest posts
+    pub max_posts: Option<usize>,
+}
+
+impl FromStr for Retention {
+    type Err = anyhow::Error;
+
+    /// Parse `90d`, `200`, `90d,200` or `forever`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut retention = Retention::default();
+        if s.trim() == "forever" {
+            return Ok(retention);
+        }
+        for part in s.split(',').map(str::trim) {
+            if let Some(days) = part.strip_suffix('d') {
+                retention.max_age_days = Some(
+                    days.parse()
+                        .with_context(|| format!("invalid number of days: {days:?}"))?,
+                );
+            } else if let Ok(posts) = part.parse() {
+                retention.max_posts = Some(posts);
+            } else {
+                bail!("expected e.g. 90d (days), 200 (posts), 90d,200 or forever");
+            }
+        }
+        Ok(retention)
+    }
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.max_age_days, self.max_posts) {
+            (None, None) => write!(f, "forever"),
+            (Some(days), None) => write!(f, "{days}d"),
+            (None, Some(posts)) => write!(f, "{posts}"),
+            (Some(days), Some(posts)) => write!(f, "{days}d,{posts}"),
+        }
+    }
+}
+
+impl Retention {
+    /// Raw IDs of the posts in `posts` (all from one feed) to drop.
+    fn expired<'a>(&self, posts: &mut [&'a FeedItem], now: DateTime<Utc>) -> Vec<&'a str> {
+        posts.sort_by_key(|p| std::cmp::Reverse(p.date));
+        // A cutoff before the earliest representable date keeps every post
+        let cutoff = self
+            .max_age_days
+            .and_then(|days| now.checked_sub_signed(Duration::days(i64::from(days))));
+        posts
+            .iter()
+            .enumerate()
+            .filter(|(i, post)| {
+                self.max_posts.is_some_and(|max| *i >= max)
+                    // Undated posts can't be aged and only count towards max_posts
+                    || cutoff.is_some_and(|c| post.date.is_some_and(|d| d < c))
+            })
+            .map(|(_, post)| post.raw_id.as_str())
+            .collect()
+    }
+}
+
+/// Each feed's policy, falling back to `global` for feeds without their own.
+/// Feeds kept forever are left out.
+fn policies(
+    tx: &Transaction,
+    global: Option<Retention>,
+) -> anyhow::Result<HashMap<String, Retention>> {
+    let mut policies = HashMap::new();
+    for (id, feed) in tx.feeds.iter() {
+        let policy = if feed.retention.is_empty() {
+            global
+        } else {
+            Some(
+                feed.retention
+                    .parse()
+                    .with_context(|| format!("Invalid retention for {}", feed.url))?,
+            )
+        };
+        if let Some(policy) = policy {
+            policies.insert(id.to_string(), policy);
+        }
+    }
+    Ok(policies)
+}
+
+/// Stored posts of the feeds in `policies`, by feed. Posts queued to read
+/// later or snoozed are left out, so no policy ever drops them.
+fn posts_by_feed<'a>(
+    tx: &'a Transaction,
+    policies: &HashMap<String, Retention>,
+) -> HashMap<&'a str, Vec<&'a FeedItem>> {
+    let kept: HashSet<&str> = tx
+        .later
+        .iter()
+        .map(|(_, e)| e.post_id.as_str())
+        .chain(tx.snoozes.iter().map(|(_, e)| e.post_id.as_str()))
+        .collect();
+    let mut by_feed: HashMap<&str, Vec<&FeedItem>> = HashMap::new();
+    for (_, post) in tx.posts.iter() {
+        if policies.contains_key(&post.feed) && !kept.contains(post.raw_id.as_str()) {
+            by_feed.entry(post.feed.as_str()).or_default().push(post);
+        }
+    }
+    by_feed
+}
+
+/// Drop the fetched posts that aren't stored yet and that
+/// [`apply_retention`] would delete right after storing them. Otherwise posts
+/// still in a feed but past its policy would be stored and deleted again on
+/// every sync.
+pub(crate) fn skip_expired(
+    tx: &Transaction,
+    results: &mut [FetchResult],
+    global: Option<Retention>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let policies = policies(tx, global)?;
+    if policies.is_empty() {
+        return Ok(());
+    }
+    let by_feed = posts_by_feed(tx, &policies);
+    for (source, result) in results.iter_mut() {
+        let Ok(fetched) = result else {
+            continue;
+        };
+        let feed_id = tx.feeds.id_of(source);
+        let Some(policy) = policies.get(&feed_id) else {
+            continue;
+        };
+        let mut posts = by_feed.get(feed_id.as_str()).cloned().unwrap_or_default();
+        posts.extend(
+            fetched
+                .items
+                .iter()
+                .filter(|item| !tx.posts.contains_key(&item.raw_id)),
+        );
+        let expired: HashSet<String> = policy
+            .expired(&mut posts, now)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        fetched
+            .items
+            .retain(|item| !expired.contains(&item.raw_id) || tx.posts.contains_key(&item.raw_id));
+    }
+    Ok(())
+}
+
+/// Delete posts that fall outside each feed's retention policy, falling back
+/// to `global` for feeds without their own. Posts queued to read later or
+/// snoozed are kept. Returns how many were deleted.
+pub(crate) fn apply_retention(
+    tx: &mut Transaction,
+    global: Option<Retention>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<usize> {
+    let policies = policies(tx, global)?;
+    if policies.is_empty() {
+        return Ok(0);
+    }
+
+    let expired: HashSet<String> = posts_by_feed(tx, &policies)
+        .iter_mut()
+        .flat_map(|(feed, posts)| policies[*feed].expired(posts, now))
+        .map(str::to_string)
+        .collect();
+
+    if !expired.is_empty() {
+        tx.delete_posts_where(|p| expired.contains(&p.raw_id));
+    }
+    Ok(expired.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::days("90d", Some(90), None)]
+    #[case::posts("200", None, Some(200))]
+    #[case::both("90d,200", Some(90), Some(200))]
+    #[case::forever("forever", None, None)]
+    fn test_parse(#[case] input: &str, #[case] days: Option<u32>, #[case] posts: Option<usize>) {
+        let retention: Retention = input.parse().unwrap();
+        assert_eq!(retention.max_age_days, days);
+        assert_eq!(retention.max_posts, posts);
+        assert_eq!(retention.to_string(), input);
+    }
+
+    #[rstest]
+    #[case::unit("3 months")]
+    #[case::negative("-5d")]
+    #[case::empty("")]
+    fn test_parse_invalid(#[case] input: &str) {
+        assert!(input.parse::<Retention>().is_err());
+    }
+
+    // (ages_in_days, policy, expected_expired)
+    #[rstest]
+    #[case::by_age(vec![Some(1), Some(50), Some(100)], "30d", vec!["2", "3"])]
+    #[case::by_count(vec![Some(3), Some(1), Some(2)], "2", vec!["1"])]
+    #[case::undated_kept_by_age(vec![None, Some(100)], "30d", vec!["2"])]
+    #[case::undated_dropped_first_by_count(vec![None, Some(100)], "1", vec!["1"])]
+    #[case::both(vec![Some(1), Some(2), Some(100)], "30d,1", vec!["2", "3"])]
+    #[case::forever(vec![Some(1000)], "forever", vec![])]
+    #[case::age_beyond_dates(vec![Some(1000)], "100000000d", vec![])]
+    fn test_expired(
+        #[case] ages: Vec<Option<i64>>,
+        #[case] policy: &str,
+        #[case] expected: Vec<&str>,
+    ) {
+        let now = Utc::now();
+        let posts: Vec<FeedItem> = ages
+            .iter()
+            .enumerate()
+            .map(|(i, age)| FeedItem {
+                raw_id: (i + 1).to_string(),
+                date: age.map(|d| now - Duration::days(d)),
+                ..Default::default()
+            })
+            .collect();
+        let mut refs: Vec<&FeedItem> = posts.iter().collect();
+        let policy: Retention = policy.parse().unwrap();
+
+        let mut expired = policy.expired(&mut refs, now);
+        expired.sort();
+
+        assert_eq!(expired, expected);
+    }
+}
@@ -1,73 +1,180 @@
 use std::io::{BufReader, Read};
 
 use anyhow::Result;
-use chrono::{DateTime, FixedOffset};
+use quick_xml::XmlVersion;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::reader::NsReader;
 use rss::Channel;
 use url::Url;
 
 use super::FeedMeta;
+use super::dates::parse_date;
+use super::links::LinkCleaner;
 use crate::data::schema::FeedItem;
+use crate::utils::html::title_text;
 
-/// Parse an RFC 2822 date, falling back to stripping the colon from timezone
-/// offsets like `-07:00` → `-0700` which some feeds produce.
-fn parse_rfc2822_lenient(s: &str) -> Option<DateTime<FixedOffset>> {
-    DateTime::parse_from_rfc2822(s)
-        .or_else(|_| DateTime::parse_from_rfc2822(&strip_tz_colon(s)))
-        .ok()
+fn normalize_url(raw: &str) -> String {
+    match Url::parse(raw) {
+        Ok(url) => url.to_string(),
+        Err(_) => raw.to_string(),
+    }
 }
 
-fn strip_tz_colon(s: &str) -> String {
-    let trimmed = s.trim_end();
-    // Match a trailing +HH:MM or -HH:MM and remove the colon
-    if trimmed.len() >= 6 {
-        let (head, tail) = trimmed.split_at(trimmed.len() - 6);
-        let bytes = tail.as_bytes();
-        if (bytes[0] == b'+' || bytes[0] == b'-')
-            && bytes[1].is_ascii_digit()
-            && bytes[2].is_ascii_digit()
-            && bytes[3] == b':'
-            && bytes[4].is_ascii_digit()
-            && bytes[5].is_ascii_digit()
-        {
-            return format!("{}{}{}", head, &tail[..3], &tail[4..6]);
-        }
+/// `href` of the first `<atom:link>` on the channel with the given `rel`.
+fn atom_link(channel: &Channel, rel: &str) -> String {
+    channel
+        .atom_ext()
+        .and_then(|ext| ext.links().iter().find(|l| l.rel() == rel))
+        .map(|l| l.href().to_string())
+        .unwrap_or_default()
+}
+
+/// The item's author: `<author>`, which is usually `email (Name)`, or else
+/// Dublin Core `<dc:creator>`.
+fn item_author(item: &rss::Item) -> String {
+    if let Some(author) = item.author() {
+        let author = author.trim();
+        return match (author.find('('), author.strip_suffix(')')) {
+            (Some(open), Some(rest)) => rest[open + 1..].trim().to_string(),
+            _ => author.to_string(),
+        };
     }
-    s.to_string()
+    item.dublin_core_ext()
+        .and_then(|dc| dc.creators().first())
+        .map(|c| c.trim().to_string())
+        .unwrap_or_default()
 }
 
-fn normalize_url(raw: &str) -> String {
-    match Url::parse(raw) {
-        Ok(url) => url.to_string(),
-        Err(_) => raw.to_string(),
+/// Namespace of Media RSS elements such as `<media:thumbnail>`.
+const MEDIA_NAMESPACE: &[u8] = b"http://search.yahoo.com/mrss/";
+
+/// The `url` of a Media RSS element if it is an image: a thumbnail, or
+/// content whose `medium` or `type` says so.
+fn media_image(element: &BytesStart, decoder: quick_xml::encoding::Decoder) -> Option<String> {
+    let attr = |name: &[u8]| {
+        element
+            .attributes()
+            .flatten()
+            .find(|a| a.key.as_ref() == name)
+            .and_then(|a| {
+                a.decoded_and_normalized_value(XmlVersion::Implicit1_0, decoder)
+                    .ok()
+            })
+            .map(|v| v.trim().to_string())
+    };
+    let is_image = match element.local_name().as_ref() {
+        b"thumbnail" => true,
+        b"content" => {
+            attr(b"medium").as_deref() == Some("image")
+                || attr(b"type").is_some_and(|t| t.starts_with("image/"))
+        }
+        _ => false,
+    };
+    attr(b"url").filter(|url| is_image && !url.is_empty())
+}
+
+/// The Media RSS images of each `<item>` in `xml`, in order, including
+/// those in a `<media:group>`. The rss crate skips elements written as
+/// `<media:thumbnail url="..."/>`, which is how they usually are, so they
+/// are read here.
+fn media_images(xml: &[u8]) -> Vec<Vec<String>> {
+    let mut reader = NsReader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut items: Vec<Vec<String>> = Vec::new();
+    loop {
+        buf.clear();
+        let (ns, event) = match reader.read_resolved_event_into(&mut buf) {
+            Ok((_, Event::Eof)) | Err(_) => break,
+            Ok(resolved) => resolved,
+        };
+        let is_media = matches!(ns, ResolveResult::Bound(Namespace(MEDIA_NAMESPACE)));
+        let is_item = matches!(ns, ResolveResult::Unbound);
+        match event {
+            Event::Start(e) if is_item && e.local_name().as_ref() == b"item" => {
+                items.push(Vec::new());
+            }
+            Event::Start(e) | Event::Empty(e) if is_media => {
+                if let (Some(images), Some(url)) =
+                    (items.last_mut(), media_image(&e, reader.decoder()))
+                    && !images.contains(&url)
+                {
+                    images.push(url);
+                }
+            }
+            _ => {}
+        }
     }
+    items
 }
 
-pub fn parse<R: Read>(reader: R) -> Result<(FeedMeta, Vec<FeedItem>)> {
-    let channel = Channel::read_from(BufReader::new(reader))?;
+pub fn parse<R: Read>(mut reader: R, links: &LinkCleaner) -> Result<(FeedMeta, Vec<FeedItem>)> {
+    let mut xml = Vec::new();
+    reader.read_to_end(&mut xml)?;
+    let channel = Channel::read_from(BufReader::new(xml.as_slice()))?;
+    let mut images = media_images(&xml);
+    if images.len() != channel.items().len() {
+        // Items nested where the rss crate doesn't look would pair the
+        // images up with the wrong posts
+        images.clear();
+    }
 
     let meta = FeedMeta {
+        format: "RSS",
         title: channel.title().to_string(),
-        site_url: channel.link().to_string(),
+        site_url: links.clean(channel.link()),
         description: channel.description().to_string(),
+        hub: atom_link(&channel, "hub"),
+        topic: atom_link(&channel, "self"),
     };
+    let links = links.with_base(&meta.site_url);
 
     let items = channel
         .items()
         .iter()
-        .map(|item| FeedItem {
+        .enumerate()
+        .map(|(i, item)| FeedItem {
+            // Keyed by the link as published, so that cleaning links
+            // differently later doesn't turn stored posts into new ones
             raw_id: item
                 .guid()
                 .map(|g| g.value().to_string())
                 .or_else(|| item.link().map(normalize_url))
                 .or_else(|| item.title().map(|t| t.to_string()))
                 .unwrap_or_default(),
-            title: item.title().unwrap_or("untitled").to_string(),
-            date: item
-                .pub_date()
-                .and_then(parse_rfc2822_lenient)
-                .map(|d| d.to_utc()),
+            title: title_text(item.title().unwrap_or("untitled")),
+            date: item.pub_date().and_then(parse_date).or_else(|| {
+                item.dublin_core_ext()
+                    .and_then(|dc| dc.dates().first())
+                    .and_then(|d| parse_date(d))
+            }),
             feed: String::new(),
-            link: item.link().unwrap_or_default().to_string(),
+            link: links.clean(item.link().unwrap_or_default()),
+            // Feeds with `content:encoded` usually keep an excerpt in the
+            // description; it is the summary unless missing
+            summary: item
+                .description()
+                .or(item.content())
+                .unwrap_or_default()
+                .to_string(),
+            content: match (item.description(), item.content()) {
+                (Some(description), Some(content)) if content != description => content.to_string(),
+                _ => String::new(),
+            },
+            images: images
+                .get(i)
+                .map(|urls| urls.iter().map(|url| links.clean(url)).collect())
+                .unwrap_or_default(),
+            author: item_author(item),
+            categories: item
+                .categories()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+            comments: links.clean(item.comments().unwrap_or_default()),
+            first_seen_at: None,
+            lang: None,
+            edits: 0,
         })
         .collect();
 
@@ -77,6 +184,7 @@ pub fn parse<R: Read>(reader: R) -> Result<(FeedMeta, Vec<FeedItem>)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_multiple_items() {
@@ -95,7 +203,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items.len(), 2);
         assert_eq!(items[0].title, "First Post");
@@ -124,13 +232,30 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
         let date = items[0].date.unwrap();
 
         assert_eq!(date.format("%Y-%m-%d").to_string(), "2024-01-02");
         assert_eq!(date.format("%H:%M").to_string(), "04:00");
     }
 
+    #[test]
+    fn test_websub_links() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+          <channel>
+            <title>Test</title>
+            <atom:link rel="hub" href="https://hub.example/"/>
+            <atom:link rel="self" href="https://blog.example/rss.xml"/>
+          </channel>
+        </rss>"#;
+
+        let (meta, _) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(meta.hub, "https://hub.example/");
+        assert_eq!(meta.topic, "https://blog.example/rss.xml");
+    }
+
     #[test]
     fn test_missing_title() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -143,7 +268,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].title, "untitled");
     }
@@ -160,7 +285,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].date, None);
     }
@@ -174,7 +299,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert!(items.is_empty());
     }
@@ -192,7 +317,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].raw_id, "https://example.com/post/1");
     }
@@ -210,7 +335,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].raw_id, "https://example.com/post/1");
     }
@@ -228,11 +353,37 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].raw_id, "https://example.com/post/1");
     }
 
+    #[test]
+    fn test_id_from_link_is_not_cleaned() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Test</title>
+            <link>https://example.com/blog/</link>
+            <item>
+              <title>Tracked</title>
+              <link>https://example.com/post/1?utm_source=rss</link>
+            </item>
+            <item>
+              <title>Relative</title>
+              <link>post/2</link>
+            </item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].raw_id, "https://example.com/post/1?utm_source=rss");
+        assert_eq!(items[0].link, "https://example.com/post/1");
+        assert_eq!(items[1].raw_id, "post/2");
+        assert_eq!(items[1].link, "https://example.com/blog/post/2");
+    }
+
     #[test]
     fn test_id_falls_back_to_title_when_no_guid_or_link() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -245,7 +396,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].raw_id, "Post");
     }
@@ -265,7 +416,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items.len(), 2);
         assert_ne!(items[0].raw_id, items[1].raw_id);
@@ -284,7 +435,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert!(
             items[0].date.is_some(),
@@ -309,7 +460,7 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items.len(), 1);
         assert_eq!(items[0].title, "Bad Date Post");
@@ -331,7 +482,92 @@ mod tests {
 
         // This may error (invalid UTF-8 in XML) or parse with replacement —
         // the important thing is it doesn't panic.
-        let _ = parse(&xml_start[..]);
+        let _ = parse(&xml_start[..], &LinkCleaner::default());
+    }
+
+    #[test]
+    fn test_summary_from_description() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Test</title>
+            <item>
+              <title>Post</title>
+              <description>&lt;p&gt;A short summary&lt;/p&gt;</description>
+            </item>
+            <item>
+              <title>No Summary</title>
+            </item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].summary, "<p>A short summary</p>");
+        assert_eq!(items[1].summary, "");
+    }
+
+    #[test]
+    fn test_content_encoded() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+          <channel>
+            <title>Test</title>
+            <item>
+              <title>Excerpt and full text</title>
+              <description>An excerpt</description>
+              <content:encoded><![CDATA[<p>The full text</p>]]></content:encoded>
+            </item>
+            <item>
+              <title>Full text only</title>
+              <content:encoded><![CDATA[<p>Only here</p>]]></content:encoded>
+            </item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].summary, "An excerpt");
+        assert_eq!(items[0].content, "<p>The full text</p>");
+        assert_eq!(items[0].body(), "<p>The full text</p>");
+        assert_eq!(items[1].summary, "<p>Only here</p>");
+        assert_eq!(items[1].content, "");
+    }
+
+    #[test]
+    fn test_media_rss_images() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:m="http://search.yahoo.com/mrss/">
+          <channel>
+            <title>Test</title>
+            <link>https://blog.example/</link>
+            <item>
+              <title>Pictures</title>
+              <m:thumbnail url="/thumb.jpg"/>
+              <m:content url="https://blog.example/photo.jpg" medium="image"/>
+              <m:content url="https://blog.example/clip.mp4" type="video/mp4"/>
+              <m:group>
+                <m:content url="https://blog.example/big.png" type="image/png"></m:content>
+                <m:thumbnail url="https://blog.example/photo.jpg"/>
+              </m:group>
+            </item>
+            <item>
+              <title>No pictures</title>
+            </item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(
+            items[0].images,
+            [
+                "https://blog.example/thumb.jpg",
+                "https://blog.example/photo.jpg",
+                "https://blog.example/big.png",
+            ]
+        );
+        assert!(items[1].images.is_empty());
     }
 
     #[test]
@@ -348,8 +584,108 @@ mod tests {
           </channel>
         </rss>"#;
 
-        let (_, items) = parse(xml.as_bytes()).unwrap();
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
 
         assert_eq!(items[0].raw_id, "urn:uuid:123");
     }
+
+    #[test]
+    fn test_author() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <channel>
+            <title>Test</title>
+            <item><title>A</title><author>jane@example.com (Jane Doe)</author></item>
+            <item><title>B</title><author>bob@example.com</author></item>
+            <item><title>C</title><dc:creator>Carol</dc:creator></item>
+            <item><title>D</title></item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+        let authors: Vec<&str> = items.iter().map(|i| i.author.as_str()).collect();
+
+        assert_eq!(authors, ["Jane Doe", "bob@example.com", "Carol", ""]);
+    }
+
+    #[test]
+    fn test_categories_and_comments() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Test</title>
+            <item>
+              <title>A</title>
+              <category>security</category>
+              <category domain="https://example.com/tags">Rust</category>
+              <comments>https://example.com/a#comments</comments>
+            </item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].categories, ["security", "Rust"]);
+        assert_eq!(items[0].comments, "https://example.com/a#comments");
+    }
+
+    #[rstest]
+    #[case::channel_link("<link>https://blog.example/</link>", "https://blog.example/posts/a")]
+    #[case::relative_channel_link("<link>/blog/</link>", "https://feeds.example/blog/posts/a")]
+    #[case::feed_url("", "https://feeds.example/posts/a")]
+    fn test_relative_links_are_resolved(#[case] channel_link: &str, #[case] expected: &str) {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0">
+              <channel>
+                <title>Test</title>
+                {channel_link}
+                <item><title>A</title><link>posts/a</link></item>
+              </channel>
+            </rss>"#
+        );
+
+        let links = LinkCleaner::default().with_base("https://feeds.example/rss.xml");
+        let (_, items) = parse(xml.as_bytes(), &links).unwrap();
+
+        assert_eq!(items[0].link, expected);
+        assert_eq!(items[0].raw_id, "posts/a");
+    }
+
+    #[test]
+    fn test_title_markup_and_entities() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0">
+          <channel>
+            <title>Test</title>
+            <item><title><![CDATA[Why <em>Rust</em>&#8217;s Vec<T> &amp; friends]]></title></item>
+            <item><title>It&amp;#8217;s &lt;b&gt;bold&lt;/b&gt;</title></item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+
+        assert_eq!(items[0].title, "Why Rust\u{2019}s Vec<T> & friends");
+        assert_eq!(items[1].title, "It\u{2019}s bold");
+    }
+
+    #[test]
+    fn test_lenient_and_dublin_core_dates() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <channel>
+            <title>Test</title>
+            <item><title>A</title><pubDate>2024-01-05T10:00:00Z</pubDate></item>
+            <item><title>B</title><dc:date>2024-01-06T10:00:00+00:00</dc:date></item>
+          </channel>
+        </rss>"#;
+
+        let (_, items) = parse(xml.as_bytes(), &LinkCleaner::default()).unwrap();
+        let dates: Vec<String> = items.iter().map(|i| i.date.unwrap().to_rfc3339()).collect();
+
+        assert_eq!(
+            dates,
+            ["2024-01-05T10:00:00+00:00", "2024-01-06T10:00:00+00:00"]
+        );
+    }
 }
@@ -0,0 +1,87 @@
+//! Shortcuts accepted by `blog feed add` in place of a URL for aggregators
+//! whose feed URLs are hard to remember.
+
+use anyhow::bail;
+
+/// Feeds offered by hnrss.org, by the name used after `hn:`.
+const HN_FEEDS: &[&str] = &[
+    "frontpage",
+    "newest",
+    "best",
+    "ask",
+    "show",
+    "jobs",
+    "polls",
+    "active",
+    "classic",
+    "launches",
+    "newcomments",
+    "bestcomments",
+];
+
+/// Subreddit and user names: letters, digits, `_` and `-`.
+fn is_reddit_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Expand `r/<subreddit>`, `u/<user>` or `hn:<feed>` into a feed URL.
+/// Returns `None` when `input` isn't a shortcut.
+pub(crate) fn expand(input: &str) -> anyhow::Result<Option<String>> {
+    if let Some(name) = input.strip_prefix("hn:") {
+        if !HN_FEEDS.contains(&name) {
+            bail!(
+                "Unknown Hacker News feed: {name} (expected one of: {})",
+                HN_FEEDS.join(", ")
+            );
+        }
+        return Ok(Some(format!("https://hnrss.org/{name}")));
+    }
+
+    let input = input.strip_prefix('/').unwrap_or(input);
+    let (kind, name) = match input.split_once('/') {
+        Some(("r", name)) => ("r", name),
+        Some(("u" | "user", name)) => ("user", name),
+        _ => return Ok(None),
+    };
+    let name = name.trim_end_matches('/');
+    if !is_reddit_name(name) {
+        bail!("Invalid Reddit name: {name:?}");
+    }
+    Ok(Some(format!("https://www.reddit.com/{kind}/{name}/.rss")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::subreddit("r/rust", "https://www.reddit.com/r/rust/.rss")]
+    #[case::leading_slash("/r/rust/", "https://www.reddit.com/r/rust/.rss")]
+    #[case::user("u/spez", "https://www.reddit.com/user/spez/.rss")]
+    #[case::hn_frontpage("hn:frontpage", "https://hnrss.org/frontpage")]
+    #[case::hn_best("hn:best", "https://hnrss.org/best")]
+    fn test_expand(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expand(input).unwrap().as_deref(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::url("https://example.com/feed.xml")]
+    #[case::shorthand("@a")]
+    #[case::other_path("blog/feed")]
+    fn test_not_a_shortcut(#[case] input: &str) {
+        assert_eq!(expand(input).unwrap(), None);
+    }
+
+    #[rstest]
+    #[case::unknown_hn("hn:nope", "Unknown Hacker News feed")]
+    #[case::bad_subreddit("r/", "Invalid Reddit name")]
+    #[case::nested("r/rust/comments", "Invalid Reddit name")]
+    fn test_invalid_shortcut(#[case] input: &str, #[case] message: &str) {
+        let err = expand(input).unwrap_err().to_string();
+        assert!(err.contains(message), "got: {err}");
+    }
+}
@@ -0,0 +1,331 @@
+//! WebSub (formerly PubSubHubbub) support for `blog watch`.
+//!
+//! Feeds that advertise a hub get a subscription whose callback points at a
+//! small HTTP listener. The hub first confirms the subscription with a GET
+//! carrying a challenge, then POSTs the feed whenever it changes, signed with
+//! the secret we handed it.
+
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hmac::digest::block_api::EagerHash;
+use hmac::{Hmac, KeyInit, Mac};
+
+use crate::utils::server::{Request, read_request, write_response};
+
+/// Lease we ask hubs for; `blog watch` renews well before it runs out.
+pub(crate) const LEASE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest lease we take a hub's word for. Verification requests aren't
+/// signed, so anyone who knows the callback can claim one.
+const MAX_LEASE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Largest request body the callback listener accepts.
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+/// A subscription we asked a hub for.
+#[derive(Clone, Debug)]
+pub(crate) struct Subscription {
+    pub feed_url: String,
+    pub topic: String,
+    pub secret: String,
+    /// The secret before a renewal the hub hasn't confirmed yet, which its
+    /// pushes may still be signed with
+    pub old_secret: Option<String>,
+}
+
+/// Subscriptions by callback path (the feed's ID), shared with the listener.
+pub(crate) type Subscriptions = Arc<Mutex<HashMap<String, Subscription>>>;
+
+/// Something the callback listener received from a hub.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Event {
+    /// The hub confirmed our subscription for `lease`
+    Verified { feed_url: String, lease: Duration },
+    /// The hub pushed new feed content
+    Content { feed_url: String, body: Vec<u8> },
+}
+
+#[derive(Debug, PartialEq)]
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// A fresh random secret for signing pushes.
+pub(crate) fn random_secret() -> anyhow::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("no random source: {e}"))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Ask `hub` to push updates of `topic` to `callback`.
+pub(crate) fn subscribe(
+    client: &ureq::Agent,
+    hub: &str,
+    callback: &str,
+    sub: &Subscription,
+) -> anyhow::Result<()> {
+    client.post(hub).send_form([
+        ("hub.mode", "subscribe"),
+        ("hub.topic", sub.topic.as_str()),
+        ("hub.callback", callback),
+        ("hub.secret", sub.secret.as_str()),
+        ("hub.lease_seconds", LEASE.as_secs().to_string().as_str()),
+    ])?;
+    Ok(())
+}
+
+/// Answer hub requests on `listener` forever, forwarding what they tell us.
+pub(crate) fn serve(listener: TcpListener, subs: Subscriptions, events: Sender<Event>) {
+    for mut stream in listener.incoming().flatten() {
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+        let (reply, event) = match read_request(&mut stream, MAX_BODY) {
+            Ok(request) => {
+                let mut subs = subs.lock().unwrap_or_else(|e| e.into_inner());
+                handle(&request, &mut subs)
+            }
+            Err(_) => (Reply::new(400, "Bad Request"), None),
+        };
+        let _ = write_response(&mut stream, reply.status, "text/plain", &reply.body);
+        if let Some(event) = event
+            && events.send(event).is_err()
+        {
+            return;
+        }
+    }
+}
+
+fn handle(request: &Request, subs: &mut HashMap<String, Subscription>) -> (Reply, Option<Event>) {
+    // The callback URL may sit below a prefix, e.g. behind a reverse proxy
+    let id = request.path.rsplit('/').next().unwrap_or_default();
+    let Some(sub) = subs.get_mut(id) else {
+        return (Reply::new(404, "Not Found"), None);
+    };
+    let param = |name: &str| request.query.get(name).map(String::as_str);
+
+    match request.method.as_str() {
+        "GET" => {
+            if param("hub.mode") != Some("subscribe") || param("hub.topic") != Some(&sub.topic) {
+                return (Reply::new(404, "Not Found"), None);
+            }
+            // The hub signs with the new secret from now on
+            sub.old_secret = None;
+            let lease = param("hub.lease_seconds")
+                .and_then(|s| s.parse().ok())
+                .map_or(LEASE, Duration::from_secs)
+                .min(MAX_LEASE);
+            let event = Event::Verified {
+                feed_url: sub.feed_url.clone(),
+                lease,
+            };
+            let challenge = param("hub.challenge").unwrap_or_default();
+            (Reply::new(200, challenge), Some(event))
+        }
+        "POST" => {
+            let signed = request.headers.get("x-hub-signature").is_some_and(|sig| {
+                std::iter::once(&sub.secret)
+                    .chain(&sub.old_secret)
+                    .any(|secret| signature_matches(secret, sig, &request.body))
+            });
+            // Per the spec, forged pushes are acknowledged but ignored
+            let event = signed.then(|| Event::Content {
+                feed_url: sub.feed_url.clone(),
+                body: request.body.clone(),
+            });
+            (Reply::new(202, ""), event)
+        }
+        _ => (Reply::new(400, "Bad Request"), None),
+    }
+}
+
+/// Check an `X-Hub-Signature` header (`method=hexdigest`) against `body`.
+fn signature_matches(secret: &str, header: &str, body: &[u8]) -> bool {
+    let Some((method, hex)) = header.split_once('=') else {
+        return false;
+    };
+    let Some(expected) = decode_hex(hex) else {
+        return false;
+    };
+    match method {
+        "sha1" => hmac_matches::<sha1::Sha1>(secret, body, &expected),
+        "sha256" => hmac_matches::<sha2::Sha256>(secret, body, &expected),
+        "sha512" => hmac_matches::<sha2::Sha512>(secret, body, &expected),
+        _ => false,
+    }
+}
+
+fn hmac_matches<D: EagerHash>(secret: &str, body: &[u8], expected: &[u8]) -> bool
+where
+    Hmac<D>: KeyInit + Mac,
+{
+    let Ok(mut mac) = <Hmac<D> as KeyInit>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(expected).is_ok()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn subs() -> HashMap<String, Subscription> {
+        HashMap::from([(
+            "abc123".to_string(),
+            Subscription {
+                feed_url: "https://blog.example/feed.xml".to_string(),
+                topic: "https://blog.example/feed.xml".to_string(),
+                secret: "s3cret".to_string(),
+                old_secret: None,
+            },
+        )])
+    }
+
+    /// An `X-Hub-Signature` header for `body` signed with `secret`.
+    fn sha256_signature(secret: &str, body: &[u8]) -> String {
+        let mut mac = <Hmac<sha2::Sha256> as KeyInit>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!("sha256={hex}")
+    }
+
+    fn request(raw: &str) -> Request {
+        read_request(raw.as_bytes(), MAX_BODY).unwrap()
+    }
+
+    #[rstest]
+    #[case::given("&hub.lease_seconds=600", Duration::from_secs(600))]
+    #[case::missing("", LEASE)]
+    #[case::invalid("&hub.lease_seconds=soon", LEASE)]
+    #[case::huge("&hub.lease_seconds=18446744073709551615", MAX_LEASE)]
+    fn test_verification_echoes_challenge(#[case] lease_param: &str, #[case] lease: Duration) {
+        let req = request(&format!(
+            "GET /websub/abc123?hub.mode=subscribe&hub.topic=https%3A%2F%2Fblog.example%2Ffeed.xml&hub.challenge=xyz{lease_param} HTTP/1.1\r\n\r\n",
+        ));
+        let (reply, event) = handle(&req, &mut subs());
+        assert_eq!(reply, Reply::new(200, "xyz"));
+        assert_eq!(
+            event,
+            Some(Event::Verified {
+                feed_url: "https://blog.example/feed.xml".to_string(),
+                lease,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::unknown_path(
+        "GET /nope?hub.mode=subscribe&hub.topic=https%3A%2F%2Fblog.example%2Ffeed.xml&hub.challenge=x HTTP/1.1\r\n\r\n"
+    )]
+    #[case::wrong_topic(
+        "GET /abc123?hub.mode=subscribe&hub.topic=https%3A%2F%2Fevil.example&hub.challenge=x HTTP/1.1\r\n\r\n"
+    )]
+    #[case::unsubscribe(
+        "GET /abc123?hub.mode=unsubscribe&hub.topic=https%3A%2F%2Fblog.example%2Ffeed.xml&hub.challenge=x HTTP/1.1\r\n\r\n"
+    )]
+    fn test_verification_refused(#[case] raw: &str) {
+        let (reply, event) = handle(&request(raw), &mut subs());
+        assert_eq!(reply.status, 404);
+        assert_eq!(event, None);
+    }
+
+    #[rstest]
+    #[case::wrong_digest("sha1=9cdd6e4d1a7b7f2b0c94f8e0a64a8b6a3c2cb4b1")]
+    #[case::missing_method("9cdd6e4d")]
+    #[case::unknown_method("md5=9cdd6e4d")]
+    #[case::bad_hex("sha1=zz")]
+    fn test_push_with_bad_signature_is_ignored(#[case] signature: &str) {
+        let raw = format!(
+            "POST /abc123 HTTP/1.1\r\nContent-Length: 6\r\nX-Hub-Signature: {signature}\r\n\r\n<rss/>"
+        );
+        let (reply, event) = handle(&request(&raw), &mut subs());
+        assert_eq!(reply.status, 202);
+        assert_eq!(event, None);
+    }
+
+    #[rstest]
+    #[case::sha1("sha1")]
+    #[case::sha256("sha256")]
+    fn test_signed_push_is_forwarded(#[case] method: &str) {
+        fn sign<D: EagerHash>(body: &[u8]) -> Vec<u8>
+        where
+            Hmac<D>: KeyInit + Mac,
+        {
+            let mut mac = <Hmac<D> as KeyInit>::new_from_slice(b"s3cret").unwrap();
+            mac.update(body);
+            mac.finalize().into_bytes().to_vec()
+        }
+        let digest = match method {
+            "sha1" => sign::<sha1::Sha1>(b"<rss/>"),
+            _ => sign::<sha2::Sha256>(b"<rss/>"),
+        };
+        let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        let raw = format!(
+            "POST /abc123 HTTP/1.1\r\nContent-Length: 6\r\nX-Hub-Signature: {method}={hex}\r\n\r\n<rss/>"
+        );
+        let (reply, event) = handle(&request(&raw), &mut subs());
+        assert_eq!(reply.status, 202);
+        assert_eq!(
+            event,
+            Some(Event::Content {
+                feed_url: "https://blog.example/feed.xml".to_string(),
+                body: b"<rss/>".to_vec(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_old_secret_is_accepted_until_renewal_is_verified() {
+        let mut subs = subs();
+        let sub = subs.get_mut("abc123").unwrap();
+        sub.old_secret = Some(std::mem::replace(&mut sub.secret, "n3w".to_string()));
+        let push = format!(
+            "POST /abc123 HTTP/1.1\r\nContent-Length: 6\r\nX-Hub-Signature: {}\r\n\r\n<rss/>",
+            sha256_signature("s3cret", b"<rss/>")
+        );
+
+        let (_, event) = handle(&request(&push), &mut subs);
+        assert!(event.is_some());
+
+        let verify = "GET /abc123?hub.mode=subscribe&hub.topic=https%3A%2F%2Fblog.example%2Ffeed.xml&hub.challenge=x HTTP/1.1\r\n\r\n";
+        handle(&request(verify), &mut subs);
+        let (_, event) = handle(&request(&push), &mut subs);
+        assert_eq!(event, None);
+    }
+
+    #[test]
+    fn test_random_secret() {
+        let a = random_secret().unwrap();
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, random_secret().unwrap());
+    }
+}
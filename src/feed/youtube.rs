@@ -0,0 +1,160 @@
+//! YouTube publishes an Atom feed for every channel and playlist, but its
+//! pages are heavy and often hide behind a consent screen, so HTML discovery
+//! is unreliable. These helpers map YouTube URLs to their feed directly.
+
+const FEED_BASE: &str = "https://www.youtube.com/feeds/videos.xml";
+
+/// Markers that precede a channel ID in a YouTube page, most reliable first.
+const CHANNEL_ID_MARKERS: &[&str] = &[
+    "feeds/videos.xml?channel_id=",
+    "\"externalId\":\"",
+    "<meta itemprop=\"channelId\" content=\"",
+    "\"channelId\":\"",
+    "youtube.com/channel/",
+];
+
+/// Where a YouTube URL leads.
+#[derive(Debug, PartialEq)]
+pub(crate) enum YoutubeUrl {
+    /// The feed URL is known from the URL alone
+    Feed(String),
+    /// A handle, custom URL or video: the channel ID must be read off the page
+    Page,
+}
+
+fn is_youtube_host(host: &str) -> bool {
+    matches!(
+        host,
+        "youtube.com" | "www.youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtu.be"
+    )
+}
+
+/// Channel IDs are `UC` followed by 22 URL-safe base64 characters.
+fn is_channel_id(s: &str) -> bool {
+    s.len() == 24
+        && s.starts_with("UC")
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+fn feed_url(param: &str, value: &str) -> String {
+    let mut url = url::Url::parse(FEED_BASE).expect("valid base URL");
+    url.query_pairs_mut().append_pair(param, value);
+    url.to_string()
+}
+
+/// Recognise a YouTube channel, user, playlist, handle or video URL.
+pub(crate) fn classify(url: &str) -> Option<YoutubeUrl> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !is_youtube_host(parsed.host_str()?) {
+        return None;
+    }
+    let query = |name: &str| {
+        parsed
+            .query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.into_owned())
+    };
+    if parsed.path() == "/feeds/videos.xml" {
+        return Some(YoutubeUrl::Feed(url.to_string()));
+    }
+    if let Some(list) = query("list") {
+        return Some(YoutubeUrl::Feed(feed_url("playlist_id", &list)));
+    }
+
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["channel", id, ..] if is_channel_id(id) => {
+            Some(YoutubeUrl::Feed(feed_url("channel_id", id)))
+        }
+        ["user", name, ..] => Some(YoutubeUrl::Feed(feed_url("user", name))),
+        [handle, ..] if handle.starts_with('@') => Some(YoutubeUrl::Page),
+        ["c", _, ..] | ["watch", ..] | ["shorts", _, ..] | ["live", _, ..] => {
+            Some(YoutubeUrl::Page)
+        }
+        // youtu.be/<video>
+        [_] if parsed.host_str() == Some("youtu.be") => Some(YoutubeUrl::Page),
+        _ => None,
+    }
+}
+
+/// Find the channel ID in a channel or video page.
+pub(crate) fn channel_id_in_page(html: &str) -> Option<String> {
+    CHANNEL_ID_MARKERS.iter().find_map(|marker| {
+        html.match_indices(marker).find_map(|(i, _)| {
+            let id = html.get(i + marker.len()..i + marker.len() + 24)?;
+            is_channel_id(id).then(|| id.to_string())
+        })
+    })
+}
+
+/// Feed URL for a channel ID.
+pub(crate) fn channel_feed_url(channel_id: &str) -> String {
+    feed_url("channel_id", channel_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const CHANNEL: &str = "UCsXVk37bltHxD1rDPwtNM8Q";
+
+    #[rstest]
+    #[case::channel(
+        "https://www.youtube.com/channel/UCsXVk37bltHxD1rDPwtNM8Q",
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"
+    )]
+    #[case::channel_tab(
+        "https://youtube.com/channel/UCsXVk37bltHxD1rDPwtNM8Q/videos",
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"
+    )]
+    #[case::user(
+        "https://m.youtube.com/user/Computerphile",
+        "https://www.youtube.com/feeds/videos.xml?user=Computerphile"
+    )]
+    #[case::playlist(
+        "https://www.youtube.com/playlist?list=PLzH6n4zXuckpfMu_4Ff8E7Z1behQks5ba",
+        "https://www.youtube.com/feeds/videos.xml?playlist_id=PLzH6n4zXuckpfMu_4Ff8E7Z1behQks5ba"
+    )]
+    #[case::already_a_feed(
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q",
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"
+    )]
+    fn test_classify_feed(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(classify(url), Some(YoutubeUrl::Feed(expected.to_string())));
+    }
+
+    #[rstest]
+    #[case::handle("https://www.youtube.com/@veritasium")]
+    #[case::custom("https://www.youtube.com/c/Computerphile")]
+    #[case::video("https://www.youtube.com/watch?v=dQw4w9WgXcQ")]
+    #[case::short_link("https://youtu.be/dQw4w9WgXcQ")]
+    fn test_classify_page(#[case] url: &str) {
+        assert_eq!(classify(url), Some(YoutubeUrl::Page));
+    }
+
+    #[rstest]
+    #[case::other_site("https://blog.example/channel/UCsXVk37bltHxD1rDPwtNM8Q")]
+    #[case::home("https://www.youtube.com/")]
+    #[case::bad_channel_id("https://www.youtube.com/channel/nope")]
+    fn test_classify_other(#[case] url: &str) {
+        assert_eq!(classify(url), None);
+    }
+
+    #[rstest]
+    #[case::rss_link(
+        r#"<link rel="alternate" href="https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q">"#
+    )]
+    #[case::external_id(r#"{"externalId":"UCsXVk37bltHxD1rDPwtNM8Q","title":"x"}"#)]
+    #[case::video_page(r#"{"channelId":"UCsXVk37bltHxD1rDPwtNM8Q"}"#)]
+    #[case::skips_invalid(r#""channelId":"nope" ... "channelId":"UCsXVk37bltHxD1rDPwtNM8Q""#)]
+    fn test_channel_id_in_page(#[case] html: &str) {
+        assert_eq!(channel_id_in_page(html).as_deref(), Some(CHANNEL));
+    }
+
+    #[test]
+    fn test_channel_id_missing() {
+        assert_eq!(channel_id_in_page("<html>consent</html>"), None);
+    }
+}
@@ -17,19 +17,101 @@ use shorthand::RESERVED_COMMANDS;
 struct Args {
     #[command(subcommand)]
     command: Option<Command>,
+    #[command(flatten)]
+    show: ShowFlags,
+    /// Use the store at this path (overrides RSS_STORE)
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "profile")]
+    store: Option<PathBuf>,
+    /// Use the named store instead of the default one, e.g. work
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    /// Log each feed fetch and git operation to stderr; repeat (-vv) to also
+    /// log each request and parse. `blog sync -v` also lists the new posts
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Append the log to this file instead, at -v level unless -vv is given
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
 }
 
+#[derive(clap::Args, Default)]
+struct ShowFlags {
+    /// Print the categories and a short summary excerpt under each post
+    #[arg(short, long)]
+    long: bool,
+    /// Show only posts in this language, e.g. en or deu
+    #[arg(long, value_name = "CODE")]
+    lang: Option<String>,
+    /// Show only posts the feed changed after they were fetched, marked `~`
+    #[arg(long)]
+    updated: bool,
+    /// Show only posts the feed files under this category, e.g. security
+    #[arg(long, value_name = "NAME")]
+    category: Option<String>,
+    /// Order, group and filter posts by their publication date or by when
+    /// they were first fetched
+    #[arg(long, value_enum, value_name = "KEY")]
+    sort: Option<commands::show::SortKey>,
+    /// List at most N posts per group, then how many more there are
+    #[arg(long, value_name = "N")]
+    per_group: Option<usize>,
+    /// Output format
+    #[arg(long, value_enum)]
+    format: Option<commands::show::ShowFormat>,
+    /// Like --format tsv, but end each post with a NUL byte, e.g. for xargs -0
+    #[arg(long)]
+    print0: bool,
+}
+
+impl ShowFlags {
+    fn merge(&self, other: &ShowFlags) -> commands::show::ShowOptions {
+        commands::show::ShowOptions {
+            long: self.long || other.long,
+            lang: self.lang.clone().or_else(|| other.lang.clone()),
+            updated: self.updated || other.updated,
+            category: self.category.clone().or_else(|| other.category.clone()),
+            sort: self.sort.or(other.sort).unwrap_or_default(),
+            per_group: self.per_group.or(other.per_group),
+            format: self.format.or(other.format).unwrap_or_default(),
+            print0: self.print0 || other.print0,
+        }
+    }
+}
+
+const SEARCH_HELP: &str = "\
+SEARCH TERMS:
+  rust async          Posts mentioning both words
+  \"borrow checker\"    The exact phrase
+  rust OR go          Either word
+  title:rust          Only in the title
+  feed:@myblog        Only posts from @myblog
+  date:>2024-01-01    Posts after that day (also >=, <, <= or a day)
+  date:>=1m           Posts from the last month
+
+  Results are numbered; `blog search rust --open 1` opens the top one.
+
+  Quote terms with < or > for the shell: blog search 'rust date:>2024-01-01'
+";
+
 const QUERY_HELP: &str = "\
 QUERY LANGUAGE:
   Grouping (up to 2):
     /d          Group by date
     /w          Group by week
     /f          Group by feed
+    /a          Group by author
+    /t          Group by tag (the feed's categories)
+    /g          Group by folder, then feed (see `blog feed folder`)
+    /n          No grouping
+    /ta         Same as /t /a
 
   Filtering:
     @shorthand  Show only posts from a specific feed
+    /Tech       Show only posts from feeds in a folder and its subfolders
     .read       Show only read posts
     .unread     Show only unread posts
+    ~word       Show only posts mentioning word in the title or summary
+    %view       Use the query saved as a view (see `blog view add`)
     .all        Show all posts (override default filter)
 
   Date range:
@@ -53,13 +135,33 @@ EXAMPLES:
   blog 3m..1m                 Posts from 1-3 months ago
   blog /d 2w..1w              Posts from 1-2 weeks ago, grouped by date
   blog a open                 Open post with shorthand 'a'
-  blog a read                 Print URL of post 'a'
+  blog @myblog open           Open the website of @myblog
+  blog @myblog open --all     Open every post from @myblog
+  blog open --date 2024-05-01 Open every post from that day
+  blog a read                 Read post 'a' in the terminal
+  blog a read --plain         Print URL of post 'a'
+  blog read --next            Read the post listed after the last one read
+  blog a comments             Open the comments page of post 'a'
+  blog a discuss              Find discussions of post 'a' on HN and Lobsters
+  blog discover https://x.org List the feeds of a site without subscribing
+  blog suggest @myblog        Offer feeds of the sites @myblog links to most
   blog a unread               Mark post 'a' as unread
+  blog snooze a 3d            Hide post 'a' for 3 days, then list it marked !
+  blog a save                 Send post 'a' to your read-it-later service
+  blog next                   Read the oldest unread post (repeat to go on)
+  blog a later                Queue post 'a' to read later
+  blog later pop              Read the first post in the read-later queue
+  blog @myblog next           Read the oldest unread post from @myblog
   blog .unread                Show only unread posts
   blog @myblog .unread        Show unread posts from @myblog
   blog .all                   Show all posts (bypass defaults)
+  blog today                  Show today's posts by feed (also: yesterday)
   blog .all export             Export all posts as JSONL
-  blog @myblog export          Export posts from @myblog as JSONL";
+  blog @myblog export          Export posts from @myblog as JSONL
+  blog .all export --format atom -o river.xml
+                              Export the 50 newest posts as an Atom feed
+  blog a b export --format epub -o posts.epub
+                              Bundle posts 'a' and 'b' into an EPUB";
 
 #[derive(Subcommand)]
 enum Command {
@@ -68,29 +170,223 @@ enum Command {
     Show {
         /// Query arguments (see below)
         args: Vec<String>,
+        #[command(flatten)]
+        flags: ShowFlags,
+    },
+    /// Open a post, or a feed's website when given only @shorthand, in the
+    /// default browser
+    Open {
+        /// Open every matching post, e.g. all of `@shorthand`'s
+        #[arg(long)]
+        all: bool,
+        /// Open every matching post from this day, e.g. 2024-05-01 or
+        /// yesterday
+        #[arg(long, value_name = "DATE")]
+        date: Option<String>,
+        /// Don't ask for confirmation before opening several posts
+        #[arg(short, long)]
+        yes: bool,
+        /// Don't mark the opened posts as read
+        #[arg(long)]
+        no_mark: bool,
+    },
+    /// Print a post's content formatted for the terminal
+    Read {
+        /// Print only the post URL, e.g. to pass it to another program
+        #[arg(long)]
+        plain: bool,
+        /// Open the post's numbered link N instead, or print it with --plain
+        #[arg(long, value_name = "N")]
+        link: Option<usize>,
+        /// Read the post listed after the last one read
+        #[arg(long, conflicts_with = "prev")]
+        next: bool,
+        /// Read the post listed before the last one read
+        #[arg(long)]
+        prev: bool,
+        /// Don't mark the posts as read
+        #[arg(long)]
+        no_mark: bool,
+    },
+    /// Open a post's comments page in the default browser
+    Comments {
+        /// Print the URL instead
+        #[arg(long)]
+        plain: bool,
+    },
+    /// List where a post was discussed on Hacker News and Lobsters
+    Discuss {
+        /// Open the most discussed thread in the default browser instead
+        #[arg(long)]
+        open: bool,
+    },
+    /// List the feeds `feed add` would find at a URL, without subscribing
+    Discover {
+        /// A feed, or a page to look for feeds on
+        url: String,
+    },
+    /// Suggest feeds from the sites your feeds link to most, to subscribe to
+    Suggest {
+        /// Only look at the links of this feed (URL or @shorthand)
+        #[arg(value_name = "@SHORTHAND")]
+        feed: Option<String>,
     },
-    /// Open a post in the default browser
-    Open,
-    /// Print the URL of a post to stdout
-    Read,
     /// Manage feed subscriptions
     Feed {
         #[command(subcommand)]
         command: FeedCommand,
     },
     /// Fetch feeds and sync with remote
+    #[command(visible_alias = "pull")]
     Sync {
+        /// Fetch only these feeds, e.g. @hn; skips the git remote unless --git
+        #[arg(value_name = "@SHORTHAND")]
+        shorthands: Vec<String>,
         /// Repeat to sync only selected feeds by @shorthand
         #[arg(long = "feed", value_name = "SHORTHAND")]
         feeds: Vec<String>,
+        /// Sync with the git remote even when only some feeds are fetched
+        #[arg(long, conflicts_with_all = ["no_push", "offline"])]
+        git: bool,
+        /// Only sync with the git remote; don't fetch any feeds
+        #[arg(long, conflicts_with = "shorthands")]
+        no_fetch: bool,
+        /// Only fetch feeds; don't pull from or push to the git remote
+        #[arg(long)]
+        no_push: bool,
+        /// Only fetch the feeds whose last fetch failed; skips the git remote
+        /// unless --git
+        #[arg(long, conflicts_with_all = ["no_fetch", "offline"])]
+        retry_failed: bool,
+        /// Don't use the network at all, just apply retention and commit locally
+        #[arg(long)]
+        offline: bool,
+        /// Print nothing but warnings and errors
+        #[arg(short, long)]
+        quiet: bool,
+        /// Fetch at most N feeds at once (default: `fetch_jobs` config or 16)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<std::num::NonZeroUsize>,
+    },
+    /// Keep syncing in the foreground, receiving WebSub pushes where feeds support it
+    Watch {
+        /// Minutes between polls of feeds that aren't pushed
+        #[arg(long, value_name = "MINUTES", default_value_t = 30)]
+        interval: u64,
+        /// Public URL hubs deliver pushes to (default: `websub_callback` config)
+        #[arg(long, value_name = "URL")]
+        callback: Option<String>,
+        /// Local address for the push listener (default: `websub_listen` config or 127.0.0.1:8470)
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+        /// Fetch at most N feeds at once (default: `fetch_jobs` config or 16)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<std::num::NonZeroUsize>,
+    },
+    /// Serve the store over HTTP until interrupted
+    Serve {
+        /// Serve a read-only web interface on this address, e.g. 127.0.0.1:8080
+        #[arg(long, value_name = "ADDR")]
+        web: Option<String>,
+        /// Serve the JSON API on this address, for clients sending the token set in `BLOG_API_TOKEN`
+        #[arg(long, value_name = "ADDR")]
+        api: Option<String>,
+    },
+    /// Read the oldest unread post and mark it read, to work through a backlog
+    Next {
+        /// Print only the post URL
+        #[arg(long, conflicts_with = "open")]
+        plain: bool,
+        /// Open the post in the default browser instead
+        #[arg(long)]
+        open: bool,
+    },
+    /// Show the posts added since the last `blog new` on this machine, read or not
+    New,
+    /// Queue posts to read later, or list the queue when given no posts
+    Later {
+        #[command(subcommand)]
+        command: Option<LaterCommand>,
+    },
+    /// Save named queries, used as %name in place of a query
+    View {
+        #[command(subcommand)]
+        command: ViewCommand,
+    },
+    /// Back up or restore the whole store
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Hide posts from listings until a time, then list them again marked `!`
+    Snooze {
+        /// Post shorthands, then how long, e.g. `abc 3d`; also 2w, 1m,
+        /// tomorrow or 2024-06-01
+        #[arg(required = true, value_name = "POSTS.. UNTIL")]
+        args: Vec<String>,
     },
     /// Mark a post as unread
     Unread,
-    /// Export matching posts as JSONL
+    /// Send posts to Wallabag, linkding or Shiori (see `save_service` config)
+    Save,
+    /// Search post titles and summaries, best matches first
+    #[command(after_help = SEARCH_HELP)]
+    Search {
+        /// Words, "quoted phrases", feed:@shorthand and date: filters
+        #[arg(required = true)]
+        terms: Vec<String>,
+        /// Show at most N posts
+        #[arg(short = 'n', long, value_name = "N", default_value_t = commands::search::SEARCH_DEFAULT_LIMIT)]
+        limit: usize,
+        /// Print a short summary excerpt under each post
+        #[arg(short, long)]
+        long: bool,
+        /// Open the result with this number in the browser
+        #[arg(long, value_name = "N")]
+        open: Option<usize>,
+    },
+    /// Show posts dated today, grouped by feed
+    Today {
+        #[command(flatten)]
+        flags: ShowFlags,
+    },
+    /// Show posts dated yesterday, grouped by feed
+    Yesterday {
+        #[command(flatten)]
+        flags: ShowFlags,
+    },
+    /// Export matching posts as JSONL, EPUB, Atom or Markdown
     #[command(after_help = QUERY_HELP)]
     Export {
         /// Query arguments (see below)
         args: Vec<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: commands::export::ExportFormat,
+        /// Write to a file instead of stdout (required for EPUB)
+        #[arg(short, long, visible_alias = "out", value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Export only the N newest matching posts (default for atom: 50)
+        #[arg(short = 'n', long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// Summarise recent posts, optionally as an email
+    #[command(after_help = QUERY_HELP)]
+    Digest {
+        /// Query arguments (see below)
+        args: Vec<String>,
+        /// Include posts since this date, unless the query has a date range
+        #[arg(long, value_name = "DATE", default_value = "1d")]
+        since: String,
+        /// Print a MIME email (plain text and HTML), e.g. for `sendmail -t`
+        #[arg(long, conflicts_with = "send")]
+        email: bool,
+        /// Send the email via the `smtp_url` server
+        #[arg(long)]
+        send: bool,
+        /// Recipient address (default: `digest_to` config)
+        #[arg(long, value_name = "ADDRESS")]
+        to: Option<String>,
     },
     /// Manage configuration
     Config {
@@ -102,10 +398,46 @@ enum Command {
         /// Arguments to pass to git
         args: Vec<String>,
     },
+    /// Show the feeds, posts and other rows that differ between the store
+    /// and its remote, or between two revisions of it
+    Diff {
+        /// Revision to compare from; without one, HEAD is compared with the
+        /// remote branch
+        from: Option<String>,
+        /// Revision to compare to (default: HEAD)
+        to: Option<String>,
+        /// Fetch the remote first, to see what was pushed since the last sync
+        #[arg(long)]
+        fetch: bool,
+    },
+    /// List recent changes to the store, with the rows each one changed
+    History {
+        /// How many changes to list
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Drop rows deleted long ago from the store and repack its git
+    /// repository
+    Maintenance,
+    /// Create a store with a git repository, optionally with a remote to sync
+    /// with
+    Init {
+        /// Git remote to sync with, e.g. `user/repo` for a GitHub repository
+        remote: Option<String>,
+    },
     /// Clone an existing feed database from a git remote
     Clone {
         /// Git-clonable URL
         url: String,
+        /// Fetch the whole history instead of only the latest commit
+        #[arg(long)]
+        full: bool,
+    },
+    /// Write man pages for blog and each of its commands
+    Man {
+        /// Directory to write the pages to
+        #[arg(short, long, visible_alias = "out", value_name = "DIR")]
+        output: PathBuf,
     },
 }
 
@@ -134,49 +466,219 @@ enum ConfigCommand {
 enum FeedCommand {
     /// Subscribe to a feed by URL
     Add {
-        /// The feed URL to subscribe to
+        /// The feed URL to subscribe to, or `-` to read URLs from stdin
         urls: Vec<String>,
+        /// Also add the URLs listed in this file, one per line
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// When a page links to several feeds, pick from a menu (the default
+        /// when stdin is a terminal)
+        #[arg(long)]
+        select: bool,
     },
     /// Unsubscribe from a feed by URL or @shorthand
     Rm {
         /// The feed URL or @shorthand to unsubscribe from
         urls: Vec<String>,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Keep the feed's posts, labeled as archived, instead of deleting them
+        #[arg(long)]
+        keep_posts: bool,
     },
     /// List subscribed feeds
-    Ls,
-    /// Import feeds from an OPML file
+    Ls {
+        /// Show each feed's newest post, most recently active feeds first
+        #[arg(long)]
+        by_recent: bool,
+    },
+    /// Show everything known about a feed
+    Info {
+        /// The feed URL or @shorthand
+        url: String,
+    },
+    /// Stop syncing feeds until they are resumed
+    Pause {
+        /// The feed URLs or @shorthands to pause
+        urls: Vec<String>,
+        /// Also leave their posts out of listings (`.all` or @shorthand still shows them)
+        #[arg(long)]
+        hide: bool,
+    },
+    /// Hide a feed's posts and stop fetching it until a time, then resume it
+    Snooze {
+        /// The feed URL or @shorthand
+        url: String,
+        /// e.g. 3d, 2w, 1m, tomorrow or 2024-06-01
+        until: String,
+    },
+    /// Sync paused or snoozed feeds again
+    Resume {
+        /// The feed URLs or @shorthands to resume
+        urls: Vec<String>,
+    },
+    /// Set how long a feed's posts are kept, overriding the `retention` config
+    Retain {
+        /// The feed URL or @shorthand
+        url: String,
+        /// e.g. 90d (days), 200 (newest posts), 90d,200, forever, or default
+        policy: String,
+    },
+    /// Set how long fetching a feed may take, overriding the `timeout` config
+    Timeout {
+        /// The feed URL or @shorthand
+        url: String,
+        /// Seconds, e.g. 60, or default
+        seconds: String,
+    },
+    /// File a feed in a folder, used by the /g grouping and /Folder filters
+    Folder {
+        /// The feed URL or @shorthand
+        url: String,
+        /// e.g. Tech or Tech/Rust for a nested folder, or none
+        folder: String,
+    },
+    /// Import feeds from an OPML file, or from the sites in browser bookmarks
     Import {
         /// Path to the OPML file
-        path: std::path::PathBuf,
+        #[arg(required_unless_present = "bookmarks")]
+        path: Option<std::path::PathBuf>,
+        /// Look for feeds on the sites bookmarked in this bookmarks.html
+        /// export and pick which to subscribe to
+        #[arg(long, value_name = "FILE", conflicts_with = "path")]
+        bookmarks: Option<std::path::PathBuf>,
     },
     /// Export feeds as OPML to stdout
     Export,
 }
 
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Write feeds, posts, read state and all other tables as one JSON document
+    Export {
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Merge a `blog db export` document into the store (`-` reads stdin)
+    Import { path: PathBuf },
+    /// Check the store's files for unreadable rows, leftover merge conflicts,
+    /// wrong ids and misplaced or duplicate rows
+    Doctor {
+        /// Rewrite the files with the rows that can be read, and commit them
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ViewCommand {
+    /// Save a query as a view, replacing any view of that name
+    Add {
+        name: String,
+        /// Query arguments (see `blog --help`)
+        args: Vec<String>,
+        /// Only posts whose title or summary contain these words (adds ~word)
+        #[arg(long = "match", value_name = "WORDS")]
+        matches: Vec<String>,
+        /// Only unread posts (adds .unread)
+        #[arg(long)]
+        unread: bool,
+    },
+    /// List saved views
+    Ls,
+    /// Remove a view
+    Rm { name: String },
+}
+
+#[derive(Subcommand)]
+enum LaterCommand {
+    /// List queued posts, first queued first
+    Ls,
+    /// Read the first queued post and take it off the queue
+    Pop {
+        /// Print only the post URL
+        #[arg(long)]
+        plain: bool,
+    },
+    /// Take posts off the queue without reading them
+    Rm,
+}
+
+/// Top-level flags that take a separate value, which must stay with the flag
+/// rather than be read as part of the filter.
+const VALUE_FLAGS: &[&str] = &[
+    "--lang",
+    "--category",
+    "--sort",
+    "--per-group",
+    "--format",
+    "--store",
+    "--profile",
+    "--log-file",
+];
+
 fn split_at_command(args: Vec<String>) -> (Vec<String>, Vec<String>) {
-    let cmd_pos = args[1..]
-        .iter()
-        .position(|a| RESERVED_COMMANDS.contains(&a.as_str()));
-    let split = cmd_pos.map(|p| p + 1).unwrap_or(args.len());
-    let (flags, filter): (Vec<_>, Vec<_>) = args[1..split]
-        .iter()
-        .cloned()
-        .partition(|a| a.starts_with('-'));
+    let mut filter = Vec::new();
     let mut cmd_args = vec![args[0].clone()];
-    cmd_args.extend(flags);
-    cmd_args.extend_from_slice(&args[split..]);
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        if RESERVED_COMMANDS.contains(&arg.as_str()) {
+            cmd_args.push(arg.clone());
+            break;
+        }
+        if arg.starts_with('-') {
+            cmd_args.push(arg.clone());
+            if VALUE_FLAGS.contains(&arg.as_str()) {
+                cmd_args.extend(rest.next().cloned());
+            }
+        } else {
+            filter.push(arg.clone());
+        }
+    }
+    cmd_args.extend(rest.cloned());
     (filter, cmd_args)
 }
 
-fn store_dir() -> anyhow::Result<PathBuf> {
-    if let Ok(val) = std::env::var("RSS_STORE") {
+/// Index of the first argument that is neither a flag nor a flag's value.
+fn first_positional(args: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        if !args[i].starts_with('-') {
+            return Some(i);
+        }
+        if VALUE_FLAGS.contains(&args[i].as_str()) {
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The store to use: `--store`, then the `--profile` store, then RSS_STORE,
+/// then the default store.
+fn store_dir(store: Option<PathBuf>, profile: Option<&str>) -> anyhow::Result<PathBuf> {
+    if let Some(path) = store {
+        return Ok(path);
+    }
+    if profile.is_none()
+        && let Ok(val) = std::env::var("RSS_STORE")
+    {
         return Ok(PathBuf::from(val));
     }
-    // Only one store ("default") is supported for now. The stores/ nesting
-    // keeps the door open for multiple named stores in the future.
+    let name = profile.unwrap_or("default");
+    anyhow::ensure!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_'),
+        "Invalid profile name {name:?}: use letters, digits, - and _"
+    );
+    // Named stores live next to the default one
     dirs::data_dir()
-        .map(|d| d.join("blogtato").join("stores").join("default"))
-        .ok_or_else(|| anyhow::anyhow!("could not determine data directory; set RSS_STORE"))
+        .map(|d| d.join("blogtato").join("stores").join(name))
+        .ok_or_else(|| anyhow::anyhow!("could not determine data directory; use --store"))
 }
 
 fn parse_query_or_default(
@@ -188,12 +690,28 @@ fn parse_query_or_default(
         let text = default.as_deref().unwrap_or(query::DEFAULT_QUERY);
         query::parse_query_str(text)?
     } else {
-        query::parse_query(args)?
+        query::parse_query(&commands::view::expand_views(args.to_vec(), store)?)?
     };
     let text = query.to_string();
     Ok((query, text))
 }
 
+/// The query for `blog today` and `blog yesterday`: the filter limited to
+/// `range`, grouped by feed unless the filter groups otherwise.
+fn day_query(filter: &[String], range: &str, command: &str) -> anyhow::Result<query::Query> {
+    let query = query::parse_query(filter)?;
+    anyhow::ensure!(
+        query.date_filter.since.is_none() && query.date_filter.until.is_none(),
+        "{command} command does not accept a date range"
+    );
+    let args: Vec<String> = filter.iter().cloned().chain([range.to_string()]).collect();
+    let mut query = query::parse_query(&args)?;
+    if query.keys.is_empty() {
+        query.keys.push(query::GroupKey::Feed);
+    }
+    Ok(query)
+}
+
 fn reject_filter(filter: &[String], command: &str) -> anyhow::Result<()> {
     anyhow::ensure!(
         filter.is_empty(),
@@ -203,78 +721,389 @@ fn reject_filter(filter: &[String], command: &str) -> anyhow::Result<()> {
 }
 
 fn run() -> anyhow::Result<()> {
-    let (filter, cmd_args) = split_at_command(std::env::args().collect());
+    let raw_args: Vec<String> = std::env::args().collect();
+    // `blog foo ...` runs the `blog-foo` plugin if there is one
+    if let Some(i) = first_positional(&raw_args)
+        && !RESERVED_COMMANDS.contains(&raw_args[i].as_str())
+        && let Some(plugin) =
+            commands::plugin::find_plugin(&raw_args[i], std::env::var_os("PATH").as_deref())
+    {
+        let args = Args::parse_from(&raw_args[..i]);
+        let store_dir = store_dir(args.store, args.profile.as_deref())?;
+        let code = commands::plugin::run_plugin(&plugin, &store_dir, &raw_args[i + 1..])?;
+        std::process::exit(code);
+    }
+
+    let (filter, cmd_args) = split_at_command(raw_args);
     let args = Args::parse_from(cmd_args);
-    let store_dir = store_dir()?;
+    utils::logging::init(args.verbose, args.log_file.as_deref())?;
+    let show_flags = args.show;
+    let store_dir = store_dir(args.store, args.profile.as_deref())?;
 
-    if let Some(Command::Clone { ref url }) = args.command {
-        return commands::clone::cmd_clone(&store_dir, url);
+    if let Some(Command::Clone { ref url, full }) = args.command {
+        return commands::clone::cmd_clone(&store_dir, url, full);
+    }
+    if let Some(Command::Init { ref remote }) = args.command {
+        return commands::init::cmd_init(&store_dir, remote.as_deref());
+    }
+    if let Some(Command::Man { ref output }) = args.command {
+        use clap::CommandFactory;
+        // Named like the binary rather than the package
+        let cmd = Args::command().name(env!("CARGO_BIN_NAME"));
+        return commands::man::cmd_man(cmd, output);
+    }
+    // Runs before the store is loaded, since a broken store can't be
+    if let Some(Command::Db {
+        command: DbCommand::Doctor { fix },
+    }) = args.command
+    {
+        return commands::doctor::cmd_doctor(&store_dir, fix);
     }
 
     let mut store = data::BlogData::open(&store_dir)?;
     data::check_schema_version(&mut store)?;
+    utils::date::set_display_tz(data::get_config_parsed(&store, "timezone")?.unwrap_or_default());
+    let filter = commands::view::expand_views(filter, &store)?;
 
     match args.command {
         // Commands that accept a query/filter
-        Some(Command::Show { ref args }) => {
+        Some(Command::Show {
+            ref args,
+            ref flags,
+        }) => {
             let all_args: Vec<String> = filter.into_iter().chain(args.iter().cloned()).collect();
             let (q, query_text) = parse_query_or_default(&all_args, &store)?;
-            commands::show::cmd_show(&store, &q, &query_text)?;
+            let opts = flags.merge(&show_flags);
+            commands::show::cmd_show(&store, &q, &query_text, &opts)?;
         }
-        Some(Command::Export { ref args }) => {
+        Some(Command::Export {
+            ref args,
+            format,
+            ref output,
+            limit,
+        }) => {
             let all_args: Vec<String> = filter.into_iter().chain(args.iter().cloned()).collect();
             let (q, _) = parse_query_or_default(&all_args, &store)?;
-            commands::export::cmd_export(&store, &q)?;
+            commands::export::cmd_export(&store, &q, format, output.as_deref(), limit)?;
+        }
+        Some(Command::Digest {
+            ref args,
+            ref since,
+            email,
+            send,
+            ref to,
+        }) => {
+            let all_args: Vec<String> = filter.into_iter().chain(args.iter().cloned()).collect();
+            let q = query::parse_query(&commands::view::expand_views(all_args, &store)?)?;
+            let output = match (email, send) {
+                (_, true) => commands::digest::DigestOutput::Send,
+                (true, _) => commands::digest::DigestOutput::Email,
+                _ => commands::digest::DigestOutput::Text,
+            };
+            let opts = commands::digest::DigestOptions {
+                since: since.parse()?,
+                output,
+                to: to.clone(),
+            };
+            commands::digest::cmd_digest(&store, &q, &opts)?;
+        }
+        Some(Command::Open {
+            all,
+            date,
+            yes,
+            no_mark,
+        }) => {
+            let q = query::parse_query(&filter)?;
+            let group = match date {
+                Some(date) => Some(commands::open::OpenGroup {
+                    day: Some(date.parse()?),
+                    yes,
+                }),
+                None => all.then_some(commands::open::OpenGroup { day: None, yes }),
+            };
+            commands::open::cmd_open(&mut store, &q, group.as_ref(), !no_mark)?;
+        }
+        Some(Command::Read {
+            plain,
+            link,
+            next,
+            prev,
+            no_mark,
+        }) => {
+            let q = query::parse_query(&filter)?;
+            let step = match (next, prev) {
+                (true, _) => Some(commands::open::ReadStep::Next),
+                (_, true) => Some(commands::open::ReadStep::Prev),
+                _ => None,
+            };
+            commands::open::cmd_read(&mut store, &q, plain, link, step, !no_mark)?;
         }
-        Some(Command::Open) => {
+        Some(Command::Comments { plain }) => {
             let q = query::parse_query(&filter)?;
-            commands::open::cmd_open(&mut store, &q)?;
+            commands::open::cmd_comments(&store, &q, plain)?;
         }
-        Some(Command::Read) => {
+        Some(Command::Discuss { open }) => {
             let q = query::parse_query(&filter)?;
-            commands::open::cmd_read(&mut store, &q)?;
+            commands::discuss::cmd_discuss(&store, &q, open)?;
+        }
+        Some(Command::Discover { ref url }) => {
+            reject_filter(&filter, "discover")?;
+            let http = utils::http::HttpOptions::from_config(&store)?;
+            commands::discover::cmd_discover(&store, url, &http)?;
+        }
+        Some(Command::Suggest { ref feed }) => {
+            reject_filter(&filter, "suggest")?;
+            let http = utils::http::HttpOptions::from_config(&store)?;
+            commands::suggest::cmd_suggest(&mut store, feed.as_deref(), &http)?;
+        }
+        Some(Command::Next { plain, open }) => {
+            let q = query::parse_query(&filter)?;
+            commands::open::cmd_next(&mut store, &q, plain, open)?;
+        }
+        Some(Command::New) => {
+            let q = query::parse_query(&filter)?;
+            commands::new::cmd_new(&store, &q)?;
+        }
+        Some(Command::Later { ref command }) => match command {
+            None if filter.is_empty() => commands::later::cmd_later_ls(&store)?,
+            None => {
+                let q = query::parse_query(&filter)?;
+                commands::later::cmd_later_add(&mut store, &q)?;
+            }
+            Some(LaterCommand::Ls) => {
+                reject_filter(&filter, "later ls")?;
+                commands::later::cmd_later_ls(&store)?;
+            }
+            Some(LaterCommand::Pop { plain }) => {
+                reject_filter(&filter, "later pop")?;
+                commands::later::cmd_later_pop(&mut store, *plain)?;
+            }
+            Some(LaterCommand::Rm) => {
+                anyhow::ensure!(
+                    !filter.is_empty(),
+                    "Pick posts to remove, e.g. `blog abc later rm`"
+                );
+                let q = query::parse_query(&filter)?;
+                commands::later::cmd_later_rm(&mut store, &q)?;
+            }
+        },
+        Some(Command::Snooze { ref args }) => {
+            let (until, posts) = args.split_last().expect("clap requires an argument");
+            let posts: Vec<String> = filter.iter().chain(posts).cloned().collect();
+            anyhow::ensure!(
+                !posts.is_empty(),
+                "Pick posts to snooze, e.g. `blog snooze abc 3d`"
+            );
+            let until = utils::date::parse_until(until, chrono::Utc::now())?;
+            let q = query::parse_query(&posts)?;
+            commands::snooze::cmd_snooze(&mut store, &q, until)?;
         }
         Some(Command::Unread) => {
             let q = query::parse_query(&filter)?;
             commands::open::cmd_unread(&mut store, &q)?;
         }
+        Some(Command::Save) => {
+            let q = query::parse_query(&filter)?;
+            commands::save::cmd_save(&store, &q)?;
+        }
+        Some(Command::Today { ref flags }) => {
+            let q = day_query(&filter, "today..", "today")?;
+            let opts = flags.merge(&show_flags);
+            commands::show::cmd_show(&store, &q, &q.to_string(), &opts)?;
+        }
+        Some(Command::Yesterday { ref flags }) => {
+            let q = day_query(&filter, "yesterday..today", "yesterday")?;
+            let opts = flags.merge(&show_flags);
+            commands::show::cmd_show(&store, &q, &q.to_string(), &opts)?;
+        }
         None => {
             let (q, query_text) = parse_query_or_default(&filter, &store)?;
-            commands::show::cmd_show(&store, &q, &query_text)?;
+            let opts = show_flags.merge(&ShowFlags::default());
+            commands::show::cmd_show(&store, &q, &query_text, &opts)?;
         }
 
         // Commands that reject filters
+        Some(Command::Db { ref command }) => {
+            reject_filter(&filter, "db")?;
+            match command {
+                DbCommand::Export { output } => {
+                    commands::db::cmd_db_export(&store, output.as_deref())?
+                }
+                DbCommand::Import { path } => commands::db::cmd_db_import(&mut store, path)?,
+                DbCommand::Doctor { .. } => unreachable!(),
+            }
+        }
+        Some(Command::View { ref command }) => {
+            reject_filter(&filter, "view")?;
+            match command {
+                ViewCommand::Add {
+                    name,
+                    args,
+                    matches,
+                    unread,
+                } => commands::view::cmd_view_add(&mut store, name, args, matches, *unread)?,
+                ViewCommand::Ls => commands::view::cmd_view_ls(&store)?,
+                ViewCommand::Rm { name } => commands::view::cmd_view_rm(&mut store, name)?,
+            }
+        }
+        Some(Command::Search {
+            ref terms,
+            limit,
+            long,
+            open,
+        }) => {
+            reject_filter(&filter, "search")?;
+            let long = long || show_flags.long;
+            commands::search::cmd_search(&mut store, terms, limit, long, open)?;
+        }
         Some(Command::Feed {
-            command: FeedCommand::Add { ref urls },
+            command:
+                FeedCommand::Add {
+                    ref urls,
+                    ref file,
+                    select,
+                },
         }) => {
             reject_filter(&filter, "feed")?;
-            for url in urls.iter().filter(|url| !url.is_empty()) {
-                let resolved = commands::add::resolve_feed_url(url)?;
-                if resolved != *url {
-                    eprintln!("Discovered feed: {resolved}");
+            let http = utils::http::HttpOptions::from_config(&store)?;
+            if file.is_some() || urls.iter().any(|url| url == "-") {
+                let mut list = Vec::new();
+                for url in urls {
+                    if url == "-" {
+                        list.extend(commands::add::read_url_list(std::path::Path::new("-"))?);
+                    } else {
+                        list.push(url.clone());
+                    }
+                }
+                if let Some(file) = file {
+                    list.extend(commands::add::read_url_list(file)?);
                 }
-                store.transact(&format!("add feed: {resolved}"), |tx| {
-                    commands::add::cmd_add(tx, &resolved)
+                commands::add::cmd_add_bulk(&mut store, &list, &http, select)?;
+            } else {
+                let ingest_filter = data::get_config_value(&store, "ingest_filter");
+                let links = feed::links::LinkCleaner::from_config(&store)?;
+                let blocklist = feed::blocklist::Blocklist::from_config(&store)?;
+                let mut unfetched = false;
+                for url in urls.iter().filter(|url| !url.is_empty()) {
+                    commands::add::ensure_not_blocked(&blocklist, url)?;
+                    for resolved in commands::add::resolve_feed_url(url, &http, &links, select)? {
+                        commands::add::ensure_not_blocked(&blocklist, &resolved.url)?;
+                        let feed_url = resolved.url.clone();
+                        if feed_url != *url {
+                            eprintln!("Discovered feed: {feed_url}");
+                        }
+                        unfetched |= resolved.fetched.is_none();
+                        data::transact(&mut store, &format!("add feed: {feed_url}"), |tx| {
+                            commands::add::add_resolved(
+                                tx,
+                                resolved,
+                                ingest_filter.as_deref(),
+                                &blocklist,
+                            )
+                        })?;
+                        eprintln!("Added {feed_url}");
+                    }
+                }
+                if unfetched {
+                    eprintln!("Run `blog sync` to fetch posts.");
+                }
+            }
+        }
+        Some(Command::Feed {
+            command:
+                FeedCommand::Rm {
+                    ref urls,
+                    yes,
+                    keep_posts,
+                },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            commands::remove::cmd_remove_feeds(&mut store, urls, yes, keep_posts)?;
+        }
+        Some(Command::Feed {
+            command: FeedCommand::Pause { ref urls, hide },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            for url in urls.iter().filter(|url| !url.is_empty()) {
+                let paused = data::transact(&mut store, &format!("pause {url}"), |tx| {
+                    commands::pause::cmd_set_paused(tx, url, true, hide)
                 })?;
-                eprintln!("Added {resolved}");
+                eprintln!("Paused {paused}");
             }
-            eprintln!("Run `blog sync` to fetch posts.");
         }
         Some(Command::Feed {
-            command: FeedCommand::Rm { ref urls },
+            command: FeedCommand::Snooze { ref url, ref until },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            let until = utils::date::parse_until(until, chrono::Utc::now())?;
+            let url = data::transact(&mut store, &format!("snooze {url}"), |tx| {
+                commands::pause::cmd_snooze(tx, url, until)
+            })?;
+            eprintln!("Snoozed {url} until {}", utils::date::format_day(until));
+        }
+        Some(Command::Feed {
+            command: FeedCommand::Resume { ref urls },
         }) => {
             reject_filter(&filter, "feed")?;
             for url in urls.iter().filter(|url| !url.is_empty()) {
-                store.transact(&format!("remove {url}"), |tx| {
-                    commands::remove::cmd_remove(tx, url)
+                let resumed = data::transact(&mut store, &format!("resume {url}"), |tx| {
+                    commands::pause::cmd_set_paused(tx, url, false, false)
                 })?;
+                eprintln!("Resumed {resumed}");
             }
         }
         Some(Command::Feed {
-            command: FeedCommand::Ls,
+            command:
+                FeedCommand::Retain {
+                    ref url,
+                    ref policy,
+                },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            let url = data::transact(&mut store, &format!("set retention of {url}"), |tx| {
+                commands::retain::cmd_retain(tx, url, policy)
+            })?;
+            eprintln!("Retention for {url} set to {policy}");
+        }
+        Some(Command::Feed {
+            command:
+                FeedCommand::Timeout {
+                    ref url,
+                    ref seconds,
+                },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            let url = data::transact(&mut store, &format!("set timeout of {url}"), |tx| {
+                commands::timeout::cmd_timeout(tx, url, seconds)
+            })?;
+            eprintln!("Timeout for {url} set to {seconds}");
+        }
+        Some(Command::Feed {
+            command:
+                FeedCommand::Folder {
+                    ref url,
+                    ref folder,
+                },
         }) => {
             reject_filter(&filter, "feed")?;
-            commands::feed_ls::cmd_feed_ls(&store)?;
+            let url = data::transact(
+                &mut store,
+                &format!("move {url} to folder {folder}"),
+                |tx| commands::folder::cmd_folder(tx, url, folder),
+            )?;
+            eprintln!("Folder of {url} set to {folder}");
+        }
+        Some(Command::Feed {
+            command: FeedCommand::Ls { by_recent },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            commands::feed_ls::cmd_feed_ls(&store, by_recent)?;
+        }
+        Some(Command::Feed {
+            command: FeedCommand::Info { ref url },
+        }) => {
+            reject_filter(&filter, "feed")?;
+            commands::feed_info::cmd_feed_info(&store, url)?;
         }
         Some(Command::Feed {
             command: FeedCommand::Export,
@@ -283,20 +1112,94 @@ fn run() -> anyhow::Result<()> {
             commands::feed_export::cmd_feed_export(&store)?;
         }
         Some(Command::Feed {
-            command: FeedCommand::Import { ref path },
+            command:
+                FeedCommand::Import {
+                    ref path,
+                    ref bookmarks,
+                },
         }) => {
             reject_filter(&filter, "feed")?;
-            commands::import::cmd_import(&mut store, path)?;
+            match (path, bookmarks) {
+                (_, Some(bookmarks)) => {
+                    let http = utils::http::HttpOptions::from_config(&store)?;
+                    commands::import::cmd_import_bookmarks(&mut store, bookmarks, &http)?;
+                }
+                (Some(path), None) => commands::import::cmd_import(&mut store, path)?,
+                (None, None) => unreachable!("clap requires a path or --bookmarks"),
+            }
         }
 
-        Some(Command::Sync { ref feeds }) => {
+        Some(Command::Sync {
+            ref shorthands,
+            ref feeds,
+            git,
+            no_fetch,
+            no_push,
+            retry_failed,
+            offline,
+            quiet,
+            jobs,
+        }) => {
             reject_filter(&filter, "sync")?;
-            commands::sync::cmd_sync(&mut store, feeds)?;
+            utils::progress::set_quiet(quiet);
+            let selectors: Vec<String> = shorthands.iter().chain(feeds).cloned().collect();
+            let opts = commands::sync::SyncOptions {
+                jobs,
+                git,
+                no_fetch: no_fetch || offline,
+                no_push: no_push || offline,
+                retry_failed,
+                verbose: args.verbose > 0,
+            };
+            commands::sync::cmd_sync(&mut store, &selectors, &opts)?;
+        }
+        Some(Command::Watch {
+            interval,
+            ref callback,
+            ref listen,
+            jobs,
+        }) => {
+            reject_filter(&filter, "watch")?;
+            let opts = commands::watch::WatchOptions {
+                interval: std::time::Duration::from_secs(
+                    interval
+                        .checked_mul(60)
+                        .ok_or_else(|| anyhow::anyhow!("--interval is too long"))?,
+                ),
+                callback: callback.clone(),
+                listen: listen.clone(),
+                jobs,
+            };
+            commands::watch::cmd_watch(&mut store, &opts)?;
+        }
+        Some(Command::Serve { ref web, ref api }) => {
+            reject_filter(&filter, "serve")?;
+            let opts = commands::serve::ServeOptions {
+                web: web.clone(),
+                api: api.clone(),
+            };
+            commands::serve::cmd_serve(store.path(), &opts)?;
         }
         Some(Command::Git { ref args }) => {
             reject_filter(&filter, "git")?;
             store.git_passthrough(args)?;
         }
+        Some(Command::Diff {
+            ref from,
+            ref to,
+            fetch,
+        }) => {
+            reject_filter(&filter, "diff")?;
+            commands::diff::cmd_diff(store.path(), from.as_deref(), to.as_deref(), fetch)?;
+        }
+        Some(Command::History { limit }) => {
+            reject_filter(&filter, "history")?;
+            commands::history::cmd_history(store.path(), limit)?;
+        }
+        Some(Command::Maintenance) => {
+            reject_filter(&filter, "maintenance")?;
+            commands::maintenance::cmd_maintenance(&store)?;
+        }
         Some(Command::Config {
             command: ConfigCommand::Set { ref key, ref value },
         }) => {
@@ -315,7 +1218,9 @@ fn run() -> anyhow::Result<()> {
             reject_filter(&filter, "config")?;
             commands::config::cmd_config_unset(&mut store, key)?;
         }
-        Some(Command::Clone { .. }) => unreachable!(),
+        Some(Command::Clone { .. } | Command::Init { .. } | Command::Man { .. }) => {
+            unreachable!()
+        }
     }
     Ok(())
 }
@@ -323,13 +1228,20 @@ fn run() -> anyhow::Result<()> {
 fn main() {
     if let Err(e) = run() {
         eprintln!("{e}");
-        std::process::exit(1);
+        // Some feeds failing is told apart from the sync failing
+        let code = if e.is::<commands::sync::FeedsFailed>() {
+            commands::sync::FeedsFailed::EXIT_CODE
+        } else {
+            1
+        };
+        std::process::exit(code);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     fn args(input: &[&str]) -> Vec<String> {
         input.iter().map(|s| s.to_string()).collect()
@@ -363,6 +1275,29 @@ mod tests {
         assert_eq!(cmd, args(&["blog"]));
     }
 
+    #[test]
+    fn test_split_at_command_flag_with_value() {
+        let (filter, cmd) = split_at_command(args(&["blog", "@hn", "--lang", "de", "/d"]));
+        assert_eq!(filter, args(&["@hn", "/d"]));
+        assert_eq!(cmd, args(&["blog", "--lang", "de"]));
+    }
+
+    #[test]
+    fn test_split_at_command_store_flag() {
+        let (filter, cmd) =
+            split_at_command(args(&["blog", "--profile", "work", "@hn", "feed", "ls"]));
+        assert_eq!(filter, args(&["@hn"]));
+        assert_eq!(cmd, args(&["blog", "--profile", "work", "feed", "ls"]));
+    }
+
+    #[rstest]
+    #[case::command(&["blog", "podcast", "ls"], Some(1))]
+    #[case::after_flags(&["blog", "--store", "/tmp/x", "-l", "podcast"], Some(4))]
+    #[case::none(&["blog", "--profile", "work"], None)]
+    fn test_first_positional(#[case] input: &[&str], #[case] expected: Option<usize>) {
+        assert_eq!(first_positional(&args(input)), expected);
+    }
+
     #[test]
     fn test_split_at_command_help_flag() {
         let (filter, cmd) = split_at_command(args(&["blog", "--help"]));
@@ -396,7 +1331,8 @@ mod tests {
         use clap::CommandFactory;
         let clap_names: std::collections::HashSet<_> = Args::command()
             .get_subcommands()
-            .map(|c| c.get_name().to_string())
+            .flat_map(|c| std::iter::once(c.get_name()).chain(c.get_all_aliases()))
+            .map(str::to_string)
             .collect();
         let reserved: std::collections::HashSet<_> =
             RESERVED_COMMANDS.iter().map(|s| s.to_string()).collect();
@@ -409,7 +1345,7 @@ mod tests {
     #[test]
     fn test_parse_sync_without_feed_selectors() {
         let args = Args::parse_from(args(&["blog", "sync"]));
-        let Some(Command::Sync { feeds }) = args.command else {
+        let Some(Command::Sync { feeds, .. }) = args.command else {
             panic!("expected sync command");
         };
         assert!(feeds.is_empty());
@@ -418,7 +1354,7 @@ mod tests {
     #[test]
     fn test_parse_sync_with_one_feed_selector() {
         let args = Args::parse_from(args(&["blog", "sync", "--feed", "@df"]));
-        let Some(Command::Sync { feeds }) = args.command else {
+        let Some(Command::Sync { feeds, .. }) = args.command else {
             panic!("expected sync command");
         };
         assert_eq!(feeds, vec!["@df"]);
@@ -427,9 +1363,26 @@ mod tests {
     #[test]
     fn test_parse_sync_with_multiple_feed_selectors() {
         let args = Args::parse_from(args(&["blog", "sync", "--feed", "@df", "--feed", "@dg"]));
-        let Some(Command::Sync { feeds }) = args.command else {
+        let Some(Command::Sync { feeds, .. }) = args.command else {
             panic!("expected sync command");
         };
         assert_eq!(feeds, vec!["@df", "@dg"]);
     }
+
+    #[rstest]
+    #[case::long(&["blog", "sync", "--jobs", "4"], Some(4))]
+    #[case::short(&["blog", "sync", "-j", "2"], Some(2))]
+    #[case::unset(&["blog", "sync"], None)]
+    fn test_parse_sync_jobs(#[case] argv: &[&str], #[case] expected: Option<usize>) {
+        let args = Args::parse_from(args(argv));
+        let Some(Command::Sync { jobs, .. }) = args.command else {
+            panic!("expected sync command");
+        };
+        assert_eq!(jobs.map(|j| j.get()), expected);
+    }
+
+    #[test]
+    fn test_parse_sync_rejects_zero_jobs() {
+        assert!(Args::try_parse_from(args(&["blog", "sync", "-j", "0"])).is_err());
+    }
 }
@@ -2,15 +2,17 @@ use chrono::{NaiveDate, Utc};
 use chumsky::prelude::*;
 
 use super::{GroupKey, QueryDate, ReadFilter};
-use crate::utils::date::start_of_day;
+use crate::utils::date::{start_of_day, today};
 
 pub(super) enum Token {
-    Group(GroupKey),
+    Group(Vec<GroupKey>),
     FeedFilter(String),
+    FolderFilter(String),
     IdFilter(String),
     Range(Option<QueryDate>, Option<QueryDate>),
     Shorthand(String),
     ReadStatus(ReadFilter),
+    Match(String),
 }
 
 fn date_value_core<'a>() -> impl Parser<'a, &'a str, QueryDate, extra::Err<Rich<'a, char>>> {
@@ -36,8 +38,8 @@ fn date_value_core<'a>() -> impl Parser<'a, &'a str, QueryDate, extra::Err<Rich<
     choice((
         named.map(|name: &str| {
             let resolved = match name {
-                "today" => start_of_day(Utc::now().date_naive()),
-                "yesterday" => start_of_day((Utc::now() - chrono::Duration::days(1)).date_naive()),
+                "today" => start_of_day(today()),
+                "yesterday" => start_of_day(today() - chrono::Duration::days(1)),
                 _ => unreachable!(),
             };
             QueryDate {
@@ -81,17 +83,41 @@ pub(super) fn date_value_parser<'a>()
 }
 
 pub(super) fn arg_parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
+    // `/ta` is short for `/t /a`, and `/g` for grouping by folder, then feed
     let group = just('/')
-        .ignore_then(one_of("dwf").labelled("grouping: /d (date), /w (week), or /f (feed)"))
+        .ignore_then(
+            one_of("dwfatng")
+                .labelled(
+                    "grouping: /d (date), /w (week), /f (feed), /a (author), /t (tag), \
+                     /g (folder) or /n (none)",
+                )
+                .map(|c| match c {
+                    'd' => vec![GroupKey::Date],
+                    'w' => vec![GroupKey::Week],
+                    'f' => vec![GroupKey::Feed],
+                    'a' => vec![GroupKey::Author],
+                    't' => vec![GroupKey::Tag],
+                    'g' => vec![GroupKey::Folder, GroupKey::Feed],
+                    'n' => vec![GroupKey::None],
+                    _ => unreachable!(),
+                })
+                .repeated()
+                .at_least(1)
+                .collect::<Vec<_>>(),
+        )
         .then_ignore(end().labelled("end of grouping argument"))
-        .map(|c| {
-            Token::Group(match c {
-                'd' => GroupKey::Date,
-                'w' => GroupKey::Week,
-                'f' => GroupKey::Feed,
-                _ => unreachable!(),
-            })
-        });
+        .map(|groups| Token::Group(groups.concat()));
+
+    // Capitalized, to tell `/Tech` from groupings
+    let folder_filter = just('/')
+        .ignore_then(
+            any()
+                .filter(|c: &char| c.is_uppercase())
+                .then(any().repeated())
+                .to_slice(),
+        )
+        .then_ignore(end().labelled("end of folder filter"))
+        .map(|folder: &str| Token::FolderFilter(folder.to_string()));
 
     let feed_filter = just('@')
         .ignore_then(any().repeated().at_least(1).collect::<String>())
@@ -124,6 +150,11 @@ pub(super) fn arg_parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Ric
         .then_ignore(end().labelled("end of id filter"))
         .map(Token::IdFilter);
 
+    let text_match = just('~')
+        .ignore_then(any().repeated().at_least(1).collect::<String>())
+        .then_ignore(end().labelled("end of text match"))
+        .map(Token::Match);
+
     let shorthand = any()
         .filter(|c: &char| c.is_alphanumeric())
         .repeated()
@@ -132,7 +163,17 @@ pub(super) fn arg_parser<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Ric
         .then_ignore(end())
         .map(Token::Shorthand);
 
-    choice((range, group, feed_filter, id_filter, read_status, shorthand)).labelled(
-        "argument (3d..1d, /d, /w, /f, @feed, id:<id>, .read, .unread, .all, or shorthand)",
+    choice((
+        range,
+        group,
+        folder_filter,
+        feed_filter,
+        id_filter,
+        read_status,
+        text_match,
+        shorthand,
+    ))
+    .labelled(
+        "argument (3d..1d, /d, /w, /f, @feed, /Folder, id:<id>, .read, .unread, .all, ~word, or shorthand)",
     )
 }
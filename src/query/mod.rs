@@ -1,7 +1,6 @@
 mod grammar;
 pub(crate) mod resolve;
 
-use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
@@ -10,6 +9,8 @@ use chrono::{DateTime, Utc};
 use chumsky::prelude::*;
 
 use crate::data::schema::FeedItem;
+use crate::display::RenderCtx;
+use crate::utils::date::{format_day, local_date};
 use grammar::{Token, arg_parser};
 
 #[derive(Clone, Debug)]
@@ -47,28 +48,66 @@ pub(crate) struct DateFilter {
     pub until: Option<QueryDate>,
 }
 
+/// Group header of posts without categories when grouping by tag.
+pub(crate) const UNTAGGED: &str = "untagged";
+
+/// Group header of posts of feeds in no folder when grouping by folder.
+pub(crate) const NO_FOLDER: &str = "no folder";
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum GroupKey {
     Date,
     Week,
     Feed,
+    Author,
+    /// By category; posts filed under several are listed under each
+    Tag,
+    /// By the folder of the post's feed
+    Folder,
+    /// No grouping, e.g. to list `blog today` flat instead of by feed
+    None,
 }
 
 impl GroupKey {
-    pub(crate) fn extract(&self, item: &FeedItem, feed_labels: &HashMap<String, String>) -> String {
+    pub(crate) fn extract(&self, item: &FeedItem, ctx: &RenderCtx) -> String {
         match self {
             GroupKey::Date => item
                 .date
-                .map(|d| d.format("%Y-%m-%d").to_string())
+                .map(format_day)
                 .unwrap_or_else(|| "unknown".to_string()),
             GroupKey::Week => item
                 .date
-                .map(|d| d.format("%G-W%V").to_string())
+                .map(|d| local_date(d).format("%G-W%V").to_string())
                 .unwrap_or_else(|| "unknown".to_string()),
-            GroupKey::Feed => feed_labels
+            GroupKey::Feed => ctx
+                .feed_labels
                 .get(&item.feed)
                 .cloned()
                 .unwrap_or_else(|| item.feed.clone()),
+            GroupKey::Author if item.author.is_empty() => "unknown author".to_string(),
+            GroupKey::Author => item.author.clone(),
+            GroupKey::Tag => item
+                .categories
+                .first()
+                .cloned()
+                .unwrap_or_else(|| UNTAGGED.to_string()),
+            GroupKey::Folder => ctx
+                .feed_folders
+                .get(&item.feed)
+                .cloned()
+                .unwrap_or_else(|| NO_FOLDER.to_string()),
+            GroupKey::None => String::new(),
+        }
+    }
+
+    /// What posts in the same group share: the feed itself when grouping by
+    /// feed, so that feeds with the same label stay apart, the folder
+    /// ignoring case when grouping by folder, and the label otherwise.
+    pub(crate) fn identity(&self, item: &FeedItem, ctx: &RenderCtx) -> String {
+        match self {
+            GroupKey::Feed => item.feed.clone(),
+            GroupKey::Folder => self.extract(item, ctx).to_lowercase(),
+            _ => self.extract(item, ctx),
         }
     }
 
@@ -76,15 +115,35 @@ impl GroupKey {
         &self,
         a: &FeedItem,
         b: &FeedItem,
-        feed_labels: &HashMap<String, String>,
+        ctx: &RenderCtx,
     ) -> std::cmp::Ordering {
         match self {
             GroupKey::Date | GroupKey::Week => b.date.cmp(&a.date),
             GroupKey::Feed => {
-                let la = feed_labels.get(&a.feed).map_or(&a.feed, |s| s);
-                let lb = feed_labels.get(&b.feed).map_or(&b.feed, |s| s);
-                la.cmp(lb)
+                let la = ctx.feed_labels.get(&a.feed).map_or(&a.feed, |s| s);
+                let lb = ctx.feed_labels.get(&b.feed).map_or(&b.feed, |s| s);
+                la.cmp(lb).then_with(|| a.feed.cmp(&b.feed))
             }
+            // Feeds in no folder go last, and a folder comes right before the
+            // folders in it
+            GroupKey::Folder => {
+                let folder = |item: &FeedItem| {
+                    let folder = ctx.feed_folders.get(&item.feed);
+                    (folder.is_none(), folder.map(|f| f.to_lowercase()))
+                };
+                folder(a).cmp(&folder(b))
+            }
+            // Posts without an author go last
+            GroupKey::Author => (a.author.is_empty(), a.author.to_lowercase())
+                .cmp(&(b.author.is_empty(), b.author.to_lowercase())),
+            GroupKey::Tag => {
+                let tag = |item: &FeedItem| {
+                    let first = item.categories.first();
+                    (first.is_none(), first.map(|c| c.to_lowercase()))
+                };
+                tag(a).cmp(&tag(b))
+            }
+            GroupKey::None => std::cmp::Ordering::Equal,
         }
     }
 }
@@ -103,10 +162,14 @@ pub(crate) enum ReadFilter {
 pub(crate) struct Query {
     pub keys: Vec<GroupKey>,
     pub filter: Option<String>,
+    /// Only posts of feeds in this folder or the folders in it
+    pub folder: Option<String>,
     pub id_filter: Option<String>,
     pub date_filter: DateFilter,
     pub shorthands: Vec<String>,
     pub read_filter: ReadFilter,
+    /// Words that must appear in the title or summary
+    pub matches: Vec<String>,
 }
 
 pub(crate) const DEFAULT_QUERY: &str = ".unread 90d.. /w";
@@ -119,24 +182,29 @@ pub(crate) fn parse_query_str(input: &str) -> anyhow::Result<Query> {
 pub(crate) fn parse_query(args: &[String]) -> anyhow::Result<Query> {
     let mut keys = Vec::new();
     let mut filter = None;
+    let mut folder = None;
     let mut since = None;
     let mut until = None;
     let mut shorthands = Vec::new();
     let mut read_filter = ReadFilter::Any;
     let mut id_filter = None;
+    let mut matches = Vec::new();
 
     let parser = arg_parser();
 
     for arg in args {
         match parser.parse(arg).into_result() {
             Ok(token) => match token {
-                Token::Group(key) => {
-                    ensure!(keys.len() < 2, "Too many grouping arguments (max 2).");
-                    keys.push(key);
+                Token::Group(group) => {
+                    keys.extend(group);
+                    ensure!(keys.len() <= 2, "Too many grouping arguments (max 2).");
                 }
                 Token::FeedFilter(s) => {
                     filter = Some(s);
                 }
+                Token::FolderFilter(s) => {
+                    folder = Some(s);
+                }
                 Token::Range(s, u) => {
                     if let Some(s) = s {
                         since = Some(s);
@@ -154,6 +222,9 @@ pub(crate) fn parse_query(args: &[String]) -> anyhow::Result<Query> {
                 Token::IdFilter(id) => {
                     id_filter = Some(id);
                 }
+                Token::Match(word) => {
+                    matches.push(word);
+                }
             },
             Err(errs) => {
                 let messages: Vec<String> = errs.into_iter().map(|e| e.to_string()).collect();
@@ -170,18 +241,20 @@ pub(crate) fn parse_query(args: &[String]) -> anyhow::Result<Query> {
             s.resolved <= u.resolved,
             "Invalid date range: {} is after {}. \
              The start date must be before the end date, e.g. 3w..1w instead of 1w..3w.",
-            s.resolved.format("%Y-%m-%d"),
-            u.resolved.format("%Y-%m-%d"),
+            format_day(s.resolved),
+            format_day(u.resolved),
         );
     }
 
     Ok(Query {
         keys,
         filter,
+        folder,
         id_filter,
         date_filter: DateFilter { since, until },
         shorthands,
         read_filter,
+        matches,
     })
 }
 
@@ -197,17 +270,32 @@ impl fmt::Display for Query {
         if let Some(ref feed) = self.filter {
             parts.push(format!("@{feed}"));
         }
+        if let Some(ref folder) = self.folder {
+            parts.push(format!("/{folder}"));
+        }
+        for word in &self.matches {
+            parts.push(format!("~{word}"));
+        }
         match (&self.date_filter.since, &self.date_filter.until) {
             (Some(s), Some(u)) => parts.push(format!("{s}..{u}")),
             (Some(s), None) => parts.push(format!("{s}..")),
             (None, Some(u)) => parts.push(format!("..{u}")),
             (None, None) => {}
         }
-        for key in &self.keys {
+        let mut keys = self.keys.iter().peekable();
+        while let Some(key) = keys.next() {
             parts.push(match key {
                 GroupKey::Date => "/d".to_string(),
                 GroupKey::Week => "/w".to_string(),
                 GroupKey::Feed => "/f".to_string(),
+                GroupKey::Author => "/a".to_string(),
+                GroupKey::Tag => "/t".to_string(),
+                // `/g` stands for both
+                GroupKey::Folder => {
+                    keys.next_if_eq(&&GroupKey::Feed);
+                    "/g".to_string()
+                }
+                GroupKey::None => "/n".to_string(),
             });
         }
         write!(f, "{}", parts.join(" "))
@@ -229,6 +317,9 @@ mod tests {
     #[case::date("/d", GroupKey::Date)]
     #[case::week("/w", GroupKey::Week)]
     #[case::feed("/f", GroupKey::Feed)]
+    #[case::author("/a", GroupKey::Author)]
+    #[case::tag("/t", GroupKey::Tag)]
+    #[case::none("/n", GroupKey::None)]
     fn test_parse_group_arg(#[case] input: &str, #[case] expected: GroupKey) {
         let q = parse_query(&args(&[input])).unwrap();
         assert_eq!(q.keys, vec![expected]);
@@ -242,6 +333,37 @@ mod tests {
         assert!(msg.contains("Failed to parse argument"), "got: {msg}");
     }
 
+    #[test]
+    fn test_combined_group_arg() {
+        let q = parse_query(&args(&["/ta"])).unwrap();
+        assert_eq!(q.keys, vec![GroupKey::Tag, GroupKey::Author]);
+        assert_eq!(q.to_string(), "/t /a");
+
+        let result = parse_query(&args(&["/ta", "/d"]));
+        assert!(result.is_err());
+        let result = parse_query(&args(&["/dfw"]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_folder_group_arg_groups_by_folder_then_feed() {
+        let q = parse_query(&args(&["/g"])).unwrap();
+        assert_eq!(q.keys, vec![GroupKey::Folder, GroupKey::Feed]);
+        assert_eq!(q.to_string(), "/g");
+
+        assert!(parse_query(&args(&["/g", "/d"])).is_err());
+    }
+
+    #[rstest]
+    #[case::top_level("/Tech", "Tech")]
+    #[case::nested("/Tech/Rust", "Tech/Rust")]
+    fn test_folder_filter(#[case] input: &str, #[case] expected: &str) {
+        let q = parse_query(&args(&[input, "/d"])).unwrap();
+        assert_eq!(q.folder.as_deref(), Some(expected));
+        assert_eq!(q.keys, vec![GroupKey::Date]);
+        assert_eq!(q.to_string(), format!("{input} /d"));
+    }
+
     #[test]
     fn test_too_many_groups() {
         let result = parse_query(&args(&["/d", "/f", "/w"]));
@@ -531,6 +653,13 @@ mod tests {
         assert!(q.to_string().contains("@myblog"), "got: {}", q);
     }
 
+    #[test]
+    fn test_text_matches() {
+        let q = parse_query_str("~rust ~async @myblog").unwrap();
+        assert_eq!(q.matches, ["rust", "async"]);
+        assert_eq!(q.to_string(), "@myblog ~rust ~async");
+    }
+
     // ── Query Display: date ranges ───────────────────────────────────────────
 
     #[test]
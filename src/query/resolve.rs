@@ -1,41 +1,108 @@
 use std::collections::{HashMap, HashSet};
 
+use anyhow::{bail, ensure};
+use chrono::Utc;
+
 use crate::data::BlogData;
 use crate::data::index::{FeedIndex, feed_index};
+use crate::data::post_cache::{PostName, post_names};
 use crate::data::schema::FeedItem;
 use crate::display::build_feed_labels;
-use crate::shorthand::{RESERVED_COMMANDS, index_to_shorthand};
+use crate::shorthand::{compute_post_shorthands, hex_to_post_code};
+use crate::utils::html::to_text;
 
 use super::{Query, ReadFilter};
 
-pub(crate) struct PostIndex {
-    pub items: Vec<(String, FeedItem)>,
-    pub shorthands: HashMap<String, String>,
+/// How many candidates to list when a shorthand is ambiguous.
+const MAX_AMBIGUOUS_CANDIDATES: usize = 10;
+
+/// The posts of a store, newest first, with their shorthands. Posts are
+/// borrowed from the table, so that filtering a large store only copies the
+/// posts that are left.
+pub(crate) struct PostIndex<'a> {
+    pub items: Vec<(&'a str, &'a FeedItem)>,
+    /// Codes and shorthands of all posts by raw ID, including those
+    /// filtered out
+    names: HashMap<&'a str, PostName>,
 }
 
-impl PostIndex {
+impl PostIndex<'_> {
+    /// Shorthand of the post with raw ID `raw_id`, even if it was filtered
+    /// out.
+    pub(crate) fn shorthand(&self, raw_id: &str) -> Option<&str> {
+        self.names.get(raw_id).map(|name| name.shorthand.as_str())
+    }
+
+    /// Shorthands of the posts left, by raw ID.
+    pub(crate) fn shorthands(&self) -> HashMap<String, String> {
+        self.items
+            .iter()
+            .filter_map(|(_, item)| {
+                let shorthand = self.shorthand(&item.raw_id)?;
+                Some((item.raw_id.clone(), shorthand.to_string()))
+            })
+            .collect()
+    }
+
+    /// Keep posts named by `shorthands`. Like abbreviated git hashes, any
+    /// prefix of a post's code is accepted as long as it picks out a single
+    /// post among those left by the other filters.
     fn filter_by_shorthands(&mut self, shorthands: &[String]) -> anyhow::Result<()> {
         if shorthands.is_empty() {
             return Ok(());
         }
-        let sh_set: HashSet<&str> = shorthands.iter().map(|s| s.as_str()).collect();
-        let all_known: HashSet<&str> = self.shorthands.values().map(|s| s.as_str()).collect();
+        // Raw IDs of the posts named
+        let mut selected: HashSet<&str> = HashSet::new();
         for sh in shorthands {
-            if !all_known.contains(sh.as_str()) {
-                anyhow::bail!("Unknown shorthand: {sh}");
+            let exact: Vec<&str> = self
+                .names
+                .iter()
+                .filter(|(_, name)| name.shorthand == *sh)
+                .map(|(&id, _)| id)
+                .collect();
+            if !exact.is_empty() {
+                selected.extend(exact);
+                continue;
             }
+            let known: HashSet<&str> = self
+                .names
+                .iter()
+                .filter(|(_, name)| name.code.starts_with(sh.as_str()))
+                .map(|(&id, _)| id)
+                .collect();
+            ensure!(!known.is_empty(), "Unknown shorthand: {sh}");
+            let candidates: Vec<&FeedItem> = self
+                .items
+                .iter()
+                .map(|&(_, item)| item)
+                .filter(|item| known.contains(item.raw_id.as_str()))
+                .collect();
+            if candidates.len() > 1 {
+                bail!("{}", self.ambiguous_message(sh, &candidates));
+            }
+            selected.extend(candidates.iter().map(|item| item.raw_id.as_str()));
         }
-        self.items.retain(|(_, item)| {
-            self.shorthands
-                .get(&item.raw_id)
-                .is_some_and(|s| sh_set.contains(s.as_str()))
-        });
+        self.items
+            .retain(|(_, item)| selected.contains(item.raw_id.as_str()));
         Ok(())
     }
 
+    fn ambiguous_message(&self, sh: &str, candidates: &[&FeedItem]) -> String {
+        let mut msg = format!("Ambiguous shorthand: {sh} could be any of");
+        for item in candidates.iter().take(MAX_AMBIGUOUS_CANDIDATES) {
+            let shorthand = self.shorthand(&item.raw_id).unwrap_or("");
+            msg.push_str(&format!("\n  {shorthand}  {}", item.title));
+        }
+        if candidates.len() > MAX_AMBIGUOUS_CANDIDATES {
+            let more = candidates.len() - MAX_AMBIGUOUS_CANDIDATES;
+            msg.push_str(&format!("\n  ... and {more} more"));
+        }
+        msg
+    }
+
     fn filter_by_id(&mut self, id: &str) -> anyhow::Result<()> {
         let before = self.items.len();
-        self.items.retain(|(item_id, _)| item_id == id);
+        self.items.retain(|(item_id, _)| *item_id == id);
         if self.items.is_empty() && before > 0 {
             anyhow::bail!("No post found with id: {id}");
         } else if self.items.is_empty() {
@@ -52,6 +119,52 @@ impl PostIndex {
         Ok(())
     }
 
+    /// Keep posts of feeds in `folder` or a folder in it, ignoring case.
+    fn filter_by_folder(&mut self, fi: &FeedIndex, folder: &str) -> anyhow::Result<()> {
+        let wanted = folder.trim_end_matches('/').to_lowercase();
+        let feeds: HashSet<&str> = fi
+            .entries
+            .iter()
+            .filter(|e| {
+                let theirs = e.feed.folder.to_lowercase();
+                theirs == wanted || theirs.starts_with(&format!("{wanted}/"))
+            })
+            .map(|e| e.id.as_str())
+            .collect();
+        ensure!(!feeds.is_empty(), "No feeds in folder /{folder}");
+        self.items
+            .retain(|(_, item)| feeds.contains(item.feed.as_str()));
+        Ok(())
+    }
+
+    /// Drop posts of feeds paused with `--hide` or snoozed.
+    fn filter_hidden_feeds(&mut self, fi: &FeedIndex) {
+        let now = Utc::now();
+        let hidden: HashSet<&str> = fi
+            .entries
+            .iter()
+            .filter(|e| e.feed.hides_posts(now))
+            .map(|e| e.id.as_str())
+            .collect();
+        self.items
+            .retain(|(_, item)| !hidden.contains(item.feed.as_str()));
+    }
+
+    /// Drop posts snoozed with `blog snooze` until a time still to come.
+    fn filter_snoozed_posts(&mut self, store: &BlogData) {
+        let now = Utc::now();
+        let snoozed: HashSet<&str> = store
+            .snoozes()
+            .iter()
+            .filter(|(_, e)| !e.is_due(now))
+            .map(|(_, e)| e.post_id.as_str())
+            .collect();
+        if !snoozed.is_empty() {
+            self.items
+                .retain(|(_, item)| !snoozed.contains(item.raw_id.as_str()));
+        }
+    }
+
     fn filter_by_date(&mut self, query: &Query) {
         if let Some(ref since) = query.date_filter.since {
             self.items
@@ -63,6 +176,19 @@ impl PostIndex {
         }
     }
 
+    /// Keep posts whose title or summary contains all of `words`, ignoring
+    /// case.
+    fn filter_by_text(&mut self, words: &[String]) {
+        if words.is_empty() {
+            return;
+        }
+        let words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        self.items.retain(|(_, item)| {
+            let text = format!("{} {}", item.title, to_text(&item.summary)).to_lowercase();
+            words.iter().all(|w| text.contains(w.as_str()))
+        });
+    }
+
     fn filter_by_read_status(&mut self, filter: ReadFilter, store: &BlogData) {
         match filter {
             ReadFilter::Read | ReadFilter::Unread => {
@@ -80,30 +206,45 @@ impl PostIndex {
     }
 }
 
-pub(crate) fn post_index(table: &synctato::Table<FeedItem>) -> PostIndex {
-    let mut items: Vec<(String, FeedItem)> = table
-        .iter()
-        .map(|(id, item)| (id.to_string(), item.clone()))
-        .collect();
+/// Sort the posts of `table`, newest first, and work out their codes and
+/// shorthands.
+fn compute_post_names(table: &synctato::Table<FeedItem>) -> Vec<PostName> {
+    let mut items: Vec<(&str, &FeedItem)> = table.iter().collect();
     items.sort_by(|a, b| {
         b.1.date
             .cmp(&a.1.date)
             .then_with(|| a.1.raw_id.cmp(&b.1.raw_id))
     });
-    let mut idx = 0;
-    let shorthands = items
-        .iter()
-        .map(|(_, item)| {
-            loop {
-                let sh = index_to_shorthand(idx);
-                idx += 1;
-                if !RESERVED_COMMANDS.contains(&sh.as_str()) {
-                    return (item.raw_id.clone(), sh);
-                }
-            }
+    let codes: Vec<String> = items.iter().map(|(id, _)| hex_to_post_code(id)).collect();
+    let shorthands = compute_post_shorthands(&codes);
+    items
+        .into_iter()
+        .zip(codes)
+        .zip(shorthands)
+        .map(|(((id, _), code), shorthand)| PostName {
+            id: id.to_string(),
+            code,
+            shorthand,
         })
-        .collect();
-    PostIndex { items, shorthands }
+        .collect()
+}
+
+pub(crate) fn post_index(store: &BlogData) -> PostIndex<'_> {
+    let table = store.posts();
+    let names = post_names(store, || compute_post_names(table));
+    let posts: HashMap<&str, &FeedItem> = table.iter().collect();
+    let mut items = Vec::with_capacity(names.len());
+    let mut by_raw_id = HashMap::with_capacity(names.len());
+    for name in names {
+        if let Some((&id, &item)) = posts.get_key_value(name.id.as_str()) {
+            items.push((id, item));
+            by_raw_id.insert(item.raw_id.as_str(), name);
+        }
+    }
+    PostIndex {
+        items,
+        names: by_raw_id,
+    }
 }
 
 pub(crate) struct ResolvedPosts {
@@ -115,20 +256,40 @@ pub(crate) struct ResolvedPosts {
 pub(crate) fn resolve_posts(store: &BlogData, query: &Query) -> anyhow::Result<ResolvedPosts> {
     let fi = feed_index(store.feeds());
     let feed_labels = build_feed_labels(&fi);
-    let mut posts = post_index(store.posts());
-    posts.filter_by_shorthands(&query.shorthands)?;
+    let mut posts = post_index(store);
     if let Some(ref shorthand) = query.filter {
         posts.filter_by_feed(&fi, shorthand)?;
+    } else if query.read_filter != ReadFilter::All
+        && query.id_filter.is_none()
+        && query.shorthands.is_empty()
+    {
+        posts.filter_hidden_feeds(&fi);
+    }
+    if query.read_filter != ReadFilter::All
+        && query.id_filter.is_none()
+        && query.shorthands.is_empty()
+    {
+        posts.filter_snoozed_posts(store);
+    }
+    if let Some(ref folder) = query.folder {
+        posts.filter_by_folder(&fi, folder)?;
     }
     if let Some(ref id) = query.id_filter {
         posts.filter_by_id(id)?;
     }
     posts.filter_by_date(query);
+    posts.filter_by_text(&query.matches);
     posts.filter_by_read_status(query.read_filter, store);
+    // Last, so that a short prefix only has to be unique within the query
+    posts.filter_by_shorthands(&query.shorthands)?;
 
     Ok(ResolvedPosts {
-        items: posts.items,
-        shorthands: posts.shorthands,
+        shorthands: posts.shorthands(),
+        items: posts
+            .items
+            .into_iter()
+            .map(|(id, item)| (id.to_string(), item.clone()))
+            .collect(),
         feed_labels,
     })
 }
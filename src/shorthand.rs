@@ -7,10 +7,12 @@
 //! same across runs — users can rely on them in muscle memory and scripts.
 //!
 //! **Post shorthands** use a larger 34-character alphabet (home row + shifted +
-//! remaining letters) to keep shorthands short even with many posts. Posts are
-//! assigned sequentially by date order, skipping any that collide with reserved
-//! command names. These are ephemeral — they only need to stay stable between
-//! `blog sync` runs, since new posts shift the numbering.
+//! remaining letters) to keep shorthands short even with many posts. Each
+//! post's hex ID is expanded into that alphabet and the post gets the shortest
+//! prefix that no other post shares, extended if it would spell a reserved
+//! command name. Like feed shorthands they only depend on IDs, so syncing new
+//! posts leaves existing shorthands alone unless a new post happens to share
+//! their prefix, in which case they grow by a character.
 
 /// Alphabet for feed shorthands: the 9 home-row keys (base-9 encoding).
 const HOME_ROW: [char; 9] = ['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l'];
@@ -23,7 +25,39 @@ const POST_ALPHABET: [char; 34] = [
 ];
 
 pub(crate) const RESERVED_COMMANDS: &[&str] = &[
-    "show", "open", "read", "unread", "feed", "sync", "git", "clone", "export", "config",
+    "show",
+    "open",
+    "read",
+    "comments",
+    "discuss",
+    "discover",
+    "suggest",
+    "unread",
+    "feed",
+    "sync",
+    "pull",
+    "git",
+    "diff",
+    "history",
+    "maintenance",
+    "clone",
+    "init",
+    "man",
+    "export",
+    "config",
+    "watch",
+    "serve",
+    "digest",
+    "today",
+    "yesterday",
+    "next",
+    "new",
+    "later",
+    "snooze",
+    "save",
+    "search",
+    "view",
+    "db",
 ];
 
 /// Convert a hex string to a custom base using the given alphabet.
@@ -67,19 +101,57 @@ fn hex_to_home_row(hex: &str) -> String {
     hex_to_custom_base(hex, &HOME_ROW)
 }
 
-/// Convert a sequential index to a post shorthand using the post alphabet.
-pub(crate) fn index_to_shorthand(mut n: usize) -> String {
-    let base = POST_ALPHABET.len();
-    if n == 0 {
-        return POST_ALPHABET[0].to_string();
+/// Expand a hex ID into the post alphabet, most significant digit first.
+///
+/// The ID is read as a fraction `0.h1h2h3…` and repeatedly multiplied by the
+/// alphabet size, so every leading character is equally likely and a prefix of
+/// the result only depends on a prefix of the ID.
+pub(crate) fn hex_to_post_code(hex: &str) -> String {
+    let base = POST_ALPHABET.len() as u32;
+    let mut digits: Vec<u32> = hex.chars().map(|c| c.to_digit(16).unwrap_or(0)).collect();
+    // Enough characters to carry all the bits of the ID (log2(34) > 5)
+    let len = (hex.len() * 4).div_ceil(5).max(1);
+    let mut code = String::with_capacity(len);
+    for _ in 0..len {
+        let mut carry = 0;
+        for d in digits.iter_mut().rev() {
+            let v = *d * base + carry;
+            *d = v % 16;
+            carry = v / 16;
+        }
+        code.push(POST_ALPHABET[carry as usize]);
     }
-    let mut chars = Vec::new();
-    while n > 0 {
-        chars.push(POST_ALPHABET[n % base]);
-        n /= base;
+    code
+}
+
+/// Length of the common prefix of two ASCII strings.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count()
+}
+
+/// Compute the shortest prefix of each post code that no other code shares,
+/// skipping prefixes that spell a reserved command.
+pub(crate) fn compute_post_shorthands(codes: &[String]) -> Vec<String> {
+    let mut order: Vec<usize> = (0..codes.len()).collect();
+    order.sort_by(|&a, &b| codes[a].cmp(&codes[b]));
+
+    let mut shorthands = vec![String::new(); codes.len()];
+    for (pos, &i) in order.iter().enumerate() {
+        let code = &codes[i];
+        // In sorted order, the longest shared prefix is with a neighbour
+        let shared = [pos.checked_sub(1), order.get(pos + 1).map(|_| pos + 1)]
+            .into_iter()
+            .flatten()
+            .map(|n| common_prefix_len(code, &codes[order[n]]))
+            .max()
+            .unwrap_or(0);
+        let mut len = (shared + 1).min(code.len());
+        while len < code.len() && RESERVED_COMMANDS.contains(&&code[..len]) {
+            len += 1;
+        }
+        shorthands[i] = code[..len].to_string();
     }
-    chars.reverse();
-    chars.into_iter().collect()
+    shorthands
 }
 
 /// Compute the shortest unique home-row prefix for each hex ID.
@@ -154,51 +226,57 @@ mod tests {
     }
 
     #[rstest]
-    #[case::zero(0, "a")]
-    #[case::one(1, "s")]
-    #[case::thirty_three(33, "m")]
-    #[case::thirty_four(34, "sa")]
-    fn test_index_to_shorthand(#[case] index: usize, #[case] expected: &str) {
-        assert_eq!(index_to_shorthand(index), expected);
+    #[case::zero("0", "a")]
+    #[case::half("8", "L")]
+    #[case::max("ffff", "mmmG")]
+    fn test_hex_to_post_code(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(hex_to_post_code(input), expected);
     }
 
     #[test]
-    fn test_index_to_shorthand_uses_valid_chars() {
-        for i in 0..200 {
-            let sh = index_to_shorthand(i);
-            assert!(sh.chars().all(|c| POST_ALPHABET.contains(&c)));
-        }
+    fn test_post_code_prefix_depends_only_on_id_prefix() {
+        let a = hex_to_post_code("3f2a9c01d4e5b687");
+        let b = hex_to_post_code("3f2a9c01ffffffff");
+        assert_eq!(a.len(), 13);
+        assert_eq!(a[..5], b[..5]);
+        assert!(a.chars().all(|c| POST_ALPHABET.contains(&c)));
     }
 
     #[test]
-    fn test_index_to_shorthand_ordering() {
-        let sh0 = index_to_shorthand(0);
-        let sh33 = index_to_shorthand(33);
-        let sh34 = index_to_shorthand(34);
-        assert_eq!(sh0.len(), 1);
-        assert_eq!(sh33.len(), 1);
-        assert_eq!(sh34.len(), 2);
+    fn test_compute_post_shorthands_shortest_unique_prefixes() {
+        let codes: Vec<String> = ["asdf", "asgh", "kjl", "asdk"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            compute_post_shorthands(&codes),
+            ["asdf", "asg", "k", "asdk"]
+        );
+    }
+
+    #[test]
+    fn test_compute_post_shorthands_single_and_empty() {
+        assert_eq!(compute_post_shorthands(&["qwe".to_string()]), ["q"]);
+        assert!(compute_post_shorthands(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_compute_post_shorthands_stable_when_unrelated_posts_added() {
+        let before = compute_post_shorthands(&["asdf".to_string(), "kjl".to_string()]);
+        let after =
+            compute_post_shorthands(&["asdf".to_string(), "kjl".to_string(), "qwe".to_string()]);
+        assert_eq!(before, after[..2]);
     }
 
     #[test]
     fn test_shorthand_skips_reserved_commands() {
-        let mut idx = 0;
-        let mut generated = Vec::new();
-        for _ in 0..2000 {
-            loop {
-                let sh = index_to_shorthand(idx);
-                idx += 1;
-                if !RESERVED_COMMANDS.contains(&sh.as_str()) {
-                    generated.push(sh);
-                    break;
-                }
-            }
-        }
-        for sh in &generated {
-            assert!(
-                !RESERVED_COMMANDS.contains(&sh.as_str()),
-                "shorthand {sh} collides with a reserved command"
-            );
-        }
+        let codes: Vec<String> = ["openxy", "opeqq", "gitab", "giz"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            compute_post_shorthands(&codes),
+            ["openx", "opeq", "gita", "giz"]
+        );
     }
 }
@@ -1,5 +1,145 @@
-use chrono::{DateTime, NaiveDate, Utc};
+use std::str::FromStr;
+use std::sync::OnceLock;
 
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+/// Time zone that dates are shown, grouped and queried in, set with the
+/// `timezone` config key.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum DisplayTz {
+    #[default]
+    Local,
+    Utc,
+}
+
+impl FromStr for DisplayTz {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "local" => Ok(Self::Local),
+            "utc" | "UTC" => Ok(Self::Utc),
+            _ => anyhow::bail!("expected local or utc"),
+        }
+    }
+}
+
+static DISPLAY_TZ: OnceLock<DisplayTz> = OnceLock::new();
+
+/// Use `tz` for all dates from now on. Until this is called, as in unit
+/// tests, dates are in UTC.
+pub(crate) fn set_display_tz(tz: DisplayTz) {
+    let _ = DISPLAY_TZ.set(tz);
+}
+
+fn display_tz() -> DisplayTz {
+    DISPLAY_TZ.get().copied().unwrap_or(DisplayTz::Utc)
+}
+
+/// The calendar date of `dt` in the display time zone.
+pub(crate) fn local_date(dt: DateTime<Utc>) -> NaiveDate {
+    match display_tz() {
+        DisplayTz::Local => dt.with_timezone(&Local).date_naive(),
+        DisplayTz::Utc => dt.date_naive(),
+    }
+}
+
+/// `dt` as `YYYY-MM-DD` in the display time zone.
+pub(crate) fn format_day(dt: DateTime<Utc>) -> String {
+    local_date(dt).format("%Y-%m-%d").to_string()
+}
+
+/// `dt` as `YYYY-MM-DD HH:MM` in the display time zone.
+pub(crate) fn format_minute(dt: DateTime<Utc>) -> String {
+    const FORMAT: &str = "%Y-%m-%d %H:%M";
+    match display_tz() {
+        DisplayTz::Local => dt.with_timezone(&Local).format(FORMAT).to_string(),
+        DisplayTz::Utc => dt.format(FORMAT).to_string(),
+    }
+}
+
+/// Today's date in the display time zone.
+pub(crate) fn today() -> NaiveDate {
+    local_date(Utc::now())
+}
+
+/// Midnight at the start of `date` in the display time zone.
 pub(crate) fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
-    date.and_hms_opt(0, 0, 0).unwrap().and_utc()
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    match display_tz() {
+        DisplayTz::Local => Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            // Midnight skipped by a DST change: the day starts an hour later
+            .or_else(|| {
+                Local
+                    .from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| midnight.and_utc()),
+        DisplayTz::Utc => midnight.and_utc(),
+    }
+}
+
+/// When a snooze given as `until` ends: a time from `now` such as `12h`,
+/// `3d`, `2w` or `1m` (30 days), `tomorrow`, or the start of a day such as
+/// `2024-06-01`, which must be in the future.
+pub(crate) fn parse_until(until: &str, now: DateTime<Utc>) -> anyhow::Result<DateTime<Utc>> {
+    let invalid = || {
+        anyhow::anyhow!("Invalid time {until:?}: use e.g. 12h, 3d, 2w, 1m, tomorrow or 2024-06-01")
+    };
+    let end = if until == "tomorrow" {
+        start_of_day(local_date(now) + chrono::Duration::days(1))
+    } else if let Ok(day) = NaiveDate::parse_from_str(until, "%Y-%m-%d") {
+        start_of_day(day)
+    } else {
+        let unit_at = until
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let n: i64 = until[..unit_at].parse().map_err(|_| invalid())?;
+        let duration = match &until[unit_at..] {
+            "h" => chrono::Duration::hours(n),
+            "d" => chrono::Duration::days(n),
+            "w" => chrono::Duration::weeks(n),
+            "m" => chrono::Duration::days(n * 30),
+            _ => return Err(invalid()),
+        };
+        now + duration
+    };
+    anyhow::ensure!(end > now, "{until} is not in the future");
+    Ok(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[rstest]
+    #[case("12h", "2024-05-01T22:00:00Z")]
+    #[case("3d", "2024-05-04T10:00:00Z")]
+    #[case("2w", "2024-05-15T10:00:00Z")]
+    #[case("1m", "2024-05-31T10:00:00Z")]
+    #[case("tomorrow", "2024-05-02T00:00:00Z")]
+    #[case("2024-06-01", "2024-06-01T00:00:00Z")]
+    fn test_parse_until(#[case] input: &str, #[case] expected: &str) {
+        let now = at("2024-05-01T10:00:00Z");
+        assert_eq!(parse_until(input, now).unwrap(), at(expected));
+    }
+
+    #[rstest]
+    #[case("soon")]
+    #[case("3")]
+    #[case("3y")]
+    #[case("d")]
+    #[case("2024-04-01")]
+    #[case("0d")]
+    fn test_parse_until_rejects(#[case] input: &str) {
+        assert!(parse_until(input, at("2024-05-01T10:00:00Z")).is_err());
+    }
 }
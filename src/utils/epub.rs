@@ -0,0 +1,254 @@
+use std::io::{Cursor, Seek, Write};
+
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// One section of the book.
+pub(crate) struct Chapter {
+    pub title: String,
+    /// Well-formed XHTML placed inside `<body>`
+    pub body: String,
+}
+
+pub(crate) struct Book {
+    /// Unique identifier for the publication, e.g. a URN
+    pub id: String,
+    pub title: String,
+    pub modified: chrono::DateTime<chrono::Utc>,
+    pub chapters: Vec<Chapter>,
+}
+
+fn chapter_href(index: usize) -> String {
+    format!("chapter-{}.xhtml", index + 1)
+}
+
+type XmlWriter = Writer<Cursor<Vec<u8>>>;
+
+fn new_document() -> anyhow::Result<XmlWriter> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    Ok(writer)
+}
+
+fn finish_document(writer: XmlWriter) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = writer.into_inner().into_inner();
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+fn text_element(writer: &mut XmlWriter, start: BytesStart, text: &str) -> anyhow::Result<()> {
+    let name = String::from_utf8(start.name().as_ref().to_vec())?;
+    writer.write_event(Event::Start(start))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+fn html_start() -> BytesStart<'static> {
+    let mut html = BytesStart::new("html");
+    html.push_attribute(("xmlns", "http://www.w3.org/1999/xhtml"));
+    html.push_attribute(("xmlns:epub", "http://www.idpf.org/2007/ops"));
+    html
+}
+
+fn package_document(book: &Book) -> anyhow::Result<Vec<u8>> {
+    let mut w = new_document()?;
+
+    let mut package = BytesStart::new("package");
+    package.push_attribute(("xmlns", "http://www.idpf.org/2007/opf"));
+    package.push_attribute(("version", "3.0"));
+    package.push_attribute(("unique-identifier", "book-id"));
+    w.write_event(Event::Start(package))?;
+
+    let mut metadata = BytesStart::new("metadata");
+    metadata.push_attribute(("xmlns:dc", "http://purl.org/dc/elements/1.1/"));
+    w.write_event(Event::Start(metadata))?;
+    let mut identifier = BytesStart::new("dc:identifier");
+    identifier.push_attribute(("id", "book-id"));
+    text_element(&mut w, identifier, &book.id)?;
+    text_element(&mut w, BytesStart::new("dc:title"), &book.title)?;
+    text_element(&mut w, BytesStart::new("dc:language"), "en")?;
+    let mut modified = BytesStart::new("meta");
+    modified.push_attribute(("property", "dcterms:modified"));
+    let modified_at = book.modified.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    text_element(&mut w, modified, &modified_at)?;
+    w.write_event(Event::End(BytesEnd::new("metadata")))?;
+
+    w.write_event(Event::Start(BytesStart::new("manifest")))?;
+    let mut nav = BytesStart::new("item");
+    nav.push_attribute(("id", "nav"));
+    nav.push_attribute(("href", "nav.xhtml"));
+    nav.push_attribute(("media-type", "application/xhtml+xml"));
+    nav.push_attribute(("properties", "nav"));
+    w.write_event(Event::Empty(nav))?;
+    for i in 0..book.chapters.len() {
+        let mut item = BytesStart::new("item");
+        item.push_attribute(("id", format!("chapter-{}", i + 1).as_str()));
+        item.push_attribute(("href", chapter_href(i).as_str()));
+        item.push_attribute(("media-type", "application/xhtml+xml"));
+        w.write_event(Event::Empty(item))?;
+    }
+    w.write_event(Event::End(BytesEnd::new("manifest")))?;
+
+    w.write_event(Event::Start(BytesStart::new("spine")))?;
+    for i in 0..book.chapters.len() {
+        let mut itemref = BytesStart::new("itemref");
+        itemref.push_attribute(("idref", format!("chapter-{}", i + 1).as_str()));
+        w.write_event(Event::Empty(itemref))?;
+    }
+    w.write_event(Event::End(BytesEnd::new("spine")))?;
+
+    w.write_event(Event::End(BytesEnd::new("package")))?;
+    finish_document(w)
+}
+
+fn nav_document(book: &Book) -> anyhow::Result<Vec<u8>> {
+    let mut w = new_document()?;
+    w.write_event(Event::Start(html_start()))?;
+    w.write_event(Event::Start(BytesStart::new("head")))?;
+    text_element(&mut w, BytesStart::new("title"), &book.title)?;
+    w.write_event(Event::End(BytesEnd::new("head")))?;
+    w.write_event(Event::Start(BytesStart::new("body")))?;
+
+    let mut nav = BytesStart::new("nav");
+    nav.push_attribute(("epub:type", "toc"));
+    w.write_event(Event::Start(nav))?;
+    text_element(&mut w, BytesStart::new("h1"), &book.title)?;
+    w.write_event(Event::Start(BytesStart::new("ol")))?;
+    for (i, chapter) in book.chapters.iter().enumerate() {
+        w.write_event(Event::Start(BytesStart::new("li")))?;
+        let mut link = BytesStart::new("a");
+        link.push_attribute(("href", chapter_href(i).as_str()));
+        text_element(&mut w, link, &chapter.title)?;
+        w.write_event(Event::End(BytesEnd::new("li")))?;
+    }
+    w.write_event(Event::End(BytesEnd::new("ol")))?;
+    w.write_event(Event::End(BytesEnd::new("nav")))?;
+
+    w.write_event(Event::End(BytesEnd::new("body")))?;
+    w.write_event(Event::End(BytesEnd::new("html")))?;
+    finish_document(w)
+}
+
+fn chapter_document(chapter: &Chapter) -> anyhow::Result<Vec<u8>> {
+    let mut w = new_document()?;
+    w.write_event(Event::Start(html_start()))?;
+    w.write_event(Event::Start(BytesStart::new("head")))?;
+    text_element(&mut w, BytesStart::new("title"), &chapter.title)?;
+    w.write_event(Event::End(BytesEnd::new("head")))?;
+    w.write_event(Event::Start(BytesStart::new("body")))?;
+    w.write_event(Event::Text(BytesText::from_escaped(chapter.body.as_str())))?;
+    w.write_event(Event::End(BytesEnd::new("body")))?;
+    w.write_event(Event::End(BytesEnd::new("html")))?;
+    finish_document(w)
+}
+
+/// Write `book` as an EPUB 3 container.
+pub(crate) fn write_epub<W: Write + Seek>(out: W, book: &Book) -> anyhow::Result<()> {
+    let mut zip = ZipWriter::new(out);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    // The spec requires `mimetype` to be the first, uncompressed entry
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"application/epub+zip")?;
+
+    zip.start_file("META-INF/container.xml", stored)?;
+    zip.write_all(CONTAINER_XML.as_bytes())?;
+
+    zip.start_file("OEBPS/content.opf", stored)?;
+    zip.write_all(&package_document(book)?)?;
+
+    zip.start_file("OEBPS/nav.xhtml", stored)?;
+    zip.write_all(&nav_document(book)?)?;
+
+    for (i, chapter) in book.chapters.iter().enumerate() {
+        zip.start_file(format!("OEBPS/{}", chapter_href(i)), stored)?;
+        zip.write_all(&chapter_document(chapter)?)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    fn sample_book() -> Book {
+        Book {
+            id: "urn:test:1".to_string(),
+            title: "Reading <list>".to_string(),
+            modified: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            chapters: vec![
+                Chapter {
+                    title: "First & foremost".to_string(),
+                    body: "<p>Hello</p>".to_string(),
+                },
+                Chapter {
+                    title: "Second".to_string(),
+                    body: "<p>World</p>".to_string(),
+                },
+            ],
+        }
+    }
+
+    fn read_entry(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut s = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut s)
+            .unwrap();
+        s
+    }
+
+    #[test]
+    fn test_write_epub_layout() {
+        let mut buf = Cursor::new(Vec::new());
+        write_epub(&mut buf, &sample_book()).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(buf.into_inner())).unwrap();
+        let names: Vec<String> = archive.file_names().map(String::from).collect();
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        for expected in [
+            "META-INF/container.xml",
+            "OEBPS/content.opf",
+            "OEBPS/nav.xhtml",
+            "OEBPS/chapter-1.xhtml",
+            "OEBPS/chapter-2.xhtml",
+        ] {
+            assert!(names.iter().any(|n| n == expected), "missing {expected}");
+        }
+        assert_eq!(read_entry(&mut archive, "mimetype"), "application/epub+zip");
+    }
+
+    #[test]
+    fn test_write_epub_documents() {
+        let mut buf = Cursor::new(Vec::new());
+        write_epub(&mut buf, &sample_book()).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(buf.into_inner())).unwrap();
+
+        let opf = read_entry(&mut archive, "OEBPS/content.opf");
+        assert!(opf.contains("<dc:title>Reading &lt;list&gt;</dc:title>"));
+        assert!(opf.contains("1970-01-01T00:00:00Z"));
+        assert!(opf.contains(r#"<itemref idref="chapter-2"/>"#));
+
+        let nav = read_entry(&mut archive, "OEBPS/nav.xhtml");
+        assert!(nav.contains(r#"<a href="chapter-1.xhtml">First &amp; foremost</a>"#));
+
+        let chapter = read_entry(&mut archive, "OEBPS/chapter-2.xhtml");
+        assert!(chapter.contains("<p>World</p>"));
+    }
+}
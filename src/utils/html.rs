@@ -0,0 +1,350 @@
+/// Elements whose text content is never meant to be read.
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "head"];
+
+/// Elements kept by [`to_xhtml`]; any other tag is dropped but its text kept.
+const XHTML_ELEMENTS: &[&str] = &[
+    "p",
+    "br",
+    "hr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "em",
+    "strong",
+    "b",
+    "i",
+    "code",
+    "pre",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "a",
+    "dl",
+    "dt",
+    "dd",
+    "sub",
+    "sup",
+];
+const VOID_ELEMENTS: &[&str] = &["br", "hr"];
+
+/// Elements dropped from titles by [`title_text`]. Anything else that looks
+/// like a tag, such as the `<T>` in `Vec<T>`, is part of the title.
+const TITLE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "big", "br", "cite", "code", "del", "dfn", "div", "em", "font", "i", "img",
+    "ins", "kbd", "mark", "p", "q", "s", "samp", "small", "span", "strike", "strong", "sub", "sup",
+    "tt", "u", "var",
+];
+
+/// Decode a single entity body (the part between `&` and `;`).
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(num) = entity.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "hellip" => '\u{2026}',
+        "mdash" => '\u{2014}',
+        "ndash" => '\u{2013}',
+        "lsquo" => '\u{2018}',
+        "rsquo" => '\u{2019}',
+        "ldquo" => '\u{201c}',
+        "rdquo" => '\u{201d}',
+        "copy" => '\u{a9}',
+        "reg" => '\u{ae}',
+        "trade" => '\u{2122}',
+        _ => return None,
+    })
+}
+
+/// Replace HTML character references with the characters they stand for.
+/// Unknown or malformed references are left untouched.
+pub(crate) fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..=end]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Lowercased tag name of a tag body like `p class="x"` or `/p`.
+pub(crate) fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+/// Value of attribute `name` in a tag body like `a href="x"`. The name is
+/// matched case-insensitively; the value is returned as written.
+pub(crate) fn attr(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let needle = format!("{name}=");
+    let mut search_from = 0;
+    let pos = loop {
+        let pos = lower[search_from..].find(&needle)? + search_from;
+        // Require a word boundary so `href=` doesn't match `data-href=`
+        if pos > 0 && lower.as_bytes()[pos - 1].is_ascii_whitespace() {
+            break pos;
+        }
+        search_from = pos + 1;
+    };
+    let rest = &tag[pos + needle.len()..];
+    let value = match rest.chars().next()? {
+        quote @ ('"' | '\'') => rest[1..].split(quote).next()?,
+        _ => rest.split(|c: char| c.is_whitespace() || c == '>').next()?,
+    };
+    Some(decode_entities(value))
+}
+
+/// Escape text for use in XML content or attribute values.
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Clean up an HTML fragment into well-formed XHTML: only simple formatting
+/// elements survive, attributes other than absolute link targets are removed
+/// and unclosed elements are closed.
+pub(crate) fn to_xhtml(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut skipping: Option<String> = None;
+    let mut rest = html;
+
+    let push_text = |out: &mut String, text: &str| {
+        out.push_str(&escape(&decode_entities(text)));
+    };
+
+    while let Some(start) = rest.find('<') {
+        if skipping.is_none() {
+            push_text(&mut out, &rest[..start]);
+        }
+        let Some(end) = rest[start..].find('>').map(|i| i + start) else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        let name = tag_name(tag);
+        if let Some(skipped) = &skipping {
+            if tag.starts_with('/') && *skipped == name {
+                skipping = None;
+            }
+            continue;
+        }
+        if SKIPPED_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            skipping = Some(name);
+        } else if !XHTML_ELEMENTS.contains(&name.as_str()) {
+            continue;
+        } else if tag.starts_with('/') {
+            // Closing an outer element implicitly closes everything inside it
+            if let Some(pos) = open.iter().rposition(|n| *n == name) {
+                for n in open.drain(pos..).rev() {
+                    out.push_str(&format!("</{n}>"));
+                }
+            }
+        } else if VOID_ELEMENTS.contains(&name.as_str()) {
+            out.push_str(&format!("<{name}/>"));
+        } else {
+            let href = (name == "a")
+                .then(|| attr(tag, "href"))
+                .flatten()
+                .filter(|h| h.starts_with("http://") || h.starts_with("https://"));
+            match href {
+                Some(href) => out.push_str(&format!("<a href=\"{}\">", escape(&href))),
+                None => out.push_str(&format!("<{name}>")),
+            }
+            open.push(name);
+        }
+    }
+    if skipping.is_none() {
+        push_text(&mut out, rest);
+    }
+    for n in open.iter().rev() {
+        out.push_str(&format!("</{n}>"));
+    }
+    out
+}
+
+/// Convert an HTML fragment to plain text: tags are dropped, entities are
+/// decoded and runs of whitespace are collapsed into single spaces.
+pub(crate) fn to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut skipping: Option<String> = None;
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        if skipping.is_none() {
+            text.push_str(&rest[..start]);
+        }
+        let Some(end) = rest[start..].find('>').map(|i| i + start) else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..end];
+        let name = tag_name(tag);
+        match &skipping {
+            Some(skipped) if tag.starts_with('/') && *skipped == name => skipping = None,
+            Some(_) => {}
+            None if SKIPPED_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') => {
+                skipping = Some(name);
+            }
+            // Tags separate words, e.g. `<p>a</p><p>b</p>` reads as "a b"
+            None => text.push(' '),
+        }
+        rest = &rest[end + 1..];
+    }
+    if skipping.is_none() {
+        text.push_str(rest);
+    }
+
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Plain text of a post title that may contain markup: HTML tags are dropped,
+/// entities are decoded and runs of whitespace are collapsed. Unlike
+/// [`to_text`], inline tags don't separate words and only known HTML elements
+/// count as tags.
+pub(crate) fn title_text(title: &str) -> String {
+    let mut text = String::with_capacity(title.len());
+    let mut rest = title;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>').map(|i| i + start) else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start..=end];
+        let name = tag_name(&tag[1..tag.len() - 1]);
+        if !TITLE_ELEMENTS.contains(&name.as_str()) {
+            text.push_str(tag);
+        } else if matches!(name.as_str(), "br" | "p" | "div") {
+            text.push(' ');
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plain("Hello world", "Hello world")]
+    #[case::paragraphs("<p>First</p><p>Second</p>", "First Second")]
+    #[case::inline("Some <em>emphasis</em> here", "Some emphasis here")]
+    #[case::whitespace("  lots \n\n of\tspace  ", "lots of space")]
+    #[case::named_entities("Fish &amp; Chips &lt;3", "Fish & Chips <3")]
+    #[case::numeric_entities("It&#8217;s &#x2014; fine", "It\u{2019}s \u{2014} fine")]
+    #[case::unknown_entity("AT&T &bogus; rocks", "AT&T &bogus; rocks")]
+    #[case::script("<script>alert(1)</script>Visible", "Visible")]
+    #[case::style("<style>p { color: red }</style><p>Text</p>", "Text")]
+    #[case::unclosed_tag("Text <b", "Text <b")]
+    fn test_to_text(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_text(input), expected);
+    }
+
+    #[rstest]
+    #[case::plain("Hello world", "Hello world")]
+    #[case::inline_markup("Why <em>unsafe</em> is <code>fine</code>", "Why unsafe is fine")]
+    #[case::inside_word("Rust<em>ace</em>ans", "Rustaceans")]
+    #[case::attributes("<span class=\"x\">Styled</span> title", "Styled title")]
+    #[case::entities("It&#8217;s Fish &amp; Chips", "It\u{2019}s Fish & Chips")]
+    #[case::generics("Vec<T> and Option<String>", "Vec<T> and Option<String>")]
+    #[case::comparison("a < b > c", "a < b > c")]
+    #[case::line_breaks("Two<br/>lines\n here", "Two lines here")]
+    fn test_title_text(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(title_text(input), expected);
+    }
+
+    #[rstest]
+    #[case::keeps_formatting("<p>Some <em>text</em></p>", "<p>Some <em>text</em></p>")]
+    #[case::drops_attributes("<p class=\"x\" onclick=\"y\">a</p>", "<p>a</p>")]
+    #[case::unwraps_unknown("<div><span>a</span></div>", "a")]
+    #[case::void_elements("a<br>b<hr>", "a<br/>b<hr/>")]
+    #[case::closes_unclosed("<p>a<em>b", "<p>a<em>b</em></p>")]
+    #[case::implicit_close("<ul><li>a<li>b</ul>", "<ul><li>a<li>b</li></li></ul>")]
+    #[case::ignores_stray_close("a</p>b", "ab")]
+    #[case::escapes_text("1 &lt; 2 &amp; 3 > 2", "1 &lt; 2 &amp; 3 &gt; 2")]
+    #[case::keeps_http_links(
+        "<a href=\"https://e.com/?a=1&amp;b=2\" target=\"_blank\">x</a>",
+        "<a href=\"https://e.com/?a=1&amp;b=2\">x</a>"
+    )]
+    #[case::drops_script_links("<a href=\"javascript:x()\">x</a>", "<a>x</a>")]
+    #[case::skips_script("<script>var x = 1;</script>ok", "ok")]
+    fn test_to_xhtml(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_xhtml(input), expected);
+    }
+
+    #[rstest]
+    #[case::double_quoted("a href=\"/X\"", "href", Some("/X"))]
+    #[case::single_quoted("a HREF='/X'", "href", Some("/X"))]
+    #[case::unquoted("img src=/x.png alt=y", "src", Some("/x.png"))]
+    #[case::entity("a href=\"?a=1&amp;b=2\"", "href", Some("?a=1&b=2"))]
+    #[case::prefixed_name("a data-href=\"/x\"", "href", None)]
+    #[case::missing("a", "href", None)]
+    fn test_attr(#[case] tag: &str, #[case] name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(attr(tag, name).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case::open("p class=\"x\"", "p")]
+    #[case::close("/DIV", "div")]
+    #[case::self_closing("br/", "br")]
+    fn test_tag_name(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(tag_name(input), expected);
+    }
+}
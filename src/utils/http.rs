@@ -1,13 +1,550 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::num::NonZeroU64;
+use std::str::FromStr;
+use std::sync::{Condvar, Mutex};
 use std::time::Duration;
 
+use anyhow::{Context, ensure};
+
+/// Longest `Retry-After` we are willing to sleep through; servers asking for
+/// more are treated as down for this sync.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How long requests may take.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Timeouts {
+    /// The whole request, from connecting to reading the last byte
+    pub total: Duration,
+    /// Establishing the connection, including the DNS lookup and TLS
+    pub connect: Option<Duration>,
+    /// Waiting for the response to start, and then for the rest of the body
+    pub read: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            total: Duration::from_secs(10),
+            connect: None,
+            read: None,
+        }
+    }
+}
+
+impl Timeouts {
+    /// Read the `timeout`, `connect_timeout` and `read_timeout` config keys,
+    /// in seconds.
+    pub(crate) fn from_config(store: &crate::data::BlogData) -> anyhow::Result<Self> {
+        let secs = |key| {
+            crate::data::get_config_parsed::<NonZeroU64>(store, key)
+                .map(|v| v.map(|secs| Duration::from_secs(secs.get())))
+        };
+        let mut timeouts = Self::default();
+        if let Some(total) = secs("timeout")? {
+            timeouts.total = total;
+        }
+        timeouts.connect = secs("connect_timeout")?;
+        timeouts.read = secs("read_timeout")?;
+        Ok(timeouts)
+    }
+}
+
+/// Largest response body read unless the `max_response_size` config key says
+/// otherwise; ureq's own default.
+const DEFAULT_MAX_BODY: u64 = 10 * 1024 * 1024;
+
+/// A number of bytes, written as e.g. `500000`, `512KB` or `20MB`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let unit_size: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1,
+            "KB" | "K" => 1024,
+            "MB" | "M" => 1024 * 1024,
+            "GB" | "G" => 1024 * 1024 * 1024,
+            other => anyhow::bail!("unknown unit {other:?} (expected KB, MB or GB)"),
+        };
+        let number: u64 = number.parse()?;
+        ensure!(number > 0, "must be more than zero");
+        number
+            .checked_mul(unit_size)
+            .map(ByteSize)
+            .context("too large")
+    }
+}
+
+/// How to reach the network: the proxy, timeouts and response size limit
+/// from the config.
+#[derive(Clone, Debug)]
+pub(crate) struct HttpOptions {
+    /// Overrides the proxy from the environment
+    pub proxy: Option<ureq::Proxy>,
+    pub timeouts: Timeouts,
+    /// Responses with a larger body are given up on
+    pub max_body: u64,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            timeouts: Timeouts::default(),
+            max_body: DEFAULT_MAX_BODY,
+        }
+    }
+}
+
+impl HttpOptions {
+    pub(crate) fn from_config(store: &crate::data::BlogData) -> anyhow::Result<Self> {
+        Ok(Self {
+            proxy: configured_proxy(store)?,
+            timeouts: Timeouts::from_config(store)?,
+            max_body: crate::data::get_config_parsed::<ByteSize>(store, "max_response_size")?
+                .map_or(DEFAULT_MAX_BODY, |size| size.0),
+        })
+    }
+}
+
 /// Note: ureq enforces a default 10 MB limit on `read_to_vec()`/`read_to_string()`
-/// and a default cap of 10 redirects (with error on exceed),
-/// so all call sites are protected without explicit caps.
-pub(crate) fn http_client() -> ureq::Agent {
-    ureq::Agent::config_builder()
+/// and a default cap of 10 redirects (with error on exceed), so all call
+/// sites are protected without explicit caps. Feeds are read with
+/// [`HttpOptions::max_body`] instead.
+///
+/// Without an explicit proxy, ureq picks one up from `ALL_PROXY`,
+/// `HTTPS_PROXY` or `HTTP_PROXY` (honouring `NO_PROXY`).
+pub(crate) fn http_client(opts: &HttpOptions) -> ureq::Agent {
+    let timeouts = &opts.timeouts;
+    let mut config = ureq::Agent::config_builder()
         .user_agent("Mozilla/5.0 (compatible; blogtato RSS reader)")
-        .timeout_global(Some(Duration::from_secs(10)))
-        .max_idle_connections(0)
-        .build()
-        .new_agent()
+        .timeout_global(Some(timeouts.total))
+        .timeout_connect(timeouts.connect)
+        .timeout_recv_response(timeouts.read)
+        .timeout_recv_body(timeouts.read)
+        .max_idle_connections(0);
+    if let Some(proxy) = &opts.proxy {
+        config = config.proxy(Some(proxy.clone()));
+    }
+    config.build().new_agent()
+}
+
+/// Read the `proxy` config key, e.g. `http://proxy:3128` or
+/// `socks5://127.0.0.1:1080`.
+fn configured_proxy(store: &crate::data::BlogData) -> anyhow::Result<Option<ureq::Proxy>> {
+    crate::data::get_config_value(store, "proxy")
+        .map(|value| {
+            ureq::Proxy::new(&value)
+                .map_err(|e| anyhow::anyhow!("Invalid value for config proxy: {value:?} ({e})"))
+        })
+        .transpose()
+}
+
+/// A failed request, along with how long the server asked us to wait before
+/// trying again (from `Retry-After`).
+#[derive(Debug)]
+pub(crate) struct RequestError {
+    pub error: ureq::Error,
+    pub retry_after: Option<Duration>,
+}
+
+impl From<ureq::Error> for RequestError {
+    fn from(error: ureq::Error) -> Self {
+        Self {
+            error,
+            retry_after: None,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// Parse a `Retry-After` value: either delay seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Most redirects followed for one request, same as ureq's default.
+const MAX_REDIRECTS: usize = 10;
+
+/// A downloaded response body.
+#[derive(Debug)]
+pub(crate) struct Download {
+    pub body: Vec<u8>,
+    /// Final URL, when every redirect on the way there was permanent (301/308)
+    pub moved_to: Option<String>,
+}
+
+/// GET `url` and read the body, following redirects. Error statuses become
+/// [`RequestError`]s that keep the server's `Retry-After` hint, and so do
+/// bodies of more than `max_body` bytes, which are not read any further.
+pub(crate) fn get_bytes(
+    client: &ureq::Agent,
+    url: &str,
+    max_body: u64,
+) -> Result<Download, RequestError> {
+    let mut current = url.to_string();
+    let mut permanent = true;
+    for _ in 0..=MAX_REDIRECTS {
+        let mut response = client
+            .get(&current)
+            .config()
+            .http_status_as_error(false)
+            .max_redirects(0)
+            .build()
+            .call()?;
+        let status = response.status().as_u16();
+        tracing::debug!(url = %current, status, "GET");
+        let location = response
+            .headers()
+            .get("location")
+            .and_then(|v| v.to_str().ok());
+        if let (true, Some(location)) = (response.status().is_redirection(), location) {
+            permanent &= matches!(status, 301 | 308);
+            current = url::Url::parse(&current)
+                .and_then(|base| base.join(location))
+                .map_err(|_| ureq::Error::BadUri(location.to_string()))?
+                .to_string();
+            continue;
+        }
+        if status >= 400 {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+            return Err(RequestError {
+                error: ureq::Error::StatusCode(status),
+                retry_after,
+            });
+        }
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(max_body)
+            .read_to_vec()?;
+        let moved_to = (permanent && current != url).then_some(current);
+        return Ok(Download { body, moved_to });
+    }
+    Err(ureq::Error::TooManyRedirects.into())
+}
+
+/// Caps how many requests may be in flight to the same host at once.
+pub(crate) struct HostLimiter {
+    limit: usize,
+    active: Mutex<HashMap<String, usize>>,
+    released: Condvar,
+}
+
+/// A slot held for one request; freed on drop.
+pub(crate) struct HostPermit<'a> {
+    limiter: &'a HostLimiter,
+    host: String,
+}
+
+impl HostLimiter {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            active: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until a request to the host of `url` may start.
+    pub(crate) fn acquire(&self, url: &str) -> HostPermit<'_> {
+        let host = host_of(url);
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        while active.get(&host).copied().unwrap_or(0) >= self.limit {
+            active = self
+                .released
+                .wait(active)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *active.entry(host.clone()).or_default() += 1;
+        HostPermit {
+            limiter: self,
+            host,
+        }
+    }
+}
+
+impl Drop for HostPermit<'_> {
+    fn drop(&mut self) {
+        let mut active = self
+            .limiter
+            .active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(n) = active.get_mut(&self.host) {
+            *n -= 1;
+            if *n == 0 {
+                active.remove(&self.host);
+            }
+        }
+        self.limiter.released.notify_all();
+    }
+}
+
+/// The host part of `url`, or the whole string if it doesn't parse.
+pub(crate) fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// How often and how patiently to retry requests that failed for reasons
+/// that are likely to go away on their own.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RetryPolicy {
+    /// Extra attempts after the first one
+    pub retries: u32,
+    /// Delay before the first retry; doubles with every further retry
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `op`, retrying transient failures with exponential backoff. A
+    /// server's `Retry-After` is honoured when it asks for a longer wait.
+    pub(crate) fn run<T>(
+        &self,
+        mut op: impl FnMut() -> Result<T, RequestError>,
+    ) -> Result<T, RequestError> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Err(e)
+                    if attempt < self.retries
+                        && is_transient(&e.error)
+                        && e.retry_after.is_none_or(|d| d <= MAX_RETRY_AFTER) =>
+                {
+                    let wait = delay.max(e.retry_after.unwrap_or_default());
+                    tracing::info!(error = %e, wait_ms = wait.as_millis(), "retrying request");
+                    std::thread::sleep(wait);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether a failed request is worth retrying: timeouts, dropped connections
+/// and server-side errors are; client errors and bad responses are not.
+fn is_transient(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::StatusCode(code) => *code == 429 || *code >= 500,
+        ureq::Error::Timeout(_)
+        | ureq::Error::Io(_)
+        | ureq::Error::ConnectionFailed
+        | ureq::Error::BodyStalled => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn no_wait(retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            retries,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    #[rstest]
+    #[case::server_error(ureq::Error::StatusCode(503), true)]
+    #[case::too_many_requests(ureq::Error::StatusCode(429), true)]
+    #[case::not_found(ureq::Error::StatusCode(404), false)]
+    #[case::connection(ureq::Error::ConnectionFailed, true)]
+    #[case::bad_uri(ureq::Error::BadUri("x".to_string()), false)]
+    fn test_is_transient(#[case] err: ureq::Error, #[case] expected: bool) {
+        assert_eq!(is_transient(&err), expected);
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_configured_attempts() {
+        let mut calls = 0;
+        let result: Result<(), RequestError> = no_wait(2).run(|| {
+            calls += 1;
+            Err(ureq::Error::StatusCode(503).into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_retry_stops_on_success() {
+        let mut calls = 0;
+        let result = no_wait(5).run(|| {
+            calls += 1;
+            if calls < 2 {
+                Err(ureq::Error::ConnectionFailed.into())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_retry_does_not_retry_permanent_errors() {
+        let mut calls = 0;
+        let result: Result<(), RequestError> = no_wait(5).run(|| {
+            calls += 1;
+            Err(ureq::Error::StatusCode(404).into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_retry_gives_up_when_asked_to_wait_too_long() {
+        let mut calls = 0;
+        let result: Result<(), RequestError> = no_wait(5).run(|| {
+            calls += 1;
+            Err(RequestError {
+                error: ureq::Error::StatusCode(429),
+                retry_after: Some(Duration::from_secs(3600)),
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[rstest]
+    #[case::seconds("120", Some(Duration::from_secs(120)))]
+    #[case::http_date("Mon, 01 Jan 2024 00:00:30 GMT", Some(Duration::from_secs(30)))]
+    #[case::past_date("Sun, 31 Dec 2023 00:00:00 GMT", Some(Duration::ZERO))]
+    #[case::garbage("soon", None)]
+    fn test_parse_retry_after(#[case] value: &str, #[case] expected: Option<Duration>) {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(parse_retry_after(value, now), expected);
+    }
+
+    #[rstest]
+    #[case::total(Duration::from_millis(200), None, None)]
+    #[case::read(Duration::from_secs(10), None, Some(Duration::from_millis(200)))]
+    fn test_slow_response_times_out(
+        #[case] total: Duration,
+        #[case] connect: Option<Duration>,
+        #[case] read: Option<Duration>,
+    ) {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.path("/slow");
+            then.status(200).delay(Duration::from_secs(2)).body("late");
+        });
+        let client = http_client(&HttpOptions {
+            proxy: None,
+            timeouts: Timeouts {
+                total,
+                connect,
+                read,
+            },
+            ..HttpOptions::default()
+        });
+
+        let err = get_bytes(&client, &server.url("/slow"), DEFAULT_MAX_BODY).unwrap_err();
+        assert!(matches!(err.error, ureq::Error::Timeout(_)), "got: {err}");
+    }
+
+    #[rstest]
+    #[case::bytes("500000", Some(500_000))]
+    #[case::kb("512KB", Some(512 * 1024))]
+    #[case::mb_lowercase("20mb", Some(20 * 1024 * 1024))]
+    #[case::spaced(" 1 GB ", Some(1024 * 1024 * 1024))]
+    #[case::zero("0MB", None)]
+    #[case::unit_only("MB", None)]
+    #[case::unknown_unit("5TB", None)]
+    #[case::overflow("99999999999999999GB", None)]
+    fn test_parse_byte_size(#[case] input: &str, #[case] expected: Option<u64>) {
+        assert_eq!(input.parse::<ByteSize>().ok().map(|s| s.0), expected);
+    }
+
+    #[test]
+    fn test_oversized_body_is_rejected() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.path("/big");
+            then.status(200).body("x".repeat(2048));
+        });
+        let client = http_client(&HttpOptions::default());
+
+        let err = get_bytes(&client, &server.url("/big"), 1024).unwrap_err();
+        assert!(
+            matches!(err.error, ureq::Error::BodyExceedsLimit(_)),
+            "got: {err}"
+        );
+        assert!(!is_transient(&err.error));
+        let ok = get_bytes(&client, &server.url("/big"), 4096).unwrap();
+        assert_eq!(ok.body.len(), 2048);
+    }
+
+    #[test]
+    fn test_host_limiter_caps_concurrency_per_host() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let limiter = HostLimiter::new(2);
+        let current = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let (limiter, current, peak) = (&limiter, &current, &peak);
+                scope.spawn(move || {
+                    let _permit = limiter.acquire(&format!("https://same.host/feed{i}.xml"));
+                    let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    current.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_host_limiter_does_not_block_other_hosts() {
+        let limiter = HostLimiter::new(1);
+        let _a = limiter.acquire("https://a.example/feed.xml");
+        // Would deadlock if hosts shared a slot
+        let _b = limiter.acquire("https://b.example/feed.xml");
+    }
 }
@@ -0,0 +1,112 @@
+//! Diagnostic logging for `-v`, `-vv` and `--log-file`: how long each feed
+//! took to fetch, what the server answered, what its parser made of it, and
+//! what git did, to tell why a sync is slow or a feed comes up empty.
+//!
+//! Log lines are only written for events of this crate, one per line:
+//! `<time> <LEVEL> <module>: <message> <field>=<value>...`.
+
+use std::fmt::{self, Write as _};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Context;
+use chrono::Utc;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Metadata, Subscriber, span};
+
+struct Logger {
+    max: Level,
+    out: Mutex<Box<dyn Write + Send>>,
+    next_span: AtomicU64,
+}
+
+/// Formats the fields of an event: the message first, then `name=value`.
+struct Line(String);
+
+impl Visit for Line {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.push_str(value);
+        } else {
+            let _ = write!(self.0, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+impl Subscriber for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.max && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(self.max))
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut line = Line(String::new());
+        event.record(&mut line);
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(
+            out,
+            "{} {:>5} {}: {}",
+            Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            metadata.level(),
+            metadata.target(),
+            line.0
+        );
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Start logging: `verbose` is how often `-v` was given, one for each feed
+/// fetched and git operation, two for each request and parse too. The log
+/// goes to stderr, or appended to `log_file`, which logs at `-v` level
+/// even without the flag.
+pub(crate) fn init(verbose: u8, log_file: Option<&Path>) -> anyhow::Result<()> {
+    let max = match (verbose, log_file) {
+        (0, None) => return Ok(()),
+        (0 | 1, _) => Level::INFO,
+        (2, _) => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    let out: Box<dyn Write + Send> = match log_file {
+        Some(path) => Box::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open log file {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stderr()),
+    };
+    let logger = Logger {
+        max,
+        out: Mutex::new(out),
+        next_span: AtomicU64::new(1),
+    };
+    tracing::subscriber::set_global_default(logger).context("failed to start logging")
+}
@@ -1,5 +1,10 @@
 pub mod date;
+pub(crate) mod epub;
+pub mod html;
 pub mod http;
 pub(crate) mod jq;
+pub(crate) mod logging;
 pub mod progress;
+pub(crate) mod prompt;
+pub(crate) mod server;
 pub mod version_check;
@@ -1,8 +1,32 @@
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Print nothing but warnings and errors from now on, e.g. for
+/// `blog sync --quiet`.
+pub(crate) fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether status messages, such as sync's summary, should be left out.
+pub(crate) fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Progress is only drawn on a terminal: elsewhere, as in cron mail or CI
+/// logs, every redraw would end up in the output.
+fn draws_progress() -> bool {
+    !is_quiet() && std::io::stderr().is_terminal()
+}
+
 pub(crate) fn spinner(msg: &str) -> ProgressBar {
+    if !draws_progress() {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new_spinner();
     pb.set_style(
         ProgressStyle::default_spinner()
@@ -13,3 +37,19 @@ pub(crate) fn spinner(msg: &str) -> ProgressBar {
     pb.enable_steady_tick(Duration::from_millis(100));
     pb
 }
+
+/// A bar counting up to a length set later, drawn with `template`.
+pub(crate) fn progress_bar(template: &str) -> ProgressBar {
+    if !draws_progress() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(0);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(template)
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
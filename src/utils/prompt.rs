@@ -0,0 +1,16 @@
+use std::io::Write;
+
+/// Print `question` to stderr and read the answer from stdin.
+pub(crate) fn ask(question: &str) -> anyhow::Result<String> {
+    eprint!("{question}");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Ask a yes/no `question`; anything but `y` or `yes` counts as no.
+pub(crate) fn confirm(question: &str) -> anyhow::Result<bool> {
+    let answer = ask(&format!("{question} [y/N] "))?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
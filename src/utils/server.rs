@@ -0,0 +1,213 @@
+//! Just enough of an HTTP/1.1 server for the WebSub listener of `blog watch`
+//! and for `blog serve`: each connection carries one request, which is read
+//! whole, answered and closed.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use anyhow::{Context, bail};
+
+/// Largest request line and headers together that a request may have.
+const MAX_HEAD: u64 = 16 * 1024;
+
+/// Most headers a request may have.
+const MAX_HEADERS: usize = 100;
+
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// What a request is answered with.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+/// Read one line of a request's head, which must end before `head` runs out.
+fn read_head_line(head: &mut impl BufRead, line: &mut String) -> anyhow::Result<()> {
+    line.clear();
+    head.read_line(line)?;
+    if !line.ends_with('\n') {
+        bail!("Request head too large or cut short");
+    }
+    Ok(())
+}
+
+/// Read a request from `stream`, refusing bodies larger than `max_body`.
+pub(crate) fn read_request(stream: impl Read, max_body: usize) -> anyhow::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut head = reader.by_ref().take(MAX_HEAD);
+    let mut line = String::new();
+    read_head_line(&mut head, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        bail!("Malformed request line: {line:?}");
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let method = method.to_string();
+    let path = path.to_string();
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+
+    let mut headers = HashMap::new();
+    for count in 0.. {
+        read_head_line(&mut head, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if count == MAX_HEADERS {
+            bail!("Too many headers");
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = match headers.get("content-length") {
+        Some(v) => v.parse().context("Invalid Content-Length")?,
+        None => 0,
+    };
+    if length > max_body {
+        bail!("Request body too large ({length} bytes)");
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    })
+}
+
+/// Answer with `body` of type `content_type` and close the connection.
+pub(crate) fn write_response(
+    mut stream: impl Write,
+    status: u16,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len(),
+    )
+}
+
+/// Answer the requests on `listener` with `respond`, one at a time, forever.
+pub(crate) fn serve(
+    listener: TcpListener,
+    max_body: usize,
+    respond: impl Fn(&Request) -> Response,
+) {
+    for mut stream in listener.incoming().flatten() {
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+        let response = match read_request(&mut stream, max_body) {
+            Ok(request) => respond(&request),
+            Err(_) => Response {
+                status: 400,
+                content_type: "text/plain",
+                body: "Bad Request".to_string(),
+            },
+        };
+        let _ = write_response(
+            &mut stream,
+            response.status,
+            response.content_type,
+            &response.body,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /abc123?x=a%20b HTTP/1.1\r\nHost: h\r\nContent-Length: 5\r\nX-Hub-Signature: sha1=00\r\n\r\nhello";
+        let req = read_request(raw.as_bytes(), 100).unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path, "/abc123");
+        assert_eq!(req.query["x"], "a b");
+        assert_eq!(req.headers["x-hub-signature"], "sha1=00");
+        assert_eq!(req.body, b"hello");
+    }
+
+    #[test]
+    fn test_read_request_rejects_huge_body() {
+        let raw = "POST / HTTP/1.1\r\nContent-Length: 101\r\n\r\n";
+        assert!(read_request(raw.as_bytes(), 100).is_err());
+    }
+
+    #[test]
+    fn test_read_request_rejects_huge_head() {
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEAD as usize));
+        assert!(read_request(long_line.as_bytes(), 100).is_err());
+
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n",
+            "a".repeat(MAX_HEAD as usize)
+        );
+        assert!(read_request(long_header.as_bytes(), 100).is_err());
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-A: b\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert!(read_request(many_headers.as_bytes(), 100).is_err());
+    }
+
+    #[test]
+    fn test_read_request_accepts_most_headers_allowed() {
+        let raw = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            (0..MAX_HEADERS)
+                .map(|i| format!("X-{i}: b\r\n"))
+                .collect::<String>()
+        );
+        assert_eq!(
+            read_request(raw.as_bytes(), 100).unwrap().headers.len(),
+            MAX_HEADERS
+        );
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        write_response(&mut out, 404, "text/plain", "Not Found").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: 9\r\nConnection: close\r\n\r\nNot Found"
+        );
+    }
+}
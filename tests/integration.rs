@@ -94,6 +94,21 @@ impl TestContext {
             .assert()
     }
 
+    /// Shorthand currently shown for the post with the given title.
+    fn post_shorthand(&self, title: &str) -> String {
+        let stdout = self.run(&[".all"]).success().stdout_str();
+        let line = stdout
+            .lines()
+            .find(|l| l.contains(title))
+            .unwrap_or_else(|| panic!("no post titled {title} in:\n{stdout}"));
+        // Skip the read marker and date columns
+        line[2..]
+            .split_whitespace()
+            .find(|w| !w.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap()
+            .to_string()
+    }
+
     fn mock_rss_feed(&self, path: &str, xml: &str) {
         self.server.mock(|when, then| {
             when.method(GET).path(path);
@@ -280,6 +295,47 @@ fn test_sync_multiple_feeds() {
     assert!(titles.contains(&"Atom Post"));
 }
 
+#[rstest]
+#[case::flag(&["sync", "-j", "1"], None)]
+#[case::config(&["sync"], Some("1"))]
+fn test_sync_with_limited_jobs(#[case] args: &[&str], #[case] fetch_jobs: Option<&str>) {
+    let ctx = TestContext::new();
+    for i in 0..3 {
+        let xml = rss_xml_with_guids(
+            &format!("Blog {i}"),
+            &[(
+                &format!("Post {i}"),
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                &format!("guid-{i}"),
+            )],
+        );
+        ctx.mock_rss_feed(&format!("/jobs{i}.xml"), &xml);
+    }
+    let urls: Vec<String> = (0..3)
+        .map(|i| ctx.server.url(format!("/jobs{i}.xml")))
+        .collect();
+    ctx.write_feeds(&urls.iter().map(String::as_str).collect::<Vec<_>>());
+    if let Some(jobs) = fetch_jobs {
+        ctx.run(&["config", "set", "fetch_jobs", jobs]).success();
+    }
+
+    ctx.run(args).success();
+
+    assert_eq!(ctx.read_posts().len(), 3);
+}
+
+#[test]
+fn test_sync_rejects_invalid_fetch_jobs_config() {
+    let ctx = TestContext::new();
+    ctx.run(&["config", "set", "fetch_jobs", "0"]).success();
+
+    let stderr = ctx.run(&["sync"]).failure().stderr_str();
+    assert!(
+        stderr.contains("Invalid value for config fetch_jobs"),
+        "got:\n{stderr}"
+    );
+}
+
 #[test]
 fn test_sync_selected_feed_by_shorthand() {
     let ctx = TestContext::new();
@@ -313,911 +369,3503 @@ fn test_sync_selected_feed_by_shorthand() {
 }
 
 #[test]
-fn test_sync_multiple_selected_feeds_by_shorthand() {
+fn test_sync_prints_new_post_summary() {
     let ctx = TestContext::new();
-
-    let alpha = rss_xml_with_guids(
-        "Alpha Blog",
-        &[(
-            "Alpha Post",
-            "Mon, 01 Jan 2024 00:00:00 +0000",
-            "guid-alpha",
-        )],
+    let xml = rss_xml_with_guids(
+        "Alpha",
+        &[
+            (
+                "Alpha Post",
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                "guid-alpha",
+            ),
+            ("Beta Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta"),
+        ],
     );
-    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
 
-    let beta = rss_xml_with_guids(
-        "Beta Blog",
-        &[("Beta Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta")],
+    // The first sync of a feed marks its backlog read, so only the newest
+    // post counts as new
+    let stderr = ctx.run(&["sync", "--verbose"]).success().stderr_str();
+    assert!(stderr.contains("1 new post from 1 feed."), "got:\n{stderr}");
+    let shorthand = ctx.post_shorthand("Beta Post");
+    assert!(
+        stderr.contains(&format!("  {shorthand}  Beta Post (@")),
+        "got:\n{stderr}"
     );
-    ctx.mock_rss_feed("/beta.xml", &beta);
+    assert!(!stderr.contains("Alpha Post"), "got:\n{stderr}");
 
-    let gamma = rss_xml_with_guids(
-        "Gamma Blog",
-        &[(
-            "Gamma Post",
-            "Wed, 03 Jan 2024 00:00:00 +0000",
-            "guid-gamma",
-        )],
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert!(stderr.contains("No new posts."), "got:\n{stderr}");
+}
+
+#[test]
+fn test_sync_verbose_logs_fetches() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Alpha",
+        &[("Alpha Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
     );
-    ctx.mock_rss_feed("/gamma.xml", &gamma);
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    let url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&url]);
 
-    let alpha_url = ctx.server.url("/alpha.xml");
-    let beta_url = ctx.server.url("/beta.xml");
-    let gamma_url = ctx.server.url("/gamma.xml");
-    ctx.write_feeds(&[&alpha_url, &beta_url, &gamma_url]);
+    let stderr = ctx.run(&["sync", "-v"]).success().stderr_str();
+    assert!(
+        stderr.contains(&format!(
+            "INFO blog::feed::pull: fetched feed url={url} ms="
+        )),
+        "got:\n{stderr}"
+    );
+    assert!(stderr.contains("posts=1"), "got:\n{stderr}");
+    assert!(!stderr.contains("DEBUG"), "got:\n{stderr}");
 
-    let alpha_shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
-    let gamma_shorthand = feed_shorthand_for_url(&ctx, &gamma_url);
-    ctx.run(&[
-        "sync",
-        "--feed",
-        &alpha_shorthand,
-        "--feed",
-        &gamma_shorthand,
-    ])
-    .success();
+    let stderr = ctx.run(&["-vv", "sync"]).success().stderr_str();
+    assert!(
+        stderr.contains(&format!("GET url={url} status=200")),
+        "got:\n{stderr}"
+    );
+    assert!(stderr.contains("parsed feed format=RSS"), "got:\n{stderr}");
 
-    let posts = ctx.read_posts();
-    let titles: Vec<&str> = posts.iter().map(|p| p["title"].as_str().unwrap()).collect();
-    assert_eq!(posts.len(), 2);
-    assert!(titles.contains(&"Alpha Post"));
-    assert!(titles.contains(&"Gamma Post"));
-    assert!(!titles.contains(&"Beta Post"));
+    let log = ctx.dir.path().join("sync.log");
+    let stderr = ctx
+        .run(&["sync", "--log-file", &log.to_string_lossy()])
+        .success()
+        .stderr_str();
+    assert_eq!(stderr, "No new posts.\n");
+    let logged = fs::read_to_string(&log).unwrap();
+    assert!(logged.contains("fetched feed"), "got:\n{logged}");
 }
 
 #[test]
-fn test_sync_selected_feed_requires_at_prefix() {
+fn test_sync_retry_failed_fetches_only_failed_feeds() {
     let ctx = TestContext::new();
-    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+    let ok_xml = rss_xml("Fine", &[("Fine Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    let fine = ctx.server.mock(|when, then| {
+        when.method(GET).path("/fine.xml");
+        then.status(200).body(&ok_xml);
+    });
+    let mut down = ctx.server.mock(|when, then| {
+        when.method(GET).path("/down.xml");
+        then.status(404);
+    });
+    let fine_url = ctx.server.url("/fine.xml");
+    let down_url = ctx.server.url("/down.xml");
+    ctx.write_feeds(&[&fine_url, &down_url]);
+    ctx.run(&["sync"]).code(2);
+
+    down.delete();
+    let down_xml = rss_xml("Down", &[("Back Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/down.xml", &down_xml);
+
+    ctx.run(&["sync", "--retry-failed"]).success();
+    let titles: Vec<String> = ctx
+        .read_posts()
+        .iter()
+        .map(|p| p["title"].as_str().unwrap().to_string())
+        .collect();
+    assert!(titles.contains(&"Back Post".to_string()), "{titles:?}");
+    assert_eq!(fine.calls(), 1);
 
-    let output = ctx.run(&["sync", "--feed", "df"]).failure();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("Invalid feed selector: df (expected @shorthand)"),
-        "expected invalid selector error, got: {}",
-        stderr
-    );
+    // Nothing failed this time, so there's nothing to retry
+    let stderr = ctx.run(&["sync", "--retry-failed"]).success().stderr_str();
+    assert!(stderr.contains("No new posts."), "got:\n{stderr}");
 }
 
 #[test]
-fn test_sync_selected_feed_rejects_unknown_shorthand() {
+fn test_sync_quiet_prints_only_errors() {
     let ctx = TestContext::new();
-    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+    let xml = rss_xml(
+        "Alpha",
+        &[("Alpha Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
 
-    let output = ctx.run(&["sync", "--feed", "@dg"]).failure();
-    let stderr = output.stderr_str();
+    // Not a terminal: no spinners or progress redraws, just the summary
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert_eq!(stderr, "1 new post from 1 feed.\n");
+
+    let stderr = ctx.run(&["sync", "--quiet"]).success().stderr_str();
+    assert_eq!(stderr, "");
+
+    ctx.write_feeds(&[&ctx.server.url("/missing.xml")]);
+    let stderr = ctx.run(&["sync", "-q"]).code(2).stderr_str();
+    assert!(stderr.starts_with("Error fetching"), "got:\n{stderr}");
     assert!(
-        stderr.contains("Unknown feed shorthand: @dg"),
-        "expected unknown shorthand error, got: {}",
-        stderr
+        stderr.ends_with("Failed to fetch 1 feed\n"),
+        "got:\n{stderr}"
     );
+    assert!(!stderr.contains("No new posts"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_show_displays_posts() {
+fn test_new_lists_posts_added_since_last_run() {
     let ctx = TestContext::new();
+    let state = TempDir::new().unwrap();
+    let new = || {
+        blog_cmd()
+            .arg("new")
+            .env("RSS_STORE", ctx.dir.path())
+            .env("XDG_STATE_HOME", state.path())
+            .assert()
+            .success()
+    };
+    let alpha = rss_xml_with_guids(
+        "Alpha",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let beta = rss_xml_with_guids(
+        "Beta",
+        &[
+            ("Beta Old", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-beta-1"),
+            ("Beta New", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta-2"),
+        ],
+    );
+    ctx.mock_rss_feed("/beta.xml", &beta);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    ctx.run(&["sync"]).success();
 
-    let posts = r#"{"id":"1","title":"Hello World","date":"2024-01-15T00:00:00Z","feed":"Alice"}
-{"id":"2","title":"Second Post","date":"2024-01-14T00:00:00Z","feed":"Bob"}"#;
-    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    let stderr = new().stderr_str();
+    assert!(stderr.contains("Noted the 1 posts"), "got:\n{stderr}");
 
-    let output = ctx.run(&["show", "2020-01-01.."]).success();
-    let stdout = output.stdout_str();
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml"), &ctx.server.url("/beta.xml")]);
+    ctx.run(&["sync"]).success();
 
-    assert!(stdout.contains("Hello World"));
-    assert!(stdout.contains("Second Post"));
-    assert!(stdout.contains("Alice"));
-    assert!(stdout.contains("Bob"));
+    // Read or not, both posts of the new feed are listed once
+    let stdout = new().stdout_str();
+    assert!(stdout.contains("Beta Old"), "got:\n{stdout}");
+    assert!(stdout.contains("Beta New"), "got:\n{stdout}");
+    assert!(!stdout.contains("Alpha Post"), "got:\n{stdout}");
+
+    let again = new();
+    assert!(again.get_output().stdout.is_empty());
+    assert!(again.stderr_str().contains("No new posts"));
 }
 
 #[test]
-fn test_show_rejects_too_many_grouping_args() {
+fn test_sync_tracks_post_updates() {
     let ctx = TestContext::new();
+    let first = rss_xml_with_guids(
+        "Alpha",
+        &[
+            ("Typo Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-1"),
+            ("Other Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-2"),
+        ],
+    );
+    let mut mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/alpha.xml");
+        then.status(200).body(&first);
+    });
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    ctx.run(&["sync"]).success();
+    ctx.run(&[".all", "--updated"]).failure();
 
-    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
-    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
-
-    let output = ctx.run(&["show", "/d", "/f", "/d"]).failure();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("Too many grouping arguments"),
-        "expected error about too many grouping args, got: {}",
-        stderr,
+    mock.delete();
+    let second = rss_xml_with_guids(
+        "Alpha",
+        &[
+            ("Fixed Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-1"),
+            ("Other Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-2"),
+        ],
     );
+    ctx.mock_rss_feed("/alpha.xml", &second);
+    ctx.run(&["sync"]).success();
+    ctx.run(&["sync"]).success();
+
+    let posts = ctx.read_posts();
+    let fixed = posts.iter().find(|p| p["raw_id"] == "guid-1").unwrap();
+    assert_eq!(fixed["title"], "Fixed Post");
+    assert_eq!(fixed["edits"], 1);
+    let other = posts.iter().find(|p| p["raw_id"] == "guid-2").unwrap();
+    assert!(other.get("edits").is_none());
+
+    let stdout = ctx.run(&[".all", "--updated"]).success().stdout_str();
+    assert_eq!(stdout.lines().count(), 1, "got:\n{stdout}");
+    assert!(stdout.starts_with("*~"), "got:\n{stdout}");
+    assert!(stdout.contains("Fixed Post"), "got:\n{stdout}");
 }
 
 #[test]
-fn test_show_rejects_unknown_argument() {
+fn test_sort_seen_orders_posts_by_when_they_were_fetched() {
     let ctx = TestContext::new();
+    let first = rss_xml_with_guids(
+        "Alpha",
+        &[("Early Fetch", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-1")],
+    );
+    let mut mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/alpha.xml");
+        then.status(200).body(&first);
+    });
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    ctx.run(&["sync"]).success();
+    let seen_at = ctx.read_posts()[0]["first_seen_at"].clone();
+    assert!(seen_at.is_string(), "got: {seen_at}");
 
-    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
-    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
-
-    let output = ctx.run(&["show", "/x"]).failure();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("Failed to parse argument"),
-        "expected error about failed to parse argument, got: {}",
-        stderr,
+    // An older post the feed only lists later
+    mock.delete();
+    let second = rss_xml_with_guids(
+        "Alpha",
+        &[
+            ("Early Fetch", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-1"),
+            ("Late Fetch", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-2"),
+        ],
     );
-}
+    ctx.mock_rss_feed("/alpha.xml", &second);
+    ctx.run(&["sync"]).success();
 
-#[test]
-fn test_show_with_grouping() {
-    let ctx = TestContext::new();
+    let posts = ctx.read_posts();
+    let early = posts.iter().find(|p| p["raw_id"] == "guid-1").unwrap();
+    assert_eq!(early["first_seen_at"], seen_at);
 
-    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}
-{"id":"2","title":"Post B","date":"2024-01-15T00:00:00Z","feed":"Bob"}
-{"id":"3","title":"Post C","date":"2024-01-14T00:00:00Z","feed":"Alice"}"#;
-    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    let by_date = ctx.run(&[".all", "--format", "tsv"]).success().stdout_str();
+    let titles: Vec<&str> = by_date
+        .lines()
+        .map(|l| l.split('\t').nth(3).unwrap())
+        .collect();
+    assert_eq!(titles, ["Early Fetch", "Late Fetch"]);
 
-    let output = ctx.run(&["show", "/d"]).success();
-    let stdout = output.stdout_str();
+    let by_seen = ctx
+        .run(&[".all", "--sort", "seen", "--format", "tsv"])
+        .success()
+        .stdout_str();
+    let titles: Vec<&str> = by_seen
+        .lines()
+        .map(|l| l.split('\t').nth(3).unwrap())
+        .collect();
+    assert_eq!(titles, ["Late Fetch", "Early Fetch"]);
 
-    assert!(stdout.contains("=== 2024-01-15 ==="));
-    assert!(stdout.contains("=== 2024-01-14 ==="));
-    assert!(stdout.contains("Post A"));
-    assert!(stdout.contains("Post B"));
-    assert!(stdout.contains("Post C"));
+    // Date ranges apply to when posts were fetched, too
+    let stdout = ctx.run(&["1d..", "--sort", "seen"]).success().stdout_str();
+    assert!(stdout.contains("Late Fetch"), "got:\n{stdout}");
+    ctx.run(&["1d.."]).failure();
 }
 
 #[test]
-fn test_show_default_no_subcommand() {
+fn test_paused_feed_is_skipped_by_sync_until_resumed() {
     let ctx = TestContext::new();
 
-    let posts = r#"{"id":"1","title":"Default Show","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
-    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&alpha_url]);
+    let shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
 
-    let output = ctx.run(&["2020-01-01.."]).success();
-    let stdout = output.stdout_str();
+    ctx.run(&["feed", "pause", &shorthand]).success();
+    let stdout = ctx.run(&["feed", "ls"]).success().stdout_str();
+    assert!(stdout.contains("[paused]"), "got:\n{stdout}");
 
-    assert!(stdout.contains("Default Show"));
-    assert!(stdout.contains("Alice"));
+    ctx.run(&["sync"]).success();
+    assert!(ctx.read_posts().is_empty());
+
+    ctx.run(&["feed", "resume", &shorthand]).success();
+    ctx.run(&["sync"]).success();
+    assert_eq!(ctx.read_posts().len(), 1);
 }
 
 #[test]
-fn test_sync_then_show() {
+fn test_paused_feed_with_hide_leaves_posts_out_of_listings() {
     let ctx = TestContext::new();
-    let xml = rss_xml(
-        "Roundtrip Blog",
-        &[("Roundtrip Post", "Wed, 03 Jan 2024 00:00:00 +0000")],
+
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
     );
-    ctx.mock_rss_feed("/feed.xml", &xml);
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let beta = rss_xml_with_guids(
+        "Beta Blog",
+        &[("Beta Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta")],
+    );
+    ctx.mock_rss_feed("/beta.xml", &beta);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    let beta_url = ctx.server.url("/beta.xml");
+    ctx.write_feeds(&[&alpha_url, &beta_url]);
+    ctx.run(&["sync"]).success();
+    let shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
 
-    let url = ctx.server.url("/feed.xml");
-    ctx.write_feeds(&[&url]);
+    ctx.run(&["feed", "pause", "--hide", &shorthand]).success();
 
-    ctx.run(&["sync"]).success();
+    let stdout = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(!stdout.contains("Alpha Post"), "got:\n{stdout}");
+    assert!(stdout.contains("Beta Post"), "got:\n{stdout}");
 
-    let output = ctx.run(&["show", "2020-01-01.."]).success();
-    let stdout = output.stdout_str();
+    let stdout = ctx.run(&[".all"]).success().stdout_str();
+    assert!(stdout.contains("Alpha Post"), "got:\n{stdout}");
+    let stdout = ctx
+        .run(&[&shorthand, "2020-01-01.."])
+        .success()
+        .stdout_str();
+    assert!(stdout.contains("Alpha Post"), "got:\n{stdout}");
 
-    assert!(stdout.contains("Roundtrip Post"));
-    assert!(stdout.contains("Roundtrip Blog"));
+    ctx.run(&["feed", "resume", &shorthand]).success();
+    let stdout = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(stdout.contains("Alpha Post"), "got:\n{stdout}");
 }
 
 #[test]
-fn test_serde_roundtrip() {
+fn test_snoozed_feed_is_hidden_and_skipped_until_resumed() {
     let ctx = TestContext::new();
-    let xml = rss_xml(
-        "Serde Blog",
-        &[
-            ("Post One", "Mon, 01 Jan 2024 12:00:00 +0000"),
-            ("Post Two", "Tue, 02 Jan 2024 12:00:00 +0000"),
-        ],
-    );
-    ctx.mock_rss_feed("/feed.xml", &xml);
 
-    let url = ctx.server.url("/feed.xml");
-    ctx.write_feeds(&[&url]);
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let beta = rss_xml_with_guids(
+        "Beta Blog",
+        &[("Beta Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta")],
+    );
+    ctx.mock_rss_feed("/beta.xml", &beta);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    let beta_url = ctx.server.url("/beta.xml");
+    ctx.write_feeds(&[&alpha_url, &beta_url]);
+    let shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
+
+    let stderr = ctx
+        .run(&["feed", "snooze", &shorthand, "2w"])
+        .success()
+        .stderr_str();
+    assert!(stderr.contains("Snoozed"), "got:\n{stderr}");
+    let stdout = ctx.run(&["feed", "ls"]).success().stdout_str();
+    assert!(stdout.contains("[snoozed until "), "got:\n{stdout}");
 
     ctx.run(&["sync"]).success();
+    assert_eq!(ctx.read_posts().len(), 1);
+    let stdout = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(stdout.contains("Beta Post"), "got:\n{stdout}");
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 2);
+    ctx.run(&["feed", "resume", &shorthand]).success();
+    ctx.run(&["sync"]).success();
+    let stdout = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(stdout.contains("Alpha Post"), "got:\n{stdout}");
+}
 
-    // Write back and re-read to verify roundtrip
-    let mut out = String::new();
-    for post in &posts {
-        out.push_str(&serde_json::to_string(post).unwrap());
-        out.push('\n');
-    }
-    // Remove existing shard files and write all to a single shard
-    if let Ok(entries) = fs::read_dir(ctx.dir.path().join("posts")) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if let Some(fname) = path.file_name().and_then(|f| f.to_str())
-                && fname.starts_with("items_")
-                && fname.ends_with(".jsonl")
-            {
-                fs::remove_file(&path).unwrap();
-            }
-        }
-    }
-    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), &out).unwrap();
+#[test]
+fn test_snooze_rejects_a_time_in_the_past() {
+    let ctx = TestContext::new();
+    let url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&url]);
 
-    let posts2 = ctx.read_posts();
-    assert_eq!(posts, posts2);
+    ctx.run(&["feed", "snooze", &url, "2020-01-01"]).failure();
 }
 
 #[test]
-fn test_sync_twice_no_duplicates() {
+fn test_pause_unknown_feed_fails() {
     let ctx = TestContext::new();
 
-    let xml1 = rss_xml_with_guids(
-        "Blog",
-        &[
-            ("Post A", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-a"),
-            ("Post B", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-b"),
-        ],
-    );
-    ctx.mock_rss_feed("/feed.xml", &xml1);
+    let stderr = ctx
+        .run(&["feed", "pause", "https://example.com/nope.xml"])
+        .failure()
+        .stderr_str();
 
-    let url = ctx.server.url("/feed.xml");
-    ctx.write_feeds(&[&url]);
+    assert!(stderr.contains("Feed not found"), "got:\n{stderr}");
+}
 
-    ctx.run(&["sync"]).success();
-    let posts1 = ctx.read_posts();
-    assert_eq!(posts1.len(), 2);
+#[test]
+fn test_sync_applies_retention() {
+    let ctx = TestContext::new();
 
-    // Second pull with overlapping + new item
-    let xml2 = rss_xml_with_guids(
-        "Blog",
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
         &[
-            (
-                "Post B Updated",
-                "Tue, 02 Jan 2024 00:00:00 +0000",
-                "guid-b",
-            ),
-            ("Post C", "Wed, 03 Jan 2024 00:00:00 +0000", "guid-c"),
+            ("Old", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-old"),
+            ("Older", "Sun, 31 Dec 2023 00:00:00 +0000", "guid-older"),
+            ("Oldest", "Sat, 30 Dec 2023 00:00:00 +0000", "guid-oldest"),
         ],
     );
-    ctx.mock_rss_feed("/feed2.xml", &xml2);
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&alpha_url]);
+    ctx.run(&["config", "set", "retention", "2"]).success();
+
+    // Posts past the policy are never stored, so there is nothing to remove
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert!(!stderr.contains("Removed"), "got:\n{stderr}");
+    let titles: Vec<String> = ctx
+        .read_posts()
+        .iter()
+        .map(|p| p["title"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(titles.len(), 2);
+    assert!(!titles.contains(&"Oldest".to_string()));
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert!(!stderr.contains("Removed"), "got:\n{stderr}");
+    assert_eq!(ctx.read_posts().len(), 2);
+
+    // A per-feed policy overrides the config
+    let shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
+    ctx.run(&["feed", "retain", &shorthand, "forever"])
+        .success();
+    ctx.run(&["sync"]).success();
+    assert_eq!(ctx.read_posts().len(), 3);
 
-    let url2 = ctx.server.url("/feed2.xml");
-    ctx.write_feeds(&[&url2]);
+    ctx.run(&["feed", "retain", &shorthand, "30d"]).success();
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert!(stderr.contains("Removed 3 posts"), "got:\n{stderr}");
+    assert!(ctx.read_posts().is_empty());
+}
 
+#[test]
+fn test_retention_keeps_posts_queued_for_later_or_snoozed() {
+    let ctx = TestContext::new();
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[
+            ("Old", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-old"),
+            ("Older", "Sun, 31 Dec 2023 00:00:00 +0000", "guid-older"),
+            ("Oldest", "Sat, 30 Dec 2023 00:00:00 +0000", "guid-oldest"),
+        ],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
     ctx.run(&["sync"]).success();
-    let posts2 = ctx.read_posts();
+    ctx.run(&[&ctx.post_shorthand("Older"), "later"]).success();
+    ctx.run(&["snooze", &ctx.post_shorthand("Oldest"), "3d"])
+        .success();
 
-    // Should have 3 items: A (from first pull, preserved), B (updated), C (new)
-    assert_eq!(posts2.len(), 3);
+    ctx.run(&["config", "set", "retention", "1"]).success();
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
 
-    let titles: Vec<&str> = posts2
-        .iter()
-        .map(|p| p["title"].as_str().unwrap())
-        .collect();
-    assert!(titles.contains(&"Post A"));
-    assert!(titles.contains(&"Post B Updated"));
-    assert!(titles.contains(&"Post C"));
-    // Original "Post B" should be overwritten
-    assert!(!titles.contains(&"Post B"));
+    assert!(!stderr.contains("Removed"), "got:\n{stderr}");
+    assert_eq!(ctx.read_posts().len(), 3);
+    let later = ctx.run(&["later"]).success().stdout_str();
+    assert!(later.contains("Older"), "got:\n{later}");
 }
 
 #[test]
-fn test_add_creates_feed() {
+fn test_feed_retain_rejects_invalid_policy() {
     let ctx = TestContext::new();
-    let xml = rss_xml("Test Feed", &[]);
-    ctx.mock_rss_feed("/feed.xml", &xml);
+    ctx.write_feeds(&["https://example.com/feed.xml"]);
 
-    let url = ctx.server.url("/feed.xml");
-    ctx.run(&["feed", "add", &url]).success();
+    let stderr = ctx
+        .run(&["feed", "retain", "https://example.com/feed.xml", "soon"])
+        .failure()
+        .stderr_str();
 
-    let feeds = ctx.read_feeds();
-    assert_eq!(feeds.len(), 1);
-    assert_eq!(feeds[0]["url"].as_str().unwrap(), url);
+    assert!(stderr.contains("Invalid retention"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_add_then_sync() {
+fn test_feed_timeout_overrides_config() {
     let ctx = TestContext::new();
-    let xml = rss_xml(
-        "Added Blog",
-        &[("Added Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    let xml = rss_xml_with_guids(
+        "Slow",
+        &[("Slow Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1")],
     );
-    ctx.mock_rss_feed("/added.xml", &xml);
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/slow.xml");
+        then.status(200)
+            .delay(std::time::Duration::from_secs(2))
+            .body(&xml);
+    });
+    let url = ctx.server.url("/slow.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["config", "set", "timeout", "1"]).success();
+    ctx.run(&["config", "set", "fetch_retries", "0"]).success();
 
-    let url = ctx.server.url("/added.xml");
-    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["sync"]).code(2);
+    assert!(ctx.read_posts().is_empty());
+
+    let shorthand = feed_shorthand_for_url(&ctx, &url);
+    ctx.run(&["feed", "timeout", &shorthand, "10"]).success();
+    let info = ctx
+        .run(&["feed", "info", &shorthand])
+        .success()
+        .stdout_str();
+    assert!(info.contains("timeout      10s"), "got:\n{info}");
     ctx.run(&["sync"]).success();
+    assert_eq!(ctx.read_posts().len(), 1);
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 1);
-    assert_eq!(posts[0]["title"].as_str().unwrap(), "Added Post");
+    ctx.run(&["feed", "timeout", &shorthand, "default"])
+        .success();
+    let info = ctx
+        .run(&["feed", "info", &shorthand])
+        .success()
+        .stdout_str();
+    assert!(!info.contains("timeout"), "got:\n{info}");
+
+    let stderr = ctx
+        .run(&["feed", "timeout", &shorthand, "soon"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("Invalid timeout"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_sync_continues_after_feed_failure() {
+fn test_feed_folders_group_and_filter_posts() {
     let ctx = TestContext::new();
+    let mut urls = vec![];
+    for name in ["Rust", "World", "Misc"] {
+        let path = format!("/{}.xml", name.to_lowercase());
+        let xml = rss_xml_with_guids(
+            name,
+            &[(
+                &format!("{name} Post"),
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                &format!("guid-{name}"),
+            )],
+        );
+        ctx.mock_rss_feed(&path, &xml);
+        urls.push(ctx.server.url(&path));
+    }
+    let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+    ctx.write_feeds(&urls);
+    ctx.run(&["sync"]).success();
+    let rust = feed_shorthand_for_url(&ctx, urls[0]);
+    let world = feed_shorthand_for_url(&ctx, urls[1]);
+    ctx.run(&["feed", "folder", &rust, "Tech/Rust"]).success();
+    ctx.run(&["feed", "folder", &world, "News"]).success();
 
-    // One feed returns a 500 error
-    ctx.server.mock(|when, then| {
-        when.method(GET).path("/broken.xml");
-        then.status(500).body("Internal Server Error");
-    });
-
-    // The other feed works fine
-    let xml = rss_xml(
-        "Good Blog",
-        &[("Good Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    let grouped = ctx.run(&[".all", "/g"]).success().stdout_str();
+    let headers: Vec<&str> = grouped.lines().filter(|l| l.starts_with("===")).collect();
+    assert_eq!(
+        headers,
+        [
+            "=== News (1) ===",
+            "=== Tech/Rust (1) ===",
+            "=== no folder (1) ==="
+        ],
+        "got:\n{grouped}"
     );
-    ctx.mock_rss_feed("/good.xml", &xml);
-
-    let broken_url = ctx.server.url("/broken.xml");
-    let good_url = ctx.server.url("/good.xml");
-    ctx.write_feeds(&[&broken_url, &good_url]);
-
-    let output = ctx.run(&["sync"]).success();
-    let stderr = output.stderr_str();
+    assert!(grouped.contains("--- @"), "got:\n{grouped}");
 
-    // Error message should mention the HTTP status, not a confusing XML parse error
+    let tech = ctx.run(&[".all", "/tech"]).failure().stderr_str();
+    assert!(tech.contains("Failed to parse argument"), "got:\n{tech}");
+    let tech = ctx.run(&[".all", "/Tech"]).success().stdout_str();
+    assert!(tech.contains("Rust Post"), "got:\n{tech}");
+    assert!(!tech.contains("World Post"), "got:\n{tech}");
+    let stderr = ctx.run(&[".all", "/Sports"]).failure().stderr_str();
     assert!(
-        stderr.contains("500"),
-        "error should mention HTTP 500 status, got: {}",
-        stderr
+        stderr.contains("No feeds in folder /Sports"),
+        "got:\n{stderr}"
     );
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 1);
-    assert_eq!(posts[0]["title"].as_str().unwrap(), "Good Post");
+    let info = ctx.run(&["feed", "info", &rust]).success().stdout_str();
+    assert!(info.contains("folder       Tech/Rust"), "got:\n{info}");
+    ctx.run(&["feed", "folder", &rust, "none"]).success();
+    let info = ctx.run(&["feed", "info", &rust]).success().stdout_str();
+    assert!(!info.contains("folder"), "got:\n{info}");
 }
 
 #[test]
-fn test_sync_reports_http_404_clearly() {
+fn test_sync_runs_on_new_post_hook_for_new_posts() {
     let ctx = TestContext::new();
+    let out = ctx.dir.path().join("hook.txt");
+    let json = ctx.dir.path().join("hook.jsonl");
 
-    ctx.server.mock(|when, then| {
-        when.method(GET).path("/gone.xml");
-        then.status(404).body("Not Found");
+    let first = rss_xml_with_guids(
+        "Alpha Blog",
+        &[
+            ("Newest", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-2"),
+            ("Older", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1"),
+        ],
+    );
+    let mut mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/alpha.xml");
+        then.status(200).body(&first);
     });
+    let url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&url]);
+    let hook = format!(
+        "echo \"$BLOG_TITLE|$BLOG_FEED_URL\" >> {}; cat >> {}",
+        out.display(),
+        json.display()
+    );
+    let sync = || {
+        blog_cmd()
+            .arg("sync")
+            .env("RSS_STORE", ctx.dir.path())
+            .env("BLOG_ON_NEW_POST", &hook)
+            .assert()
+            .success()
+    };
 
-    let xml = rss_xml(
-        "Good Blog",
-        &[("Good Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    // The first fetch marks old posts read; only unread ones are new
+    sync();
+    assert_eq!(fs::read_to_string(&out).unwrap(), format!("Newest|{url}\n"));
+
+    mock.delete();
+    let second = rss_xml_with_guids(
+        "Alpha Blog",
+        &[
+            ("Fresh", "Wed, 03 Jan 2024 00:00:00 +0000", "guid-3"),
+            ("Newest", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-2"),
+        ],
     );
-    ctx.mock_rss_feed("/good.xml", &xml);
+    ctx.mock_rss_feed("/alpha.xml", &second);
+    sync();
 
-    let gone_url = ctx.server.url("/gone.xml");
-    let good_url = ctx.server.url("/good.xml");
-    ctx.write_feeds(&[&gone_url, &good_url]);
+    assert_eq!(
+        fs::read_to_string(&out).unwrap(),
+        format!("Newest|{url}\nFresh|{url}\n")
+    );
+    let posts: Vec<serde_json::Value> = fs::read_to_string(&json)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(posts[1]["title"], "Fresh");
+    assert_eq!(posts[1]["feed_title"], "Alpha Blog");
+}
 
-    let output = ctx.run(&["sync"]).success();
-    let stderr = output.stderr_str();
+#[test]
+fn test_sync_survives_failing_on_new_post_hook() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_guids(
+        "Alpha Blog",
+        &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1")],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+
+    let stderr = blog_cmd()
+        .arg("sync")
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BLOG_ON_NEW_POST", "exit 3")
+        .assert()
+        .success()
+        .stderr_str();
 
     assert!(
-        stderr.contains("404"),
-        "error should mention HTTP 404 status, got: {}",
-        stderr
+        stderr.contains("BLOG_ON_NEW_POST hook failed"),
+        "got:\n{stderr}"
     );
-
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 1);
-    assert_eq!(posts[0]["title"].as_str().unwrap(), "Good Post");
+    assert_eq!(ctx.read_posts().len(), 1);
 }
 
 #[test]
-fn test_remove_feed() {
+fn test_sync_ignores_on_new_post_hook_from_store() {
     let ctx = TestContext::new();
-    let xml = rss_xml(
-        "Blog to Remove",
-        &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    let out = ctx.dir.path().join("hook.txt");
+    let xml = rss_xml_with_guids(
+        "Alpha Blog",
+        &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1")],
     );
-    ctx.mock_rss_feed("/removable.xml", &xml);
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    // A hook synced in from someone else's push must not run here
+    ctx.run(&[
+        "config",
+        "set",
+        "on_new_post",
+        &format!("touch {}", out.display()),
+    ])
+    .success();
 
-    let url = ctx.server.url("/removable.xml");
-    ctx.run(&["feed", "add", &url]).success();
     ctx.run(&["sync"]).success();
 
-    let output = ctx.run(&["show", "2020-01-01.."]).success();
-    let stdout = output.stdout_str();
-    assert!(stdout.contains("Blog to Remove"));
+    assert!(!out.exists());
+    assert_eq!(ctx.read_posts().len(), 1);
+}
 
-    ctx.run(&["feed", "rm", &url]).success();
+#[test]
+fn test_sync_posts_new_posts_to_webhook() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Alpha Blog",
+        &[(
+            "Hello",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-1",
+            "https://alpha.example/hello",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    let webhook = ctx.server.mock(|when, then| {
+        when.method(POST)
+            .path("/hook")
+            .header("Content-Type", "application/json")
+            .body_includes(
+                "\"content\":\"1 new post\\n• Hello (Alpha Blog) https://alpha.example/hello\"",
+            )
+            .body_includes("\"raw_id\":\"guid-1\"");
+        then.status(204);
+    });
+    ctx.run(&["config", "set", "webhook_url", &ctx.server.url("/hook")])
+        .success();
 
-    // Pull should no longer fetch the removed feed
     ctx.run(&["sync"]).success();
 
-    // Feed and its posts should be gone — show should report no posts
-    let output = ctx.run(&["show", "2020-01-01.."]).failure();
-    let stderr = output.stderr_str();
-    assert!(stderr.contains("No matching posts"));
+    webhook.assert_calls(1);
 }
 
 #[test]
-fn test_remove_feed_deletes_its_posts() {
+fn test_sync_survives_failing_webhook() {
     let ctx = TestContext::new();
-
-    let xml1 = rss_xml_with_guids(
-        "Keep Blog",
-        &[("Keep Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-keep")],
+    let xml = rss_xml_with_guids(
+        "Alpha Blog",
+        &[("Hello", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1")],
     );
-    ctx.mock_rss_feed("/keep.xml", &xml1);
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/alpha.xml")]);
+    let webhook = ctx.server.mock(|when, then| {
+        when.method(POST).path("/hook").body_includes("1 new post");
+        then.status(500);
+    });
+    ctx.run(&["config", "set", "webhook_url", &ctx.server.url("/hook")])
+        .success();
+    ctx.run(&["config", "set", "webhook_format", "text"])
+        .success();
+
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+
+    webhook.assert_calls(1);
+    assert!(
+        stderr.contains("failed to send new posts to webhook"),
+        "got:\n{stderr}"
+    );
+    assert_eq!(ctx.read_posts().len(), 1);
+}
+
+#[test]
+fn test_sync_multiple_selected_feeds_by_shorthand() {
+    let ctx = TestContext::new();
+
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+
+    let beta = rss_xml_with_guids(
+        "Beta Blog",
+        &[("Beta Post", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-beta")],
+    );
+    ctx.mock_rss_feed("/beta.xml", &beta);
+
+    let gamma = rss_xml_with_guids(
+        "Gamma Blog",
+        &[(
+            "Gamma Post",
+            "Wed, 03 Jan 2024 00:00:00 +0000",
+            "guid-gamma",
+        )],
+    );
+    ctx.mock_rss_feed("/gamma.xml", &gamma);
+
+    let alpha_url = ctx.server.url("/alpha.xml");
+    let beta_url = ctx.server.url("/beta.xml");
+    let gamma_url = ctx.server.url("/gamma.xml");
+    ctx.write_feeds(&[&alpha_url, &beta_url, &gamma_url]);
+
+    let alpha_shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
+    let gamma_shorthand = feed_shorthand_for_url(&ctx, &gamma_url);
+    ctx.run(&[
+        "sync",
+        "--feed",
+        &alpha_shorthand,
+        "--feed",
+        &gamma_shorthand,
+    ])
+    .success();
+
+    let posts = ctx.read_posts();
+    let titles: Vec<&str> = posts.iter().map(|p| p["title"].as_str().unwrap()).collect();
+    assert_eq!(posts.len(), 2);
+    assert!(titles.contains(&"Alpha Post"));
+    assert!(titles.contains(&"Gamma Post"));
+    assert!(!titles.contains(&"Beta Post"));
+}
+
+#[test]
+fn test_sync_selected_feed_requires_at_prefix() {
+    let ctx = TestContext::new();
+    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+
+    let output = ctx.run(&["sync", "--feed", "df"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Invalid feed selector: df (expected @shorthand)"),
+        "expected invalid selector error, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_sync_selected_feed_rejects_unknown_shorthand() {
+    let ctx = TestContext::new();
+    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+
+    let output = ctx.run(&["sync", "--feed", "@dg"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Unknown feed shorthand: @dg"),
+        "expected unknown shorthand error, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_show_displays_posts() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Hello World","date":"2024-01-15T00:00:00Z","feed":"Alice"}
+{"id":"2","title":"Second Post","date":"2024-01-14T00:00:00Z","feed":"Bob"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let output = ctx.run(&["show", "2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+
+    assert!(stdout.contains("Hello World"));
+    assert!(stdout.contains("Second Post"));
+    assert!(stdout.contains("Alice"));
+    assert!(stdout.contains("Bob"));
+}
+
+#[test]
+fn test_show_rejects_too_many_grouping_args() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let output = ctx.run(&["show", "/d", "/f", "/d"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Too many grouping arguments"),
+        "expected error about too many grouping args, got: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn test_show_rejects_unknown_argument() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let output = ctx.run(&["show", "/x"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Failed to parse argument"),
+        "expected error about failed to parse argument, got: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn test_show_with_grouping() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}
+{"id":"2","title":"Post B","date":"2024-01-15T00:00:00Z","feed":"Bob"}
+{"id":"3","title":"Post C","date":"2024-01-14T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let output = ctx.run(&["show", "/d"]).success();
+    let stdout = output.stdout_str();
+
+    assert!(stdout.contains("=== 2024-01-15 ("));
+    assert!(stdout.contains("=== 2024-01-14 ("));
+    assert!(stdout.contains("Post A"));
+    assert!(stdout.contains("Post B"));
+    assert!(stdout.contains("Post C"));
+}
+
+#[test]
+fn test_show_per_group_caps_groups() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}
+{"id":"2","title":"Post B","date":"2024-01-15T00:00:00Z","feed":"Bob"}
+{"id":"3","title":"Post C","date":"2024-01-15T00:00:00Z","feed":"Carol"}
+{"id":"4","title":"Post D","date":"2024-01-14T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx
+        .run(&["show", "/d", "--per-group", "2"])
+        .success()
+        .stdout_str();
+
+    assert!(stdout.contains("=== 2024-01-15 (3) ==="), "{stdout}");
+    assert!(stdout.contains("… and 1 more"), "{stdout}");
+    assert_eq!(stdout.matches("Post ").count(), 3, "{stdout}");
+    assert!(stdout.contains("=== 2024-01-14 (1) ==="), "{stdout}");
+    assert!(stdout.contains("Post D"), "{stdout}");
+}
+
+#[test]
+fn test_show_grouped_by_author_and_show_author() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice","author":"Jane"}
+{"id":"2","title":"Post B","date":"2024-01-15T00:00:00Z","feed":"Alice","author":"bob"}
+{"id":"3","title":"Post C","date":"2024-01-14T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx.run(&["show", "/a"]).success().stdout_str();
+    let bob = stdout.find("=== bob (").unwrap();
+    let jane = stdout.find("=== Jane (").unwrap();
+    let unknown = stdout.find("=== unknown author (").unwrap();
+    assert!(bob < jane && jane < unknown, "{stdout}");
+
+    assert!(
+        !ctx.run(&["show", "/d"])
+            .success()
+            .stdout_str()
+            .contains("Jane")
+    );
+    ctx.run(&["config", "set", "show_author", "true"]).success();
+    let stdout = ctx.run(&["show", "/d"]).success().stdout_str();
+    assert!(stdout.contains("Post A (Alice, Jane)"), "{stdout}");
+    assert!(stdout.contains("Post C (Alice)\n"), "{stdout}");
+}
+
+#[test]
+fn test_show_filters_by_category() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice","categories":["Security","rust"]}
+{"id":"2","title":"Post B","date":"2024-01-15T00:00:00Z","feed":"Alice","categories":["rust"]}
+{"id":"3","title":"Post C","date":"2024-01-14T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx
+        .run(&["show", "/d", "--category", "security", "--long"])
+        .success()
+        .stdout_str();
+    assert!(stdout.contains("Post A"), "{stdout}");
+    assert!(stdout.contains("Categories: Security, rust"), "{stdout}");
+    assert!(!stdout.contains("Post B"), "{stdout}");
+    assert!(!stdout.contains("Post C"), "{stdout}");
+
+    let stderr = ctx
+        .run(&["show", "/d", "--category", "go"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("No matching posts"));
+}
+
+#[test]
+fn test_dates_are_shown_in_local_time_unless_utc_is_configured() {
+    let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Evening Post","date":"2024-01-15T02:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    let show = |args: &[&str]| {
+        blog_cmd()
+            .args(args)
+            .env("RSS_STORE", ctx.dir.path())
+            .env("TZ", "EST5")
+            .assert()
+            .success()
+            .stdout_str()
+    };
+
+    let stdout = show(&[".all", "/d"]);
+    assert!(stdout.contains("=== 2024-01-14 ("), "got:\n{stdout}");
+    // 2024-01-15 starts at local midnight, after the post
+    let stdout = show(&[".all", "2024-01-14.."]);
+    assert!(stdout.contains("Evening Post"), "got:\n{stdout}");
+    blog_cmd()
+        .args([".all", "2024-01-15.."])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("TZ", "EST5")
+        .assert()
+        .failure();
+
+    ctx.run(&["config", "set", "timezone", "utc"]).success();
+    let stdout = show(&[".all", "/d"]);
+    assert!(stdout.contains("=== 2024-01-15 ("), "got:\n{stdout}");
+}
+
+#[test]
+fn test_colors_config_rejects_unknown_color() {
+    let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Post A","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    ctx.run(&["config", "set", "colors.date", "bright-black"])
+        .success();
+    ctx.run(&[".all"]).success();
+
+    ctx.run(&["config", "set", "colors.date", "teal"]).success();
+
+    let stderr = ctx.run(&[".all"]).failure().stderr_str();
+    assert!(stderr.contains("colors.date"), "got:\n{stderr}");
+}
+
+#[rstest]
+#[case::today("today", "Fresh", &["Stale", "Old"])]
+#[case::yesterday("yesterday", "Stale", &["Fresh", "Old"])]
+fn test_day_commands_show_one_day(
+    #[case] command: &str,
+    #[case] expected: &str,
+    #[case] excluded: &[&str],
+) {
+    let ctx = TestContext::new();
+    let day = |days_ago: i64| {
+        (chrono::Utc::now().date_naive() - chrono::Duration::days(days_ago))
+            .and_hms_opt(0, 0, 1)
+            .unwrap()
+            .and_utc()
+            .to_rfc3339()
+    };
+    let posts = format!(
+        r#"{{"id":"1","title":"Fresh","date":"{}","feed":"Alice"}}
+{{"id":"2","title":"Stale","date":"{}","feed":"Bob"}}
+{{"id":"3","title":"Old","date":"{}","feed":"Alice"}}"#,
+        day(0),
+        day(1),
+        day(5)
+    );
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx.run(&[command]).success().stdout_str();
+
+    assert!(stdout.contains("==="), "should be grouped, got:\n{stdout}");
+    assert!(stdout.contains(expected), "got:\n{stdout}");
+    for title in excluded {
+        assert!(!stdout.contains(title), "got:\n{stdout}");
+    }
+}
+
+#[test]
+fn test_day_commands_reject_date_range() {
+    let ctx = TestContext::new();
+
+    let stderr = ctx.run(&["1w..", "today"]).failure().stderr_str();
+    assert!(stderr.contains("date range"), "got:\n{stderr}");
+}
+
+#[test]
+fn test_show_default_no_subcommand() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Default Show","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let output = ctx.run(&["2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+
+    assert!(stdout.contains("Default Show"));
+    assert!(stdout.contains("Alice"));
+}
+
+#[rstest]
+#[case::show_subcommand(&["show", "--long", "2020-01-01.."])]
+#[case::no_subcommand(&["--long", "2020-01-01.."])]
+#[case::short_flag(&["-l", "2020-01-01.."])]
+fn test_show_long_prints_summary_excerpt(#[case] args: &[&str]) {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Hello World","date":"2024-01-15T00:00:00Z","feed":"Alice","summary":"<p>A <em>short</em> summary &amp; more</p>"}
+{"id":"2","title":"No Summary","date":"2024-01-14T00:00:00Z","feed":"Bob"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx.run(args).success().stdout_str();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines.len(), 3, "expected one excerpt line, got:\n{stdout}");
+    assert!(lines[0].contains("Hello World"));
+    assert_eq!(lines[1].trim(), "A short summary & more");
+    assert!(lines[2].contains("No Summary"));
+}
+
+#[test]
+fn test_sync_then_show() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Roundtrip Blog",
+        &[("Roundtrip Post", "Wed, 03 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/feed.xml", &xml);
+
+    let url = ctx.server.url("/feed.xml");
+    ctx.write_feeds(&[&url]);
+
+    ctx.run(&["sync"]).success();
+
+    let output = ctx.run(&["show", "2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+
+    assert!(stdout.contains("Roundtrip Post"));
+    assert!(stdout.contains("Roundtrip Blog"));
+}
+
+#[test]
+fn test_serde_roundtrip() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Serde Blog",
+        &[
+            ("Post One", "Mon, 01 Jan 2024 12:00:00 +0000"),
+            ("Post Two", "Tue, 02 Jan 2024 12:00:00 +0000"),
+        ],
+    );
+    ctx.mock_rss_feed("/feed.xml", &xml);
+
+    let url = ctx.server.url("/feed.xml");
+    ctx.write_feeds(&[&url]);
+
+    ctx.run(&["sync"]).success();
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 2);
+
+    // Write back and re-read to verify roundtrip
+    let mut out = String::new();
+    for post in &posts {
+        out.push_str(&serde_json::to_string(post).unwrap());
+        out.push('\n');
+    }
+    // Remove existing shard files and write all to a single shard
+    if let Ok(entries) = fs::read_dir(ctx.dir.path().join("posts")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(fname) = path.file_name().and_then(|f| f.to_str())
+                && fname.starts_with("items_")
+                && fname.ends_with(".jsonl")
+            {
+                fs::remove_file(&path).unwrap();
+            }
+        }
+    }
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), &out).unwrap();
+
+    let posts2 = ctx.read_posts();
+    assert_eq!(posts, posts2);
+}
+
+#[test]
+fn test_sync_twice_no_duplicates() {
+    let ctx = TestContext::new();
+
+    let xml1 = rss_xml_with_guids(
+        "Blog",
+        &[
+            ("Post A", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-a"),
+            ("Post B", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-b"),
+        ],
+    );
+    ctx.mock_rss_feed("/feed.xml", &xml1);
+
+    let url = ctx.server.url("/feed.xml");
+    ctx.write_feeds(&[&url]);
+
+    ctx.run(&["sync"]).success();
+    let posts1 = ctx.read_posts();
+    assert_eq!(posts1.len(), 2);
+
+    // Second pull with overlapping + new item
+    let xml2 = rss_xml_with_guids(
+        "Blog",
+        &[
+            (
+                "Post B Updated",
+                "Tue, 02 Jan 2024 00:00:00 +0000",
+                "guid-b",
+            ),
+            ("Post C", "Wed, 03 Jan 2024 00:00:00 +0000", "guid-c"),
+        ],
+    );
+    ctx.mock_rss_feed("/feed2.xml", &xml2);
+
+    let url2 = ctx.server.url("/feed2.xml");
+    ctx.write_feeds(&[&url2]);
+
+    ctx.run(&["sync"]).success();
+    let posts2 = ctx.read_posts();
+
+    // Should have 3 items: A (from first pull, preserved), B (updated), C (new)
+    assert_eq!(posts2.len(), 3);
+
+    let titles: Vec<&str> = posts2
+        .iter()
+        .map(|p| p["title"].as_str().unwrap())
+        .collect();
+    assert!(titles.contains(&"Post A"));
+    assert!(titles.contains(&"Post B Updated"));
+    assert!(titles.contains(&"Post C"));
+    // Original "Post B" should be overwritten
+    assert!(!titles.contains(&"Post B"));
+}
+
+#[test]
+fn test_add_creates_feed() {
+    let ctx = TestContext::new();
+    let xml = rss_xml("Test Feed", &[]);
+    ctx.mock_rss_feed("/feed.xml", &xml);
+
+    let url = ctx.server.url("/feed.xml");
+    ctx.run(&["feed", "add", &url]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"].as_str().unwrap(), url);
+}
+
+#[test]
+fn test_add_then_sync() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Added Blog",
+        &[("Added Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/added.xml", &xml);
+
+    let url = ctx.server.url("/added.xml");
+    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["sync"]).success();
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Added Post");
+}
+
+#[test]
+fn test_add_stores_feed_title_and_posts_without_sync() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Added Blog",
+        &[("Added Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/added.xml", &xml);
+
+    let url = ctx.server.url("/added.xml");
+    let stderr = ctx.run(&["feed", "add", &url]).success().stderr_str();
+
+    assert!(!stderr.contains("blog sync"), "got:\n{stderr}");
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds[0]["title"], "Added Blog");
+    assert_eq!(feeds[0]["is_fetched"], true);
+    let stdout = ctx.run(&["feed", "ls"]).success().stdout_str();
+    assert!(stdout.contains("Added Blog"), "got:\n{stdout}");
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Added Post");
+}
+
+#[test]
+fn test_sync_continues_after_feed_failure() {
+    let ctx = TestContext::new();
+
+    // One feed returns a 500 error
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/broken.xml");
+        then.status(500).body("Internal Server Error");
+    });
+
+    // The other feed works fine
+    let xml = rss_xml(
+        "Good Blog",
+        &[("Good Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/good.xml", &xml);
+
+    let broken_url = ctx.server.url("/broken.xml");
+    let good_url = ctx.server.url("/good.xml");
+    ctx.write_feeds(&[&broken_url, &good_url]);
+
+    let output = ctx.run(&["sync"]).code(2);
+    let stderr = output.stderr_str();
+
+    // Error message should mention the HTTP status, not a confusing XML parse error
+    assert!(
+        stderr.contains("500"),
+        "error should mention HTTP 500 status, got: {}",
+        stderr
+    );
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Good Post");
+}
+
+#[rstest]
+#[case::server_error(503, 3)]
+#[case::rate_limited(429, 3)]
+#[case::not_found(404, 1)]
+fn test_sync_retries_only_transient_failures(#[case] status: u16, #[case] expected_calls: usize) {
+    let ctx = TestContext::new();
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/flaky.xml");
+        then.status(status);
+    });
+    ctx.write_feeds(&[&ctx.server.url("/flaky.xml")]);
+    ctx.run(&["config", "set", "fetch_backoff_ms", "0"])
+        .success();
+
+    ctx.run(&["sync"]).code(2);
+
+    mock.assert_calls(expected_calls);
+}
+
+#[test]
+fn test_sync_retry_count_is_configurable() {
+    let ctx = TestContext::new();
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/down.xml");
+        then.status(500);
+    });
+    ctx.write_feeds(&[&ctx.server.url("/down.xml")]);
+    ctx.run(&["config", "set", "fetch_backoff_ms", "0"])
+        .success();
+    ctx.run(&["config", "set", "fetch_retries", "0"]).success();
+
+    ctx.run(&["sync"]).code(2);
+
+    mock.assert_calls(1);
+}
+
+#[test]
+fn test_sync_waits_for_retry_after() {
+    let ctx = TestContext::new();
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/limited.xml");
+        then.status(429).header("Retry-After", "1");
+    });
+    ctx.write_feeds(&[&ctx.server.url("/limited.xml")]);
+    ctx.run(&["config", "set", "fetch_backoff_ms", "0"])
+        .success();
+    ctx.run(&["config", "set", "fetch_retries", "1"]).success();
+
+    let started = std::time::Instant::now();
+    ctx.run(&["sync"]).code(2);
+
+    mock.assert_calls(2);
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[test]
+fn test_sync_gives_up_on_long_retry_after() {
+    let ctx = TestContext::new();
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/limited.xml");
+        then.status(429).header("Retry-After", "3600");
+    });
+    ctx.write_feeds(&[&ctx.server.url("/limited.xml")]);
+
+    let stderr = ctx.run(&["sync"]).code(2).stderr_str();
+
+    assert!(stderr.contains("429"), "got: {stderr}");
+    mock.assert_calls(1);
+}
+
+#[test]
+fn test_sync_goes_through_configured_proxy() {
+    let ctx = TestContext::new();
+    let xml = rss_xml("Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/feed.xml");
+        then.status(200).body(&xml);
+    });
+    ctx.write_feeds(&[&ctx.server.url("/feed.xml")]);
+    // Nothing listens here, so a direct fetch would succeed but a proxied one fails
+    ctx.run(&["config", "set", "proxy", "socks5://127.0.0.1:1"])
+        .success();
+    ctx.run(&["config", "set", "fetch_retries", "0"]).success();
+
+    let stderr = ctx.run(&["sync"]).code(2).stderr_str();
+
+    assert!(stderr.contains("Error fetching"), "got:\n{stderr}");
+    mock.assert_calls(0);
+}
+
+#[test]
+fn test_sync_rejects_invalid_proxy_config() {
+    let ctx = TestContext::new();
+    ctx.run(&["config", "set", "proxy", "gopher://proxy:70"])
+        .success();
+
+    let stderr = ctx.run(&["sync"]).failure().stderr_str();
+    assert!(
+        stderr.contains("Invalid value for config proxy"),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn test_sync_follows_permanent_redirect_and_updates_url() {
+    let ctx = TestContext::new();
+    let first = rss_xml_with_guids(
+        "Moving Blog",
+        &[("Old Post", "Mon, 01 Jan 2024 00:00:00 +0000", "old")],
+    );
+    let mut old = ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(200).body(&first);
+    });
+    let old_url = ctx.server.url("/old.xml");
+    let new_url = ctx.server.url("/new.xml");
+    ctx.write_feeds(&[&old_url]);
+    ctx.run(&["sync"]).success();
+    old.delete();
+
+    let moved = ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(301).header("Location", "/new.xml");
+    });
+    let second = rss_xml_with_guids(
+        "Moving Blog",
+        &[
+            ("Old Post", "Mon, 01 Jan 2024 00:00:00 +0000", "old"),
+            ("New Post", "Tue, 02 Jan 2024 00:00:00 +0000", "new"),
+        ],
+    );
+    let new = ctx.server.mock(|when, then| {
+        when.method(GET).path("/new.xml");
+        then.status(200).body(&second);
+    });
+
+    let stderr = ctx.run(&["sync"]).success().stderr_str();
+    assert!(
+        stderr.contains(&format!("Feed moved: {old_url} -> {new_url}")),
+        "got:\n{stderr}"
+    );
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"], new_url.as_str());
+
+    // Posts from before the move stay attached to the feed
+    let stdout = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
+    let labels: Vec<&str> = stdout
+        .lines()
+        .map(|l| &l[l.find("(@").expect("post without feed label")..])
+        .collect();
+    assert_eq!(labels.len(), 2, "got:\n{stdout}");
+    assert_eq!(labels[0], labels[1]);
+
+    // Later syncs go straight to the new location
+    ctx.run(&["sync"]).success();
+    moved.assert_calls(1);
+    new.assert_calls(2);
+}
+
+#[test]
+fn test_sync_redirect_to_subscribed_feed_keeps_its_settings() {
+    let ctx = TestContext::new();
+    let old_xml = rss_xml_with_guids(
+        "Old Blog",
+        &[("Old Post", "Mon, 01 Jan 2024 00:00:00 +0000", "old")],
+    );
+    let mut old = ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(200).body(&old_xml);
+    });
+    let new_xml = rss_xml_with_guids(
+        "New Blog",
+        &[("New Post", "Tue, 02 Jan 2024 00:00:00 +0000", "new")],
+    );
+    ctx.mock_rss_feed("/new.xml", &new_xml);
+    let old_url = ctx.server.url("/old.xml");
+    let new_url = ctx.server.url("/new.xml");
+    ctx.write_feeds(&[&old_url, &new_url]);
+    ctx.run(&["sync"]).success();
+    let shorthand = feed_shorthand_for_url(&ctx, &new_url);
+    ctx.run(&["feed", "folder", &shorthand, "Tech"]).success();
+    old.delete();
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(301).header("Location", "/new.xml");
+    });
+
+    ctx.run(&["sync"]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"], new_url.as_str());
+    assert_eq!(feeds[0]["folder"], "Tech");
+    // The posts of the old feed move over to the one already subscribed
+    let shorthand = feed_shorthand_for_url(&ctx, &new_url);
+    let stdout = ctx
+        .run(&[&shorthand, "2020-01-01.."])
+        .success()
+        .stdout_str();
+    assert!(stdout.contains("Old Post"), "got:\n{stdout}");
+    assert!(stdout.contains("New Post"), "got:\n{stdout}");
+}
+
+#[test]
+fn test_sync_keeps_url_on_temporary_redirect() {
+    let ctx = TestContext::new();
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/feed.xml");
+        then.status(302).header("Location", "/elsewhere.xml");
+    });
+    let xml = rss_xml("Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/elsewhere.xml", &xml);
+    let url = ctx.server.url("/feed.xml");
+    ctx.write_feeds(&[&url]);
+
+    ctx.run(&["sync"]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds[0]["url"], url.as_str());
+    assert_eq!(ctx.read_posts().len(), 1);
+}
+
+/// Read one HTTP request from `stream`, returning the request line and body.
+fn read_http_request(stream: &mut std::net::TcpStream) -> (String, String) {
+    use std::io::Read;
+    let mut reader = std::io::BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+    (request_line, String::from_utf8(body).unwrap())
+}
+
+/// Send a request to `url` and return the response body.
+fn send_http(url: &str, method: &str, headers: &[(&str, &str)], body: &str) -> String {
+    use std::io::Read;
+    let url = url::Url::parse(url).unwrap();
+    let mut stream =
+        std::net::TcpStream::connect((url.host_str().unwrap(), url.port().unwrap())).unwrap();
+    let target = match url.query() {
+        Some(q) => format!("{}?{}", url.path(), q),
+        None => url.path().to_string(),
+    };
+    let mut request = format!(
+        "{method} {target} HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.split_once("\r\n\r\n").unwrap().1.to_string()
+}
+
+#[test]
+fn test_watch_rejects_interval_too_long_to_schedule() {
+    let ctx = TestContext::new();
+    let stderr = ctx
+        .run(&["watch", "--interval", &u64::MAX.to_string()])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("--interval is too long"), "got:\n{stderr}");
+}
+
+#[test]
+fn test_watch_receives_websub_pushes() {
+    use hmac::{KeyInit, Mac};
+
+    let ctx = TestContext::new();
+    let hub = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let hub_url = format!("http://{}/", hub.local_addr().unwrap());
+    let feed_url = ctx.server.url("/feed.xml");
+    let feed = |items: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>Pushy Blog</title>
+    <atom:link rel="hub" href="{hub_url}"/>
+    <atom:link rel="self" href="{feed_url}"/>
+    {items}
+  </channel>
+</rss>"#
+        )
+    };
+    let first = "<item><title>Polled Post</title><guid>polled</guid></item>";
+    let xml = feed(first);
+    ctx.mock_rss_feed("/feed.xml", &xml);
+    ctx.write_feeds(&[&feed_url]);
+    ctx.run(&["sync"]).success();
+    assert_eq!(ctx.read_feeds()[0]["hub"], hub_url.as_str());
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let listen = format!("127.0.0.1:{port}");
+    let _watch = KillOnDrop(
+        std::process::Command::new(env!("CARGO_BIN_EXE_blog"))
+            .args(["watch", "--listen", &listen, "--callback"])
+            .arg(format!("http://{listen}/cb/"))
+            .env("RSS_STORE", ctx.dir.path())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+
+    // Play the hub: take the subscription request, verify, then push
+    let (mut stream, _) = hub.accept().unwrap();
+    let (request_line, body) = read_http_request(&mut stream);
+    stream
+        .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    drop(stream);
+    assert!(request_line.starts_with("POST"), "got {request_line}");
+    let form: std::collections::HashMap<String, String> =
+        url::form_urlencoded::parse(body.as_bytes())
+            .into_owned()
+            .collect();
+    assert_eq!(form["hub.mode"], "subscribe");
+    assert_eq!(form["hub.topic"], feed_url);
+    let callback = &form["hub.callback"];
+    assert!(callback.starts_with(&format!("http://{listen}/cb/")));
+
+    let mut verify = url::Url::parse(callback).unwrap();
+    verify
+        .query_pairs_mut()
+        .append_pair("hub.mode", "subscribe")
+        .append_pair("hub.topic", &feed_url)
+        .append_pair("hub.challenge", "ch4llenge")
+        .append_pair("hub.lease_seconds", "3600");
+    assert_eq!(send_http(verify.as_str(), "GET", &[], ""), "ch4llenge");
+
+    let pushed = feed(&format!(
+        "{first}<item><title>Pushed Post</title><guid>pushed</guid></item>"
+    ));
+    let mut mac = hmac::Hmac::<Sha256>::new_from_slice(form["hub.secret"].as_bytes()).unwrap();
+    mac.update(pushed.as_bytes());
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let forged = pushed.replace("Pushed Post", "Forged Post");
+    send_http(
+        callback,
+        "POST",
+        &[("X-Hub-Signature", &format!("sha256={signature}"))],
+        &forged,
+    );
+    send_http(
+        callback,
+        "POST",
+        &[("X-Hub-Signature", &format!("sha256={signature}"))],
+        &pushed,
+    );
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let titles = loop {
+        let titles: Vec<String> = ctx
+            .read_posts()
+            .iter()
+            .map(|p| p["title"].as_str().unwrap().to_string())
+            .collect();
+        if titles.len() > 1 || std::time::Instant::now() > deadline {
+            break titles;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    assert!(
+        titles.contains(&"Pushed Post".to_string()),
+        "got {titles:?}"
+    );
+    assert!(
+        !titles.contains(&"Forged Post".to_string()),
+        "got {titles:?}"
+    );
+}
+
+/// Kills a background `blog` when the test ends.
+struct KillOnDrop(std::process::Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start `blog serve <flag> ADDR` on a free port and wait until it accepts
+/// connections.
+fn spawn_serve(ctx: &TestContext, flag: &str, envs: &[(&str, &str)]) -> (String, KillOnDrop) {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let addr = format!("127.0.0.1:{port}");
+    let child = KillOnDrop(
+        std::process::Command::new(env!("CARGO_BIN_EXE_blog"))
+            .args(["serve", flag, &addr])
+            .env("RSS_STORE", ctx.dir.path())
+            .envs(envs.iter().copied())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while std::net::TcpStream::connect(&addr).is_err() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "blog serve didn't start"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    (addr, child)
+}
+
+#[test]
+fn test_serve_web_lists_posts_and_shows_articles() {
+    let ctx = TestContext::new();
+    let date = recent_rss_date(1);
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Web Blog</title>
+    <item><title>Tom &amp; Jerry</title><pubDate>{date}</pubDate><guid>tj</guid><link>https://web.example/tj</link>
+      <description><![CDATA[<p>Hello <b>browser</b></p><script>alert(1)</script>]]></description></item>
+    <item><title>Other Post</title><pubDate>{date}</pubDate><guid>other</guid></item>
+  </channel>
+</rss>"#
+    );
+    ctx.mock_rss_feed("/web.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/web.xml")]);
+    ctx.run(&["sync"]).success();
+    let shorthand = ctx.post_shorthand("Tom & Jerry");
+
+    let (addr, _serve) = spawn_serve(&ctx, "--web", &[]);
+
+    let listing = send_http(&format!("http://{addr}/?q=.all+/f"), "GET", &[], "");
+    assert!(listing.contains("<h2>"), "got {listing}");
+    assert!(
+        listing.contains(&format!(
+            "<a href=\"/post/{shorthand}\">Tom &amp; Jerry</a>"
+        )),
+        "got {listing}"
+    );
+    assert!(listing.contains("Other Post"), "got {listing}");
+
+    let filtered = send_http(&format!("http://{addr}/?q=~jerry"), "GET", &[], "");
+    assert!(filtered.contains("Tom &amp; Jerry"), "got {filtered}");
+    assert!(!filtered.contains("Other Post"), "got {filtered}");
+
+    let article = send_http(&format!("http://{addr}/post/{shorthand}"), "GET", &[], "");
+    assert!(
+        article.contains("<h1>Tom &amp; Jerry</h1>"),
+        "got {article}"
+    );
+    assert!(article.contains("<b>browser</b>"), "got {article}");
+    assert!(!article.contains("<script>"), "got {article}");
+    assert!(article.contains("https://web.example/tj"), "got {article}");
+
+    // Reading in the browser doesn't mark anything read
+    let unread = ctx.run(&[".unread"]).success().stdout_str();
+    assert!(unread.contains("Tom & Jerry"), "got {unread}");
+}
+
+#[test]
+fn test_sync_with_greader_account() {
+    let ctx = TestContext::new();
+    let api = "/api/greader.php";
+    ctx.server.mock(|when, then| {
+        when.method(POST)
+            .path(format!("{api}/accounts/ClientLogin"))
+            .body_includes("Email=me")
+            .body_includes("Passwd=hunter2");
+        then.status(200).body("SID=x\nLSID=x\nAuth=tok123\n");
+    });
+    ctx.server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{api}/reader/api/0/subscription/list"))
+            .header("Authorization", "GoogleLogin auth=tok123");
+        then.status(200).body(
+            r#"{"subscriptions": [{"id": "feed/1", "title": "Server Blog",
+                "url": "https://www.server.example/feed.xml", "htmlUrl": "https://server.example/"}]}"#,
+        );
+    });
+    ctx.server.mock(|when, then| {
+        when.method(GET).path(format!(
+            "{api}/reader/api/0/stream/contents/user/-/state/com.google/reading-list"
+        ));
+        then.status(200).body(
+            r#"{"items": [
+                {"id": "tag:google.com,2005:reader/item/0000000000000001", "title": "Read There",
+                 "published": 1704067200, "origin": {"streamId": "feed/1"},
+                 "categories": ["user/-/state/com.google/read"]},
+                {"id": "tag:google.com,2005:reader/item/0000000000000002", "title": "Unread There",
+                 "published": 1704153600, "origin": {"streamId": "feed/1"},
+                 "alternate": [{"href": "https://server.example/unread"}], "categories": []}
+            ]}"#,
+        );
+    });
+    ctx.server.mock(|when, then| {
+        when.method(GET).path(format!("{api}/reader/api/0/token"));
+        then.status(200).body("csrf\n");
+    });
+    let edit = ctx.server.mock(|when, then| {
+        when.method(POST)
+            .path(format!("{api}/reader/api/0/edit-tag"))
+            .body_includes("a=user%2F-%2Fstate%2Fcom.google%2Fread")
+            .body_includes("T=csrf")
+            .body_includes("i=tag%3Agoogle.com%2C2005%3Areader%2Fitem%2F0000000000000002");
+        then.status(200).body("OK");
+    });
+    ctx.run(&["config", "set", "greader_url", &ctx.server.url(api)])
+        .success();
+    ctx.run(&["config", "set", "greader_user", "me"]).success();
+
+    let sync = || {
+        blog_cmd()
+            .arg("sync")
+            .env("RSS_STORE", ctx.dir.path())
+            .env("BLOG_GREADER_PASSWORD", "hunter2")
+            .assert()
+            .success()
+    };
+    sync();
+
+    // Stored normalized, like feeds added here
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"], "https://server.example/feed.xml");
+    assert_eq!(feeds[0]["title"], "Server Blog");
+    let stdout = ctx.run(&[".unread", "2020-01-01.."]).success().stdout_str();
+    assert!(stdout.contains("Unread There"), "got:\n{stdout}");
+    assert!(!stdout.contains(" Read There"), "got:\n{stdout}");
+    edit.assert_calls(0);
+
+    // Reading it here pushes the read state to the server on the next sync
+    let sh = ctx.post_shorthand("Unread There");
+    ctx.run(&[sh.as_str(), "read", "--plain"]).success();
+    sync();
+    edit.assert_calls(1);
+}
+
+#[test]
+fn test_sync_with_greader_requires_password() {
+    let ctx = TestContext::new();
+    ctx.run(&[
+        "config",
+        "set",
+        "greader_url",
+        "https://rss.example/api/greader.php",
+    ])
+    .success();
+    ctx.run(&["config", "set", "greader_user", "me"]).success();
+
+    let stderr = blog_cmd()
+        .arg("sync")
+        .env("RSS_STORE", ctx.dir.path())
+        .env_remove("BLOG_GREADER_PASSWORD")
+        .assert()
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("BLOG_GREADER_PASSWORD"), "got:\n{stderr}");
+}
+
+#[test]
+fn test_sync_reports_http_404_clearly() {
+    let ctx = TestContext::new();
+
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/gone.xml");
+        then.status(404).body("Not Found");
+    });
+
+    let xml = rss_xml(
+        "Good Blog",
+        &[("Good Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/good.xml", &xml);
+
+    let gone_url = ctx.server.url("/gone.xml");
+    let good_url = ctx.server.url("/good.xml");
+    ctx.write_feeds(&[&gone_url, &good_url]);
+
+    let output = ctx.run(&["sync"]).code(2);
+    let stderr = output.stderr_str();
+
+    assert!(
+        stderr.contains("404"),
+        "error should mention HTTP 404 status, got: {}",
+        stderr
+    );
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Good Post");
+}
+
+#[test]
+fn test_remove_feed() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Blog to Remove",
+        &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/removable.xml", &xml);
+
+    let url = ctx.server.url("/removable.xml");
+    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["sync"]).success();
+
+    let output = ctx.run(&["show", "2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+    assert!(stdout.contains("Blog to Remove"));
+
+    ctx.run(&["feed", "rm", "--yes", &url]).success();
+
+    // Pull should no longer fetch the removed feed
+    ctx.run(&["sync"]).success();
+
+    // Feed and its posts should be gone — show should report no posts
+    let output = ctx.run(&["show", "2020-01-01.."]).failure();
+    let stderr = output.stderr_str();
+    assert!(stderr.contains("No matching posts"));
+}
+
+#[test]
+fn test_remove_feed_deletes_its_posts() {
+    let ctx = TestContext::new();
+
+    let xml1 = rss_xml_with_guids(
+        "Keep Blog",
+        &[("Keep Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-keep")],
+    );
+    ctx.mock_rss_feed("/keep.xml", &xml1);
+
+    let xml2 = rss_xml_with_guids(
+        "Remove Blog",
+        &[(
+            "Remove Post",
+            "Tue, 02 Jan 2024 00:00:00 +0000",
+            "guid-remove",
+        )],
+    );
+    ctx.mock_rss_feed("/remove.xml", &xml2);
+
+    let keep_url = ctx.server.url("/keep.xml");
+    let remove_url = ctx.server.url("/remove.xml");
+    ctx.write_feeds(&[&keep_url, &remove_url]);
+    ctx.run(&["sync"]).success();
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 2);
+
+    ctx.run(&["feed", "rm", "--yes", &remove_url]).success();
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Keep Post");
+}
+
+#[test]
+fn test_remove_feed_cleans_up_read_marks() {
+    let ctx = TestContext::new();
+
+    let date_a = recent_rss_date(1);
+    let xml = rss_xml_with_links(
+        "Doomed Blog",
+        &[(
+            "Doomed Post",
+            &date_a,
+            "guid-doomed",
+            "https://example.com/doomed",
+        )],
+    );
+    ctx.mock_rss_feed("/doomed.xml", &xml);
+    let url = ctx.server.url("/doomed.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    // Mark the post as read by opening it
+    let sh = ctx.post_shorthand("Doomed Post");
+    blog_cmd()
+        .args([sh.as_str(), "open"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "true")
+        .assert()
+        .success();
+
+    // Verify we have a read mark
+    let reads_before = read_table(&ctx.dir.path().join("reads"));
+    assert_eq!(
+        reads_before.len(),
+        1,
+        "expected 1 read mark before removal, got: {reads_before:?}"
+    );
+
+    // Now remove the feed
+    ctx.run(&["feed", "rm", "--yes", &url]).success();
+
+    // Read marks for deleted posts should be cleaned up
+    let reads_after = read_table(&ctx.dir.path().join("reads"));
+    assert_eq!(
+        reads_after.len(),
+        0,
+        "expected 0 read marks after feed removal (orphaned ReadMarks), got: {reads_after:?}"
+    );
+}
+
+#[test]
+fn test_feed_ls_by_recent() {
+    let ctx = TestContext::new();
+    let dormant = rss_xml_with_guids(
+        "Dormant Blog",
+        &[("Last Words", "Mon, 01 Jan 2018 00:00:00 +0000", "d-1")],
+    );
+    let active = rss_xml_with_guids(
+        "Active Blog",
+        &[
+            ("Older News", "Mon, 01 Jan 2024 00:00:00 +0000", "a-1"),
+            ("Fresh News", "Tue, 02 Jan 2024 00:00:00 +0000", "a-2"),
+        ],
+    );
+    ctx.mock_rss_feed("/dormant.xml", &dormant);
+    ctx.mock_rss_feed("/active.xml", &active);
+    ctx.write_feeds(&[
+        &ctx.server.url("/dormant.xml"),
+        &ctx.server.url("/active.xml"),
+        &ctx.server.url("/empty.xml"),
+    ]);
+    ctx.run(&["sync"]).code(2);
+
+    let stdout = ctx
+        .run(&["feed", "ls", "--by-recent"])
+        .success()
+        .stdout_str();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "got:\n{stdout}");
+    assert!(lines[0].contains("2024-01-02"), "got:\n{stdout}");
+    assert!(lines[0].ends_with("Fresh News"), "got:\n{stdout}");
+    assert!(lines[1].contains("2018-01-01"), "got:\n{stdout}");
+    assert!(lines[1].ends_with("Last Words"), "got:\n{stdout}");
+    // Never fetched, so named by its (truncated) URL and without a post
+    assert!(lines[2].contains("  -  "), "got:\n{stdout}");
+    assert!(lines[2].contains("http://127.0.0.1"), "got:\n{stdout}");
+
+    // Titles line up in one column
+    let column = lines[0].find("Fresh News").unwrap();
+    assert_eq!(lines[1].find("Last Words"), Some(column));
+}
+
+#[test]
+fn test_feed_ls() {
+    let ctx = TestContext::new();
+
+    insert_feed(ctx.dir.path(), "https://example.com/feed1.xml");
+    insert_feed(ctx.dir.path(), "https://example.com/feed2.xml");
+
+    let output = ctx.run(&["feed", "ls"]).success();
+    let stdout = output.stdout_str();
+
+    assert!(stdout.contains("https://example.com/feed1.xml"));
+    assert!(stdout.contains("https://example.com/feed2.xml"));
+
+    // Each line should start with a shorthand consisting only of home-row characters
+    let home_row_chars: &[char] = &['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l'];
+    for line in stdout.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
+        assert!(
+            first_word.starts_with('@'),
+            "line should start with @shorthand: {}",
+            line
+        );
+        let shorthand = &first_word[1..];
+        assert!(
+            !shorthand.is_empty(),
+            "shorthand should not be empty: {}",
+            line
+        );
+        assert!(
+            shorthand.chars().all(|c| home_row_chars.contains(&c)),
+            "shorthand '{}' contains non-home-row characters in line: {}",
+            shorthand,
+            line,
+        );
+    }
+}
+
+#[test]
+fn test_feed_info() {
+    let ctx = TestContext::new();
+
+    let alpha = rss_xml_with_guids(
+        "Alpha Blog",
+        &[
+            ("First", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-1"),
+            ("Second", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-2"),
+        ],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&alpha_url]);
+    ctx.run(&["sync"]).success();
+    let shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
+
+    let stdout = ctx
+        .run(&["feed", "info", &shorthand])
+        .success()
+        .stdout_str();
+
+    assert!(
+        stdout.contains(&format!("url          {alpha_url}")),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("title        Alpha Blog"), "got:\n{stdout}");
+    assert!(stdout.contains("last fetch   ok"), "got:\n{stdout}");
+    assert!(stdout.contains("posts        2 "), "got:\n{stdout}");
+    assert!(
+        stdout.contains("newest post  2024-01-02 Second"),
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn test_feed_info_shows_last_fetch_error() {
+    let ctx = TestContext::new();
+
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/gone.xml");
+        then.status(404);
+    });
+    let url = ctx.server.url("/gone.xml");
+    ctx.write_feeds(&[&url]);
+    let stderr = ctx.run(&["sync"]).code(2).stderr_str();
+    assert!(!stderr.contains("failing since"), "got:\n{stderr}");
+
+    let stdout = ctx.run(&["feed", "info", &url]).success().stdout_str();
+    assert!(
+        stdout.contains("last fetch   failing since "),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("404"), "got:\n{stdout}");
+
+    // Once it failed before, the report says since when
+    let stderr = ctx.run(&["sync"]).code(2).stderr_str();
+    assert!(
+        stderr.contains(&format!("Error fetching {url}: ")),
+        "got:\n{stderr}"
+    );
+    assert!(stderr.contains(" (failing since "), "got:\n{stderr}");
+}
+
+#[test]
+fn test_feed_ls_no_feeds_prints_error() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run(&["feed", "ls"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("No feeds found"),
+        "expected 'No feeds found' on stderr, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_trailing_slash_does_not_duplicate_feed() {
+    let ctx = TestContext::new();
+
+    let xml = rss_xml("Slash Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/feed.xml", &xml);
+    ctx.mock_rss_feed("/feed.xml/", &xml);
+
+    let url = ctx.server.url("/feed.xml");
+    let url_slash = format!("{}/", url);
+
+    ctx.run(&["feed", "add", &url]).success();
+    let stderr = ctx.run(&["feed", "add", &url_slash]).failure().stderr_str();
+    assert!(
+        stderr.contains(&format!("already subscribed to {url} as @")),
+        "got:\n{stderr}"
+    );
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(
+        feeds.len(),
+        1,
+        "trailing slash should not create a duplicate feed, got: {feeds:?}"
+    );
+}
+
+#[test]
+fn test_adding_feed_that_redirects_to_a_subscription_is_refused() {
+    let ctx = TestContext::new();
+    let xml = rss_xml("Moved Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/feed.xml", &xml);
+    let url = ctx.server.url("/feed.xml");
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(301).header("Location", &url);
+    });
+
+    ctx.run(&["feed", "add", &url]).success();
+    let shorthand = feed_shorthand_for_url(&ctx, &url);
+    let stderr = ctx
+        .run(&["feed", "add", &ctx.server.url("/old.xml")])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains(&shorthand), "got:\n{stderr}");
+    assert_eq!(ctx.read_feeds().len(), 1);
+
+    // In a batch, the duplicate fails on its own
+    let other = rss_xml("Other Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/other.xml", &other);
+    let stderr = ctx
+        .run(&[
+            "feed",
+            "add",
+            &ctx.server.url("/other.xml"),
+            &ctx.server.url("/old.xml"),
+            "--file",
+            "/dev/null",
+        ])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("Added 1 feed."), "got:\n{stderr}");
+    assert_eq!(ctx.read_feeds().len(), 2);
+}
+
+#[test]
+fn test_feed_remove_by_shorthand() {
+    let ctx = TestContext::new();
+
+    let xml1 = rss_xml_with_guids(
+        "Keep Blog",
+        &[("Keep Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-keep")],
+    );
+    ctx.mock_rss_feed("/keep.xml", &xml1);
+
+    let xml2 = rss_xml_with_guids(
+        "Remove Blog",
+        &[(
+            "Remove Post",
+            "Tue, 02 Jan 2024 00:00:00 +0000",
+            "guid-remove",
+        )],
+    );
+    ctx.mock_rss_feed("/remove.xml", &xml2);
+
+    let keep_url = ctx.server.url("/keep.xml");
+    let remove_url = ctx.server.url("/remove.xml");
+    ctx.write_feeds(&[&keep_url, &remove_url]);
+    ctx.run(&["sync"]).success();
+
+    // Run feed ls and parse the shorthand for the remove_url
+    let output = ctx.run(&["feed", "ls"]).success();
+    let stdout = output.stdout_str();
+
+    let shorthand = stdout
+        .lines()
+        .find(|line| line.contains(&remove_url))
+        .map(|line| {
+            let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
+            first_word // includes the '@' prefix
+        })
+        .expect("should find remove_url in feed ls output");
+
+    // Remove using the shorthand
+    ctx.run(&["feed", "rm", "--yes", &shorthand]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"].as_str().unwrap(), keep_url);
+
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "Keep Post");
+}
+
+#[test]
+fn test_show_no_posts_prints_error() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run(&["show"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("No matching posts"),
+        "expected 'No matching posts' on stderr, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_show_filter_by_shorthand() {
+    let ctx = TestContext::new();
+
+    let xml1 = rss_xml_with_guids(
+        "Alpha Blog",
+        &[
+            ("Alpha Post 1", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-a1"),
+            ("Alpha Post 2", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-a2"),
+        ],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &xml1);
+
+    let xml2 = rss_xml_with_guids(
+        "Beta Blog",
+        &[("Beta Post 1", "Wed, 03 Jan 2024 00:00:00 +0000", "guid-b1")],
+    );
+    ctx.mock_rss_feed("/beta.xml", &xml2);
+
+    let alpha_url = ctx.server.url("/alpha.xml");
+    let beta_url = ctx.server.url("/beta.xml");
+    ctx.write_feeds(&[&alpha_url, &beta_url]);
+    ctx.run(&["sync"]).success();
+
+    // Get shorthand for alpha feed
+    let output = ctx.run(&["feed", "ls"]).success();
+    let stdout = output.stdout_str();
+
+    let alpha_shorthand = stdout
+        .lines()
+        .find(|line| line.contains(&alpha_url))
+        .map(|line| {
+            let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
+            first_word
+        })
+        .expect("should find alpha_url in feed ls output");
+
+    // Filter with `show @shorthand` — should only show alpha posts
+    let output = ctx.run(&["show", &alpha_shorthand]).success();
+    let stdout = output.stdout_str();
+
+    assert!(
+        stdout.contains("Alpha Post 1"),
+        "should contain Alpha Post 1"
+    );
+    assert!(
+        stdout.contains("Alpha Post 2"),
+        "should contain Alpha Post 2"
+    );
+    assert!(
+        !stdout.contains("Beta Post 1"),
+        "should NOT contain Beta Post 1"
+    );
+
+    // Also test with no subcommand: `blog @shorthand`
+    let output = ctx.run(&[&alpha_shorthand]).success();
+    let stdout = output.stdout_str();
+
+    assert!(
+        stdout.contains("Alpha Post 1"),
+        "no-subcommand: should contain Alpha Post 1"
+    );
+    assert!(
+        stdout.contains("Alpha Post 2"),
+        "no-subcommand: should contain Alpha Post 2"
+    );
+    assert!(
+        !stdout.contains("Beta Post 1"),
+        "no-subcommand: should NOT contain Beta Post 1"
+    );
+}
+
+#[test]
+fn test_show_filter_unknown_shorthand() {
+    let ctx = TestContext::new();
+
+    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+
+    let output = ctx.run(&["show", "@zzz"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Unknown feed shorthand"),
+        "expected unknown shorthand error, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_remove_then_readd_feed() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Returning Blog",
+        &[("Old Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+    );
+    ctx.mock_rss_feed("/returning.xml", &xml);
+
+    let url = ctx.server.url("/returning.xml");
+    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["sync"]).success();
+    ctx.run(&["feed", "rm", "--yes", &url]).success();
+
+    // Re-add and pull again
+    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["sync"]).success();
+
+    let output = ctx.run(&["show", "2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+    assert!(stdout.contains("Returning Blog"));
+    assert!(stdout.contains("Old Post"));
+}
+
+#[test]
+fn test_show_displays_post_shorthands() {
+    let ctx = TestContext::new();
+
+    let date_a = recent_rss_date(2);
+    let date_b = recent_rss_date(3);
+    let xml = rss_xml_with_guids(
+        "Shorthand Blog",
+        &[
+            ("Post Alpha", &date_a, "guid-alpha"),
+            ("Post Beta", &date_b, "guid-beta"),
+        ],
+    );
+    ctx.mock_rss_feed("/shorthand.xml", &xml);
+
+    let url = ctx.server.url("/shorthand.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let output = ctx.run(&["show", ".all", "2020-01-01.."]).success();
+    let stdout = output.stdout_str();
+
+    let post_alphabet: &[char] = &[
+        'a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'A', 'S', 'D', 'F', 'G', 'H', 'J', 'K', 'L',
+        'q', 'w', 'e', 'r', 't', 'y', 'i', 'o', 'p', 'z', 'x', 'c', 'v', 'b', 'n', 'm',
+    ];
+
+    for line in stdout.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Lines are: "* YYYY-MM-DD  shorthand title (meta)"
+        let words: Vec<&str> = line.split_whitespace().collect();
+        assert!(
+            words.len() >= 3,
+            "line should have a marker, date, and shorthand: {}",
+            line
+        );
+        let shorthand = words[2];
+        assert!(
+            shorthand.chars().all(|c| post_alphabet.contains(&c)),
+            "shorthand '{}' should only contain POST_ALPHABET characters in line: {}",
+            shorthand,
+            line,
+        );
+    }
+}
+
+#[test]
+fn test_open_unknown_shorthand() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run(&["zzzzz", "open"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("Unknown shorthand: zzzzz"),
+        "expected 'Unknown shorthand: zzzzz' on stderr, got: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn test_open_valid_shorthand() {
+    let ctx = TestContext::new();
+
+    let xml = rss_xml_with_links(
+        "Open Blog",
+        &[(
+            "Open Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-open",
+            "https://example.com/post/1",
+        )],
+    );
+    ctx.mock_rss_feed("/open.xml", &xml);
+
+    let url = ctx.server.url("/open.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    // Running `<shorthand> open` should resolve the shorthand without error.
+    // Use BROWSER=true to prevent actually opening a browser.
+    let sh = ctx.post_shorthand("Open Post");
+    let output = blog_cmd()
+        .args([sh.as_str(), "open"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "true")
+        .assert();
+    let stderr = output.stderr_str();
+    assert!(
+        !stderr.contains("Unknown shorthand"),
+        "should resolve shorthand {sh}, got: {}",
+        stderr,
+    );
+    assert!(
+        !stderr.contains("Post has no link"),
+        "post should have a link, got: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn test_open_feed_shorthand_opens_site_url() {
+    let ctx = TestContext::new();
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Site Blog</title>
+    <link>https://blog.example.com/</link>
+    <item><title>Post</title><pubDate>Mon, 01 Jan 2024 00:00:00 +0000</pubDate><guid>g</guid><link>https://blog.example.com/post</link></item>
+  </channel>
+</rss>"#;
+    ctx.mock_rss_feed("/site.xml", xml);
+    let url = ctx.server.url("/site.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let shorthand = ctx
+        .run(&["feed", "ls"])
+        .success()
+        .stdout_str()
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string();
+    let stdout = blog_cmd()
+        .args([shorthand.as_str(), "open"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout_str();
+    assert_eq!(stdout, "https://blog.example.com/\n");
+
+    // Opening the website doesn't mark the feed's posts as read
+    let unread = ctx.run(&[".unread", "2020-01-01.."]).success().stdout_str();
+    assert!(unread.contains("Post"), "got:\n{unread}");
+}
+
+#[test]
+fn test_open_all_posts_of_a_day_or_feed() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Group Blog",
+        &[
+            (
+                "Later",
+                "Mon, 01 Jan 2024 14:00:00 +0000",
+                "g2",
+                "https://example.com/2",
+            ),
+            (
+                "Earlier",
+                "Mon, 01 Jan 2024 10:00:00 +0000",
+                "g1",
+                "https://example.com/1",
+            ),
+            (
+                "Next Day",
+                "Tue, 02 Jan 2024 12:00:00 +0000",
+                "g3",
+                "https://example.com/3",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/group.xml", &xml);
+    let url = ctx.server.url("/group.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
 
-    let xml2 = rss_xml_with_guids(
-        "Remove Blog",
-        &[(
-            "Remove Post",
-            "Tue, 02 Jan 2024 00:00:00 +0000",
-            "guid-remove",
-        )],
+    let stdout = blog_cmd()
+        .args(["open", "--date", "2024-01-01", "--yes"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout_str();
+    assert_eq!(stdout, "https://example.com/1\nhttps://example.com/2\n");
+    let unread = ctx.run(&[".unread", "2020-01-01.."]).success().stdout_str();
+    assert!(!unread.contains("Earlier"), "{unread}");
+    assert!(unread.contains("Next Day"), "{unread}");
+
+    let shorthand = feed_shorthand_for_url(&ctx, &url);
+    let output = blog_cmd()
+        .args([shorthand.as_str(), "open", "--all"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "echo")
+        .write_stdin("n\n")
+        .assert()
+        .success();
+    assert!(
+        output
+            .stderr_str()
+            .contains("Open 3 posts in the browser? [y/N]")
     );
-    ctx.mock_rss_feed("/remove.xml", &xml2);
+    assert_eq!(output.stdout_str(), "");
+}
 
-    let keep_url = ctx.server.url("/keep.xml");
-    let remove_url = ctx.server.url("/remove.xml");
-    ctx.write_feeds(&[&keep_url, &remove_url]);
+#[test]
+fn test_read_next_and_prev_move_from_last_read_post() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Nav Blog",
+        &[
+            (
+                "Newest",
+                "Wed, 03 Jan 2024 12:00:00 +0000",
+                "g3",
+                "https://example.com/3",
+            ),
+            (
+                "Middle",
+                "Tue, 02 Jan 2024 12:00:00 +0000",
+                "g2",
+                "https://example.com/2",
+            ),
+            (
+                "Oldest",
+                "Mon, 01 Jan 2024 12:00:00 +0000",
+                "g1",
+                "https://example.com/1",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/nav.xml", &xml);
+    let url = ctx.server.url("/nav.xml");
+    ctx.write_feeds(&[&url]);
     ctx.run(&["sync"]).success();
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 2);
+    let stderr = ctx.run(&["read", "--next"]).failure().stderr_str();
+    assert!(stderr.contains("No post read yet"), "{stderr}");
 
-    ctx.run(&["feed", "rm", &remove_url]).success();
+    let sh = ctx.post_shorthand("Middle");
+    ctx.run(&[&sh, "read", "--plain"]).success();
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 1);
-    assert_eq!(posts[0]["title"].as_str().unwrap(), "Keep Post");
+    let next = ctx.run(&["read", "--next", "--plain"]).success();
+    assert_eq!(next.stdout_str(), "https://example.com/1\n");
+    let stderr = ctx.run(&["read", "--next"]).failure().stderr_str();
+    assert!(stderr.contains("is the last one"), "{stderr}");
+
+    let prev = ctx.run(&["read", "--prev", "--plain"]).success();
+    assert_eq!(prev.stdout_str(), "https://example.com/2\n");
+    let prev = ctx.run(&["read", "--prev", "--plain"]).success();
+    assert_eq!(prev.stdout_str(), "https://example.com/3\n");
+    let stderr = ctx.run(&["read", "--prev"]).failure().stderr_str();
+    assert!(stderr.contains("is the first one"), "{stderr}");
 }
 
 #[test]
-fn test_remove_feed_cleans_up_read_marks() {
+fn test_read_no_mark_and_hidden_read_posts() {
     let ctx = TestContext::new();
-
-    let date_a = recent_rss_date(1);
     let xml = rss_xml_with_links(
-        "Doomed Blog",
-        &[(
-            "Doomed Post",
-            &date_a,
-            "guid-doomed",
-            "https://example.com/doomed",
-        )],
+        "Mark Blog",
+        &[
+            (
+                "First",
+                "Tue, 02 Jan 2024 12:00:00 +0000",
+                "g1",
+                "https://example.com/1",
+            ),
+            (
+                "Second",
+                "Mon, 01 Jan 2024 12:00:00 +0000",
+                "g2",
+                "https://example.com/2",
+            ),
+        ],
     );
-    ctx.mock_rss_feed("/doomed.xml", &xml);
-    let url = ctx.server.url("/doomed.xml");
+    ctx.mock_rss_feed("/mark.xml", &xml);
+    let url = ctx.server.url("/mark.xml");
     ctx.write_feeds(&[&url]);
     ctx.run(&["sync"]).success();
 
-    // Mark the post as read by opening it
-    blog_cmd()
-        .args(["a", "open"])
+    // The first fetch marks all but the newest post read
+    let sh = ctx.post_shorthand("First");
+    ctx.run(&[&sh, "read", "--no-mark"]).success();
+    let unread = ctx.run(&[".unread", "2020-01-01.."]).success().stdout_str();
+    assert!(unread.contains("First"), "{unread}");
+
+    ctx.run(&["config", "set", "read_posts", "hide"]).success();
+    let listed = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(listed.contains("First"), "{listed}");
+    assert!(!listed.contains("Second"), "{listed}");
+    let all = ctx.run(&[".all", "2020-01-01.."]).success().stdout_str();
+    assert!(all.contains("Second"), "{all}");
+
+    ctx.run(&["config", "set", "read_posts", "loud"]).success();
+    let stderr = ctx.run(&["2020-01-01.."]).failure().stderr_str();
+    assert!(
+        stderr.contains("Invalid value for config read_posts"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_comments_opens_discussion_page() {
+    let ctx = TestContext::new();
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Blog</title>
+    <item><title>With Comments</title><pubDate>Mon, 01 Jan 2024 00:00:00 +0000</pubDate><guid>a</guid><link>https://blog.example.com/a</link><comments>https://news.example.com/item?id=1</comments></item>
+    <item><title>Without Comments</title><pubDate>Mon, 01 Jan 2024 00:00:00 +0000</pubDate><guid>b</guid><link>https://blog.example.com/b</link></item>
+  </channel>
+</rss>"#;
+    ctx.mock_rss_feed("/comments.xml", xml);
+    let url = ctx.server.url("/comments.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let sh = ctx.post_shorthand("With Comments");
+    let stdout = blog_cmd()
+        .args([sh.as_str(), "comments"])
         .env("RSS_STORE", ctx.dir.path())
-        .env("BROWSER", "true")
+        .env("BROWSER", "echo")
         .assert()
-        .success();
+        .success()
+        .stdout_str();
+    assert_eq!(stdout, "https://news.example.com/item?id=1\n");
+
+    let sh = ctx.post_shorthand("Without Comments");
+    let stderr = ctx
+        .run(&[&sh, "comments", "--plain"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("Post has no comments link"), "{stderr}");
+}
 
-    // Verify we have a read mark
-    let reads_before = read_table(&ctx.dir.path().join("reads"));
-    assert_eq!(
-        reads_before.len(),
-        1,
-        "expected 1 read mark before removal, got: {reads_before:?}"
+#[test]
+fn test_post_shorthand_survives_new_posts() {
+    let ctx = TestContext::new();
+    let post_a = (
+        "Post A",
+        "Mon, 01 Jan 2024 00:00:00 +0000",
+        "guid-a",
+        "https://example.com/a",
+    );
+    let mut mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/stable.xml");
+        then.status(200)
+            .body(rss_xml_with_links("Stable Blog", &[post_a]));
+    });
+    let url = ctx.server.url("/stable.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+    let before = ctx.post_shorthand("Post A");
+
+    // Newer posts used to push older ones down the list and renumber them
+    mock.delete();
+    let newer: Vec<(String, String)> = (0..5)
+        .map(|i| (format!("Newer {i}"), format!("guid-new-{i}")))
+        .collect();
+    let mut items: Vec<(&str, &str, &str, &str)> = newer
+        .iter()
+        .map(|(title, guid)| {
+            (
+                title.as_str(),
+                "Tue, 02 Jan 2024 00:00:00 +0000",
+                guid.as_str(),
+                "https://example.com/new",
+            )
+        })
+        .collect();
+    items.push(post_a);
+    ctx.mock_rss_feed("/stable.xml", &rss_xml_with_links("Stable Blog", &items));
+    ctx.run(&["sync"]).success();
+
+    let after = ctx.post_shorthand("Post A");
+    assert!(
+        after.starts_with(&before),
+        "shorthand changed from {before} to {after}"
     );
+    let output = ctx.run(&[&after, "read", "--plain"]).success().stdout_str();
+    assert_eq!(output, "https://example.com/a\n");
+}
 
-    // Now remove the feed
-    ctx.run(&["feed", "rm", &url]).success();
+fn write_posts_with_shared_prefix(ctx: &TestContext) {
+    // IDs this close together expand to codes sharing a long prefix
+    let posts = r#"{"id":"0000000000000001","title":"First Post","date":"2024-01-15T00:00:00Z","feed":"Alice","link":"https://example.com/1"}
+{"id":"0000000000000002","title":"Second Post","date":"2023-01-15T00:00:00Z","feed":"Alice","link":"https://example.com/2"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+}
 
-    // Read marks for deleted posts should be cleaned up
-    let reads_after = read_table(&ctx.dir.path().join("reads"));
-    assert_eq!(
-        reads_after.len(),
-        0,
-        "expected 0 read marks after feed removal (orphaned ReadMarks), got: {reads_after:?}"
+#[test]
+fn test_ambiguous_shorthand_lists_candidates() {
+    let ctx = TestContext::new();
+    write_posts_with_shared_prefix(&ctx);
+
+    let stderr = ctx.run(&["a", "read"]).failure().stderr_str();
+    assert!(stderr.contains("Ambiguous shorthand: a"), "got:\n{stderr}");
+    let first = ctx.post_shorthand("First Post");
+    let second = ctx.post_shorthand("Second Post");
+    assert!(
+        stderr.contains(&format!("{first}  First Post")),
+        "got:\n{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("{second}  Second Post")),
+        "got:\n{stderr}"
     );
 }
 
 #[test]
-fn test_feed_ls() {
+fn test_shorthand_prefix_unique_within_query_resolves() {
     let ctx = TestContext::new();
+    write_posts_with_shared_prefix(&ctx);
 
-    insert_feed(ctx.dir.path(), "https://example.com/feed1.xml");
-    insert_feed(ctx.dir.path(), "https://example.com/feed2.xml");
+    let output = ctx
+        .run(&["a", "2024-01-01..", "read", "--plain"])
+        .success()
+        .stdout_str();
+    assert_eq!(output, "https://example.com/1\n");
+}
 
-    let output = ctx.run(&["feed", "ls"]).success();
-    let stdout = output.stdout_str();
+#[test]
+fn test_next_reads_oldest_unread_first() {
+    let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Newest","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"r1","link":"https://example.com/1"}
+{"id":"2","title":"Undated","feed":"Alice","raw_id":"r2","link":"https://example.com/2"}
+{"id":"3","title":"Oldest","date":"2024-01-13T00:00:00Z","feed":"Bob","raw_id":"r3","link":"https://example.com/3"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
 
-    assert!(stdout.contains("https://example.com/feed1.xml"));
-    assert!(stdout.contains("https://example.com/feed2.xml"));
+    let output = ctx.run(&["next", "--plain"]).success();
+    assert_eq!(output.stdout_str(), "https://example.com/3\n");
+    assert!(output.stderr_str().contains("2 unread posts left."));
 
-    // Each line should start with a shorthand consisting only of home-row characters
-    let home_row_chars: &[char] = &['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l'];
-    for line in stdout.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
-        assert!(
-            first_word.starts_with('@'),
-            "line should start with @shorthand: {}",
-            line
-        );
-        let shorthand = &first_word[1..];
-        assert!(
-            !shorthand.is_empty(),
-            "shorthand should not be empty: {}",
-            line
-        );
-        assert!(
-            shorthand.chars().all(|c| home_row_chars.contains(&c)),
-            "shorthand '{}' contains non-home-row characters in line: {}",
-            shorthand,
-            line,
-        );
-    }
+    let output = ctx.run(&["next", "--plain"]).success();
+    assert_eq!(output.stdout_str(), "https://example.com/1\n");
+    assert!(output.stderr_str().contains("1 unread post left."));
+
+    let output = ctx.run(&["next", "--plain"]).success();
+    assert_eq!(output.stdout_str(), "https://example.com/2\n");
+    assert!(
+        output
+            .stderr_str()
+            .contains("That was the last unread post.")
+    );
+
+    let stderr = ctx.run(&["next"]).failure().stderr_str();
+    assert!(stderr.contains("No unread posts"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_feed_ls_no_feeds_prints_error() {
+fn test_next_respects_filter() {
     let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Newest","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"r1","link":"https://example.com/1"}
+{"id":"3","title":"Oldest","date":"2024-01-13T00:00:00Z","feed":"Bob","raw_id":"r3","link":"https://example.com/3"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
 
-    let output = ctx.run(&["feed", "ls"]).failure();
-    let stderr = output.stderr_str();
+    let output = ctx
+        .run(&["2024-01-14..", "next", "--plain"])
+        .success()
+        .stdout_str();
+    assert_eq!(output, "https://example.com/1\n");
+
+    let stderr = ctx.run(&[".read", "next"]).failure().stderr_str();
+    assert!(stderr.contains("unread"), "got:\n{stderr}");
+}
+
+fn write_later_posts(ctx: &TestContext) {
+    let posts = r#"{"id":"1","title":"First","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"r1","link":"https://example.com/1"}
+{"id":"2","title":"Second","date":"2024-01-14T00:00:00Z","feed":"Alice","raw_id":"r2","link":"https://example.com/2"}
+{"id":"3","title":"Third","date":"2024-01-13T00:00:00Z","feed":"Bob","raw_id":"r3","link":"https://example.com/3"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+}
+
+#[test]
+fn test_later_queue_pops_in_queue_order() {
+    let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    let second = ctx.post_shorthand("Second");
+    let first = ctx.post_shorthand("First");
+
+    let stderr = ctx.run(&[&second, "later"]).success().stderr_str();
+    assert!(stderr.contains("Queued 1 post(s)"), "got:\n{stderr}");
+    ctx.run(&[&first, "later"]).success();
+
+    let listing = ctx.run(&["later"]).success().stdout_str();
+    let titles: Vec<&str> = listing
+        .lines()
+        .filter_map(|l| {
+            ["First", "Second", "Third"]
+                .into_iter()
+                .find(|t| l.contains(t))
+        })
+        .collect();
+    assert_eq!(titles, ["Second", "First"], "got:\n{listing}");
+    assert_eq!(ctx.run(&["later", "ls"]).success().stdout_str(), listing);
+
+    let output = ctx.run(&["later", "pop", "--plain"]).success();
+    assert_eq!(output.stdout_str(), "https://example.com/2\n");
+    assert!(output.stderr_str().contains("1 post(s) left"));
+    let reads = ctx.run(&[".read"]).success().stdout_str();
     assert!(
-        stderr.contains("No feeds found"),
-        "expected 'No feeds found' on stderr, got: {}",
-        stderr
+        reads.contains("Second"),
+        "popped post should be read:\n{reads}"
     );
+
+    ctx.run(&["later", "pop", "--plain"]).success();
+    let stderr = ctx.run(&["later", "pop"]).failure().stderr_str();
+    assert!(stderr.contains("queue is empty"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_trailing_slash_does_not_duplicate_feed() {
+fn test_later_rm_keeps_post_unread() {
     let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    let third = ctx.post_shorthand("Third");
+    ctx.run(&[&third, "later"]).success();
 
-    let xml = rss_xml("Slash Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
-    ctx.mock_rss_feed("/feed.xml", &xml);
-    ctx.mock_rss_feed("/feed.xml/", &xml);
+    ctx.run(&[&third, "later", "rm"]).success();
 
-    let url = ctx.server.url("/feed.xml");
-    let url_slash = format!("{}/", url);
+    let stderr = ctx.run(&["later"]).failure().stderr_str();
+    assert!(stderr.contains("queue is empty"), "got:\n{stderr}");
+    let unread = ctx.run(&[".unread"]).success().stdout_str();
+    assert!(unread.contains("Third"), "got:\n{unread}");
+    assert!(
+        ctx.run(&["later", "rm"])
+            .failure()
+            .stderr_str()
+            .contains("Pick posts")
+    );
+}
 
-    ctx.run(&["feed", "add", &url]).success();
-    ctx.run(&["feed", "add", &url_slash]).success();
+#[test]
+fn test_snoozed_post_is_hidden_until_due() {
+    let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    let second = ctx.post_shorthand("Second");
 
-    let feeds = ctx.read_feeds();
-    assert_eq!(
-        feeds.len(),
-        1,
-        "trailing slash should not create a duplicate feed, got: {feeds:?}"
+    let stderr = ctx.run(&["snooze", &second, "3d"]).success().stderr_str();
+    assert!(stderr.contains("Snoozed 1 post(s)"), "got:\n{stderr}");
+
+    let listing = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    assert!(!listing.contains("Second"), "got:\n{listing}");
+    assert!(listing.contains("First"), "got:\n{listing}");
+    let all = ctx.run(&[".all"]).success().stdout_str();
+    assert!(all.contains("Second"), "got:\n{all}");
+    assert!(
+        ctx.run(&["snooze", "3d"])
+            .failure()
+            .stderr_str()
+            .contains("Pick posts")
     );
 }
 
 #[test]
-fn test_feed_remove_by_shorthand() {
+fn test_snoozed_post_comes_back_marked_due_until_read() {
     let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    fs::create_dir_all(ctx.dir.path().join("snoozes")).unwrap();
+    fs::write(
+        ctx.dir.path().join("snoozes").join("items_.jsonl"),
+        r#"{"id":"s2","post_id":"r2","until":"2024-02-01T00:00:00Z"}"#,
+    )
+    .unwrap();
+    let second = ctx.post_shorthand("Second");
 
-    let xml1 = rss_xml_with_guids(
-        "Keep Blog",
-        &[("Keep Post", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-keep")],
+    let listing = ctx.run(&["2020-01-01.."]).success().stdout_str();
+    let line = listing.lines().find(|l| l.contains("Second")).unwrap();
+    assert!(line.starts_with("! "), "got:\n{listing}");
+
+    ctx.run(&[&second, "read", "--plain"]).success();
+    let listing = ctx.run(&[".all"]).success().stdout_str();
+    let line = listing.lines().find(|l| l.contains("Second")).unwrap();
+    assert!(!line.starts_with('!'), "got:\n{listing}");
+}
+
+#[test]
+fn test_db_doctor_and_maintenance_cover_snoozes() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Snoozy",
+        &[
+            (
+                "First",
+                "Tue, 02 Jan 2024 00:00:00 +0000",
+                "g1",
+                "https://snoozy.example/1",
+            ),
+            (
+                "Second",
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                "g2",
+                "https://snoozy.example/2",
+            ),
+        ],
     );
-    ctx.mock_rss_feed("/keep.xml", &xml1);
+    ctx.mock_rss_feed("/snoozy.xml", &xml);
+    ctx.run(&["feed", "add", &ctx.server.url("/snoozy.xml")])
+        .success();
+    let second = ctx.post_shorthand("Second");
+    ctx.run(&["snooze", &second, "3d"]).success();
+    // Reading the post ends its snooze, leaving a tombstone
+    ctx.run(&[&second, "read", "--plain"]).success();
 
-    let xml2 = rss_xml_with_guids(
-        "Remove Blog",
-        &[(
-            "Remove Post",
-            "Tue, 02 Jan 2024 00:00:00 +0000",
-            "guid-remove",
-        )],
+    ctx.run(&["config", "set", "tombstone_days", "0"]).success();
+    let stderr = ctx.run(&["maintenance"]).success().stderr_str();
+    assert!(
+        stderr.contains("Dropped 1 row deleted more than 0 days ago."),
+        "{stderr}"
     );
-    ctx.mock_rss_feed("/remove.xml", &xml2);
 
-    let keep_url = ctx.server.url("/keep.xml");
-    let remove_url = ctx.server.url("/remove.xml");
-    ctx.write_feeds(&[&keep_url, &remove_url]);
-    ctx.run(&["sync"]).success();
+    ctx.run(&["snooze", &ctx.post_shorthand("First"), "3d"])
+        .success();
+    let shard = fs::read_dir(ctx.dir.path().join("snoozes"))
+        .unwrap()
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.extension().is_some_and(|e| e == "jsonl"))
+        .unwrap();
+    let content = fs::read_to_string(&shard).unwrap();
+    fs::write(
+        &shard,
+        format!("<<<<<<< HEAD\n{content}=======\n{content}>>>>>>> origin/main\n"),
+    )
+    .unwrap();
+
+    let stdout = ctx.run(&["db", "doctor"]).failure().stdout_str();
+    assert!(
+        stdout.contains("leftover merge conflict marker"),
+        "got:\n{stdout}"
+    );
+    ctx.run(&["db", "doctor", "--fix"]).success();
+    let stderr = ctx.run(&["db", "doctor"]).success().stderr_str();
+    assert!(stderr.contains("No problems found"), "got:\n{stderr}");
+}
 
-    // Run feed ls and parse the shorthand for the remove_url
-    let output = ctx.run(&["feed", "ls"]).success();
-    let stdout = output.stdout_str();
+#[test]
+fn test_save_posts_to_linkding() {
+    let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    let mock = ctx.server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/bookmarks/")
+            .header("Authorization", "Token s3cret")
+            .body_includes("https://example.com/1");
+        then.status(201).body("{}");
+    });
+    ctx.run(&["config", "set", "save_service", "linkding"])
+        .success();
+    ctx.run(&["config", "set", "save_url", &ctx.server.url("")])
+        .success();
+    let first = ctx.post_shorthand("First");
 
-    let shorthand = stdout
-        .lines()
-        .find(|line| line.contains(&remove_url))
-        .map(|line| {
-            let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
-            first_word // includes the '@' prefix
-        })
-        .expect("should find remove_url in feed ls output");
+    let stderr = blog_cmd()
+        .args([first.as_str(), "save"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BLOG_SAVE_TOKEN", "s3cret")
+        .assert()
+        .success()
+        .stderr_str();
 
-    // Remove using the shorthand
-    ctx.run(&["feed", "rm", &shorthand]).success();
+    mock.assert_calls(1);
+    assert!(
+        stderr.contains("Saved https://example.com/1"),
+        "got:\n{stderr}"
+    );
+}
 
-    let feeds = ctx.read_feeds();
-    assert_eq!(feeds.len(), 1);
-    assert_eq!(feeds[0]["url"].as_str().unwrap(), keep_url);
+#[test]
+fn test_save_needs_token() {
+    let ctx = TestContext::new();
+    write_later_posts(&ctx);
+    ctx.run(&["config", "set", "save_service", "wallabag"])
+        .success();
+    ctx.run(&["config", "set", "save_url", "https://bag.example"])
+        .success();
 
-    let posts = ctx.read_posts();
-    assert_eq!(posts.len(), 1);
-    assert_eq!(posts[0]["title"].as_str().unwrap(), "Keep Post");
+    let stderr = blog_cmd()
+        .args(["id:1", "save"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env_remove("BLOG_SAVE_TOKEN")
+        .assert()
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("BLOG_SAVE_TOKEN"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_show_no_posts_prints_error() {
+fn test_open_post_without_link() {
     let ctx = TestContext::new();
 
-    let output = ctx.run(&["show"]).failure();
+    let posts = r#"{"id":"1","title":"No Link Post","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"no-link-1","link":""}"#;
+    std::fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    std::fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let sh = ctx.post_shorthand("No Link Post");
+    let output = ctx.run(&[sh.as_str(), "open"]).failure();
     let stderr = output.stderr_str();
     assert!(
-        stderr.contains("No matching posts"),
-        "expected 'No matching posts' on stderr, got: {}",
-        stderr
+        stderr.contains("Post has no link"),
+        "expected 'Post has no link' on stderr, got: {}",
+        stderr,
     );
 }
 
 #[test]
-fn test_show_filter_by_shorthand() {
+fn test_open_marks_post_as_read() {
     let ctx = TestContext::new();
 
-    let xml1 = rss_xml_with_guids(
-        "Alpha Blog",
+    let date_a = recent_rss_date(1);
+    let date_b = recent_rss_date(2);
+    let xml = rss_xml_with_links(
+        "Read Blog",
         &[
-            ("Alpha Post 1", "Mon, 01 Jan 2024 00:00:00 +0000", "guid-a1"),
-            ("Alpha Post 2", "Tue, 02 Jan 2024 00:00:00 +0000", "guid-a2"),
+            ("Post A", &date_a, "guid-a", "https://example.com/a"),
+            ("Post B", &date_b, "guid-b", "https://example.com/b"),
         ],
     );
-    ctx.mock_rss_feed("/alpha.xml", &xml1);
-
-    let xml2 = rss_xml_with_guids(
-        "Beta Blog",
-        &[("Beta Post 1", "Wed, 03 Jan 2024 00:00:00 +0000", "guid-b1")],
-    );
-    ctx.mock_rss_feed("/beta.xml", &xml2);
-
-    let alpha_url = ctx.server.url("/alpha.xml");
-    let beta_url = ctx.server.url("/beta.xml");
-    ctx.write_feeds(&[&alpha_url, &beta_url]);
+    ctx.mock_rss_feed("/read.xml", &xml);
+    let url = ctx.server.url("/read.xml");
+    ctx.write_feeds(&[&url]);
     ctx.run(&["sync"]).success();
 
-    // Get shorthand for alpha feed
-    let output = ctx.run(&["feed", "ls"]).success();
-    let stdout = output.stdout_str();
-
-    let alpha_shorthand = stdout
-        .lines()
-        .find(|line| line.contains(&alpha_url))
-        .map(|line| {
-            let first_word: String = line.chars().take_while(|c| *c != ' ').collect();
-            first_word
-        })
-        .expect("should find alpha_url in feed ls output");
-
-    // Filter with `show @shorthand` — should only show alpha posts
-    let output = ctx.run(&["show", &alpha_shorthand]).success();
-    let stdout = output.stdout_str();
-
-    assert!(
-        stdout.contains("Alpha Post 1"),
-        "should contain Alpha Post 1"
-    );
-    assert!(
-        stdout.contains("Alpha Post 2"),
-        "should contain Alpha Post 2"
-    );
-    assert!(
-        !stdout.contains("Beta Post 1"),
-        "should NOT contain Beta Post 1"
-    );
+    // Before opening: both posts are unread (shown with *)
+    let before = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
+    assert_eq!(before.lines().filter(|l| l.starts_with('*')).count(), 2);
 
-    // Also test with no subcommand: `blog @shorthand`
-    let output = ctx.run(&[&alpha_shorthand]).success();
-    let stdout = output.stdout_str();
+    // Open the newest post
+    let sh = ctx.post_shorthand("Post A");
+    blog_cmd()
+        .args([sh.as_str(), "open"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "true")
+        .assert()
+        .success();
 
-    assert!(
-        stdout.contains("Alpha Post 1"),
-        "no-subcommand: should contain Alpha Post 1"
-    );
-    assert!(
-        stdout.contains("Alpha Post 2"),
-        "no-subcommand: should contain Alpha Post 2"
+    // After opening: one post is read, one is still unread
+    let after = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
+    assert_eq!(
+        after.lines().filter(|l| l.starts_with('*')).count(),
+        1,
+        "expected 1 unread post after opening one, got:\n{after}"
     );
-    assert!(
-        !stdout.contains("Beta Post 1"),
-        "no-subcommand: should NOT contain Beta Post 1"
+    assert_eq!(
+        after.lines().filter(|l| l.starts_with("  ")).count(),
+        1,
+        "expected 1 read post after opening one, got:\n{after}"
     );
 }
 
-#[test]
-fn test_show_filter_unknown_shorthand() {
+#[rstest]
+#[case::declined("n\n", 1)]
+#[case::no_answer("", 1)]
+#[case::confirmed("y\n", 0)]
+fn test_remove_feed_asks_for_confirmation(#[case] answer: &str, #[case] remaining: usize) {
     let ctx = TestContext::new();
+    let xml = rss_xml(
+        "Doomed Blog",
+        &[
+            ("One", "Mon, 01 Jan 2024 00:00:00 +0000"),
+            ("Two", "Tue, 02 Jan 2024 00:00:00 +0000"),
+        ],
+    );
+    ctx.mock_rss_feed("/doomed.xml", &xml);
+    let url = ctx.server.url("/doomed.xml");
+    ctx.run(&["feed", "add", &url]).success();
 
-    insert_feed(ctx.dir.path(), "https://example.com/feed.xml");
+    let assert = blog_cmd()
+        .args(["feed", "rm", &url])
+        .env("RSS_STORE", ctx.dir.path())
+        .write_stdin(answer)
+        .assert();
+    let assert = if remaining == 0 {
+        assert.success()
+    } else {
+        assert.failure()
+    };
 
-    let output = ctx.run(&["show", "@zzz"]).failure();
-    let stderr = output.stderr_str();
+    let stderr = assert.stderr_str();
     assert!(
-        stderr.contains("Unknown feed shorthand"),
-        "expected unknown shorthand error, got: {}",
-        stderr
+        stderr.contains(&format!("Doomed Blog {url} (2 posts)")),
+        "got:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("Remove 1 feed and 2 posts? [y/N]"),
+        "got:\n{stderr}"
     );
+    assert_eq!(ctx.read_feeds().len(), remaining);
+    assert_eq!(ctx.read_posts().len(), remaining * 2);
 }
 
 #[test]
-fn test_remove_then_readd_feed() {
+fn test_remove_feed_keep_posts_archives_it() {
     let ctx = TestContext::new();
     let xml = rss_xml(
-        "Returning Blog",
-        &[("Old Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+        "Old Blog",
+        &[("Kept Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
     );
-    ctx.mock_rss_feed("/returning.xml", &xml);
-
-    let url = ctx.server.url("/returning.xml");
+    let mock = ctx.server.mock(|when, then| {
+        when.method(GET).path("/old.xml");
+        then.status(200)
+            .header("Content-Type", "application/rss+xml")
+            .body(&xml);
+    });
+    let url = ctx.server.url("/old.xml");
     ctx.run(&["feed", "add", &url]).success();
-    ctx.run(&["sync"]).success();
-    ctx.run(&["feed", "rm", &url]).success();
 
-    // Re-add and pull again
-    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["feed", "rm", "--keep-posts", &url]).success();
     ctx.run(&["sync"]).success();
 
-    let output = ctx.run(&["show", "2020-01-01.."]).success();
-    let stdout = output.stdout_str();
-    assert!(stdout.contains("Returning Blog"));
-    assert!(stdout.contains("Old Post"));
+    // Only fetched when it was added
+    mock.assert_calls(1);
+    assert_eq!(ctx.read_posts().len(), 1);
+    let stdout = ctx.run(&["feed", "ls"]).success().stdout_str();
+    assert!(stdout.contains("[archived]"), "got:\n{stdout}");
+    let stdout = ctx.run(&[".all"]).success().stdout_str();
+    assert!(stdout.contains("Kept Post"), "got:\n{stdout}");
+    assert!(stdout.contains("Old Blog [archived]"), "got:\n{stdout}");
+
+    ctx.run(&["feed", "resume", &url]).success();
+    let stdout = ctx.run(&["feed", "ls"]).success().stdout_str();
+    assert!(!stdout.contains("[archived]"), "got:\n{stdout}");
 }
 
 #[test]
-fn test_show_displays_post_shorthands() {
+fn test_db_export_then_import_into_new_store() {
     let ctx = TestContext::new();
-
-    let date_a = recent_rss_date(2);
-    let date_b = recent_rss_date(3);
-    let xml = rss_xml_with_guids(
-        "Shorthand Blog",
+    let xml = rss_xml_with_links(
+        "Backed Up",
         &[
-            ("Post Alpha", &date_a, "guid-alpha"),
-            ("Post Beta", &date_b, "guid-beta"),
+            (
+                "First",
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                "first",
+                "https://b.example/1",
+            ),
+            (
+                "Second",
+                "Tue, 02 Jan 2024 00:00:00 +0000",
+                "second",
+                "https://b.example/2",
+            ),
         ],
     );
-    ctx.mock_rss_feed("/shorthand.xml", &xml);
-
-    let url = ctx.server.url("/shorthand.xml");
-    ctx.write_feeds(&[&url]);
-    ctx.run(&["sync"]).success();
+    ctx.mock_rss_feed("/backup.xml", &xml);
+    ctx.run(&["feed", "add", &ctx.server.url("/backup.xml")])
+        .success();
+    ctx.run(&["view", "add", "firsts", "~First"]).success();
+    let shorthand = ctx.post_shorthand("First");
+    ctx.run(&[&shorthand, "read", "--plain"]).success();
 
-    let output = ctx.run(&["show", ".all", "2020-01-01.."]).success();
-    let stdout = output.stdout_str();
+    let backup = ctx.dir.path().join("backup.json");
+    ctx.run(&["db", "export", "-o", backup.to_str().unwrap()])
+        .success();
 
-    let post_alphabet: &[char] = &[
-        'a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'A', 'S', 'D', 'F', 'G', 'H', 'J', 'K', 'L',
-        'q', 'w', 'e', 'r', 't', 'y', 'i', 'o', 'p', 'z', 'x', 'c', 'v', 'b', 'n', 'm',
-    ];
+    let other = TestContext::new();
+    other
+        .run(&["db", "import", backup.to_str().unwrap()])
+        .success();
 
-    for line in stdout.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        // Lines are: "* YYYY-MM-DD  shorthand title (meta)"
-        let words: Vec<&str> = line.split_whitespace().collect();
-        assert!(
-            words.len() >= 3,
-            "line should have a marker, date, and shorthand: {}",
-            line
-        );
-        let shorthand = words[2];
-        assert!(
-            shorthand.chars().all(|c| post_alphabet.contains(&c)),
-            "shorthand '{}' should only contain POST_ALPHABET characters in line: {}",
-            shorthand,
-            line,
-        );
-    }
+    let feeds = other.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["id"], ctx.read_feeds()[0]["id"]);
+    assert_eq!(feeds[0]["title"], "Backed Up");
+    assert_eq!(other.read_posts().len(), 2);
+    let stdout = other.run(&["%firsts", ".all"]).success().stdout_str();
+    assert!(stdout.contains("First"), "got:\n{stdout}");
+    let stdout = other.run(&[".unread"]).success().stdout_str();
+    assert!(!stdout.contains("First"), "got:\n{stdout}");
+    assert!(stdout.contains("Second"), "got:\n{stdout}");
 }
 
 #[test]
-fn test_open_unknown_shorthand() {
+fn test_db_import_rejects_other_json() {
     let ctx = TestContext::new();
+    let path = ctx.dir.path().join("other.json");
+    std::fs::write(&path, r#"{"hello": "world"}"#).unwrap();
 
-    let output = ctx.run(&["zzzzz", "open"]).failure();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("Unknown shorthand: zzzzz"),
-        "expected 'Unknown shorthand: zzzzz' on stderr, got: {}",
-        stderr,
-    );
+    let stderr = ctx
+        .run(&["db", "import", path.to_str().unwrap()])
+        .failure()
+        .stderr_str();
+
+    assert!(stderr.contains("Not a blogtato backup"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_open_valid_shorthand() {
+fn test_db_doctor_repairs_half_merged_conflict() {
     let ctx = TestContext::new();
+    ctx.mock_rss_feed(
+        "/doc.xml",
+        &rss_xml(
+            "Doc",
+            &[("Healthy Post", "Mon, 01 Jan 2024 00:00:00 +0000")],
+        ),
+    );
+    ctx.run(&["feed", "add", &ctx.server.url("/doc.xml")])
+        .success();
 
-    let xml = rss_xml_with_links(
-        "Open Blog",
-        &[(
-            "Open Post",
-            "Mon, 01 Jan 2024 00:00:00 +0000",
-            "guid-open",
-            "https://example.com/post/1",
-        )],
-    );
-    ctx.mock_rss_feed("/open.xml", &xml);
-
-    let url = ctx.server.url("/open.xml");
-    ctx.write_feeds(&[&url]);
-    ctx.run(&["sync"]).success();
-
-    // Running `open a` should resolve the shorthand without error.
-    // Use BROWSER=true to prevent actually opening a browser.
-    let output = blog_cmd()
-        .args(["a", "open"])
-        .env("RSS_STORE", ctx.dir.path())
-        .env("BROWSER", "true")
-        .assert();
-    let stderr = output.stderr_str();
-    assert!(
-        !stderr.contains("Unknown shorthand"),
-        "should resolve shorthand 'a', got: {}",
-        stderr,
-    );
+    // Leave conflict markers around a shard's contents, as an abandoned merge would
+    let posts_dir = ctx.dir.path().join("posts");
+    let shard = std::fs::read_dir(&posts_dir)
+        .unwrap()
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.extension().is_some_and(|e| e == "jsonl"))
+        .unwrap();
+    let content = std::fs::read_to_string(&shard).unwrap();
+    std::fs::write(
+        &shard,
+        format!("<<<<<<< HEAD\n{content}=======\n{content}>>>>>>> origin/main\n"),
+    )
+    .unwrap();
+    ctx.run(&[".all"]).failure();
+
+    let stdout = ctx.run(&["db", "doctor"]).failure().stdout_str();
     assert!(
-        !stderr.contains("Post has no link"),
-        "post should have a link, got: {}",
-        stderr,
+        stdout.contains("leftover merge conflict marker"),
+        "got:\n{stdout}"
     );
+    assert!(stdout.contains("duplicate of row"), "got:\n{stdout}");
+
+    ctx.run(&["db", "doctor", "--fix"]).success();
+
+    let stdout = ctx.run(&[".all"]).success().stdout_str();
+    assert!(stdout.contains("Healthy Post"), "got:\n{stdout}");
+    let stderr = ctx.run(&["db", "doctor"]).success().stderr_str();
+    assert!(stderr.contains("No problems found"), "got:\n{stderr}");
 }
 
 #[test]
-fn test_open_post_without_link() {
+fn test_store_flag_overrides_rss_store() {
     let ctx = TestContext::new();
+    ctx.mock_rss_feed("/feed.xml", &rss_xml("Other Feed", &[]));
+    let url = ctx.server.url("/feed.xml");
+    let other = TempDir::new().unwrap();
+    let other = other.path().to_str().unwrap();
 
-    let posts = r#"{"id":"1","title":"No Link Post","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"no-link-1","link":""}"#;
-    std::fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
-    std::fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    ctx.run(&["--store", other, "feed", "add", &url]).success();
 
-    let output = ctx.run(&["a", "open"]).failure();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("Post has no link"),
-        "expected 'Post has no link' on stderr, got: {}",
-        stderr,
-    );
+    assert!(ctx.read_feeds().is_empty());
+    let stdout = ctx
+        .run(&["feed", "ls", "--store", other])
+        .success()
+        .stdout_str();
+    assert!(stdout.contains(&url), "got:\n{stdout}");
 }
 
 #[test]
-fn test_open_marks_post_as_read() {
+fn test_profiles_are_separate_stores() {
     let ctx = TestContext::new();
+    ctx.mock_rss_feed("/work.xml", &rss_xml("Work Feed", &[]));
+    ctx.mock_rss_feed("/home.xml", &rss_xml("Home Feed", &[]));
+    let data = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        blog_cmd()
+            .args(args)
+            .env_remove("RSS_STORE")
+            .env("XDG_DATA_HOME", data.path())
+            .assert()
+    };
 
-    let date_a = recent_rss_date(1);
-    let date_b = recent_rss_date(2);
-    let xml = rss_xml_with_links(
-        "Read Blog",
-        &[
-            ("Post A", &date_a, "guid-a", "https://example.com/a"),
-            ("Post B", &date_b, "guid-b", "https://example.com/b"),
-        ],
-    );
-    ctx.mock_rss_feed("/read.xml", &xml);
-    let url = ctx.server.url("/read.xml");
-    ctx.write_feeds(&[&url]);
-    ctx.run(&["sync"]).success();
+    run(&[
+        "--profile",
+        "work",
+        "feed",
+        "add",
+        &ctx.server.url("/work.xml"),
+    ])
+    .success();
+    run(&[
+        "--profile",
+        "home",
+        "feed",
+        "add",
+        &ctx.server.url("/home.xml"),
+    ])
+    .success();
 
-    // Before opening: both posts are unread (shown with *)
-    let before = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
-    assert_eq!(before.lines().filter(|l| l.starts_with('*')).count(), 2);
+    let work = run(&["--profile", "work", "feed", "ls"])
+        .success()
+        .stdout_str();
+    assert!(work.contains("work.xml"), "got:\n{work}");
+    assert!(!work.contains("home.xml"), "got:\n{work}");
+    assert!(data.path().join("blogtato/stores/home/feeds").exists());
+    run(&["--profile", "../x", "feed", "ls"]).failure();
+}
 
-    // Open first post (shorthand "a")
-    blog_cmd()
-        .args(["a", "open"])
-        .env("RSS_STORE", ctx.dir.path())
-        .env("BROWSER", "true")
-        .assert()
-        .success();
+#[cfg(unix)]
+#[test]
+fn test_unknown_command_runs_plugin() {
+    use std::os::unix::fs::PermissionsExt;
 
-    // After opening: one post is read, one is still unread
-    let after = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
+    let ctx = TestContext::new();
+    let bin = TempDir::new().unwrap();
+    let plugin = bin.path().join("blog-hello");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\necho \"store=$RSS_STORE args=$*\"\nexit 3\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = std::env::join_paths(
+        std::iter::once(bin.path().to_path_buf())
+            .chain(std::env::split_paths(&std::env::var_os("PATH").unwrap())),
+    )
+    .unwrap();
+    let other = TempDir::new().unwrap();
+
+    let output = blog_cmd()
+        .args(["--store", other.path().to_str().unwrap()])
+        .args(["hello", "a", "--flag"])
+        .env("PATH", &path)
+        .assert()
+        .code(3)
+        .stdout_str();
     assert_eq!(
-        after.lines().filter(|l| l.starts_with('*')).count(),
-        1,
-        "expected 1 unread post after opening one, got:\n{after}"
+        output.trim(),
+        format!("store={} args=a --flag", other.path().display())
     );
-    assert_eq!(
-        after.lines().filter(|l| l.starts_with("  ")).count(),
-        1,
-        "expected 1 read post after opening one, got:\n{after}"
+
+    // Without --store the plugin gets RSS_STORE
+    let output = blog_cmd()
+        .arg("hello")
+        .env("PATH", &path)
+        .env("RSS_STORE", ctx.dir.path())
+        .assert()
+        .code(3)
+        .stdout_str();
+    assert!(
+        output.starts_with(&format!("store={} ", ctx.dir.path().display())),
+        "got: {output}"
     );
 }
 
@@ -1297,6 +3945,7 @@ fn test_remove_multiple_feeds_by_url() {
     ctx.run(&[
         "feed",
         "rm",
+        "--yes",
         "https://example.com/feed1.xml",
         "https://example.com/feed2.xml",
     ])
@@ -1370,8 +4019,14 @@ fn test_remove_multiple_feeds_by_shorthand() {
         .map(|(s, _)| *s)
         .expect("shorthand for feed2 not found");
 
-    ctx.run(&["feed", "rm", &format!("@{sh1}"), &format!("@{sh2}")])
-        .success();
+    ctx.run(&[
+        "feed",
+        "rm",
+        "--yes",
+        &format!("@{sh1}"),
+        &format!("@{sh2}"),
+    ])
+    .success();
 
     let feeds = ctx.read_feeds();
     assert_eq!(feeds.len(), 1);
@@ -1418,7 +4073,7 @@ fn test_sync_continues_after_non_utf8_feed() {
     ctx.write_feeds(&[&bad_url, &good_url]);
 
     // Pull should succeed overall — the bad feed errors but doesn't crash
-    ctx.run(&["sync"]).success();
+    ctx.run(&["sync"]).code(2);
 
     // At minimum the good feed's post should be present
     let posts = ctx.read_posts();
@@ -1489,6 +4144,8 @@ fn git_cmd() -> std::process::Command {
 #[allow(deprecated)]
 fn blog_cmd() -> assert_cmd::Command {
     let mut cmd = Command::cargo_bin("blog").unwrap();
+    // Show and query dates in UTC, like the fixtures, whatever the local zone
+    cmd.env("TZ", "UTC");
     for var in GIT_HOOK_VARS {
         cmd.env_remove(var);
     }
@@ -1602,108 +4259,492 @@ fn clone_store(origin_dir: &Path) -> (TempDir, std::path::PathBuf) {
     (dir, p)
 }
 
-fn run_blog(store_dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
-    blog_cmd().args(args).env("RSS_STORE", store_dir).assert()
+fn run_blog(store_dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    blog_cmd().args(args).env("RSS_STORE", store_dir).assert()
+}
+
+/// Sync a store whose feeds, added with `insert_feed`, don't exist: the store
+/// still syncs with its remotes, but sync exits with 2 for the failed feeds.
+fn sync_fake_feeds(store_dir: &Path) -> assert_cmd::assert::Assert {
+    run_blog(store_dir, &["sync"]).code(2)
+}
+
+#[test]
+fn test_history_describes_store_changes() {
+    let ctx = TestContext::new();
+    git(ctx.dir.path(), &["init"]);
+    git_config_test_user(ctx.dir.path());
+    let xml = rss_xml(
+        "History Blog",
+        &[
+            ("Post A", "Tue, 02 Jan 2024 00:00:00 +0000"),
+            ("Post B", "Mon, 01 Jan 2024 00:00:00 +0000"),
+        ],
+    );
+    ctx.mock_rss_feed("/history.xml", &xml);
+    let url = ctx.server.url("/history.xml");
+    ctx.run(&["feed", "add", &url]).success();
+
+    let stdout = ctx.run(&["history"]).success().stdout_str();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(
+        lines[0].ends_with("  added feed 127.0.0.1  (+1 feed, +2 posts, +1 read mark)"),
+        "{stdout}"
+    );
+    assert!(
+        lines[1].ends_with("  set schema version  (+1 setting)"),
+        "{stdout}"
+    );
+
+    let stdout = ctx.run(&["history", "-n", "1"]).success().stdout_str();
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+
+    let stderr = TestContext::new().run(&["history"]).failure().stderr_str();
+    assert!(stderr.contains("isn't a git repository"), "{stderr}");
+}
+
+#[test]
+fn test_sync_no_remote_warns() {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init"]);
+    git_config_test_user(dir.path());
+
+    let output = run_blog(dir.path(), &["sync"]).success();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("no remote"),
+        "expected 'no remote' warning, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_sync_dirty_repo_fails() {
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+
+    let store_dir = TempDir::new().unwrap();
+    init_git_store(store_dir.path(), origin_dir.path());
+
+    // Make it dirty with a data file (use a dir the store doesn't read)
+    fs::create_dir_all(store_dir.path().join("extra")).unwrap();
+    fs::write(store_dir.path().join("extra/items_00.jsonl"), "dirty").unwrap();
+
+    let output = run_blog(store_dir.path(), &["sync"]).failure();
+    let stderr = output.stderr_str();
+    assert!(
+        stderr.contains("uncommitted"),
+        "expected 'uncommitted' error, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_diff_shows_rows_pushed_by_another_machine() {
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+    let store_dir = TempDir::new().unwrap();
+    init_git_store(store_dir.path(), origin_dir.path());
+    run_blog(store_dir.path(), &["sync"]).success();
+
+    let (_clone_td, clone_dir) = clone_store(origin_dir.path());
+    insert_feed(&clone_dir, "https://example.com/feed.xml");
+    sync_fake_feeds(&clone_dir);
+
+    let stdout = run_blog(store_dir.path(), &["diff", "--fetch"])
+        .success()
+        .stdout_str();
+    assert!(stdout.starts_with("+1 feed\n"), "{stdout}");
+    assert!(
+        stdout.contains("\nfeeds\n  + https://example.com/feed.xml\n"),
+        "{stdout}"
+    );
+
+    sync_fake_feeds(store_dir.path());
+    let stderr = run_blog(store_dir.path(), &["diff"]).success().stderr_str();
+    assert!(stderr.contains("No differences."), "{stderr}");
+
+    let stderr = run_blog(store_dir.path(), &["diff", "nope"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("Unknown revision: nope"), "{stderr}");
+}
+
+#[test]
+fn test_maintenance_drops_old_tombstones_and_commits() {
+    let ctx = TestContext::new();
+    git(ctx.dir.path(), &["init"]);
+    git_config_test_user(ctx.dir.path());
+    let xml = rss_xml("Gone Blog", &[("Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    ctx.mock_rss_feed("/gone.xml", &xml);
+    let url = ctx.server.url("/gone.xml");
+    ctx.run(&["feed", "add", &url]).success();
+    ctx.run(&["feed", "rm", "--yes", &url]).success();
+
+    let stderr = ctx.run(&["maintenance"]).success().stderr_str();
+    assert!(
+        stderr.contains("No rows deleted more than 90 days ago."),
+        "{stderr}"
+    );
+
+    ctx.run(&["config", "set", "tombstone_days", "0"]).success();
+    let stderr = ctx.run(&["maintenance"]).success().stderr_str();
+    // The feed and its post
+    assert!(
+        stderr.contains("Dropped 2 rows deleted more than 0 days ago."),
+        "{stderr}"
+    );
+    for table in ["feeds", "posts", "reads"] {
+        let dir = ctx.dir.path().join(table);
+        for entry in fs::read_dir(&dir).unwrap() {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            assert!(!content.contains("deleted_at"), "{table}: {content}");
+        }
+    }
+    let status = git_cmd()
+        .args([
+            "-C",
+            &ctx.dir.path().to_string_lossy(),
+            "status",
+            "--porcelain",
+        ])
+        .output()
+        .unwrap();
+    assert!(status.stdout.is_empty(), "store left uncommitted changes");
+    let history = ctx.run(&["history", "-n", "1"]).success().stdout_str();
+    assert!(history.contains("compact store"), "{history}");
+}
+
+#[test]
+fn test_maintenance_rejects_too_many_tombstone_days() {
+    let ctx = TestContext::new();
+    git(ctx.dir.path(), &["init"]);
+    git_config_test_user(ctx.dir.path());
+    ctx.run(&["config", "set", "tombstone_days", "4000000000"])
+        .success();
+    let stderr = ctx.run(&["maintenance"]).failure().stderr_str();
+    assert!(
+        stderr.contains("Invalid value for config tombstone_days"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_sync_first_push() {
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+
+    let store_dir = TempDir::new().unwrap();
+    // Init repo but don't push yet (no remote branch)
+    git(store_dir.path(), &["init"]);
+    git_config_test_user(store_dir.path());
+    git(
+        store_dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            &path_to_file_url(origin_dir.path()),
+        ],
+    );
+
+    // Add a feed (insert_feed auto-commits since git repo exists)
+    insert_feed(store_dir.path(), "https://example.com/feed.xml");
+
+    // Sync should push
+    sync_fake_feeds(store_dir.path());
+
+    // Verify we can clone and see the data
+    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    let feeds = read_table(&clone_dir.join("feeds"));
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(
+        feeds[0]["url"].as_str().unwrap(),
+        "https://example.com/feed.xml"
+    );
+    drop(clone_td);
+}
+
+#[test]
+fn test_init_creates_a_store_that_syncs() {
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+    git(
+        origin_dir.path(),
+        &["symbolic-ref", "HEAD", "refs/heads/main"],
+    );
+    let parent = TempDir::new().unwrap();
+    let store_dir = parent.path().join("store");
+
+    run_blog(&store_dir, &["init", &path_to_file_url(origin_dir.path())]).success();
+    assert_eq!(
+        fs::read_to_string(store_dir.join(".gitignore")).unwrap(),
+        ".lock\n*.tmp\n"
+    );
+    let head = git_cmd()
+        .args(["-C", &store_dir.to_string_lossy(), "log", "--format=%s %D"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&head.stdout).trim(),
+        "init store HEAD -> main"
+    );
+
+    git_config_test_user(&store_dir);
+    insert_feed(&store_dir, "https://example.com/feed.xml");
+    sync_fake_feeds(&store_dir);
+    let status = git_cmd()
+        .args(["-C", &store_dir.to_string_lossy(), "status", "--porcelain"])
+        .output()
+        .unwrap();
+    assert!(status.stdout.is_empty(), "lock files aren't ignored");
+    let (_clone_td, clone_dir) = clone_store(origin_dir.path());
+    assert_eq!(read_table(&clone_dir.join("feeds")).len(), 1);
+
+    let stderr = run_blog(&store_dir, &["init"]).failure().stderr_str();
+    assert!(stderr.contains("already a git repository"), "{stderr}");
+}
+
+#[test]
+fn test_init_commits_an_existing_store() {
+    let ctx = TestContext::new();
+    ctx.write_feeds(&["https://example.com/feed.xml"]);
+
+    ctx.run(&["init"]).success();
+    let files = git_cmd()
+        .args(["-C", &ctx.dir.path().to_string_lossy(), "ls-files"])
+        .output()
+        .unwrap();
+    let files = String::from_utf8_lossy(&files.stdout);
+    assert!(files.lines().any(|f| f.starts_with("feeds/")), "{files}");
+    assert!(!files.contains(".lock"), "{files}");
+}
+
+#[test]
+fn test_man_writes_pages_without_a_store() {
+    let ctx = TestContext::new();
+    let out = ctx.dir.path().join("man");
+
+    ctx.run(&["man", "--out", &out.to_string_lossy()]).success();
+
+    let page = std::fs::read_to_string(out.join("blog-feed-add.1")).unwrap();
+    assert!(page.starts_with(".TH BLOG\\-FEED\\-ADD 1"), "{page}");
+    assert!(page.contains("\\fB\\-\\-store\\fR"), "{page}");
+    let main = std::fs::read_to_string(out.join("blog.1")).unwrap();
+    assert!(main.contains("\\fBblog\\-sync\\fR(1)"), "{main}");
+    assert!(!ctx.dir.path().join("feeds").exists());
 }
 
 #[test]
-fn test_sync_no_remote_warns() {
-    let dir = TempDir::new().unwrap();
-    git(dir.path(), &["init"]);
-    git_config_test_user(dir.path());
+fn test_sync_single_feed_skips_remote_unless_git() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_guids(
+        "Alpha",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+    init_git_store(ctx.dir.path(), origin_dir.path());
+    let url = ctx.server.url("/alpha.xml");
+    insert_feed(ctx.dir.path(), &url);
+    let shorthand = feed_shorthand_for_url(&ctx, &url);
 
-    let output = run_blog(dir.path(), &["sync"]).success();
-    let stderr = output.stderr_str();
-    assert!(
-        stderr.contains("no remote"),
-        "expected 'no remote' warning, got: {}",
-        stderr
+    ctx.run(&["pull", &shorthand]).success();
+
+    assert_eq!(ctx.read_posts().len(), 1);
+    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    assert!(read_table(&clone_dir.join("feeds")).is_empty());
+    drop(clone_td);
+
+    ctx.run(&["sync", &shorthand, "--git"]).success();
+
+    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    assert_eq!(read_table(&clone_dir.join("posts")).len(), 1);
+    drop(clone_td);
+}
+
+#[test]
+fn test_sync_no_fetch_no_push_and_offline() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_guids(
+        "Alpha",
+        &[(
+            "Alpha Post",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-alpha",
+        )],
     );
+    ctx.mock_rss_feed("/alpha.xml", &xml);
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+    init_git_store(ctx.dir.path(), origin_dir.path());
+    insert_feed(ctx.dir.path(), &ctx.server.url("/alpha.xml"));
+    let origin_rows = |table: &str| {
+        let (clone_td, clone_dir) = clone_store(origin_dir.path());
+        let rows = read_table(&clone_dir.join(table)).len();
+        drop(clone_td);
+        rows
+    };
+
+    ctx.run(&["sync", "--offline"]).success();
+    assert!(ctx.read_posts().is_empty());
+    assert_eq!(origin_rows("feeds"), 0);
+
+    // Pushes the feed without fetching it
+    ctx.run(&["sync", "--no-fetch"]).success();
+    assert!(ctx.read_posts().is_empty());
+    assert_eq!(origin_rows("feeds"), 1);
+
+    ctx.run(&["sync", "--no-push"]).success();
+    assert_eq!(ctx.read_posts().len(), 1);
+    assert_eq!(origin_rows("posts"), 0);
+
+    ctx.run(&["sync", "--offline", "--git"]).failure();
 }
 
 #[test]
-fn test_sync_dirty_repo_fails() {
+fn test_sync_local_ahead_only() {
     let origin_dir = TempDir::new().unwrap();
     git(origin_dir.path(), &["init", "--bare"]);
 
     let store_dir = TempDir::new().unwrap();
     init_git_store(store_dir.path(), origin_dir.path());
 
-    // Make it dirty with a data file (use a dir the store doesn't read)
-    fs::create_dir_all(store_dir.path().join("extra")).unwrap();
-    fs::write(store_dir.path().join("extra/items_00.jsonl"), "dirty").unwrap();
+    // Add feed locally (insert_feed auto-commits)
+    insert_feed(store_dir.path(), "https://example.com/a.xml");
 
-    let output = run_blog(store_dir.path(), &["sync"]).failure();
-    let stderr = output.stderr_str();
+    // Sync should push
+    sync_fake_feeds(store_dir.path());
+
+    // Verify
+    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    let feeds = read_table(&clone_dir.join("feeds"));
     assert!(
-        stderr.contains("uncommitted"),
-        "expected 'uncommitted' error, got: {}",
-        stderr
+        feeds
+            .iter()
+            .any(|f| f["url"].as_str() == Some("https://example.com/a.xml")),
+        "remote should have the feed after sync"
     );
+    drop(clone_td);
 }
 
 #[test]
-fn test_sync_first_push() {
+fn test_sync_pushes_to_mirror_remotes() {
     let origin_dir = TempDir::new().unwrap();
     git(origin_dir.path(), &["init", "--bare"]);
+    let backup_dir = TempDir::new().unwrap();
+    git(backup_dir.path(), &["init", "--bare"]);
 
     let store_dir = TempDir::new().unwrap();
-    // Init repo but don't push yet (no remote branch)
-    git(store_dir.path(), &["init"]);
-    git_config_test_user(store_dir.path());
+    init_git_store(store_dir.path(), origin_dir.path());
     git(
         store_dir.path(),
         &[
             "remote",
             "add",
-            "origin",
-            &path_to_file_url(origin_dir.path()),
+            "backup",
+            &path_to_file_url(backup_dir.path()),
         ],
     );
+    insert_feed(store_dir.path(), "https://example.com/a.xml");
 
-    // Add a feed (insert_feed auto-commits since git repo exists)
-    insert_feed(store_dir.path(), "https://example.com/feed.xml");
-
-    // Sync should push
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
-    // Verify we can clone and see the data
-    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    let (clone_td, clone_dir) = clone_store(backup_dir.path());
     let feeds = read_table(&clone_dir.join("feeds"));
-    assert_eq!(feeds.len(), 1);
-    assert_eq!(
-        feeds[0]["url"].as_str().unwrap(),
-        "https://example.com/feed.xml"
+    assert!(
+        feeds
+            .iter()
+            .any(|f| f["url"].as_str() == Some("https://example.com/a.xml")),
+        "mirror should have the feed after sync"
     );
     drop(clone_td);
 }
 
 #[test]
-fn test_sync_local_ahead_only() {
+fn test_sync_reports_failed_mirror_push_without_failing() {
     let origin_dir = TempDir::new().unwrap();
     git(origin_dir.path(), &["init", "--bare"]);
+    let missing = TempDir::new().unwrap();
 
     let store_dir = TempDir::new().unwrap();
     init_git_store(store_dir.path(), origin_dir.path());
-
-    // Add feed locally (insert_feed auto-commits)
+    git(
+        store_dir.path(),
+        &[
+            "remote",
+            "add",
+            "backup",
+            &path_to_file_url(&missing.path().join("nope")),
+        ],
+    );
     insert_feed(store_dir.path(), "https://example.com/a.xml");
 
-    // Sync should push
-    run_blog(store_dir.path(), &["sync"]).success();
+    let stderr = sync_fake_feeds(store_dir.path()).stderr_str();
 
-    // Verify
-    let (clone_td, clone_dir) = clone_store(origin_dir.path());
-    let feeds = read_table(&clone_dir.join("feeds"));
     assert!(
-        feeds
-            .iter()
-            .any(|f| f["url"].as_str() == Some("https://example.com/a.xml")),
-        "remote should have the feed after sync"
+        stderr.contains("failed to push to backup"),
+        "got:\n{stderr}"
     );
+    let (clone_td, clone_dir) = clone_store(origin_dir.path());
+    assert_eq!(read_table(&clone_dir.join("feeds")).len(), 1);
     drop(clone_td);
 }
 
+#[test]
+fn test_commits_use_configured_author_and_message() {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init"]);
+    git_config_test_user(dir.path());
+
+    run_blog(
+        dir.path(),
+        &["config", "set", "commit_message", "{action} on {host}"],
+    )
+    .success();
+    run_blog(
+        dir.path(),
+        &["config", "set", "commit_author_name", "Laptop"],
+    )
+    .success();
+    run_blog(
+        dir.path(),
+        &["config", "set", "commit_author_email", "laptop@example.com"],
+    )
+    .success();
+    blog_cmd()
+        .args(["config", "set", "default_query", ".all"])
+        .env("RSS_STORE", dir.path())
+        .env("HOSTNAME", "laptop")
+        .assert()
+        .success();
+
+    let output = git_cmd()
+        .arg("-C")
+        .arg(dir.path())
+        .args(["log", "-1", "--format=%an|%ae|%s"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Laptop|laptop@example.com|config set default_query on laptop"
+    );
+
+    run_blog(dir.path(), &["config", "unset", "commit_author_name"]).success();
+    let output = git_cmd()
+        .arg("-C")
+        .arg(dir.path())
+        .args(["config", "--local", "--get", "user.name"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
 #[test]
 fn test_sync_remote_ahead_only() {
     let origin_dir = TempDir::new().unwrap();
@@ -1719,7 +4760,7 @@ fn test_sync_remote_ahead_only() {
     drop(other_td);
 
     // Local sync should merge the remote feed
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
     let feeds = read_table(&store_dir.path().join("feeds"));
     assert!(
@@ -1748,7 +4789,7 @@ fn test_sync_both_diverged() {
     insert_feed(store_dir.path(), "https://example.com/a.xml");
 
     // Sync merges both
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
     let feeds = read_table(&store_dir.path().join("feeds"));
     let urls: Vec<&str> = feeds.iter().filter_map(|f| f["url"].as_str()).collect();
@@ -1776,14 +4817,14 @@ fn test_sync_two_way() {
 
     // Clone 1 adds feed A (auto-committed) and syncs
     insert_feed(store1.path(), "https://example.com/a.xml");
-    run_blog(store1.path(), &["sync"]).success();
+    sync_fake_feeds(store1.path());
 
     // Clone 2 adds feed B (auto-committed) and syncs
     insert_feed(&store2_dir, "https://example.com/b.xml");
-    run_blog(&store2_dir, &["sync"]).success();
+    sync_fake_feeds(&store2_dir);
 
     // Clone 1 syncs again to pick up B
-    run_blog(store1.path(), &["sync"]).success();
+    sync_fake_feeds(store1.path());
 
     // Both should have A and B
     let feeds1 = read_table(&store1.path().join("feeds"));
@@ -1958,12 +4999,12 @@ fn test_sync_already_in_sync_creates_no_commits() {
 
     // Add a feed and sync
     insert_feed(store_dir.path(), "https://example.com/feed.xml");
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
     let commits_before = commit_count(store_dir.path());
 
     // Sync again — nothing changed, should not create any new commits
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
     let commits_after = commit_count(store_dir.path());
     assert_eq!(
@@ -1977,7 +5018,7 @@ fn test_sync_no_git_repo() {
     // sync should work with no git repo at all (pure feed pulling)
     let dir = TempDir::new().unwrap();
     insert_feed(dir.path(), "https://example.com/feed.xml");
-    run_blog(dir.path(), &["sync"]).success();
+    sync_fake_feeds(dir.path());
 
     let feeds = read_table(&dir.path().join("feeds"));
     assert_eq!(feeds.len(), 1);
@@ -1996,7 +5037,7 @@ fn test_sync_local_ahead_pushes_without_merge() {
     insert_feed(store_dir.path(), "https://example.com/b.xml");
 
     // Sync should just push (no merge commit)
-    run_blog(store_dir.path(), &["sync"]).success();
+    sync_fake_feeds(store_dir.path());
 
     // Verify no merge commits (all commits should have at most 1 parent)
     let output = git_cmd()
@@ -2019,12 +5060,6 @@ fn test_sync_local_ahead_pushes_without_merge() {
     }
 }
 
-#[test]
-fn test_pull_command_removed() {
-    let dir = TempDir::new().unwrap();
-    run_blog(dir.path(), &["pull"]).failure();
-}
-
 #[test]
 fn test_add_direct_feed_url_still_works() {
     let ctx = TestContext::new();
@@ -2073,17 +5108,200 @@ fn test_add_html_page_discovers_feed() {
     assert_eq!(feeds[0]["url"].as_str().unwrap(), feed_url);
 }
 
+#[rstest]
+#[case::channel(
+    "https://www.youtube.com/channel/UCsXVk37bltHxD1rDPwtNM8Q",
+    "https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"
+)]
+#[case::playlist(
+    "https://youtube.com/playlist?list=PLzH6n4zXuckpfMu_4Ff8E7Z1behQks5ba",
+    "https://www.youtube.com/feeds/videos.xml?playlist_id=PLzH6n4zXuckpfMu_4Ff8E7Z1behQks5ba"
+)]
+fn test_add_youtube_url_uses_its_feed(#[case] url: &str, #[case] expected: &str) {
+    let ctx = TestContext::new();
+
+    let stderr = ctx.run(&["feed", "add", url]).success().stderr_str();
+
+    assert!(
+        stderr.contains(&format!("Discovered feed: {expected}")),
+        "got:\n{stderr}"
+    );
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    // Stored URLs are normalized, which drops the `www.`
+    assert_eq!(
+        feeds[0]["url"].as_str().unwrap(),
+        expected.replace("www.", "")
+    );
+}
+
+#[rstest]
+#[case::subreddit("r/rust", "https://reddit.com/r/rust/.rss")]
+#[case::hacker_news("hn:best", "https://hnrss.org/best")]
+fn test_add_source_shortcut(#[case] shortcut: &str, #[case] expected: &str) {
+    let ctx = TestContext::new();
+
+    ctx.run(&["feed", "add", shortcut]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(feeds[0]["url"].as_str().unwrap(), expected);
+}
+
+#[test]
+fn test_add_unknown_hn_shortcut_fails() {
+    let ctx = TestContext::new();
+
+    let stderr = ctx.run(&["feed", "add", "hn:nope"]).failure().stderr_str();
+
+    assert!(stderr.contains("frontpage"), "got:\n{stderr}");
+    assert!(ctx.read_feeds().is_empty());
+}
+
 #[test]
 fn test_add_html_page_multiple_feeds_fails() {
     let ctx = TestContext::new();
 
-    let feed1_url = ctx.server.url("/feed1.xml");
-    let feed2_url = ctx.server.url("/feed2.xml");
+    let feed1_url = ctx.server.url("/feed1.xml");
+    let feed2_url = ctx.server.url("/feed2.xml");
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{feed1_url}" title="Feed 1">
+        <link rel="alternate" type="application/atom+xml" href="{feed2_url}" title="Feed 2">
+        </head><body></body></html>"#
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/blog");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+
+    let blog_url = ctx.server.url("/blog");
+    ctx.run(&["feed", "add", &blog_url]).failure();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 0);
+}
+
+#[test]
+fn test_discover_lists_feeds_without_subscribing() {
+    let ctx = TestContext::new();
+
+    ctx.mock_rss_feed(
+        "/feed1.xml",
+        &rss_xml(
+            "Posts",
+            &[
+                ("A", "Mon, 01 Jan 2024 00:00:00 +0000"),
+                ("B", "Tue, 02 Jan 2024 00:00:00 +0000"),
+            ],
+        ),
+    );
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        </head><body></body></html>"#,
+        ctx.server.url("/feed1.xml")
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/blog");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+
+    let stdout = ctx
+        .run(&["discover", &ctx.server.url("/blog")])
+        .success()
+        .stdout_str();
+
+    assert!(stdout.starts_with("RSS"), "got:\n{stdout}");
+    assert!(stdout.contains("2 posts"), "got:\n{stdout}");
+    assert!(
+        stdout.contains(&ctx.server.url("/feed1.xml")),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("Posts"), "got:\n{stdout}");
+    assert!(ctx.read_feeds().is_empty());
+}
+
+#[test]
+fn test_suggest_offers_feeds_of_linked_sites() {
+    let ctx = TestContext::new();
+
+    // Served by the same mock server, but a different site by name
+    let friend = ctx.server.url("/").replace("127.0.0.1", "localhost");
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>My Blog</title>
+    <item><title>Links</title><guid>guid-1</guid>
+      <description>See &lt;a href="{friend}posts/1"&gt;my friend&lt;/a&gt;</description></item>
+  </channel>
+</rss>"#
+    );
+    ctx.mock_rss_feed("/mine.xml", &xml);
+    ctx.mock_rss_feed(
+        "/friend.xml",
+        &rss_xml("Friend", &[("F", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{friend}friend.xml">
+        </head><body></body></html>"#
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+    let mine = ctx.server.url("/mine.xml");
+    ctx.write_feeds(&[&mine]);
+    ctx.run(&["sync"]).success();
+    let shorthand = feed_shorthand_for_url(&ctx, &mine);
+
+    let stderr = blog_cmd()
+        .args(["suggest", &shorthand])
+        .env("RSS_STORE", ctx.dir.path())
+        .write_stdin("1\n")
+        .assert()
+        .success()
+        .stderr_str();
+
+    assert!(stderr.contains("1) Friend"), "got:\n{stderr}");
+    let mut urls: Vec<String> = ctx
+        .read_feeds()
+        .iter()
+        .map(|f| f["url"].as_str().unwrap().to_string())
+        .collect();
+    urls.sort();
+    assert_eq!(urls, [mine, format!("{friend}friend.xml")]);
+}
+
+#[rstest]
+#[case::one("2\n", &["/feed2.xml"])]
+#[case::several("1 2\n", &["/feed1.xml", "/feed2.xml"])]
+fn test_add_select_picks_from_discovered_feeds(#[case] answer: &str, #[case] expected: &[&str]) {
+    let ctx = TestContext::new();
+
+    ctx.mock_rss_feed(
+        "/feed1.xml",
+        &rss_xml("Posts", &[("A", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    ctx.mock_rss_feed(
+        "/feed2.xml",
+        &rss_xml("Comments", &[("B", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
     let html = format!(
         r#"<html><head>
-        <link rel="alternate" type="application/rss+xml" href="{feed1_url}" title="Feed 1">
-        <link rel="alternate" type="application/atom+xml" href="{feed2_url}" title="Feed 2">
-        </head><body></body></html>"#
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        </head><body></body></html>"#,
+        ctx.server.url("/feed1.xml"),
+        ctx.server.url("/feed2.xml")
     );
     ctx.server.mock(|when, then| {
         when.method(GET).path("/blog");
@@ -2092,11 +5310,75 @@ fn test_add_html_page_multiple_feeds_fails() {
             .body(&html);
     });
 
-    let blog_url = ctx.server.url("/blog");
-    ctx.run(&["feed", "add", &blog_url]).failure();
+    let stderr = blog_cmd()
+        .args(["feed", "add", "--select", &ctx.server.url("/blog")])
+        .env("RSS_STORE", ctx.dir.path())
+        .write_stdin(answer)
+        .assert()
+        .success()
+        .stderr_str();
 
-    let feeds = ctx.read_feeds();
-    assert_eq!(feeds.len(), 0);
+    assert!(stderr.contains("1) Posts"), "got:\n{stderr}");
+    assert!(stderr.contains("2) Comments"), "got:\n{stderr}");
+    let mut urls: Vec<String> = ctx
+        .read_feeds()
+        .iter()
+        .map(|f| f["url"].as_str().unwrap().to_string())
+        .collect();
+    urls.sort();
+    let expected: Vec<String> = expected.iter().map(|p| ctx.server.url(*p)).collect();
+    assert_eq!(urls, expected);
+}
+
+#[rstest]
+#[case::stdin(&["feed", "add", "-"])]
+#[case::file(&["feed", "add", "--file", "urls.txt"])]
+fn test_add_bulk_reports_added_and_failed(#[case] args: &[&str]) {
+    let ctx = TestContext::new();
+
+    ctx.mock_rss_feed(
+        "/a.xml",
+        &rss_xml("A", &[("A post", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    ctx.mock_rss_feed(
+        "/b.xml",
+        &rss_xml("B", &[("B post", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/gone");
+        then.status(404);
+    });
+    let list = format!(
+        "# blogroll\n{}\n\n{}\n{}\n",
+        ctx.server.url("/a.xml"),
+        ctx.server.url("/gone"),
+        ctx.server.url("/b.xml")
+    );
+    let list_path = ctx.dir.path().join("urls.txt");
+    std::fs::write(&list_path, &list).unwrap();
+
+    let args: Vec<&str> = args
+        .iter()
+        .map(|a| {
+            if *a == "urls.txt" {
+                list_path.to_str().unwrap()
+            } else {
+                a
+            }
+        })
+        .collect();
+    let stderr = blog_cmd()
+        .args(&args)
+        .env("RSS_STORE", ctx.dir.path())
+        .write_stdin(list)
+        .assert()
+        .failure()
+        .stderr_str();
+
+    assert!(stderr.contains("Added 2 feeds."), "got:\n{stderr}");
+    assert!(stderr.contains("Failed to add 1 feed:"), "got:\n{stderr}");
+    assert!(stderr.contains(&ctx.server.url("/gone")), "got:\n{stderr}");
+    assert_eq!(ctx.read_feeds().len(), 2);
 }
 
 #[test]
@@ -2194,6 +5476,85 @@ fn test_add_html_page_deduplicates_feed_candidates() {
     assert_eq!(feeds[0]["url"].as_str().unwrap(), feed_url);
 }
 
+#[test]
+fn test_add_html_page_falls_back_to_root_feed_paths() {
+    let ctx = TestContext::new();
+
+    let feed_json = r#"{
+      "version": "https://jsonfeed.org/version/1.1",
+      "title": "JSON Blog",
+      "items": [{"id": "1", "title": "A Post", "date_published": "2024-01-01T00:00:00Z"}]
+    }"#;
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/feed.json");
+        then.status(200)
+            .header("Content-Type", "application/feed+json")
+            .body(feed_json);
+    });
+
+    // The advertised feed is gone, so only the root paths are left to try
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        </head><body></body></html>"#,
+        ctx.server.url("/blog/old-feed.xml")
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/blog/post");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+
+    ctx.run(&["feed", "add", &ctx.server.url("/blog/post")])
+        .success();
+    ctx.run(&["sync"]).success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(
+        feeds[0]["url"].as_str().unwrap(),
+        ctx.server.url("/feed.json")
+    );
+    let posts = ctx.read_posts();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"].as_str().unwrap(), "A Post");
+}
+
+#[test]
+fn test_add_html_page_root_fallback_stops_at_first_feed() {
+    let ctx = TestContext::new();
+
+    // The site serves the same feed under several of the usual names
+    let feed_xml = rss_xml("My Feed", &[("A Post", "Mon, 01 Jan 2024 00:00:00 +0000")]);
+    for path in ["/feed.xml", "/rss.xml", "/atom.xml", "/rss"] {
+        ctx.mock_rss_feed(path, &feed_xml);
+    }
+    // The advertised feed is gone, so only the root paths are left to try
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        </head><body></body></html>"#,
+        ctx.server.url("/blog/old-feed.xml")
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/blog/post");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+
+    ctx.run(&["feed", "add", &ctx.server.url("/blog/post")])
+        .success();
+
+    let feeds = ctx.read_feeds();
+    assert_eq!(feeds.len(), 1);
+    assert_eq!(
+        feeds[0]["url"].as_str().unwrap(),
+        ctx.server.url("/feed.xml")
+    );
+}
+
 #[test]
 fn test_add_html_page_caps_candidate_validation() {
     let ctx = TestContext::new();
@@ -2276,6 +5637,39 @@ fn test_clone_into_empty_dir() {
     );
 }
 
+#[rstest]
+#[case::shallow_by_default(&[], "true")]
+#[case::full(&["--full"], "false")]
+fn test_clone_history_depth(#[case] flags: &[&str], #[case] shallow: &str) {
+    let origin_dir = TempDir::new().unwrap();
+    git(origin_dir.path(), &["init", "--bare"]);
+    let work_dir = TempDir::new().unwrap();
+    init_git_store(work_dir.path(), origin_dir.path());
+    insert_feed(work_dir.path(), "https://example.com/a.xml");
+    insert_feed(work_dir.path(), "https://example.com/b.xml");
+    git(work_dir.path(), &["push", "origin", "HEAD"]);
+
+    let store_dir = TempDir::new().unwrap();
+    let target = store_dir.path().join("store");
+    let url = path_to_file_url(origin_dir.path());
+    let mut args = vec!["clone", url.as_str()];
+    args.extend_from_slice(flags);
+    blog_cmd()
+        .args(&args)
+        .env("RSS_STORE", &target)
+        .assert()
+        .success();
+
+    let output = git_cmd()
+        .arg("-C")
+        .arg(&target)
+        .args(["rev-parse", "--is-shallow-repository"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), shallow);
+    assert_eq!(read_table(&target.join("feeds")).len(), 2);
+}
+
 #[test]
 fn test_clone_merges_with_existing_store() {
     // Set up a "remote" bare repo with feed B
@@ -2409,7 +5803,7 @@ fn test_show_range_with_grouping() {
     let stdout = output.stdout_str();
 
     assert!(
-        stdout.contains("=== 2024-01-15 ==="),
+        stdout.contains("=== 2024-01-15 ("),
         "Should show date group header for 2024-01-15"
     );
     assert!(
@@ -2441,9 +5835,10 @@ fn test_unread_command() {
     let before = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
     assert_eq!(before.lines().filter(|l| l.starts_with('*')).count(), 2);
 
-    // Open first post (shorthand "a") to mark it read
+    // Open the newest post to mark it read
+    let sh = ctx.post_shorthand("Post A");
     blog_cmd()
-        .args(["a", "open"])
+        .args([sh.as_str(), "open"])
         .env("RSS_STORE", ctx.dir.path())
         .env("BROWSER", "true")
         .assert()
@@ -2458,7 +5853,7 @@ fn test_unread_command() {
     );
 
     // Mark it unread again
-    ctx.run(&["a", "unread"]).success();
+    ctx.run(&[sh.as_str(), "unread"]).success();
 
     // After unread: both posts should be unread again
     let after_unread = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
@@ -2491,47 +5886,374 @@ fn test_target_first_open() {
     let before = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
     assert_eq!(before.lines().filter(|l| l.starts_with('*')).count(), 2);
 
-    // Use target-first syntax: `a open` instead of `open a`
+    // Use target-first syntax: `<shorthand> open` instead of `open <shorthand>`
+    let sh = ctx.post_shorthand("Post A");
     blog_cmd()
-        .args(["a", "open"])
+        .args([sh.as_str(), "open"])
         .env("RSS_STORE", ctx.dir.path())
         .env("BROWSER", "true")
         .assert()
         .success();
 
-    // After opening: one post is read
-    let after = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
-    assert_eq!(
-        after.lines().filter(|l| l.starts_with('*')).count(),
-        1,
-        "expected 1 unread post after target-first open, got:\n{after}"
+    // After opening: one post is read
+    let after = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
+    assert_eq!(
+        after.lines().filter(|l| l.starts_with('*')).count(),
+        1,
+        "expected 1 unread post after target-first open, got:\n{after}"
+    );
+}
+
+#[test]
+fn test_target_first_read() {
+    let ctx = TestContext::new();
+
+    let xml = rss_xml_with_links(
+        "Target First Read Blog",
+        &[(
+            "Post A",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-a",
+            "https://example.com/a",
+        )],
+    );
+    ctx.mock_rss_feed("/tfr.xml", &xml);
+    let url = ctx.server.url("/tfr.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    // Use target-first syntax: `<shorthand> read` instead of `read <shorthand>`
+    let sh = ctx.post_shorthand("Post A");
+    let output = ctx.run(&[sh.as_str(), "read"]).success().stdout_str();
+    assert!(
+        output.contains("https://example.com/a"),
+        "expected URL in output, got: {output}"
+    );
+}
+
+#[test]
+fn test_read_plain_prints_only_url() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Plain Read Blog",
+        &[(
+            "Post A",
+            "Mon, 01 Jan 2024 00:00:00 +0000",
+            "guid-a",
+            "https://example.com/a",
+        )],
+    );
+    ctx.mock_rss_feed("/plain.xml", &xml);
+    let url = ctx.server.url("/plain.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let sh = ctx.post_shorthand("Post A");
+    let output = ctx
+        .run(&[sh.as_str(), "read", "--plain"])
+        .success()
+        .stdout_str();
+    assert_eq!(output, "https://example.com/a\n");
+}
+
+#[test]
+fn test_read_renders_post_content() {
+    let ctx = TestContext::new();
+
+    let posts = r#"{"id":"1","title":"Hello World","date":"2024-01-15T00:00:00Z","feed":"Alice","link":"https://example.com/hello","summary":"<h2>Intro</h2><p>Some <strong>bold</strong> text.</p><ul><li>One</li><li>Two</li></ul>"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stdout = ctx.run(&["id:1", "read"]).success().stdout_str();
+    assert!(stdout.starts_with("# Hello World\n"), "got:\n{stdout}");
+    assert!(stdout.contains("https://example.com/hello\n"));
+    assert!(
+        stdout.ends_with("\n## **Intro**\n\nSome **bold** text.\n\n- One\n- Two\n"),
+        "got:\n{stdout}"
+    );
+
+    let shown = ctx.run(&[".read", "2020-01-01.."]).success().stdout_str();
+    assert!(
+        shown.contains("Hello World"),
+        "read should mark the post as read, got:\n{shown}"
+    );
+}
+
+#[test]
+fn test_show_lang_filters_by_detected_language() {
+    let ctx = TestContext::new();
+
+    let posts = [
+        r#"{"id":"1","title":"Why we rewrote our build system","date":"2024-01-15T00:00:00Z","feed":"Alice","summary":"<p>Last year our builds took almost an hour, and nobody on the team was happy about it.</p>"}"#,
+        r#"{"id":"2","title":"Warum wir unser Build-System neu geschrieben haben","date":"2024-01-16T00:00:00Z","feed":"Alice","summary":"<p>Letztes Jahr dauerten unsere Builds fast eine Stunde, und niemand im Team war damit zufrieden.</p>"}"#,
+    ];
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(
+        ctx.dir.path().join("posts").join("items_.jsonl"),
+        posts.join("\n"),
+    )
+    .unwrap();
+
+    let german = ctx.run(&[".all", "--lang", "de"]).success().stdout_str();
+    assert!(german.contains("Warum wir"), "got:\n{german}");
+    assert!(!german.contains("Why we"), "got:\n{german}");
+
+    let english = ctx
+        .run(&[".all", "show", "--lang", "eng"])
+        .success()
+        .stdout_str();
+    assert!(english.contains("Why we"), "got:\n{english}");
+    assert!(!english.contains("Warum wir"), "got:\n{english}");
+
+    ctx.run(&[".all", "--lang", "fr"]).failure();
+}
+
+#[test]
+fn test_show_tsv_and_print0() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Test Feed",
+        &[
+            (
+                "First Post",
+                "Mon, 01 Jan 2024 00:00:00 +0000",
+                "p1",
+                "https://a.example/1",
+            ),
+            (
+                "Second Post",
+                "Tue, 02 Jan 2024 00:00:00 +0000",
+                "p2",
+                "https://a.example/2",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/feed.xml", &xml);
+    ctx.run(&["feed", "add", &ctx.server.url("/feed.xml")])
+        .success();
+    ctx.run(&["sync"]).success();
+
+    let tsv = ctx
+        .run(&[".all", "/d", "--format", "tsv"])
+        .success()
+        .stdout_str();
+    let rows: Vec<Vec<&str>> = tsv.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 2, "got:\n{tsv}");
+    assert!(rows.iter().all(|r| r.len() == 5), "got:\n{tsv}");
+    let first = rows.iter().find(|r| r[4] == "https://a.example/1").unwrap();
+    assert_eq!(first[0], ctx.post_shorthand("First"));
+    assert_eq!(first[1], "2024-01-01");
+    assert!(first[2].contains("Test Feed"), "got: {first:?}");
+    assert_eq!(first[3], "First Post");
+
+    let print0 = ctx
+        .run(&[".all", "show", "--print0"])
+        .success()
+        .stdout_str();
+    assert_eq!(print0.split_terminator('\0').count(), 2, "got: {print0:?}");
+    assert!(!print0.contains('\n'));
+}
+
+#[test]
+fn test_search_ranks_and_filters_posts() {
+    let ctx = TestContext::new();
+    let cache = tempfile::tempdir().unwrap();
+    let alpha = rss_xml_with_links(
+        "Alpha",
+        &[
+            (
+                "Rust borrow checker",
+                "Fri, 05 Jan 2024 00:00:00 +0000",
+                "alpha-1",
+                "https://alpha.example/1",
+            ),
+            (
+                "Gardening tips",
+                "Sat, 06 Jan 2024 00:00:00 +0000",
+                "alpha-2",
+                "https://alpha.example/2",
+            ),
+        ],
+    );
+    let beta = rss_xml_with_links(
+        "Beta",
+        &[(
+            "Weekly rust notes",
+            "Thu, 01 Feb 2024 00:00:00 +0000",
+            "beta-1",
+            "https://beta.example/1",
+        )],
+    );
+    ctx.mock_rss_feed("/alpha.xml", &alpha);
+    ctx.mock_rss_feed("/beta.xml", &beta);
+    let alpha_url = ctx.server.url("/alpha.xml");
+    ctx.write_feeds(&[&alpha_url, &ctx.server.url("/beta.xml")]);
+    ctx.run(&["sync"]).success();
+    let alpha_shorthand = feed_shorthand_for_url(&ctx, &alpha_url);
+
+    let search = |terms: &[&str]| {
+        blog_cmd()
+            .arg("search")
+            .args(terms)
+            .env("RSS_STORE", ctx.dir.path())
+            .env("XDG_CACHE_HOME", cache.path())
+            .assert()
+    };
+
+    let both = search(&["rust"]).success().stdout_str();
+    assert!(both.contains("Rust borrow checker"), "got:\n{both}");
+    assert!(both.contains("Weekly rust notes"), "got:\n{both}");
+    assert!(!both.contains("Gardening"), "got:\n{both}");
+
+    let phrase = search(&["\"borrow checker\""]).success().stdout_str();
+    assert!(phrase.contains("Rust borrow checker"), "got:\n{phrase}");
+    assert!(!phrase.contains("Weekly"), "got:\n{phrase}");
+
+    let by_feed = search(&["rust", &format!("feed:{alpha_shorthand}")])
+        .success()
+        .stdout_str();
+    assert!(by_feed.contains("Rust borrow checker"), "got:\n{by_feed}");
+    assert!(!by_feed.contains("Weekly"), "got:\n{by_feed}");
+
+    let by_date = search(&["rust date:>2024-01-31"]).success().stdout_str();
+    assert!(by_date.contains("Weekly rust notes"), "got:\n{by_date}");
+    assert!(!by_date.contains("borrow"), "got:\n{by_date}");
+
+    search(&["kubernetes"]).failure();
+
+    let ranked = search(&["rust"]).success().stdout_str();
+    assert!(
+        ranked
+            .lines()
+            .all(|l| l.starts_with("1 ") || l.starts_with("2 ")),
+        "got:\n{ranked}"
+    );
+    blog_cmd()
+        .args(["search", "notes", "--open", "1"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout("https://beta.example/1\n");
+    let opened = search(&["notes"]).success().stdout_str();
+    assert!(
+        opened.starts_with("1   "),
+        "opening marks it read, got:\n{opened}"
+    );
+    let stderr = search(&["notes", "--open", "2"]).failure().stderr_str();
+    assert!(stderr.contains("No result 2"), "got:\n{stderr}");
+
+    // Syncing keeps the index the first search created up to date
+    let sync = blog_cmd()
+        .arg("sync")
+        .env("RSS_STORE", ctx.dir.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .assert()
+        .success()
+        .stderr_str();
+    assert!(!sync.contains("search index"), "got:\n{sync}");
+}
+
+#[test]
+fn test_saved_views() {
+    let ctx = TestContext::new();
+
+    let posts = [
+        r#"{"id":"1","raw_id":"r1","title":"Rust 2.0 released","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#,
+        r#"{"id":"2","raw_id":"r2","title":"Gardening","date":"2024-01-16T00:00:00Z","feed":"Alice","summary":"<p>Rust on my tools</p>"}"#,
+        r#"{"id":"3","raw_id":"r3","title":"Cooking","date":"2024-01-17T00:00:00Z","feed":"Alice"}"#,
+    ];
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(
+        ctx.dir.path().join("posts").join("items_.jsonl"),
+        posts.join("\n"),
+    )
+    .unwrap();
+
+    ctx.run(&[
+        "view",
+        "add",
+        "rust-news",
+        "2020-01-01..",
+        "--match",
+        "rust",
+    ])
+    .success();
+    let ls = ctx.run(&["view", "ls"]).success().stdout_str();
+    assert_eq!(ls, "%rust-news  2020-01-01.. ~rust\n");
+
+    for args in [
+        &["%rust-news"][..],
+        &["show", "%rust-news"],
+        &["%rust-news", "show"],
+    ] {
+        let shown = ctx.run(args).success().stdout_str();
+        assert!(shown.contains("Rust 2.0 released"), "got:\n{shown}");
+        assert!(
+            shown.contains("Gardening"),
+            "matches summaries, got:\n{shown}"
+        );
+        assert!(!shown.contains("Cooking"), "got:\n{shown}");
+    }
+
+    ctx.run(&[
+        "view",
+        "add",
+        "rust-news",
+        "2020-01-01..",
+        "--match",
+        "rust",
+        "--unread",
+    ])
+    .success();
+    ctx.run(&["id:2", "read"]).success();
+    let unread = ctx.run(&["%rust-news"]).success().stdout_str();
+    assert!(!unread.contains("Gardening"), "got:\n{unread}");
+
+    ctx.run(&["view", "rm", "rust-news"]).success();
+    let stderr = ctx.run(&["%rust-news"]).failure().stderr_str();
+    assert!(
+        stderr.contains("Unknown view: %rust-news"),
+        "got:\n{stderr}"
     );
 }
 
 #[test]
-fn test_target_first_read() {
+fn test_read_numbers_links() {
     let ctx = TestContext::new();
 
-    let xml = rss_xml_with_links(
-        "Target First Read Blog",
-        &[(
-            "Post A",
-            "Mon, 01 Jan 2024 00:00:00 +0000",
-            "guid-a",
-            "https://example.com/a",
-        )],
-    );
-    ctx.mock_rss_feed("/tfr.xml", &xml);
-    let url = ctx.server.url("/tfr.xml");
-    ctx.write_feeds(&[&url]);
-    ctx.run(&["sync"]).success();
+    let posts = r#"{"id":"1","title":"Links","date":"2024-01-15T00:00:00Z","feed":"Alice","link":"https://example.com/links","summary":"<p>See <a href=\"/about\">about</a> and <a href=\"https://rust-lang.org/\">Rust</a>.</p>"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
 
-    // Use target-first syntax: `a read` instead of `read a`
-    let output = ctx.run(&["a", "read"]).success().stdout_str();
+    let stdout = ctx.run(&["id:1", "read"]).success().stdout_str();
     assert!(
-        output.contains("https://example.com/a"),
-        "expected URL in output, got: {output}"
+        stdout.ends_with(
+            "See about[1] and Rust[2].\n\n[1]: https://example.com/about\n[2]: https://rust-lang.org/\n"
+        ),
+        "got:\n{stdout}"
     );
+
+    let link = ctx
+        .run(&["id:1", "read", "--link", "2", "--plain"])
+        .success()
+        .stdout_str();
+    assert_eq!(link, "https://rust-lang.org/\n");
+
+    blog_cmd()
+        .args(["id:1", "read", "--link", "1"])
+        .env("RSS_STORE", ctx.dir.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout("https://example.com/about\n");
+
+    let stderr = ctx
+        .run(&["id:1", "read", "--link", "3"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("No link [3]"), "got:\n{stderr}");
 }
 
 #[test]
@@ -2553,8 +6275,9 @@ fn test_target_first_unread() {
     ctx.run(&["sync"]).success();
 
     // Mark as read first via target-first open
+    let sh = ctx.post_shorthand("Post A");
     blog_cmd()
-        .args(["a", "open"])
+        .args([sh.as_str(), "open"])
         .env("RSS_STORE", ctx.dir.path())
         .env("BROWSER", "true")
         .assert()
@@ -2567,8 +6290,8 @@ fn test_target_first_unread() {
         "expected 0 unread posts after open"
     );
 
-    // Use target-first syntax: `a unread` instead of `unread a`
-    ctx.run(&["a", "unread"]).success();
+    // Use target-first syntax: `<shorthand> unread` instead of `unread <shorthand>`
+    ctx.run(&[sh.as_str(), "unread"]).success();
 
     let after_unread = ctx.run(&["show", "2020-01-01.."]).success().stdout_str();
     assert_eq!(
@@ -2609,7 +6332,8 @@ fn test_show_default_query_hides_read_posts() {
     );
 
     // Mark it read
-    ctx.run(&["a", "read"]).success();
+    let sh = ctx.post_shorthand("Recent Post");
+    ctx.run(&[sh.as_str(), "read"]).success();
 
     // Default show should now hide the read post
     let after = ctx.run(&[]);
@@ -2727,7 +6451,8 @@ fn test_export_outputs_jsonl() {
     );
 
     // Mark first post as read, then export again
-    ctx.run(&["a", "read"]).success();
+    let sh = ctx.post_shorthand("Post A");
+    ctx.run(&[sh.as_str(), "read"]).success();
     let output2 = ctx.run(&[".all", "export"]).success().stdout_str();
     let lines2: Vec<&str> = output2.lines().collect();
     // Find the read post (Post A)
@@ -2747,6 +6472,157 @@ fn test_export_outputs_jsonl() {
     );
 }
 
+#[test]
+fn test_export_epub_bundles_selected_posts() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Epub Blog",
+        &[
+            (
+                "Post A",
+                "Mon, 15 Jan 2024 00:00:00 +0000",
+                "guid-a",
+                "https://example.com/a",
+            ),
+            (
+                "Post B",
+                "Sun, 14 Jan 2024 00:00:00 +0000",
+                "guid-b",
+                "https://example.com/b",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/epub.xml", &xml);
+    let url = ctx.server.url("/epub.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let out = ctx.dir.path().join("posts.epub");
+    let out_str = out.to_str().unwrap();
+    let sh = ctx.post_shorthand("Post A");
+    ctx.run(&[sh.as_str(), "export", "--format", "epub", "-o", out_str])
+        .success();
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+    assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+    let mut chapter = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("OEBPS/chapter-1.xhtml").unwrap(),
+        &mut chapter,
+    )
+    .unwrap();
+    assert!(chapter.contains("Post A"), "got:\n{chapter}");
+    assert!(
+        archive.by_name("OEBPS/chapter-2.xhtml").is_err(),
+        "only the selected post should be exported"
+    );
+}
+
+fn digest_ctx() -> TestContext {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Digest Blog",
+        &[
+            (
+                "Fresh &amp; New",
+                &recent_rss_date(0),
+                "guid-a",
+                "https://example.com/a",
+            ),
+            (
+                "Last Week",
+                &recent_rss_date(7),
+                "guid-b",
+                "https://example.com/b",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/digest.xml", &xml);
+    ctx.write_feeds(&[&ctx.server.url("/digest.xml")]);
+    ctx.run(&["sync"]).success();
+    ctx
+}
+
+#[test]
+fn test_digest_lists_posts_since() {
+    let ctx = digest_ctx();
+
+    let output = ctx.run(&["digest"]).success().stdout_str();
+    assert!(output.starts_with("1 new post since "), "got:\n{output}");
+    assert!(output.contains("Digest Blog\n  Fresh & New\n  https://example.com/a\n"));
+    assert!(!output.contains("Last Week"));
+
+    let output = ctx
+        .run(&["digest", "--since", "30d"])
+        .success()
+        .stdout_str();
+    assert!(output.contains("Last Week"), "got:\n{output}");
+}
+
+#[test]
+fn test_digest_email_is_multipart() {
+    let ctx = digest_ctx();
+
+    let output = ctx
+        .run(&["digest", "--email", "--to", "me@example.com"])
+        .success()
+        .stdout_str();
+    assert!(output.contains("To: me@example.com"), "got:\n{output}");
+    assert!(output.contains("Subject: blogtato digest: 1 new post since "));
+    assert!(output.contains("multipart/alternative"));
+    assert!(output.contains("text/plain"));
+    assert!(output.contains("text/html"));
+    assert!(output.contains("Fresh &amp; New"));
+}
+
+#[test]
+fn test_digest_email_needs_recipient() {
+    let ctx = digest_ctx();
+
+    let stderr = ctx.run(&["digest", "--email"]).failure().stderr_str();
+    assert!(stderr.contains("digest_to"), "got:\n{stderr}");
+
+    ctx.run(&["config", "set", "digest_to", "me@example.com"])
+        .success();
+    ctx.run(&["digest", "--email"]).success();
+}
+
+#[test]
+fn test_digest_send_needs_smtp_url() {
+    let ctx = digest_ctx();
+
+    let stderr = ctx
+        .run(&["digest", "--send", "--to", "me@example.com"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("smtp_url"), "got:\n{stderr}");
+}
+
+#[test]
+fn test_digest_without_posts_fails() {
+    let ctx = digest_ctx();
+
+    let stderr = ctx
+        .run(&["digest", "2000-01-01..2000-02-01"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("No new posts"), "got:\n{stderr}");
+}
+
+#[test]
+fn test_export_epub_requires_output_file() {
+    let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Hello","date":"2024-01-15T00:00:00Z","feed":"Alice"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+
+    let stderr = ctx
+        .run(&["id:1", "export", "--format", "epub"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("--output"), "got:\n{stderr}");
+}
+
 #[test]
 fn test_export_respects_filters() {
     let ctx = TestContext::new();
@@ -2783,6 +6659,87 @@ fn test_export_respects_filters() {
     assert!(lines[0].contains("Post A"));
 }
 
+#[test]
+fn test_export_atom_river() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "River Blog",
+        &[
+            (
+                "Post A",
+                "Mon, 15 Jan 2024 00:00:00 +0000",
+                "guid-a",
+                "https://example.com/a",
+            ),
+            (
+                "Post B",
+                "Sun, 14 Jan 2024 00:00:00 +0000",
+                "guid-b",
+                "https://example.com/b",
+            ),
+            (
+                "Post C",
+                "Sat, 13 Jan 2024 00:00:00 +0000",
+                "guid-c",
+                "https://example.com/c",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/river.xml", &xml);
+    let url = ctx.server.url("/river.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let out = ctx.dir.path().join("river.xml");
+    ctx.run(&[
+        ".all",
+        "export",
+        "--format",
+        "feed",
+        "--out",
+        out.to_str().unwrap(),
+        "--limit",
+        "2",
+    ])
+    .success();
+
+    let feed: atom_syndication::Feed = fs::read_to_string(&out).unwrap().parse().unwrap();
+    let titles: Vec<&str> = feed.entries().iter().map(|e| e.title().as_str()).collect();
+    assert_eq!(titles, ["Post A", "Post B"]);
+    let entry = &feed.entries()[0];
+    assert_eq!(entry.links()[0].href(), "https://example.com/a");
+    let source = entry.source().expect("entry should name its feed");
+    assert_eq!(source.title().as_str(), "River Blog");
+    assert_eq!(source.id(), url);
+}
+
+#[test]
+fn test_export_markdown() {
+    let ctx = TestContext::new();
+    let posts = r#"{"id":"1","title":"Hello","date":"2024-01-15T00:00:00Z","feed":"Alice","raw_id":"r1","link":"https://example.com/1"}"#;
+    fs::create_dir_all(ctx.dir.path().join("posts")).unwrap();
+    fs::write(ctx.dir.path().join("posts").join("items_.jsonl"), posts).unwrap();
+    let out = ctx.dir.path().join("notes.md");
+
+    ctx.run(&[
+        "id:1",
+        "export",
+        "--format",
+        "md",
+        "--out",
+        out.to_str().unwrap(),
+    ])
+    .success();
+
+    let markdown = fs::read_to_string(&out).unwrap();
+    assert!(
+        markdown.starts_with("# blogtato export\n"),
+        "got:\n{markdown}"
+    );
+    assert!(markdown.contains("## [Hello](<https://example.com/1>)\n"));
+    assert!(markdown.contains("- Date: 2024-01-15\n"));
+}
+
 #[test]
 fn test_invalid_ingest_filter_returns_error_on_sync() {
     let ctx = TestContext::new();
@@ -2833,6 +6790,8 @@ fn test_ingest_filter_readme_examples(
 
     ctx.run(&["config", "set", "ingest_filter", filter])
         .success();
+    // Keep the tracking parameters for the filters to strip
+    ctx.run(&["config", "set", "tracking_params", ""]).success();
 
     let post_date = recent_rss_date(3);
     let sponsored_date = recent_rss_date(2);
@@ -2895,6 +6854,112 @@ fn test_ingest_filter_readme_examples(
     }
 }
 
+#[test]
+fn test_tracking_params_are_stripped_from_links() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Tech News Daily",
+        &[
+            (
+                "Tracked",
+                &recent_rss_date(2),
+                "guid-1",
+                "https://technews.example.com/a?id=7&amp;utm_source=rss&amp;fbclid=abc",
+            ),
+            (
+                "Referred",
+                &recent_rss_date(1),
+                "guid-2",
+                "https://technews.example.com/b?ref=hn",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/technews.xml", &xml);
+    let url = ctx.server.url("/technews.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let mut links: Vec<String> = ctx
+        .read_posts()
+        .iter()
+        .map(|p| p["link"].as_str().unwrap().to_string())
+        .collect();
+    links.sort();
+    assert_eq!(
+        links,
+        [
+            "https://technews.example.com/a?id=7",
+            "https://technews.example.com/b?ref=hn"
+        ]
+    );
+}
+
+#[test]
+fn test_blocklist_drops_posts_and_refuses_feeds() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Aggregator",
+        &[
+            (
+                "Real News",
+                &recent_rss_date(2),
+                "guid-1",
+                "https://news.example.com/a",
+            ),
+            (
+                "Spam",
+                &recent_rss_date(1),
+                "guid-2",
+                "https://www.spam.example/win",
+            ),
+        ],
+    );
+    ctx.mock_rss_feed("/aggregator.xml", &xml);
+    let url = ctx.server.url("/aggregator.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&[
+        "config",
+        "set",
+        "blocklist",
+        "spam.example, 127.0.0.1/blocked",
+    ])
+    .success();
+    ctx.run(&["sync"]).success();
+
+    let titles: Vec<String> = ctx
+        .read_posts()
+        .iter()
+        .map(|p| p["title"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(titles, ["Real News"]);
+
+    let stderr = ctx
+        .run(&["feed", "add", &ctx.server.url("/blocked/feed.xml")])
+        .failure()
+        .stderr_str();
+    assert!(
+        stderr.contains("blocked by blocklist entry"),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn test_relative_links_are_resolved_against_feed_url() {
+    let ctx = TestContext::new();
+    let xml = rss_xml_with_links(
+        "Relative Blog",
+        &[("Relative", &recent_rss_date(1), "guid-1", "/posts/a")],
+    );
+    ctx.mock_rss_feed("/blog/feed.xml", &xml);
+    let url = ctx.server.url("/blog/feed.xml");
+    ctx.write_feeds(&[&url]);
+    ctx.run(&["sync"]).success();
+
+    let sh = ctx.post_shorthand("Relative");
+    let stdout = ctx.run(&[&sh, "read", "--plain"]).success().stdout_str();
+    assert_eq!(stdout, format!("{}\n", ctx.server.url("/posts/a")));
+}
+
 /// When a feed rotates all its posts between syncs (no overlapping GUIDs),
 /// the second batch should NOT get initial read marks — only the first pull should.
 #[test]
@@ -3016,6 +7081,63 @@ fn test_feed_import_opml() {
     assert_eq!(urls.len(), 3, "should have exactly 3 feeds, got: {urls:?}");
 }
 
+#[test]
+fn test_feed_import_bookmarks_offers_feeds_of_bookmarked_sites() {
+    let ctx = TestContext::new();
+    ctx.mock_rss_feed(
+        "/feed1.xml",
+        &rss_xml("Posts", &[("A", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    ctx.mock_rss_feed(
+        "/feed2.xml",
+        &rss_xml("Comments", &[("B", "Mon, 01 Jan 2024 00:00:00 +0000")]),
+    );
+    let html = format!(
+        r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        <link rel="alternate" type="application/rss+xml" href="{}">
+        </head><body></body></html>"#,
+        ctx.server.url("/feed1.xml"),
+        ctx.server.url("/feed2.xml")
+    );
+    ctx.server.mock(|when, then| {
+        when.method(GET).path("/");
+        then.status(200)
+            .header("Content-Type", "text/html")
+            .body(&html);
+    });
+    let bookmarks = format!(
+        r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><A HREF="{}">A post</A>
+    <DT><A HREF="{}">About</A>
+</DL><p>"#,
+        ctx.server.url("/posts/1"),
+        ctx.server.url("/about")
+    );
+    let path = ctx.dir.path().join("bookmarks.html");
+    fs::write(&path, bookmarks).unwrap();
+
+    let stderr = blog_cmd()
+        .args(["feed", "import", "--bookmarks", path.to_str().unwrap()])
+        .env("RSS_STORE", ctx.dir.path())
+        .write_stdin("2\n")
+        .assert()
+        .success()
+        .stderr_str();
+
+    assert!(
+        stderr.contains("Found 2 feeds on 1 of 1 bookmarked sites"),
+        "got:\n{stderr}"
+    );
+    let urls: Vec<String> = ctx
+        .read_feeds()
+        .iter()
+        .map(|f| f["url"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(urls, [ctx.server.url("/feed2.xml")]);
+}
+
 /// Write a meta entry directly into the store's meta table.
 fn insert_meta(store_dir: &Path, key: &str, value: &str) {
     let meta_dir = store_dir.join("meta");
@@ -3448,3 +7570,89 @@ fn test_show_includes_summary_footer() {
         "stderr should contain summary footer with counts and query, got:\n{stderr}"
     );
 }
+
+#[test]
+fn test_serve_api_needs_token_and_reads_and_changes_the_store() {
+    let ctx = TestContext::new();
+    let xml = rss_xml(
+        "API Blog",
+        &[
+            ("First Post", &recent_rss_date(1)),
+            ("Second Post", &recent_rss_date(2)),
+        ],
+    );
+    ctx.mock_rss_feed("/api.xml", &xml);
+    ctx.mock_rss_feed(
+        "/new.xml",
+        &rss_xml("New Blog", &[("New Post", &recent_rss_date(1))]),
+    );
+    let feed_url = ctx.server.url("/api.xml");
+    ctx.write_feeds(&[&feed_url]);
+    ctx.run(&["sync"]).success();
+    let shorthand = ctx.post_shorthand("First Post");
+
+    let stderr = ctx
+        .run(&["serve", "--api", "127.0.0.1:0"])
+        .failure()
+        .stderr_str();
+    assert!(stderr.contains("BLOG_API_TOKEN"), "got {stderr}");
+
+    let (addr, _serve) = spawn_serve(&ctx, "--api", &[("BLOG_API_TOKEN", "s3cret")]);
+    let auth = [("Authorization", "Bearer s3cret")];
+    let get = |path: &str, headers: &[(&str, &str)]| -> serde_json::Value {
+        serde_json::from_str(&send_http(
+            &format!("http://{addr}{path}"),
+            "GET",
+            headers,
+            "",
+        ))
+        .unwrap()
+    };
+
+    let denied = get("/feeds", &[("Authorization", "Bearer wrong")]);
+    assert_eq!(denied["error"], "Unauthorized");
+
+    let feeds = get("/feeds", &auth);
+    assert_eq!(feeds[0]["url"], feed_url.as_str());
+    assert_eq!(feeds[0]["title"], "API Blog");
+    assert!(feeds[0]["shorthand"].is_string());
+
+    let posts = get("/posts?q=.all+~first", &auth);
+    let posts = posts.as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"], "First Post");
+    assert_eq!(posts[0]["shorthand"], shorthand.as_str());
+    assert_eq!(posts[0]["feed"], feed_url.as_str());
+    assert_eq!(posts[0]["read"], false);
+
+    let marked = send_http(
+        &format!("http://{addr}/posts/{shorthand}/read"),
+        "POST",
+        &auth,
+        "",
+    );
+    assert!(marked.contains(r#""read":true"#), "got {marked}");
+    let unread = ctx.run(&[".unread"]).success().stdout_str();
+    assert!(!unread.contains("First Post"), "got {unread}");
+    assert!(unread.contains("Second Post"), "got {unread}");
+
+    let new_url = ctx.server.url("/new.xml");
+    let added = send_http(
+        &format!("http://{addr}/feeds"),
+        "POST",
+        &auth,
+        &format!(r#"{{"url": "{new_url}"}}"#),
+    );
+    assert!(added.contains(&new_url), "got {added}");
+    let feeds: Vec<String> = ctx
+        .read_feeds()
+        .iter()
+        .map(|f| f["url"].as_str().unwrap().to_string())
+        .collect();
+    assert!(feeds.contains(&new_url), "got {feeds:?}");
+    let posts = ctx.run(&[".all"]).success().stdout_str();
+    assert!(posts.contains("New Post"), "got {posts}");
+
+    let bad = send_http(&format!("http://{addr}/feeds"), "POST", &auth, "not json");
+    assert!(bad.contains("error"), "got {bad}");
+}